@@ -0,0 +1,60 @@
+//! Compare how fast the column-major and row-major contents take a paste.
+//!
+//! Run with `cargo bench --bench paste`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use tabular::list::Cell;
+use tabular::reference::Reference;
+use tabular::{list, rows, Address, Edit, Range, Tabular};
+
+const COLUMNS: usize = 50;
+const ROWS: usize = 10_000;
+const RUNS: u32 = 20;
+
+fn main() {
+    let text: Vec<Vec<String>> = (0..ROWS)
+        .map(|y| (0..COLUMNS).map(|x| format!("{x}:{y}")).collect())
+        .collect();
+
+    let mut column_major = list::Content::<Cell>::default();
+    column_major.insert(Address::new(COLUMNS - 1, ROWS - 1), Cell::default());
+    column_major.set_text_conversion(
+        |cell| cell.content.clone(),
+        |text| Cell::from(text.to_string()),
+    );
+
+    let mut row_major = rows::Content::<Cell>::default();
+    row_major.insert(Address::new(COLUMNS - 1, ROWS - 1), Cell::default());
+    row_major.set_text_conversion(
+        |cell| cell.content.clone(),
+        |text| Cell::from(text.to_string()),
+    );
+
+    report("list::Content", time(&mut column_major, &text));
+    report("rows::Content", time(&mut row_major, &text));
+}
+
+// The average time a paste of the text over the whole table takes
+fn time(content: &mut impl Tabular<Cell, Address, Range>, text: &[Vec<String>]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        content.perform(
+            Edit::Paste {
+                anchor: Address::new(0, 0),
+                rows: text.to_vec(),
+            }
+            .into(),
+        );
+        black_box(&*content);
+    }
+    start.elapsed() / RUNS
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let cells = (COLUMNS * ROWS) as f64;
+    println!(
+        "{name}: {elapsed:?} per paste, {:.1} million cells per second",
+        cells / elapsed.as_secs_f64() / 1e6
+    );
+}
@@ -0,0 +1,50 @@
+//! Show that iterating over a range allocates nothing, however large it is.
+//!
+//! Run with `cargo bench --bench range_iter`.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use tabular::reference::{Reference, ReferenceRange};
+use tabular::{Address, Range};
+
+// Count the bytes allocated through the global allocator
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    for side in [10, 100, 1_000, 3_000] {
+        let range = Range::new(Address::new(0, 0), Some(Address::new(side - 1, side - 1)));
+
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let mut sum = 0;
+        for cell in range.iter() {
+            sum += black_box(cell).x();
+        }
+        let elapsed = start.elapsed();
+        let allocated = ALLOCATED.load(Ordering::Relaxed) - before;
+
+        black_box(sum);
+        println!(
+            "{side}x{side}: {} cells in {elapsed:?}, {allocated} bytes allocated",
+            side * side
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use iced::widget::{column, container, scrollable, text};
+use iced::{Background, Color, Element, Length};
+
+use tabular::list::{Cell, Content};
+use tabular::reference::*;
+use tabular::{Address, ColumnType, Table, Tabular};
+
+const ROWS: usize = 100_000;
+const COLUMNS: usize = 10;
+
+fn main() -> iced::Result {
+    iced::application("tabular • a hundred thousand rows", App::update, App::view)
+        .window_size((900.0, 600.0))
+        .run_with(App::new)
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(tabular::Action),
+    Instruction(tabular::Instruction),
+    Hovered(Option<Address>),
+}
+
+struct App {
+    cells: Content,
+    // The column the rows are sorted by, and whether in ascending order
+    sorted: Option<(usize, bool)>,
+    // The cell under the cursor
+    hovered: Option<Address>,
+}
+
+impl App {
+    fn new() -> (Self, iced::Task<Message>) {
+        let mut cells: Content = Content::with_range(Range::new(
+            Address::new(0, 0),
+            Some(Address::new(COLUMNS - 1, ROWS - 1)),
+        ));
+
+        for (address, cell) in cells.iter_mut() {
+            cell.content = ((address.x() + 1) * (address.y() * 7919 % ROWS)).to_string();
+        }
+
+        cells.set_comparator(|column_type, a: &Cell, b: &Cell| {
+            column_type.compare(&a.content, &b.content)
+        });
+        for column in 0..COLUMNS {
+            cells.set_column_type(column, ColumnType::Integer);
+        }
+
+        (
+            Self {
+                cells,
+                sorted: None,
+                hovered: None,
+            },
+            iced::Task::none(),
+        )
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Action(action) => self.cells.perform(action),
+            Message::Instruction(tabular::Instruction::HeaderClicked(column)) => {
+                // clicking the same header again reverses the order
+                let ascending = self.sorted != Some((column, true));
+                self.sorted = Some((column, ascending));
+                self.cells
+                    .perform(tabular::Action::Sort { column, ascending });
+            }
+            Message::Instruction(_) => {}
+            Message::Hovered(hovered) => self.hovered = hovered,
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let stripe: Background = Color::from_rgba(0.5, 0.5, 0.5, 0.08).into();
+
+        // only the cells scrolled into view are built
+        let table = Table::lazy(&self.cells, |_, cell: &Cell| {
+            container(text(&cell.content).size(12))
+                .padding([0, 4])
+                .into()
+        })
+        .on_action(Message::Action)
+        .on_instruction(Message::Instruction)
+        .on_hover(Message::Hovered)
+        .show_column_headers(true)
+        .show_row_numbers(true)
+        // relaying out this many rows as a divider moves is too slow
+        .resize_preview(true)
+        .row_background(move |row| (row % 2 == 1).then_some(stripe))
+        .with_height(Length::Shrink);
+
+        column![
+            text(format!(
+                "{ROWS} rows × {COLUMNS} columns, click a header to sort{}",
+                self.hovered
+                    .map(|address| format!(" • {address}"))
+                    .unwrap_or_default()
+            )),
+            scrollable(table).height(Length::Fill),
+        ]
+        .padding(20)
+        .spacing(10)
+        .into()
+    }
+}
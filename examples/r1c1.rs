@@ -0,0 +1,59 @@
+use iced::widget::{center, column, text};
+use iced::Element;
+
+use tabular::list::{Cell, Content};
+use tabular::reference::Reference;
+use tabular::{tabular, Action, R1C1Range, Tabular, R1C1};
+
+fn main() -> iced::Result {
+    iced::application("tabular • R1C1 references", App::update, App::view)
+        .window_size((600.0, 400.0))
+        .run_with(App::new)
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(Action<R1C1, R1C1Range>),
+}
+
+struct App {
+    // the table refers to its cells as "R3C2" rather than "B3"
+    cells: Content<Cell, R1C1, R1C1Range>,
+}
+
+impl App {
+    fn new() -> (Self, iced::Task<Message>) {
+        let mut cells = Content::default()
+            .with_row_heights(vec![20.0; 8])
+            .with_column_widths(vec![60.0; 6]);
+
+        for y in 0..8 {
+            for x in 0..6 {
+                let reference = R1C1::new(x, y);
+                cells.insert(reference, reference.to_string());
+            }
+        }
+
+        (Self { cells }, iced::Task::none())
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Action(action) => self.cells.perform(action),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        column![
+            text(format!("Selected: {}", self.cells.selection())),
+            tabular(&self.cells, |_, cell: &Cell| center(
+                text(&cell.content).size(12)
+            )
+            .into())
+            .on_action(Message::Action),
+        ]
+        .padding(20)
+        .spacing(20)
+        .into()
+    }
+}
@@ -0,0 +1,120 @@
+use iced::advanced::widget;
+use iced::keyboard::key;
+use iced::widget::{center, column, text};
+use iced::{keyboard, Element, Task};
+
+use tabular::list::{Cell, Content};
+use tabular::reference::*;
+use tabular::{tabular, Address, Recorder, Tabular};
+
+fn main() -> iced::Result {
+    iced::application("tabular • recording actions", App::update, App::view)
+        .window_size((600.0, 400.0))
+        .run_with(App::new)
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(tabular::Action),
+    ToggleRecording,
+    Replay,
+}
+
+struct App {
+    cells: Content,
+    recorder: Recorder,
+    // The active cell when the recording started
+    origin: Address,
+    status: String,
+}
+
+use std::sync::LazyLock;
+static TABLE: LazyLock<widget::Id> = LazyLock::new(|| widget::Id::new("table"));
+
+impl App {
+    fn new() -> (Self, Task<Message>) {
+        let mut cells = Content::default()
+            .with_row_heights(vec![20.0; 6])
+            .with_column_widths(vec![50.0; 6]);
+
+        for y in 0..6 {
+            for x in 0..6 {
+                cells.insert(Address::new(x, y), String::new());
+            }
+        }
+
+        (
+            Self {
+                cells,
+                recorder: Recorder::new(),
+                origin: Address::default(),
+                status: "F5 starts and stops recording, F6 replays at the active cell".into(),
+            },
+            tabular::focus(TABLE.clone()),
+        )
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Action(action) => {
+                self.recorder.record(&action);
+                self.cells.perform(action);
+            }
+            Message::ToggleRecording => {
+                if self.recorder.is_recording() {
+                    self.recorder.stop();
+                    self.status = format!("Recorded {} actions", self.recorder.entries().len());
+                } else {
+                    self.origin = self.cells.selection().start();
+                    self.recorder.start();
+                    self.status = format!("Recording from {}...", self.origin);
+                }
+            }
+            Message::Replay => {
+                let anchor = self.cells.selection().start();
+                let origin = self.origin;
+
+                // translate every reference from the origin to the anchor
+                let remap = move |cell: Address| {
+                    Address::new(
+                        (cell.x() + anchor.x()).saturating_sub(origin.x()),
+                        (cell.y() + anchor.y()).saturating_sub(origin.y()),
+                    )
+                };
+
+                let actions = self.recorder.actions();
+                let replay = tabular::replay(&mut self.cells, &actions, Some(&remap));
+                self.status = format!(
+                    "Replayed {} actions at {} ({} skipped)",
+                    replay.performed,
+                    anchor,
+                    replay.skipped.len()
+                );
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        column![
+            text(&self.status),
+            tabular(&self.cells, |_, cell: &Cell| center(
+                text(&cell.content).size(12)
+            )
+            .into())
+            .id(TABLE.clone())
+            .on_action(Message::Action)
+            .key_binding(|key_press| match key_press.key.as_ref() {
+                keyboard::Key::Named(key::Named::F5) => {
+                    Some(tabular::Binding::Custom(Message::ToggleRecording))
+                }
+                keyboard::Key::Named(key::Named::F6) => {
+                    Some(tabular::Binding::Custom(Message::Replay))
+                }
+                _ => tabular::Binding::from_key_press(key_press),
+            }),
+        ]
+        .padding(20)
+        .spacing(20)
+        .into()
+    }
+}
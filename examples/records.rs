@@ -0,0 +1,144 @@
+use iced::widget::{column, pick_list, text};
+use iced::{Element, Length};
+
+use tabular::records::{Column, Records};
+use tabular::reference::Reference;
+use tabular::{ColumnType, Edit, ScaleMode, Table, Tabular};
+
+fn main() -> iced::Result {
+    iced::application("tabular • a table of records", App::update, App::view)
+        .window_size((600.0, 400.0))
+        .run_with(App::new)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Planet {
+    name: String,
+    moons: u32,
+    rings: bool,
+    note: String,
+}
+
+// the planets are drawn as plain cells, without borders of their own
+impl tabular::Cell for Planet {
+    fn has_borders(&self) -> bool {
+        false
+    }
+
+    fn fill_border_quads<R: iced::advanced::Renderer>(
+        &self,
+        _renderer: &mut R,
+        _bounds: iced::Rectangle,
+        _scaling: f32,
+    ) {
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(tabular::Action),
+}
+
+struct App {
+    planets: Records<'static, Planet>,
+}
+
+impl App {
+    fn new() -> (Self, iced::Task<Message>) {
+        let planets = [
+            ("Mercury", 0, false, "The smallest planet"),
+            ("Venus", 0, false, ""),
+            ("Earth", 1, false, ""),
+            ("Mars", 2, false, ""),
+            (
+                "Jupiter",
+                95,
+                true,
+                "The largest planet, with a storm wider than the Earth that has raged for centuries",
+            ),
+            ("Saturn", 146, true, "Its rings are mostly made of ice"),
+            ("Uranus", 28, true, ""),
+            ("Neptune", 16, true, ""),
+        ]
+        .into_iter()
+        .map(|(name, moons, rings, note)| Planet {
+            name: name.to_string(),
+            moons,
+            rings,
+            note: note.to_string(),
+        })
+        .collect();
+
+        // each column reads a field of a planet, and writes it back when edited
+        let columns = vec![
+            Column::new("Name", |planet: &Planet| planet.name.clone())
+                .with_setter(|planet, text| planet.name = text.to_string())
+                .with_width(140.0),
+            Column::new("Moons", |planet: &Planet| planet.moons.to_string())
+                .with_setter(|planet, text| planet.moons = text.parse().unwrap_or_default())
+                .with_type(ColumnType::Integer),
+            Column::new("Rings", |planet: &Planet| {
+                if planet.rings { "TRUE" } else { "FALSE" }.to_string()
+            })
+            .with_setter(|planet, text| planet.rings = text == "TRUE")
+            .with_type(ColumnType::Bool),
+            Column::new("Note", |planet: &Planet| planet.note.clone())
+                .with_setter(|planet, text| planet.note = text.to_string())
+                .with_width(200.0),
+        ];
+
+        (
+            Self {
+                planets: Records::owned(planets, columns),
+            },
+            iced::Task::none(),
+        )
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::Action(action) => self.planets.perform(action),
+        }
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let cell_text = self.planets.cell_text();
+        let table = Table::new(&self.planets, move |cell, planet: &Planet| {
+            // the rings are picked from a menu, shown over the table
+            if cell.x() == 2 {
+                let rings = if planet.rings { "TRUE" } else { "FALSE" };
+                return pick_list(["TRUE", "FALSE"], Some(rings), move |rings| {
+                    Message::Action(
+                        Edit::Paste {
+                            anchor: cell,
+                            rows: vec![vec![rings.to_string()]],
+                        }
+                        .into(),
+                    )
+                })
+                .text_size(12)
+                .into();
+            }
+
+            text(cell_text(cell, planet)).into()
+        })
+        .cell_padding([0, 4])
+        .inherit_text_color(true)
+        .on_action(Message::Action)
+        .use_system_clipboard(true)
+        .show_column_headers(true)
+        // the rows grow to show the long notes in full
+        .auto_row_height(true)
+        .scale_mode(ScaleMode::FitWidth)
+        .with_width(Length::Fill)
+        .with_height(Length::Fill);
+
+        column![
+            text("Paste or delete over the cells to edit the planets"),
+            table,
+        ]
+        .padding(20)
+        .spacing(10)
+        .into()
+    }
+}
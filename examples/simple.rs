@@ -1,13 +1,27 @@
 use iced::advanced::widget::operation::Focusable;
 use iced::keyboard::key;
-use iced::widget::{center, checkbox, column, container, row, slider, text, text_input};
-use iced::{Center, Element, Rectangle, Task, keyboard};
+use iced::widget::{
+    button, center, checkbox, column, container, mouse_area, row, slider, stack, text, text_input,
+};
+use iced::{keyboard, Center, Element, Fill, Padding, Point, Rectangle, Task};
 
 use tabular::list::{Cell, Content};
 use tabular::reference::*;
-use tabular::{Address, Tabular, tabular};
+use tabular::{tabular, Address, Tabular};
 
 fn main() -> iced::Result {
+    // with the `debug-trace` feature, the table logs what it does
+    #[cfg(feature = "debug-trace")]
+    {
+        use tracing_subscriber::filter::{LevelFilter, Targets};
+        use tracing_subscriber::prelude::*;
+
+        tracing_subscriber::registry()
+            .with(tracing_subscriber::fmt::layer())
+            .with(Targets::new().with_target("tabular", LevelFilter::TRACE))
+            .init();
+    }
+
     iced::application(
         "iced • how quickly can I make a grid/table widget",
         App::update,
@@ -27,17 +41,23 @@ enum Message {
     Action(tabular::Action),
     Instruction(tabular::Instruction),
     Spacing(f32),
-    ShowGridlines(bool),
+    ShowRowLines(bool),
+    ShowColumnLines(bool),
     Edit(Address, String),
-    FocusSelection,
     FocusTable,
+    Undo,
+    Redo,
+    CloseMenu,
 }
 
 struct App {
     cells: Content,
-    clipboard: String,
     spacing: f32,
-    show_gridlines: bool,
+    show_row_lines: bool,
+    show_column_lines: bool,
+    rejection: Option<String>,
+    // The context menu opened by a right click on a cell, and where it opened
+    menu: Option<(Address, Point)>,
 }
 
 use std::sync::LazyLock;
@@ -47,7 +67,8 @@ impl App {
     fn new() -> (Self, Task<Message>) {
         let mut cells = Content::default()
             .with_row_heights(vec![5.0, 20.0, 20.0, 20.0])
-            .with_column_widths(vec![50.0, 50.0, 50.0, 50.0]);
+            .with_column_widths(vec![50.0, 50.0, 50.0, 50.0])
+            .with_history(100);
 
         [
             ["Item", "Price", "Qty", "Total"],
@@ -60,15 +81,40 @@ impl App {
         .for_each(|(y, row)| {
             row.iter()
                 .enumerate()
-                .for_each(|(x, &content)| cells.insert((x, y), content.to_string()));
+                .for_each(|(x, &content)| cells.insert(Address::new(x, y), content.to_string()));
+        });
+        cells.clear_history();
+
+        // the fruits can be collapsed under the header row
+        cells.set_row_group(0..4, 1);
+
+        // only accept whole numbers (or nothing) in the "Qty" column
+        cells.set_validator(|address: Address, cell: &Cell| {
+            let is_quantity = cell.content.is_empty() || cell.content.parse::<u32>().is_ok();
+            if address.x() == 2 && address.y() != 0 && !is_quantity {
+                Err(format!("{address}: \"{}\" is not a quantity", cell.content))
+            } else {
+                Ok(())
+            }
         });
 
+        // cells are copied and pasted as their text
+        cells.set_text_conversion(
+            |cell: &Cell| cell.content.clone(),
+            |text| Cell::from(text.to_string()),
+        );
+
+        // Ctrl+Arrow stops at the edges of the filled cells
+        cells.set_is_empty(|cell: &Cell| cell.content.is_empty());
+
         (
             Self {
                 cells,
-                clipboard: String::new(),
                 spacing: 0.0,
-                show_gridlines: true,
+                show_row_lines: true,
+                show_column_lines: true,
+                rejection: None,
+                menu: None,
             },
             tabular::focus(TABLE.clone()),
         )
@@ -79,41 +125,91 @@ impl App {
             Message::Spacing(value) => {
                 self.spacing = value;
             }
-            Message::ShowGridlines(value) => {
-                self.show_gridlines = value;
+            Message::ShowRowLines(value) => {
+                self.show_row_lines = value;
+            }
+            Message::ShowColumnLines(value) => {
+                self.show_column_lines = value;
             }
-            Message::Action(action) => self.cells.perform(action),
+            Message::Action(action) => {
+                self.menu = None;
+                self.cells.perform(action);
+            }
+            Message::CloseMenu => self.menu = None,
             Message::Instruction(instruction) => match instruction {
-                tabular::Instruction::Cut => self.cut(),
-                tabular::Instruction::Copy => self.copy(),
-                tabular::Instruction::Paste => self.paste(),
+                // the table uses the system clipboard by itself
+                tabular::Instruction::Cut
+                | tabular::Instruction::Copy
+                | tabular::Instruction::Paste => {}
                 tabular::Instruction::Activate(address) => {
                     if address.y() != 0 {
                         return text_input::focus(address);
                     }
                 }
+                tabular::Instruction::ActivateWith(address, text) => {
+                    if address.y() != 0 {
+                        self.cells.insert(address, text.to_string());
+                        return text_input::focus(address)
+                            .chain(text_input::move_cursor_to_end(address));
+                    }
+                }
+                tabular::Instruction::ToggleGroup(row) => self.cells.toggle_row_group(row),
+                tabular::Instruction::EditRejected { reason, .. } => {
+                    self.rejection = Some(reason);
+                }
+                tabular::Instruction::HeaderClicked(_) | tabular::Instruction::Resized { .. } => {}
+                tabular::Instruction::SecondaryClick { cell, position } => {
+                    self.menu = Some((cell, position));
+                }
+                tabular::Instruction::AutoFit {
+                    axis: tabular::Axis::Column,
+                    index,
+                    texts,
+                } => {
+                    // roughly 8 pixels per character of the default font
+                    let widest = texts
+                        .iter()
+                        .map(|text| text.chars().count())
+                        .max()
+                        .unwrap_or_default();
+                    self.cells.perform(tabular::Action::SetColumnWidth(
+                        index,
+                        widest as f32 * 8.0 + 16.0,
+                    ));
+                }
+                tabular::Instruction::AutoFit {
+                    axis: tabular::Axis::Row,
+                    ..
+                } => {}
             },
             Message::Edit(address, content) => {
-                if let Some(cell) = self.cells.get_mut(address) {
-                    cell.content = content;
-                }
+                self.rejection = None;
+                self.cells.insert(address, content);
             }
             Message::FocusTable => return tabular::focus(TABLE.clone()),
-            Message::FocusSelection => {
-                let address = self.cells.selection().start();
-                return text_input::focus(address);
+            Message::Undo => {
+                if let Some(range) = self.cells.undo() {
+                    self.cells.perform(tabular::Action::Select(range));
+                }
+            }
+            Message::Redo => {
+                if let Some(range) = self.cells.redo() {
+                    self.cells.perform(tabular::Action::Select(range));
+                }
             }
         }
         Task::none()
     }
 
-    fn view(&self) -> Element<Message> {
-        column![
+    fn view(&self) -> Element<'_, Message> {
+        let content = column![
             text("How quickly can I make a grid/table widget?"),
             row![
                 "Spacing:",
                 slider(0.0..=50.0, self.spacing, Message::Spacing),
-                checkbox("Show gridlines", self.show_gridlines).on_toggle(Message::ShowGridlines)
+                checkbox("Row lines", self.show_row_lines).on_toggle(Message::ShowRowLines),
+                checkbox("Column lines", self.show_column_lines)
+                    .on_toggle(Message::ShowColumnLines),
             ]
             .spacing(5)
             .align_y(Center),
@@ -121,79 +217,72 @@ impl App {
                 .id(TABLE.clone())
                 .on_action(Message::Action)
                 .on_instruction(Message::Instruction)
+                // Enter activates the selected cell, which focuses its editor,
+                // and once the edit is submitted the selection moves down
+                .on_submit_motion(tabular::Motion::Down)
                 .key_binding(|key_press| match key_press.key.as_ref() {
-                    keyboard::Key::Named(key::Named::Enter)
-                        if key_press.status == tabular::Status::Focused =>
-                        Some(tabular::Binding::Custom(Message::FocusSelection)),
                     keyboard::Key::Named(key::Named::Escape)
                         if key_press.status == tabular::Status::Unfocused =>
                         Some(tabular::Binding::Custom(Message::FocusTable)),
+                    keyboard::Key::Character("z" | "Z") if key_press.modifiers.command() => {
+                        Some(tabular::Binding::Custom(if key_press.modifiers.shift() {
+                            Message::Redo
+                        } else {
+                            Message::Undo
+                        }))
+                    }
+                    keyboard::Key::Character("y") if key_press.modifiers.command() => {
+                        Some(tabular::Binding::Custom(Message::Redo))
+                    }
                     _ => tabular::Binding::from_key_press(key_press),
                 })
-                .show_gridlines(self.show_gridlines)
-                .with_spacing((self.spacing, self.spacing)),
+                .use_system_clipboard(true)
+                .show_row_lines(self.show_row_lines)
+                .show_column_lines(self.show_column_lines)
+                .min_column_width(24.0)
+                .min_row_height(12.0)
+                .with_spacing((self.spacing, self.spacing))
+                .style(table),
+            text(self.rejection.as_deref().unwrap_or_default()).size(12),
         ]
         .padding(20)
-        .spacing(20)
-        .into()
-    }
-
-    fn cut(&mut self) {
-        self.copy();
-
-        for cell in self.cells.selection().normalize().iter() {
-            self.cells.insert(cell, String::new());
-        }
-    }
-
-    fn copy(&mut self) {
-        let range = self.cells.selection().normalize();
-        let mut all_content = Vec::new();
-
-        for y in range.start().y()..=range.end().unwrap_or(range.start()).y() {
-            let mut row_content = Vec::new();
-
-            for x in range.start().x()..=range.end().unwrap_or(range.start()).x() {
-                let content = self
-                    .cells
-                    .get((y, x))
-                    .cloned()
-                    .unwrap_or(Default::default());
-                row_content.push(content.content);
-            }
-
-            // row cells (column-wise) are joined by tabs
-            all_content.push(row_content.join("\t"));
-        }
-
-        // rows are joined by newlines
-        self.clipboard = all_content.join("\n");
-    }
-
-    fn paste(&mut self) {
-        let start = self.cells.selection().start();
-        let rows: Vec<&str> = self.clipboard.split('\n').collect();
-
-        let max_rows = self.cells.row_count();
-        let max_cols = self.cells.column_count();
-
-        for (row_offset, row_content) in rows.iter().enumerate() {
-            let target_row = start.y() + row_offset; // bounds check
-            if target_row >= max_rows {
-                break;
-            }
-
-            let columns: Vec<&str> = row_content.split('\t').collect();
-            for (col_offset, content) in columns.iter().enumerate() {
-                let target_col = start.x() + col_offset; // bounds check
-                if target_col >= max_cols {
-                    break;
-                }
+        .spacing(20);
+
+        let Some((address, position)) = self.menu else {
+            return content.into();
+        };
+
+        let item = |label, action| {
+            button(text(label).size(12))
+                .on_press(Message::Action(action))
+                .style(button::text)
+                .width(Fill)
+        };
+        let menu = container(
+            column![
+                item("Insert row above", tabular::Action::InsertRow(address.y())),
+                item(
+                    "Insert row below",
+                    tabular::Action::InsertRow(address.y() + 1)
+                ),
+                item("Delete row", tabular::Action::RemoveRow(address.y())),
+                item("Clear", tabular::Edit::Delete.into()),
+            ]
+            .width(140),
+        )
+        .padding(2)
+        .style(container::bordered_box);
+
+        // a click anywhere but on the menu closes it
+        let overlay = mouse_area(
+            container(menu)
+                .padding(Padding::ZERO.top(position.y).left(position.x))
+                .width(Fill)
+                .height(Fill),
+        )
+        .on_press(Message::CloseMenu);
 
-                self.cells
-                    .insert((target_row, target_col), content.to_string());
-            }
-        }
+        stack![content, overlay].into()
     }
 }
 
@@ -214,6 +303,20 @@ fn view_cell(address: Address, cell: &Cell) -> Element<'_, Message> {
     .into()
 }
 
+// the background shows through the spacing between the cells
+fn table(theme: &iced::Theme, status: tabular::Status) -> tabular::Style {
+    let base = tabular::default(theme, status);
+
+    tabular::Style {
+        background: theme.extended_palette().background.strong.color.into(),
+        border: iced::Border {
+            radius: 4.0.into(),
+            ..base.border
+        },
+        ..base
+    }
+}
+
 fn header(theme: &iced::Theme) -> container::Style {
     container::Style {
         background: Some(theme.extended_palette().background.weak.color.into()),
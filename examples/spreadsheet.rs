@@ -0,0 +1,113 @@
+use iced::widget::{column, container, text};
+use iced::{Element, Length};
+
+use tabular::clipboard::{decode_tsv, encode_tsv};
+use tabular::list::{Cell, Content};
+use tabular::reference::{Reference, ReferenceRange};
+use tabular::{to_column_name, Address, Edit, Instruction, Table, Tabular};
+
+const ROWS: usize = 50;
+const COLUMNS: usize = 200;
+
+fn main() -> iced::Result {
+    iced::application("tabular • frozen rows and columns", App::update, App::view)
+        .window_size((900.0, 600.0))
+        .run_with(App::new)
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    Action(tabular::Action),
+    Instruction(Instruction),
+    Pasted(Option<String>),
+}
+
+struct App {
+    cells: Content,
+}
+
+impl App {
+    fn new() -> (Self, iced::Task<Message>) {
+        let mut cells = Content::default();
+
+        // the first row holds the column labels and the first column the row labels
+        for x in 1..=COLUMNS {
+            cells.insert(
+                Address::new(x, 0),
+                format!("Data {}", to_column_name(x - 1)),
+            );
+        }
+        for y in 1..=ROWS {
+            cells.insert(Address::new(0, y), format!("Row {y}"));
+            for x in 1..=COLUMNS {
+                cells.insert(Address::new(x, y), (x * y).to_string());
+            }
+        }
+
+        // a block of merged cells behaves like a single large cell
+        cells.insert(Address::new(1, 1), "Merged over B2:D3".to_string());
+        cells.merge_cells("B2:D3".parse().expect("valid range"));
+
+        (Self { cells }, iced::Task::none())
+    }
+
+    fn update(&mut self, message: Message) -> iced::Task<Message> {
+        match message {
+            Message::Action(action) => self.cells.perform(action),
+            Message::Instruction(Instruction::Copy) => return self.copy(),
+            Message::Instruction(Instruction::Cut) => {
+                let task = self.copy();
+                self.cells.perform(Edit::Delete.into());
+                return task;
+            }
+            Message::Instruction(Instruction::Paste) => {
+                return iced::clipboard::read().map(Message::Pasted)
+            }
+            Message::Instruction(_) => {}
+            Message::Pasted(Some(text)) => self.cells.perform(
+                Edit::Paste {
+                    anchor: self.cells.selection().normalize().start(),
+                    rows: decode_tsv(&text),
+                }
+                .into(),
+            ),
+            Message::Pasted(None) => {}
+        }
+        iced::Task::none()
+    }
+
+    // the app handles the clipboard itself, as tab-separated text that
+    // other spreadsheets understand
+    fn copy(&self) -> iced::Task<Message> {
+        let selection = self.cells.selection().normalize();
+        let cells = selection.iter().filter_map(|address| {
+            self.cells
+                .get(address)
+                .map(|cell| (address, cell.content.as_str()))
+        });
+        iced::clipboard::write(encode_tsv(cells, &selection))
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let table = Table::lazy(&self.cells, |_, cell: &Cell| {
+            container(text(&cell.content).size(12))
+                .padding([0, 4])
+                .into()
+        })
+        .on_action(Message::Action)
+        .on_instruction(Message::Instruction)
+        .freeze_rows(1)
+        .freeze_columns(1)
+        .scrollable(true)
+        .with_width(Length::Fill)
+        .with_height(Length::Fill);
+
+        column![
+            text("The labels stay in view while scrolling in both directions"),
+            table,
+        ]
+        .padding(20)
+        .spacing(10)
+        .into()
+    }
+}
@@ -11,13 +11,14 @@
 //!
 //! Usage:
 //!
-//! ```rust
+//! ```rust,no_run
+//! use iced::Element;
+//! use iced::widget::text;
 //! use tabular::list::{Cell, Content};
-//! use tabular::reference::*;
-//! use tabular::{Address, Tabular, tabular};
+//! use tabular::{Address, Tabular};
 //!
 //! fn main() -> iced::Result {
-//!     iced::application(App::new, App::update, App::view).run()
+//!     iced::run("tabular", App::update, App::view)
 //! }
 //!
 //! #[derive(Debug, Clone)]
@@ -26,47 +27,122 @@
 //!    Instruction(tabular::Instruction),
 //! }
 //!
+//! #[derive(Default)]
 //! struct App {
 //!    cells: Content,
 //! }
 //!
-//! TODO: finish writing this example.....
+//! impl App {
+//!     fn update(&mut self, message: Message) {
+//!         match message {
+//!             Message::Action(action) => self.cells.perform(action),
+//!             Message::Instruction(_) => {}
+//!         }
+//!     }
 //!
+//!     fn view(&self) -> Element<'_, Message> {
+//!         tabular::tabular(&self.cells, |_: Address, cell: &Cell| text(&cell.content).into())
+//!             .on_action(Message::Action)
+//!             .on_instruction(Message::Instruction)
+//!             .into()
+//!     }
+//! }
 //! ```
-use action::Edit;
-use iced::advanced::widget::{self, Tree, Widget, operation, tree};
-use iced::advanced::{Clipboard, Layout, Renderer, Shell, clipboard, layout, mouse, renderer};
-use iced::{Border, Color, Element, Length, Point, Rectangle, Size, Vector, event};
+use iced::advanced::text;
+use iced::advanced::widget::{self, operation, tree, Tree, Widget};
+use iced::advanced::{layout, mouse, overlay, renderer, Clipboard, Layout, Renderer, Shell};
+use iced::{
+    alignment, event, keyboard, touch, Background, Border, Color, Element, Length, Padding, Point,
+    Rectangle, Size, Vector,
+};
+
+// Logs an interaction event at trace level when the `debug-trace` feature
+// is enabled, and expands to nothing otherwise
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "debug-trace")]
+        tracing::trace!($($arg)*);
+    };
+}
 
+pub mod accessibility;
 mod action;
+pub mod clipboard;
+pub mod column;
 mod content;
+#[cfg(feature = "csv")]
+pub mod csv;
+pub mod export;
+pub mod recorder;
 pub mod reference;
 mod theme;
 mod update;
 mod utils;
 
+use content::list::{DEFAULT_COLUMN_WIDTH, DEFAULT_ROW_HEIGHT};
 use reference::{Reference, ReferenceRange};
 
 pub trait Cell {
     // Method to check if the cell has any borders to draw
     fn has_borders(&self) -> bool;
 
-    // Method to draw border quads for the cell
+    // Method to draw border quads for the cell, snapped to the device pixels
+    // of the given scale factor
     fn fill_border_quads<R: Renderer>(
         &self,
         renderer: &mut R,
         bounds: iced::Rectangle,
-        scaling: f32,
+        scale_factor: f32,
     );
+
+    // Method to get the background to fill the cell with, beneath its content
+    fn background(&self) -> Option<iced::Background> {
+        None
+    }
 }
 
-pub use action::{Action, Instruction};
-pub use content::{Internal, Tabular, list};
-pub use reference::{Address, Range};
+pub use action::{Action, Edit, Instruction};
+pub use column::ColumnType;
+pub use content::{
+    apply_action, list, records, rows, CellSpan, CellWindow, Dirty, Internal, RowGroup, RowHeight,
+    Sizing, Tabular,
+};
+pub use recorder::{replay, Recorder};
+pub use reference::{AbsAddress, Address, CellIndex, Cells, ParseError, R1C1Range, Range, R1C1};
 pub use theme::*;
-pub use update::{Binding, KeyPress, Update};
+pub use update::{Binding, Direction, KeyPress, Motion, Update, Wrap};
 pub use utils::*;
 
+// The width of one outline level in the row group gutter
+const GROUP_GUTTER_WIDTH: f32 = 14.0;
+
+// The height of the column header strip
+const COLUMN_HEADER_HEIGHT: f32 = 22.0;
+
+// The text size of the header labels
+const HEADER_TEXT_SIZE: f32 = 12.0;
+
+// The padding on each side of the row numbers
+const ROW_NUMBER_PADDING: f32 = 6.0;
+
+// The extra rows and columns built around the viewport of a lazy table
+const LAZY_OVERSCAN: usize = 4;
+
+// The thickness of the scrollbars of a scrollable table
+const SCROLLBAR_WIDTH: f32 = 8.0;
+
+// The shortest a scrollbar thumb gets, to keep it easy to grab
+const SCROLLBAR_MIN_THUMB: f32 = 16.0;
+
+// The size of the fill handle at the end corner of the selection
+const FILL_HANDLE_SIZE: f32 = 6.0;
+
+type OnAction<'a, K, R, Message> = Box<dyn Fn(Action<K, R>) -> Message + 'a>;
+type OnInstruction<'a, K, Message> = Box<dyn Fn(Instruction<K>) -> Message + 'a>;
+type KeyBindingFn<'a, Message> = Box<dyn Fn(KeyPress) -> Option<Binding<Message>> + 'a>;
+type OnHover<'a, K, Message> = Box<dyn Fn(Option<K>) -> Message + 'a>;
+type RowBackground<'a> = Box<dyn Fn(usize) -> Option<Background> + 'a>;
+
 pub struct Table<'a, Data, T, K, R, Message, Theme, Renderer>
 where
     Data: Tabular<T, K, R>,
@@ -84,25 +160,84 @@ where
     cells: Vec<(K, Element<'a, Message, Theme, Renderer>)>,
     // The number of columns in the grid
     columns: usize,
+    // Whether only the cells in view were built
+    lazy: bool,
+    // Whether the cells are in column-major order, so that the cells in view
+    // can be found by binary search
+    ordered: bool,
     // The width of the grid
     width: Length,
     // The height of the grid
     height: Length,
     // The spacing amount between cells
     spacing: Size,
-    // Whether to show gridlines
-    show_gridlines: bool,
+    // The padding between each cell and its content
+    cell_padding: Padding,
+    // The alignment of the cells of each column, in place of the one of its
+    // type
+    column_alignments: Vec<Option<alignment::Horizontal>>,
+    // Whether the cells draw their text in the value color of the style
+    inherit_text_color: bool,
+    // The smallest and largest widths a column can be resized to
+    column_width_limits: (f32, f32),
+    // The smallest and largest heights a row can be resized to
+    row_height_limits: (f32, f32),
+    // How far a column or row is resized by each step from the keyboard
+    resize_step: f32,
+    // Whether to show the lines between rows
+    show_row_lines: bool,
+    // Whether to show the lines between columns
+    show_column_lines: bool,
+    // Whether the grid is laid out from right to left
+    right_to_left: bool,
+    // How the columns and rows are scaled to the bounds
+    scale_mode: ScaleMode,
+    // Whether the rows are as tall as their tallest cell
+    auto_row_height: bool,
+    // Whether to show the strip of column headers above the grid
+    show_column_headers: bool,
+    // Whether to show the gutter of row numbers left of the grid
+    show_row_numbers: bool,
+    // The number of rows at the top pinned to the visible bounds
+    frozen_rows: usize,
+    // The number of columns at the start pinned to the visible bounds
+    frozen_columns: usize,
+    // The number of rows at the bottom pinned to the visible bounds
+    footer_rows: usize,
+    // Whether the footer rows can be selected and navigated to
+    footer_selectable: bool,
+    // Whether the content scrolls within the bounds of the table instead of
+    // being scaled to them
+    scrollable: bool,
+    // Whether dragging a divider only previews the new size until it's
+    // released
+    resize_preview: bool,
     // If true, all single clicks will passthrough to children. If not,
     // only double clicks will.
     passthrough: bool,
+    // How the selection moves once an edit in a cell is submitted
+    submit_motion: Motion,
+    // What the arrow keys do at the edges of the grid
+    wrap: Wrap,
+    // The scale factor of the window, to snap lines to its pixels
+    scale_factor: f32,
+    // Whether cut, copy and paste go through the system clipboard instead
+    // of being left to the app
+    system_clipboard: bool,
     // The function that is called when an action is performed in the grid
-    on_edit: Option<Box<dyn Fn(Action<K, R>) -> Message + 'a>>,
+    on_edit: Option<OnAction<'a, K, R, Message>>,
     // The function that is called when an instruction is emitted by the grid
-    on_instruction: Option<Box<dyn Fn(Instruction<K>) -> Message + 'a>>,
+    on_instruction: Option<OnInstruction<'a, K, Message>>,
+    // The function that is called when the cell under the cursor changes
+    on_hover: Option<OnHover<'a, K, Message>>,
+    // The function that gives the background of each row, e.g. to stripe them
+    row_background: Option<RowBackground<'a>>,
     // The function that is called to produce key bindings on key presses
-    key_binding: Option<Box<dyn Fn(KeyPress) -> Option<Binding<Message>> + 'a>>,
+    key_binding: Option<KeyBindingFn<'a, Message>>,
     // The style class of the grid
     class: <Theme as Catalog>::Class<'a>,
+    // The style of each cell, in place of the one from the catalog
+    cell_style: Option<CellStyleFn<'a, Theme>>,
 
     _phantom: std::marker::PhantomData<T>,
 }
@@ -120,25 +255,103 @@ where
     pub fn new(
         data: &'a Data,
         view_cell: impl Fn(K, &'a T) -> Element<'a, Message, Theme, Renderer> + 'static,
+    ) -> Self {
+        let cells = data
+            .iter()
+            .map(|(cell_ref, cell)| (cell_ref, view_cell(cell_ref, cell)))
+            .collect::<Vec<_>>();
+
+        Self::with_cells(data, cells, false)
+    }
+
+    /// Creates a [`Table`] which only builds the cells in view.
+    ///
+    /// The widget records the cells in view in the [`Internal`] state of the
+    /// data as it is scrolled, and rebuilds the view whenever they change, so
+    /// `view_cell` is never called for cells far outside the viewport.
+    /// Cells out of view can still be selected and navigated to.
+    pub fn lazy(
+        data: &'a Data,
+        view_cell: impl Fn(K, &'a T) -> Element<'a, Message, Theme, Renderer> + 'static,
+    ) -> Self
+    where
+        usize: std::slice::SliceIndex<[Vec<T>]>,
+    {
+        let cells = data
+            .internal()
+            .window()
+            .map(|window| {
+                // merged cells are built from their anchor, wherever it is
+                window
+                    .cells()
+                    .map(|(x, y)| {
+                        data.span_at(x, y)
+                            .map_or((x, y), |span| span.anchor.as_tuple())
+                    })
+                    .collect::<std::collections::BTreeSet<_>>()
+                    .into_iter()
+                    .filter_map(|(x, y)| {
+                        let cell_ref = K::new(x, y);
+                        data.get(cell_ref)
+                            .map(|cell| (cell_ref, view_cell(cell_ref, cell)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Self::with_cells(data, cells, true)
+    }
+
+    fn with_cells(
+        data: &'a Data,
+        cells: Vec<(K, Element<'a, Message, Theme, Renderer>)>,
+        lazy: bool,
     ) -> Self {
         let columns = data.column_count();
+        let ordered = cells
+            .windows(2)
+            .all(|pair| pair[0].0.as_tuple() < pair[1].0.as_tuple());
         Self {
             id: None,
             data,
-            cells: data
-                .iter()
-                .map(|(cell_ref, cell)| (cell_ref, view_cell(cell_ref, cell)))
-                .collect::<Vec<_>>(),
+            cells,
             columns,
+            lazy,
+            ordered,
             width: Length::Fill,
             height: Length::Fill,
-            show_gridlines: true,
+            show_row_lines: true,
+            show_column_lines: true,
+            right_to_left: false,
+            scale_mode: ScaleMode::Stretch,
+            auto_row_height: false,
+            show_column_headers: false,
+            show_row_numbers: false,
+            frozen_rows: 0,
+            frozen_columns: 0,
+            footer_rows: 0,
+            footer_selectable: true,
+            scrollable: false,
+            resize_preview: false,
             spacing: Size::ZERO,
+            cell_padding: Padding::ZERO,
+            column_alignments: Vec::new(),
+            inherit_text_color: false,
+            column_width_limits: (0.0, f32::INFINITY),
+            row_height_limits: (0.0, f32::INFINITY),
+            resize_step: 10.0,
             passthrough: false,
+            submit_motion: Motion::Down,
+            wrap: Wrap::Clamp,
+            scale_factor: 1.0,
+            system_clipboard: false,
             on_edit: None,
             on_instruction: None,
+            on_hover: None,
+            row_background: None,
             key_binding: None,
             class: <Theme as Catalog>::default(),
+            cell_style: None,
             _phantom: std::marker::PhantomData,
         }
     }
@@ -149,13 +362,17 @@ where
         self
     }
 
-    /// Sets the spacing between cells in the [`Table`].
+    /// Sets the width of the [`Table`].
+    ///
+    /// [`Length::Shrink`] fits the table to the sizes of its columns.
     pub fn with_width(mut self, width: impl Into<Length>) -> Self {
         self.width = width.into();
         self
     }
 
     /// Sets the height of the [`Table`].
+    ///
+    /// [`Length::Shrink`] fits the table to the sizes of its rows.
     pub fn with_height(mut self, height: impl Into<Length>) -> Self {
         self.height = height.into();
         self
@@ -167,9 +384,248 @@ where
         self
     }
 
-    /// Sets whether to show gridlines in the [`Table`].
-    pub fn show_gridlines(mut self, show: bool) -> Self {
-        self.show_gridlines = show;
+    /// Sets the padding between each cell of the [`Table`] and its content.
+    ///
+    /// Unlike wrapping each cell in a container, the padding costs no extra
+    /// widget. Gridlines, the selection and clicks still cover the whole
+    /// cell, and the padding shrinks to fit cells smaller than it.
+    pub fn cell_padding(mut self, padding: impl Into<Padding>) -> Self {
+        self.cell_padding = padding.into();
+        self
+    }
+
+    /// Sets the horizontal alignment of the cells in the given column of the
+    /// [`Table`].
+    ///
+    /// Cells narrower than their column are placed within it by this
+    /// alignment, which otherwise follows the [`ColumnType`] of the column,
+    /// e.g. numbers are right-aligned. Cells that fill their width are
+    /// unaffected. Right to left, left and right are swapped.
+    pub fn column_alignment(mut self, column: usize, alignment: alignment::Horizontal) -> Self {
+        if self.column_alignments.len() <= column {
+            self.column_alignments.resize(column + 1, None);
+        }
+        self.column_alignments[column] = Some(alignment);
+        self
+    }
+
+    /// Sets whether the cells of the [`Table`] draw their text in the
+    /// [`Style::value`] color, unless they set a color of their own.
+    ///
+    /// Otherwise the cells inherit the text color of the parent of the table.
+    pub fn inherit_text_color(mut self, inherit_text_color: bool) -> Self {
+        self.inherit_text_color = inherit_text_color;
+        self
+    }
+
+    /// Sets the smallest width a column of the [`Table`] can be resized to.
+    ///
+    /// Columns already narrower than this keep their width until they're
+    /// resized.
+    pub fn min_column_width(mut self, width: f32) -> Self {
+        self.column_width_limits.0 = width;
+        self
+    }
+
+    /// Sets the largest width a column of the [`Table`] can be resized to.
+    pub fn max_column_width(mut self, width: f32) -> Self {
+        self.column_width_limits.1 = width;
+        self
+    }
+
+    /// Sets the smallest height a row of the [`Table`] can be resized to.
+    ///
+    /// Rows already shorter than this keep their height until they're
+    /// resized.
+    pub fn min_row_height(mut self, height: f32) -> Self {
+        self.row_height_limits.0 = height;
+        self
+    }
+
+    /// Sets the largest height a row of the [`Table`] can be resized to.
+    pub fn max_row_height(mut self, height: f32) -> Self {
+        self.row_height_limits.1 = height;
+        self
+    }
+
+    /// Sets whether the [`Table`] scrolls its content within its own bounds.
+    ///
+    /// A scrollable table keeps the sizes of its columns and rows instead of
+    /// scaling them to its bounds. It scrolls with the mouse wheel, holding
+    /// <kbd>Shift</kbd> to scroll horizontally, or by dragging its
+    /// scrollbars, and keeps the active cell in view as the selection moves.
+    pub fn scrollable(mut self, scrollable: bool) -> Self {
+        self.scrollable = scrollable;
+        self
+    }
+
+    /// Sets how far a column or row of the [`Table`] is resized by each step
+    /// of a [`Binding::Resize`], e.g. when pressing <kbd>Alt</kbd> and an
+    /// arrow key.
+    pub fn resize_step(mut self, step: f32) -> Self {
+        self.resize_step = step;
+        self
+    }
+
+    /// Sets whether dragging a divider of the [`Table`] only previews the new
+    /// size of its column or row with a line, resizing it once the divider is
+    /// released.
+    ///
+    /// Otherwise the table is laid out again as the divider moves, which can
+    /// be slow for large tables.
+    pub fn resize_preview(mut self, resize_preview: bool) -> Self {
+        self.resize_preview = resize_preview;
+        self
+    }
+
+    /// Sets how the columns and rows of the [`Table`] are scaled to its
+    /// bounds.
+    ///
+    /// A scrollable table always keeps them at their own sizes. Defaults to
+    /// [`ScaleMode::Stretch`].
+    pub fn scale_mode(mut self, scale_mode: ScaleMode) -> Self {
+        self.scale_mode = scale_mode;
+        self
+    }
+
+    /// Sets whether each row of the [`Table`] is as tall as its tallest cell,
+    /// e.g. to show wrapped text in full.
+    ///
+    /// The cells are measured at the width of their column, and a row without
+    /// any cell of a finite height keeps its size from the data. The sizes in
+    /// the data are left untouched, and the cells are only measured again
+    /// once the content or the column widths change.
+    pub fn auto_row_height(mut self, auto_row_height: bool) -> Self {
+        self.auto_row_height = auto_row_height;
+        self
+    }
+
+    /// Sets how the selection moves once an edit is submitted, i.e. when
+    /// <kbd>Enter</kbd> is pressed while a cell of the [`Table`] has the
+    /// focus. Holding <kbd>Shift</kbd> moves the opposite way.
+    ///
+    /// Defaults to [`Motion::Down`].
+    pub fn on_submit_motion(mut self, motion: Motion) -> Self {
+        self.submit_motion = motion;
+        self
+    }
+
+    /// Sets what moving the selection past the edge of the [`Table`] with
+    /// the arrow keys does, e.g. to move on to the next row.
+    ///
+    /// The footer rows count as past the edge if they can't be selected.
+    ///
+    /// Defaults to [`Wrap::Clamp`].
+    pub fn wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Sets the scale factor of the window showing the [`Table`], e.g. as
+    /// given by [`window::get_scale_factor`].
+    ///
+    /// The gridlines, the border and the selection are snapped to the pixels
+    /// of the window at this scale, so they're drawn crisply. Defaults to
+    /// `1.0`.
+    ///
+    /// [`window::get_scale_factor`]: iced::window::get_scale_factor
+    pub fn scale_factor(mut self, scale_factor: f32) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    /// Sets whether the [`Table`] cuts, copies and pastes through the system
+    /// clipboard by itself.
+    ///
+    /// The selected cells are copied as tab-separated text, read with
+    /// [`Tabular::read_cell_text`], and pasted with an [`Edit::Paste`].
+    /// Otherwise, the [`Table`] emits [`Instruction::Cut`],
+    /// [`Instruction::Copy`] and [`Instruction::Paste`] for the app to
+    /// handle.
+    pub fn use_system_clipboard(mut self, system_clipboard: bool) -> Self {
+        self.system_clipboard = system_clipboard;
+        self
+    }
+
+    /// Sets whether to show gridlines in the [`Table`], both between rows
+    /// and between columns.
+    pub fn show_gridlines(self, show: bool) -> Self {
+        self.show_row_lines(show).show_column_lines(show)
+    }
+
+    /// Sets whether to show the horizontal lines between the rows of the
+    /// [`Table`].
+    pub fn show_row_lines(mut self, show: bool) -> Self {
+        self.show_row_lines = show;
+        self
+    }
+
+    /// Sets whether to show the vertical lines between the columns of the
+    /// [`Table`].
+    pub fn show_column_lines(mut self, show: bool) -> Self {
+        self.show_column_lines = show;
+        self
+    }
+
+    /// Sets whether to show a strip of column headers (A, B, C...) above the
+    /// grid of the [`Table`].
+    ///
+    /// Clicking a header selects its column and emits
+    /// [`Instruction::HeaderClicked`]. Dragging a header, or any cell with
+    /// <kbd>Alt</kbd> held, moves its column through [`Action::MoveColumn`].
+    pub fn show_column_headers(mut self, show: bool) -> Self {
+        self.show_column_headers = show;
+        self
+    }
+
+    /// Sets whether to show a gutter of 1-based row numbers left of the grid
+    /// of the [`Table`].
+    ///
+    /// The gutter is as wide as the widest row number. Clicking a row number
+    /// selects its row, and dragging it moves the row through
+    /// [`Action::MoveRow`].
+    pub fn show_row_numbers(mut self, show: bool) -> Self {
+        self.show_row_numbers = show;
+        self
+    }
+
+    /// Lays out the [`Table`] from right to left, for right-to-left locales.
+    ///
+    /// Column 0 is placed at the right edge, and the left and right arrow
+    /// keys as well as <kbd>Home</kbd> and <kbd>End</kbd> move the selection
+    /// visually. Cell references are unchanged.
+    pub fn right_to_left(mut self, right_to_left: bool) -> Self {
+        self.right_to_left = right_to_left;
+        self
+    }
+
+    /// Sets the number of rows at the top of the [`Table`] that stay pinned
+    /// to the top of its visible bounds while the body scrolls, e.g. for
+    /// headers.
+    pub fn freeze_rows(mut self, rows: usize) -> Self {
+        self.frozen_rows = rows;
+        self
+    }
+
+    /// Sets the number of columns at the start of the [`Table`] that stay
+    /// pinned to the start of its visible bounds while the body scrolls
+    /// horizontally, e.g. for row labels.
+    pub fn freeze_columns(mut self, columns: usize) -> Self {
+        self.frozen_columns = columns;
+        self
+    }
+
+    /// Sets the number of rows at the bottom of the [`Table`] that stay pinned
+    /// to the bottom of its visible bounds, e.g. for totals.
+    pub fn footer_rows(mut self, rows: usize) -> Self {
+        self.footer_rows = rows;
+        self
+    }
+
+    /// Sets whether the footer rows of the [`Table`] can be selected and
+    /// navigated to with the keyboard.
+    pub fn footer_selectable(mut self, selectable: bool) -> Self {
+        self.footer_selectable = selectable;
         self
     }
 
@@ -199,6 +655,29 @@ where
         self
     }
 
+    /// Sets the message that should be produced when the cell under the
+    /// cursor changes, with `None` once the cursor leaves the cells.
+    ///
+    /// It isn't produced while dragging, e.g. to resize a column or to
+    /// extend the selection.
+    pub fn on_hover(mut self, on_hover: impl Fn(Option<K>) -> Message + 'a) -> Self {
+        self.on_hover = Some(Box::new(on_hover));
+        self
+    }
+
+    /// Sets the closure that gives the background of each row of the
+    /// [`Table`], drawn beneath its cells.
+    ///
+    /// Rows for which it returns `None` are left as they are, so returning a
+    /// color for every other row stripes the table.
+    pub fn row_background(
+        mut self,
+        row_background: impl Fn(usize) -> Option<Background> + 'a,
+    ) -> Self {
+        self.row_background = Some(Box::new(row_background));
+        self
+    }
+
     /// Sets the closure to produce key bindings on key presses.
     ///
     /// See [`Binding`] for the list of available bindings.
@@ -220,1011 +699,7144 @@ where
         self
     }
 
+    /// Sets the style of each cell of the [`Table`], in place of
+    /// [`Catalog::cell_style`].
+    ///
+    /// This replaces the fill of the selected cells as well; use
+    /// [`theme::default_cell`] to keep it.
+    #[must_use]
+    pub fn cell_style(mut self, cell_style: impl Fn(&Theme, CellStatus) -> CellStyle + 'a) -> Self {
+        self.cell_style = Some(Box::new(cell_style));
+        self
+    }
+
     /// Sets the style class of the [`Table`].
     #[must_use]
     pub fn class(mut self, class: impl Into<<Theme as Catalog>::Class<'a>>) -> Self {
         self.class = class.into();
         self
     }
-}
 
-impl<'a, Data, T, K, R, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
-    for Table<'a, Data, T, K, R, Message, Theme, Renderer>
-where
-    Data: Tabular<T, K, R>,
-    T: Cell + Default,
-    K: Reference,
-    R: ReferenceRange<K>,
-    Message: Clone + 'a,
-    Theme: Catalog,
-    Renderer: renderer::Renderer,
-{
-    fn tag(&self) -> tree::Tag {
-        tree::Tag::of::<State>()
-    }
+    // The bands of cells pinned to the edges of the visible bounds, in the
+    // order they are drawn
+    fn pinned(&self, region: &Region, bounds: Rectangle, viewport: &Rectangle) -> Vec<Pinned> {
+        let rows: Vec<_> = self
+            .frozen(region, bounds, viewport)
+            .into_iter()
+            .chain(self.footer(region, bounds, viewport))
+            .collect();
+        let columns = self.frozen_columns(region, bounds, viewport);
 
-    fn state(&self) -> tree::State {
-        tree::State::new(State::from_data(self.data, self.spacing))
+        let corners: Vec<_> = columns
+            .iter()
+            .flat_map(|columns| rows.iter().map(|rows| Pinned::corner(rows, columns)))
+            .collect();
+
+        rows.into_iter().chain(columns).chain(corners).collect()
     }
 
-    fn size(&self) -> Size<Length> {
-        Size {
-            width: Length::Fill,
-            height: Length::Fill,
+    // The frozen columns, pinned to the leading edge of the visible bounds
+    fn frozen_columns(
+        &self,
+        region: &Region,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+    ) -> Option<Pinned> {
+        let column_count = self.data.column_count();
+        if self.frozen_columns == 0 || column_count == 0 {
+            return None;
         }
-    }
 
-    fn diff(&self, tree: &mut Tree) {
-        let state = tree.state.downcast_ref::<State>();
+        let last = self.frozen_columns.min(column_count) - 1;
+        let end = region.offset.x + region.cumulative_x.get(last)?;
+        let table_end = region.offset.x + region.cumulative_x.get(column_count - 1)?;
+        let visible = bounds.intersection(viewport)? - Vector::new(bounds.x, bounds.y);
 
-        // check if dimensions have changed
-        if self.data.row_count() != state.region.row_count
-            || self.data.column_count() != state.region.column_count
-        {
-            tree.state = tree::State::new(State::from_data(self.data, self.spacing));
+        // the band covers the gutters too, and never goes past the end of the table
+        let natural = region.mirror(Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: end,
+            height: bounds.height,
+        });
+        let shift = if region.rtl {
+            (visible.x + visible.width - (natural.x + natural.width))
+                .max(region.mirror_x(table_end) - natural.x)
+                .min(0.0)
+        } else {
+            (visible.x - natural.x).min(table_end - end).max(0.0)
+        };
+
+        Some(Pinned {
+            rows: 0..self.data.row_count(),
+            columns: 0..last + 1,
+            edge: None,
+            start: true,
+            bounds: natural,
+            shift: Vector::new(shift, 0.0),
+        })
+    }
+
+    // The frozen band, pinned below the top edge of the visible bounds
+    fn frozen(&self, region: &Region, bounds: Rectangle, viewport: &Rectangle) -> Option<Pinned> {
+        let row_count = self.data.row_count();
+        if self.frozen_rows == 0 || row_count == 0 {
+            return None;
         }
 
-        tree.diff_children(&self.cells.iter().map(|(_, el)| el).collect::<Vec<_>>());
+        let last = self.frozen_rows.min(row_count) - 1;
+        let (top, _) = region.row_span(0)?;
+        let (_, bottom) = region.row_span(last)?;
+        let (_, end) = region.row_span(row_count - 1)?;
+        let visible = bounds.intersection(viewport)? - Vector::new(bounds.x, bounds.y);
+
+        // never push the band past the end of the table
+        let shift = (visible.y - top).min(end - bottom).max(0.0);
+
+        Some(Pinned {
+            rows: 0..last + 1,
+            columns: 0..self.data.column_count(),
+            edge: Some(Edge::Top),
+            start: false,
+            bounds: Rectangle {
+                x: 0.0,
+                y: top,
+                width: bounds.width,
+                height: bottom - top,
+            },
+            shift: Vector::new(0.0, shift),
+        })
     }
 
-    fn children(&self) -> Vec<widget::Tree> {
-        self.cells.iter().map(|(_, el)| Tree::new(el)).collect()
+    // The footer band, pinned above the bottom edge of the visible bounds
+    fn footer(&self, region: &Region, bounds: Rectangle, viewport: &Rectangle) -> Option<Pinned> {
+        let row_count = self.data.row_count();
+        if self.footer_rows == 0 || row_count == 0 {
+            return None;
+        }
+
+        let first = row_count.saturating_sub(self.footer_rows);
+        let (top, _) = region.row_span(first)?;
+        let (_, bottom) = region.row_span(row_count - 1)?;
+        let visible = bounds.intersection(viewport)? - Vector::new(bounds.x, bounds.y);
+
+        let shift = (visible.y + visible.height - bottom)
+            .min(0.0)
+            .max(visible.y - top);
+
+        Some(Pinned {
+            rows: first..row_count,
+            columns: 0..self.data.column_count(),
+            edge: Some(Edge::Bottom),
+            start: false,
+            bounds: Rectangle {
+                x: 0.0,
+                y: top,
+                width: bounds.width,
+                height: bottom - top,
+            },
+            shift: Vector::new(0.0, shift),
+        })
     }
 
-    fn layout(
+    // The cells of a lazy table in view, with some overscan
+    fn visible_cells(
         &self,
-        tree: &mut widget::Tree,
-        renderer: &Renderer,
-        limits: &layout::Limits,
-    ) -> layout::Node {
-        let state = tree.state.downcast_mut::<State>();
+        region: &Region,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+    ) -> CellWindow {
+        let row_count = self.data.row_count();
+        let pinned_rows: Vec<_> = [
+            0..self.frozen_rows.min(row_count),
+            row_count.saturating_sub(self.footer_rows)..row_count,
+        ]
+        .into_iter()
+        .filter(|rows| !rows.is_empty())
+        .collect();
 
-        let size = limits.resolve(
-            self.width,
-            self.height,
-            Size::new(
-                state.region.total_raw_width(),
-                state.region.total_raw_height(),
-            ),
-        );
+        let pinned_columns: Vec<_> = (self.frozen_columns > 0)
+            .then(|| 0..self.frozen_columns.min(self.data.column_count()))
+            .into_iter()
+            .collect();
 
-        state.region.scale_to_bounds(size, self.spacing);
+        let Some(visible) = bounds.intersection(viewport) else {
+            return CellWindow {
+                columns: 0..0,
+                rows: 0..0,
+                pinned_rows,
+                pinned_columns,
+            };
+        };
 
-        let mut cells = Vec::with_capacity(self.cells.len());
-        let rows = (self.cells.len() + self.columns.saturating_sub(1))
-            .checked_div(self.columns)
-            .unwrap_or(0);
+        let visible = visible - Vector::new(bounds.x, bounds.y);
+        let first = region.find_cell(visible.position());
+        let last = region.find_cell(Point::new(
+            visible.x + visible.width,
+            visible.y + visible.height,
+        ));
 
-        // Create Rectangle from size for offset calculation
-        let bounds = Rectangle {
-            x: 0.0,
-            y: 0.0,
-            width: size.width,
-            height: size.height,
+        let span = |a: usize, b: usize, count: usize| {
+            a.min(b).saturating_sub(LAZY_OVERSCAN)..(a.max(b) + LAZY_OVERSCAN + 1).min(count)
         };
 
-        for col in 0..self.columns {
-            for row in 0..rows {
-                if col * rows + row >= self.cells.len() {
-                    break;
-                }
-
-                let pos = state.region.cell_position(row, col);
-                let cell_pos = Point::new(bounds.x + pos.x, bounds.y + pos.y);
+        CellWindow {
+            columns: span(first.column, last.column, self.data.column_count()),
+            rows: span(first.row, last.row, row_count),
+            pinned_rows,
+            pinned_columns,
+        }
+    }
 
-                cells.push((col * rows + row, cell_pos, state.region.cell_size(row, col)));
-            }
+    // The cells in view, along with their trees and layouts, including the
+    // pinned ones. Cells in column-major order are found by binary search,
+    // without going through all of them.
+    #[allow(clippy::type_complexity)]
+    fn children_in_view<'b>(
+        &'b self,
+        tree: &'b Tree,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        layout: Layout<'b>,
+    ) -> Box<
+        dyn Iterator<
+                Item = (
+                    &'b (K, Element<'a, Message, Theme, Renderer>),
+                    (&'b Tree, Layout<'b>),
+                ),
+            > + 'b,
+    > {
+        let state = tree.state.downcast_ref::<State>();
+        let (trees, nodes) = (&tree.children, &state.nodes);
+        if !self.ordered || nodes.len() != self.cells.len() {
+            return Box::new(self.cells.iter().zip(trees.iter().zip(layout.children())));
         }
 
-        let children = cells
-            .into_iter()
-            .map(|(idx, position, cell_size)| {
-                let cell_limits = layout::Limits::new(cell_size, cell_size);
-                let node = self.cells[idx].1.as_widget().layout(
-                    &mut tree.children[idx],
-                    renderer,
-                    &cell_limits,
-                );
-                node.move_to(position)
+        // merged cells are drawn from their anchor, wherever it is
+        let indices: std::collections::BTreeSet<usize> = self
+            .visible_cells(&state.region, bounds, viewport)
+            .cells()
+            .filter_map(|(x, y)| {
+                let anchor = self
+                    .data
+                    .span_at(x, y)
+                    .map_or((x, y), |span| span.anchor.as_tuple());
+                self.cells
+                    .binary_search_by(|(cell_ref, _)| cell_ref.as_tuple().cmp(&anchor))
+                    .ok()
             })
             .collect();
 
-        layout::Node::with_children(size, children)
+        // the children are laid out from the position of the table
+        let offset = layout.position() - Point::ORIGIN;
+        Box::new(indices.into_iter().map(move |index| {
+            let layout = Layout::with_offset(offset, &nodes[index]);
+            (&self.cells[index], (&trees[index], layout))
+        }))
     }
 
-    fn draw(
-        &self,
-        tree: &Tree,
-        renderer: &mut Renderer,
-        theme: &Theme,
-        defaults: &renderer::Style,
-        layout: Layout<'_>,
-        cursor: mouse::Cursor,
-        viewport: &Rectangle,
-    ) {
-        let state = tree.state.downcast_ref::<State>();
-        let bounds = layout.bounds();
-        let offset = Vector::new(bounds.x, bounds.y);
-        let status = if self.on_edit.is_none() {
-            Status::Disabled
-        } else if state.is_focused {
-            Status::Focused
+    // The last row that can be selected, if any: there's none in an empty
+    // table or one that is all footer
+    fn last_selectable_row(&self) -> Option<usize> {
+        let footer = if self.footer_selectable {
+            0
         } else {
-            Status::Unfocused
+            self.footer_rows
         };
+        self.data.row_count().checked_sub(footer)?.checked_sub(1)
+    }
 
-        let style = Catalog::style(theme, &self.class, status);
+    // Whether the given row can be selected
+    fn is_selectable_row(&self, row: usize) -> bool {
+        self.last_selectable_row().is_some_and(|last| row <= last)
+    }
 
-        // Draw children
-        if let Some(clipped_viewport) = bounds.intersection(viewport) {
-            for (child, (state, layout)) in self
-                .cells
-                .iter()
-                .zip(tree.children.iter().zip(layout.children()))
-                .filter(|(_, (_, layout))| layout.bounds().intersects(&clipped_viewport))
-            {
-                child.1.as_widget().draw(
-                    state,
-                    renderer,
-                    theme,
-                    defaults,
-                    layout,
-                    cursor,
-                    &clipped_viewport,
-                );
+    // Whether one of the cells, e.g. an editor, has the focus
+    fn has_focused_cell(
+        &self,
+        trees: &mut [Tree],
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> bool {
+        struct FindFocused(bool);
 
-                // FIXME: consider computing these at the layout pass instead and rendering simpler quads here.
-                if let Some(cell) = self.data.get(child.0) {
-                    if cell.has_borders() {
-                        cell.fill_border_quads(renderer, layout.bounds(), 1.0);
-                    }
+        impl<T> widget::Operation<T> for FindFocused {
+            fn container(
+                &mut self,
+                _id: Option<&widget::Id>,
+                _bounds: Rectangle,
+                operate_on_children: &mut dyn FnMut(&mut dyn widget::Operation<T>),
+            ) {
+                operate_on_children(self);
+            }
+
+            fn focusable(
+                &mut self,
+                state: &mut dyn operation::Focusable,
+                _id: Option<&widget::Id>,
+            ) {
+                self.0 |= state.is_focused();
+            }
+        }
+
+        let mut find = FindFocused(false);
+        self.cells
+            .iter()
+            .zip(trees)
+            .zip(layout.children())
+            .for_each(|((child, tree), child_layout)| {
+                child
+                    .1
+                    .as_widget()
+                    .operate(tree, child_layout, renderer, &mut find);
+            });
+        find.0
+    }
+
+    // The number of rows a page motion moves by: the rows in view, less the
+    // pinned ones
+    fn page_rows(&self, region: &Region, bounds: Rectangle, viewport: &Rectangle) -> usize {
+        let Some(visible) = bounds.intersection(viewport) else {
+            return 1;
+        };
+
+        let visible = visible - Vector::new(bounds.x, bounds.y);
+        let top = region.find_cell(visible.position()).row;
+        let bottom = region
+            .find_cell(Point::new(visible.x, visible.y + visible.height))
+            .row;
+        let pinned = (self.frozen_rows + self.footer_rows).min(self.data.row_count());
+
+        bottom.saturating_sub(top).saturating_sub(pinned).max(1)
+    }
+
+    // Wrap arrow motions past the edges of the grid around, following the
+    // wrap policy. Returns the action to perform instead of the binding if
+    // it wraps.
+    fn wrapped_motion(&self, binding: &Binding<Message>) -> Option<Action<K, R>> {
+        let Binding::MoveSelection(motion) = binding else {
+            return None;
+        };
+        if self.wrap == Wrap::Clamp {
+            return None;
+        }
+
+        let last = self.last_selectable_row()?;
+        let max_col = self.data.column_count().saturating_sub(1);
+        let selection = self.data.selection();
+        let start = selection.start();
+
+        // A selection of just a merged span moves like a single cell, leaving
+        // from the far side of the span
+        let span = self
+            .data
+            .span_at(start.x(), start.y())
+            .filter(|span| selection.normalize() == R::new(span.anchor, Some(span.end())));
+        let single = selection.end().is_none() || span.is_some();
+        let far = span.map_or(start, |span| span.end());
+
+        let motion = match motion {
+            Motion::Forward if single => Motion::Right,
+            Motion::Back if single => Motion::Left,
+            _ => *motion,
+        };
+
+        let visible = |mut rows: std::ops::RangeInclusive<usize>, down: bool| {
+            if down {
+                rows.find(|&y| !self.data.is_row_hidden(y))
+            } else {
+                rows.rfind(|&y| !self.data.is_row_hidden(y))
+            }
+        };
+        let first_row = visible(0..=last, true)?;
+        let last_row = visible(0..=last, false)?;
+        let wrap_table = self.wrap == Wrap::WrapTable;
+
+        let (x, y) = match motion {
+            Motion::Right if far.x() >= max_col => {
+                match visible(far.y().saturating_add(1)..=last, true) {
+                    Some(row) => (0, row),
+                    None if wrap_table => (0, first_row),
+                    None => return None,
+                }
+            }
+            Motion::Left if start.x() == 0 => {
+                let previous = start.y().checked_sub(1);
+                match previous.and_then(|row| visible(0..=row, false)) {
+                    Some(row) => (max_col, row),
+                    None if wrap_table => (max_col, last_row),
+                    None => return None,
+                }
+            }
+            Motion::Down if far.y() >= last_row => {
+                if far.x() < max_col {
+                    (far.x() + 1, first_row)
+                } else if wrap_table {
+                    (0, first_row)
+                } else {
+                    return None;
+                }
+            }
+            Motion::Up if start.y() <= first_row => {
+                if start.x() > 0 {
+                    (start.x() - 1, last_row)
+                } else if wrap_table {
+                    (max_col, last_row)
+                } else {
+                    return None;
                 }
             }
+            _ => return None,
+        };
+
+        let cell = self
+            .data
+            .span_at(x, y)
+            .map_or(K::new(x, y), |span| span.anchor);
+
+        Some(Action::Select(cell.as_range()))
+    }
+
+    // Keep keyboard motions out of a footer that can't be selected. Returns
+    // `None` if the binding can be applied as is, or the action to perform
+    // instead if it would have entered the footer.
+    fn motion_within_body(&self, binding: &Binding<Message>) -> Option<Option<Action<K, R>>> {
+        if self.footer_selectable || self.footer_rows == 0 {
+            return None;
         }
 
-        // Draw intermediate vertical lines using cumulative positions
-        if self.show_gridlines && self.columns.checked_sub(1).is_some() {
-            for x in state
-                .region
-                .cumulative_x
-                .iter()
-                .take(self.columns.checked_sub(1).unwrap_or(0))
+        // with only the footer, there's nowhere to move
+        let Some(last) = self.last_selectable_row() else {
+            return matches!(
+                binding,
+                Binding::MoveSelection(_) | Binding::ExpandSelection(_)
+            )
+            .then_some(None);
+        };
+        let max_col = self.data.column_count().saturating_sub(1);
+        let selection = self.data.selection();
+
+        match binding {
+            Binding::MoveSelection(Motion::Down) if selection.start().y() >= last => Some(None),
+            Binding::MoveSelection(Motion::DocumentEnd) => {
+                Some(Some(Action::Select(K::new(max_col, last).as_range())))
+            }
+            Binding::MoveSelection(Motion::PageDown(rows))
+                if selection.start().y().saturating_add(*rows) > last =>
             {
-                renderer.fill_quad(
-                    renderer::Quad {
-                        bounds: Rectangle {
-                            x: bounds.x + x - style.gridlines.width / 2.0,
-                            y: bounds.y,
-                            width: style.gridlines.width,
-                            height: bounds.height,
-                        },
-                        border: Border::default(),
-                        ..Default::default()
-                    },
-                    style.gridlines.color,
-                );
+                Some(Some(Action::Select(
+                    K::new(selection.start().x(), last).as_range(),
+                )))
             }
-
-            // Draw intermediate horizontal lines using cumulative positions
-            for y in state
-                .region
-                .cumulative_y
-                .iter()
-                .take(state.region.scaled_rows.len().checked_sub(1).unwrap_or(0))
+            Binding::ExpandSelection(Motion::Down)
+                if selection.end().unwrap_or(selection.start()).y() >= last =>
             {
-                renderer.fill_quad(
-                    renderer::Quad {
-                        bounds: Rectangle {
-                            x: bounds.x,
-                            y: bounds.y + y - style.gridlines.width / 2.0,
-                            width: bounds.width,
-                            height: style.gridlines.width,
-                        },
-                        border: Border::default(),
-                        ..Default::default()
-                    },
-                    style.gridlines.color,
-                );
+                Some(None)
+            }
+            Binding::ExpandSelection(Motion::DocumentEnd) => Some(Some(Action::Select(R::new(
+                selection.start(),
+                Some(K::new(max_col, last)),
+            )))),
+            Binding::MoveSelection(Motion::DataDown) => {
+                let start = selection.start().as_tuple();
+                let (x, y) = self.data.data_edge(start, Motion::DataDown);
+                (y > last).then(|| Some(Action::Select(K::new(x, last).as_range())))
+            }
+            Binding::ExpandSelection(Motion::DataDown) => {
+                let end = selection.end().unwrap_or(selection.start()).as_tuple();
+                let (x, y) = self.data.data_edge(end, Motion::DataDown);
+                (y > last).then(|| {
+                    Some(Action::Select(R::new(
+                        selection.start(),
+                        Some(K::new(x, last)),
+                    )))
+                })
+            }
+            Binding::ExpandSelection(Motion::PageDown(rows))
+                if selection
+                    .end()
+                    .unwrap_or(selection.start())
+                    .y()
+                    .saturating_add(*rows)
+                    > last =>
+            {
+                let end = selection.end().unwrap_or(selection.start());
+                Some(Some(Action::Select(R::new(
+                    selection.start(),
+                    Some(K::new(end.x(), last)),
+                ))))
             }
+            _ => None,
         }
+    }
 
-        // Draw outer border
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds,
-                border: style.border,
-                ..Default::default()
-            },
-            Color::TRANSPARENT,
-        );
+    // The cell at the given point in widget bounds, resolving merged cells
+    // to their anchor
+    fn cell_at(&self, region: &Region, position: Point) -> K {
+        let CellIndex { column, row } = region.find_cell(position);
 
-        let selection_bounds = state.region.selection_bounds(*self.data.selection()) + offset;
-        renderer.fill_quad(
-            renderer::Quad {
-                bounds: selection_bounds,
-                border: Border {
-                    width: style.selection.stroke_width,
-                    color: style.selection.stroke,
-                    radius: 0.0.into(),
-                },
-                ..Default::default()
-            },
-            style.selection.fill,
-        );
+        self.data
+            .span_at(column, row)
+            .map_or(K::new(column, row), |span| span.anchor)
     }
 
-    fn mouse_interaction(
-        &self,
-        tree: &Tree,
-        layout: Layout<'_>,
-        cursor: mouse::Cursor,
-        _viewport: &Rectangle,
-        _renderer: &Renderer,
-    ) -> mouse::Interaction {
-        let state = tree.state.downcast_ref::<State>();
+    // Whether the given cell is hidden under a merged span
+    fn is_covered(&self, cell: K) -> bool {
+        self.data
+            .span_at(cell.x(), cell.y())
+            .is_some_and(|span| span.covers(cell.x(), cell.y()))
+    }
 
-        if let Some(cursor_position) = cursor.position_in(layout.bounds()) {
-            // Check if we're hovering over a divider
-            if let Some(divider_hit) = state.region.find_nearest_divider(cursor_position) {
-                return match divider_hit.axis {
-                    Axis::Row => mouse::Interaction::ResizingVertically,
-                    Axis::Column => mouse::Interaction::ResizingHorizontally,
-                };
+    // The style the cells are drawn with, in the value color of the table if
+    // they inherit it
+    fn cell_defaults(&self, defaults: &renderer::Style, style: &Style) -> renderer::Style {
+        if self.inherit_text_color {
+            renderer::Style {
+                text_color: style.value,
             }
+        } else {
+            *defaults
         }
-        mouse::Interaction::default()
     }
 
-    fn on_event(
-        &mut self,
-        tree: &mut Tree,
-        event: iced::Event,
-        layout: Layout<'_>,
-        raw_cursor: mouse::Cursor,
-        renderer: &Renderer,
-        clipboard: &mut dyn Clipboard,
-        shell: &mut Shell<'_, Message>,
-        viewport: &Rectangle,
-    ) -> iced::event::Status {
-        let state = tree.state.downcast_mut::<State>();
-        let mut _cells = layout.children();
+    // The bounds of a whole cell, around the bounds of its content laid out
+    // within the cell padding and aligned within its column
+    fn cell_bounds(&self, region: &Region, cell: K, content: Rectangle) -> Rectangle {
+        let (_, size) = self.cell_area(region, cell);
 
-        let filtered = if self.on_edit.as_ref().is_some() {
-            match &event {
-                iced::Event::Mouse(mouse_event) => {
-                    if matches!(mouse_event, mouse::Event::ButtonPressed(_))
-                        && raw_cursor.position_in(layout.bounds()).is_none()
-                    {
-                        state.interaction = Interaction::None;
-                        state.unfocus();
-                        return event::Status::Ignored;
-                    }
-                    Some(event.clone())
-                }
-                iced::Event::Touch(_) | iced::Event::Keyboard(_) => Some(event.clone()),
-                iced::Event::Window(iced::window::Event::RedrawRequested(_)) => Some(event.clone()),
-                _ => None,
+        Rectangle::new(
+            content.position() - self.content_offset(cell.x(), size, content.width),
+            size,
+        )
+    }
+
+    // The position and size of a cell in the region, before scrolling
+    fn cell_area(&self, region: &Region, cell: K) -> (Point, Size) {
+        let (col, row) = cell.as_tuple();
+        match self.data.span_at(col, row) {
+            // cells past the grid, e.g. of ragged data, take no room
+            _ if col >= region.column_count || row >= region.row_count => {
+                (region.cell_position(cell.index()), Size::ZERO)
             }
-        } else {
-            eprintln!("Table is disabled. Enable it by calling `.on_action()`");
-            None
+            // cells hidden under a merged span take no room
+            Some(span) if span.covers(col, row) => (region.cell_position(cell.index()), Size::ZERO),
+            Some(span) => {
+                let bounds = region.merged_bounds(row, col, span.rows, span.columns);
+                (bounds.position(), bounds.size())
+            }
+            None => (
+                region.cell_position(cell.index()),
+                region.cell_size(cell.index()),
+            ),
+        }
+    }
+
+    // The offset of the content of a cell of the given size from the cell:
+    // the padding, clamped to cells smaller than it, and the room left of
+    // content narrower than the column by its alignment
+    fn content_offset(&self, column: usize, size: Size, content_width: f32) -> Vector {
+        let padding = self.cell_padding.fit(Size::ZERO, size);
+        let free = (size.width - padding.horizontal() - content_width).max(0.0);
+        let x = match self.cell_alignment(column) {
+            alignment::Horizontal::Left => 0.0,
+            alignment::Horizontal::Center => free / 2.0,
+            alignment::Horizontal::Right => free,
         };
 
-        if let Some(filtered) = filtered {
-            let on_edit = self.on_edit.as_ref().unwrap();
+        Vector::new(padding.left + x, padding.top)
+    }
 
-            if let Some(update) = Update::from_event::<K>(
-                &filtered,
-                state,
-                layout.bounds(),
-                raw_cursor,
-                self.key_binding.as_deref(),
-            ) {
-                match update {
-                    Update::RedrawRequested => {
-                        if self.data.internal().is_dirty() {
-                            self.data.internal().set_clean();
-                            *state = State::from_data(self.data, self.spacing);
-                            shell.invalidate_layout();
-                        }
-                    }
-                    Update::Click(click) => match click.kind() {
-                        mouse::click::Kind::Single => {
-                            state.last_click = Some(click);
-                            state.drag_click = Some(click.kind());
+    // The horizontal alignment of the cells in the given column, mirrored
+    // right to left
+    fn cell_alignment(&self, column: usize) -> alignment::Horizontal {
+        let alignment = self
+            .column_alignments
+            .get(column)
+            .copied()
+            .flatten()
+            .unwrap_or_else(|| self.data.column_type(column).alignment());
+        match alignment {
+            alignment::Horizontal::Left if self.right_to_left => alignment::Horizontal::Right,
+            alignment::Horizontal::Right if self.right_to_left => alignment::Horizontal::Left,
+            alignment => alignment,
+        }
+    }
 
-                            if let Some(divider_hit) =
-                                state.region.find_nearest_divider(click.position())
-                            {
-                                state.interaction = Interaction::ResizeDivider(divider_hit);
-                                state.focus();
-                                // ?
-                                return event::Status::Captured; // don't click through cells
-                            } else {
-                                let cell_ref = K::from(state.region.find_cell(click.position()));
-                                if !self.data.selection().contains(&cell_ref) {
-                                    state.focus();
-                                    shell.publish(on_edit(Action::Select(cell_ref.as_range())));
-                                }
-                                if self.passthrough {
-                                    for ((child, state), child_layout) in self
-                                        .cells
-                                        .iter_mut()
-                                        .zip(tree.children.iter_mut())
-                                        .zip(layout.children())
-                                    {
-                                        child.1.as_widget_mut().on_event(
-                                            state,
-                                            event.clone(),
-                                            child_layout,
-                                            raw_cursor,
-                                            renderer,
-                                            clipboard,
-                                            shell,
-                                            viewport,
-                                        );
-                                    }
-                                }
+    // The stretches of the divider right of the given column that lie inside
+    // merged cells, from top to bottom, offset by the given y
+    fn column_divider_gaps(&self, region: &Region, column: usize, y: f32) -> Vec<(f32, f32)> {
+        self.data
+            .spans()
+            .iter()
+            .filter(|span| span.anchor.x() <= column && column < span.end().x())
+            .filter_map(|span| {
+                let (top, _) = region.row_span(span.anchor.y())?;
+                let (_, bottom) = region.row_span(span.end().y())?;
+                Some((y + top, y + bottom))
+            })
+            .collect()
+    }
 
-                                // ?
-                                return event::Status::Captured; // early to avoid second child update()
-                            }
-                        }
-                        mouse::click::Kind::Double | mouse::click::Kind::Triple => {
-                            let cell_ref = K::from(state.region.find_cell(click.position()));
-                            if !self.data.selection().contains(&cell_ref) {
-                                shell.publish(on_edit(Action::Select(cell_ref.as_range())));
-                            }
-                            if let Some(on_instruction) = self.on_instruction.as_ref() {
-                                shell.publish(on_instruction(Instruction::Activate(cell_ref)));
-                            }
-                            for ((child, state), child_layout) in self
-                                .cells
-                                .iter_mut()
-                                .zip(tree.children.iter_mut())
-                                .zip(layout.children())
-                            {
-                                child.1.as_widget_mut().on_event(
-                                    state,
-                                    event.clone(),
-                                    child_layout,
-                                    raw_cursor,
-                                    renderer,
-                                    clipboard,
-                                    shell,
-                                    viewport,
-                                );
-                            }
-                            // shell.capture_event();
-                            return event::Status::Captured;
-                        }
-                    },
-                    Update::Release => match state.interaction {
-                        Interaction::ResizeDivider(hit) => {
-                            if let Some(start) = state.last_click.map(|c| c.position()) {
-                                if let Some(current) = raw_cursor.position_in(layout.bounds()) {
-                                    let raw_delta = match hit.axis {
-                                        Axis::Column => {
-                                            (current.x - start.x) / state.region.scale_factor_x
-                                        }
-                                        Axis::Row => {
-                                            (current.y - start.y) / state.region.scale_factor_y
-                                        }
-                                    };
+    // The stretches of the divider below the given row that lie inside merged
+    // cells, from left to right, offset by the given x
+    fn row_divider_gaps(&self, region: &Region, row: usize, x: f32) -> Vec<(f32, f32)> {
+        self.data
+            .spans()
+            .iter()
+            .filter(|span| span.anchor.y() <= row && row < span.end().y())
+            .filter_map(|span| {
+                let (left, _) = region.column_span(span.anchor.x())?;
+                let (_, right) = region.column_span(span.end().x())?;
+                let (left, right) = (region.mirror_x(left), region.mirror_x(right));
+                Some((x + left.min(right), x + left.max(right)))
+            })
+            .collect()
+    }
 
-                                    shell.publish(on_edit(Action::ResizeDivider(
-                                        hit.axis, hit.index, raw_delta,
-                                    )));
-                                }
-                            }
-                            state.interaction = Interaction::None;
-                            state.drag_click = None;
+    // The height of the column header strip, if shown
+    fn column_header_height(&self) -> f32 {
+        if self.show_column_headers {
+            COLUMN_HEADER_HEIGHT
+        } else {
+            0.0
+        }
+    }
 
-                            shell.invalidate_layout();
-                            shell.invalidate_widgets();
-                            // shell.capture_event();
-                            return event::Status::Captured;
-                        }
-                        Interaction::None => {
-                            state.drag_click = None;
-                        }
-                    },
-                    Update::Drag(raw_end) => {
-                        if let Some(start) = state.last_click.map(|c| c.position()) {
-                            match state.interaction {
-                                Interaction::ResizeDivider(hit) => {
-                                    // Convert pixel delta to raw delta using stored scale factor
-                                    let current = raw_end
-                                        - Vector::new(layout.position().x, layout.position().y);
-                                    let raw_delta = match hit.axis {
-                                        Axis::Column => {
-                                            (current.x - start.x) / state.region.scale_factor_x
-                                        }
-                                        Axis::Row => {
-                                            (current.y - start.y) / state.region.scale_factor_y
-                                        }
-                                    };
+    // The width of the row group gutter, one slot per outline level
+    fn group_gutter_width(&self) -> f32 {
+        let levels = self
+            .data
+            .row_groups()
+            .iter()
+            .map(|group| group.level)
+            .max()
+            .unwrap_or(0);
 
-                                    // Update the raw sizes
-                                    match hit.axis {
-                                        Axis::Column => {
-                                            if hit.index < state.region.raw_columns.len() {
-                                                state.region.raw_columns[hit.index] =
-                                                    (hit.original_size + raw_delta).max(0.0);
-                                            }
-                                        }
-                                        Axis::Row => {
-                                            if hit.index < state.region.raw_rows.len() {
-                                                state.region.raw_rows[hit.index] =
-                                                    (hit.original_size + raw_delta).max(0.0);
-                                            }
-                                        }
-                                    }
+        GROUP_GUTTER_WIDTH * levels as f32
+    }
 
-                                    // Rescale everything based on the new raw sizes
-                                    state
-                                        .region
-                                        .scale_to_bounds(layout.bounds().size(), self.spacing);
+    // The fill handle at the end corner of the given selection, in the same
+    // coordinates as the selection bounds
+    fn fill_handle(&self, region: &Region, selection: R) -> Rectangle {
+        let selection = region.selection_bounds(selection);
+        let x = if region.rtl {
+            selection.x
+        } else {
+            selection.x + selection.width
+        };
 
-                                    shell.invalidate_layout();
-                                    shell.invalidate_widgets();
-                                    // shell.capture_event();
-                                    return event::Status::Captured;
-                                }
-                                Interaction::None => {
-                                    // Only create a new selection if we've actually dragged to a different position
-                                    if let Some(end) = raw_cursor.position_in(layout.bounds()) {
-                                        if end != start {
-                                            let range_start = state.region.find_cell(start);
-                                            let range_end = state.region.find_cell(end);
-                                            let range = <R as ReferenceRange<K>>::new(
-                                                K::new(range_start.0, range_start.1),
-                                                Some(K::new(range_end.0, range_end.1)),
-                                            )
-                                            .normalize();
+        Rectangle {
+            x: x - FILL_HANDLE_SIZE / 2.0,
+            y: selection.y + selection.height - FILL_HANDLE_SIZE / 2.0,
+            width: FILL_HANDLE_SIZE,
+            height: FILL_HANDLE_SIZE,
+        }
+    }
 
-                                            if range != *self.data.selection() {
-                                                shell.publish(on_edit(Action::Select(range)));
-                                                shell.invalidate_layout();
-                                            }
-                                        }
-                                        // shell.capture_event();
-                                        return event::Status::Captured;
-                                    }
-                                }
-                            }
-                        }
+    // A selection requested by a `select` operation, clamped to the last row
+    // and column of the table
+    fn requested_selection(&self, (start, end): RawRange) -> Option<R> {
+        let (columns, rows) = (self.data.column_count(), self.data.row_count());
+        if columns == 0 || rows == 0 {
+            return None;
+        }
+
+        let clamp = |(x, y): (usize, usize)| K::new(x.min(columns - 1), y.min(rows - 1));
+        Some(R::new(clamp(start), end.map(clamp)))
+    }
+
+    // The selection to draw along with its active cell. A selection requested
+    // by a `select` operation is drawn until the next event publishes it, so
+    // that it shows as soon as the table is redrawn
+    fn shown_selection(&self, state: &State) -> (R, K) {
+        let requested = state
+            .select
+            .filter(|_| self.on_edit.is_some())
+            .and_then(|range| self.requested_selection(range));
+        match requested {
+            Some(selection) => {
+                let selection = self.data.snap_to_spans(selection);
+                (selection, selection.start())
+            }
+            None => (*self.data.selection(), self.data.active_cell()),
+        }
+    }
+
+    // The range that dragging the fill handle to the given cell fills: the
+    // selection extended to the cell along the axis it was dragged furthest
+    // out of it, or shrunk to the cell if it lies within the selection
+    fn fill_target(&self, (column, row): (usize, usize)) -> R {
+        let selection = self.data.selection().normalize();
+        let (start, end) = (
+            selection.start(),
+            selection.end().unwrap_or(selection.start()),
+        );
+        let (first_col, first_row, last_col, last_row) = (start.x(), start.y(), end.x(), end.y());
+
+        let out_x = if column > last_col {
+            column - last_col
+        } else {
+            first_col.saturating_sub(column)
+        };
+        let out_y = if row > last_row {
+            row - last_row
+        } else {
+            first_row.saturating_sub(row)
+        };
+
+        let (first_col, first_row, last_col, last_row) = if out_x == 0 && out_y == 0 {
+            if last_row - row >= last_col - column {
+                (first_col, first_row, last_col, row)
+            } else {
+                (first_col, first_row, column, last_row)
+            }
+        } else if out_y >= out_x {
+            (first_col, first_row.min(row), last_col, last_row.max(row))
+        } else {
+            (
+                first_col.min(column),
+                first_row,
+                last_col.max(column),
+                last_row,
+            )
+        };
+
+        R::new(
+            K::new(first_col, first_row),
+            Some(K::new(last_col, last_row)),
+        )
+    }
+
+    // Draw the fill handle of the selection and, while it is dragged, the
+    // outline of the range it fills, within the given clip bounds if any
+    fn draw_fill(
+        &self,
+        renderer: &mut Renderer,
+        state: &State,
+        origin: Vector,
+        clip: Option<Rectangle>,
+        style: &Style,
+    ) {
+        let region = &state.region;
+        let clipped =
+            |bounds: Rectangle| clip.map_or(Some(bounds), |clip| bounds.intersection(&clip));
+
+        if let Interaction::FillDrag { current } = state.interaction {
+            let target = region.selection_bounds(self.fill_target(current)) + origin;
+            if let Some(target) = clipped(target) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: target,
+                        border: Border {
+                            width: style.selection.stroke_width,
+                            color: style.selection.stroke,
+                            radius: 0.0.into(),
+                        },
+                        ..Default::default()
+                    },
+                    Color::TRANSPARENT,
+                );
+            }
+        }
+
+        let (selection, _) = self.shown_selection(state);
+        if let Some(handle) = clipped(self.fill_handle(region, selection) + origin) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: handle,
+                    ..Default::default()
+                },
+                style.selection.handle,
+            );
+        }
+    }
+
+    // Draw the outline of the selection, within the given clip bounds if any.
+    // The selected cells are filled along with the cells themselves, by their
+    // `CellStyle`. The active cell is outlined on its own, so a selection of a
+    // single cell is drawn as its active cell alone.
+    fn draw_selection(
+        &self,
+        renderer: &mut Renderer,
+        state: &State,
+        origin: Vector,
+        clip: Option<Rectangle>,
+        style: &Style,
+    ) {
+        let clipped =
+            |bounds: Rectangle| clip.map_or(Some(bounds), |clip| bounds.intersection(&clip));
+        let mut quad = |bounds: Rectangle, border: Option<(f32, Color)>, fill: Color| {
+            let Some(bounds) = clipped(bounds) else {
+                return;
+            };
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    border: border.map_or(Border::default(), |(width, color)| Border {
+                        width: snap_length(width, self.scale_factor),
+                        color,
+                        radius: 0.0.into(),
+                    }),
+                    ..Default::default()
+                },
+                fill,
+            );
+        };
+
+        let region = &state.region;
+        let (shown, active_cell) = self.shown_selection(state);
+        let selection = region.selection_bounds(shown) + origin;
+        let selection = snap_to_pixels(selection, self.scale_factor);
+        let active_range = self.data.snap_to_spans(active_cell.as_range());
+        let active = region.selection_bounds(active_range) + origin;
+        let active = snap_to_pixels(active, self.scale_factor);
+
+        if shown.end().is_some() {
+            quad(
+                selection,
+                Some((style.selection.stroke_width, style.selection.stroke)),
+                Color::TRANSPARENT,
+            );
+        }
+
+        quad(
+            active,
+            Some((
+                style.selection.active.stroke_width,
+                style.selection.active.stroke,
+            )),
+            style.selection.active.fill,
+        );
+    }
+
+    // Draw the expand/collapse markers of the row groups in the gutter
+    fn draw_row_groups(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        bounds: Rectangle,
+        style: &Style,
+    ) {
+        const MARKER_SIZE: f32 = 8.0;
+
+        for group in self.data.row_groups() {
+            if self.data.is_row_hidden(group.header()) {
+                continue;
+            }
+
+            let Some((top, bottom)) = region.row_span(group.header()) else {
+                continue;
+            };
+
+            let center = Point::new(
+                bounds.x
+                    + region.mirror_x(
+                        (group.level.max(1) - 1) as f32 * GROUP_GUTTER_WIDTH
+                            + GROUP_GUTTER_WIDTH / 2.0,
+                    ),
+                bounds.y + (top + bottom) / 2.0,
+            );
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: center.x - MARKER_SIZE / 2.0,
+                        y: center.y - MARKER_SIZE / 2.0,
+                        width: MARKER_SIZE,
+                        height: MARKER_SIZE,
+                    },
+                    border: Border {
+                        width: 1.0,
+                        color: style.outline,
+                        radius: 0.0.into(),
+                    },
+                    ..Default::default()
+                },
+                if group.collapsed {
+                    style.outline
+                } else {
+                    Color::TRANSPARENT
+                },
+            );
+
+            // Draw a bracket along the rows of an expanded group
+            let last_row = group.rows.end.min(self.data.row_count()).saturating_sub(1);
+            if let (false, Some((_, end))) = (group.collapsed, region.row_span(last_row)) {
+                let end = bounds.y + end;
+                let start = center.y + MARKER_SIZE / 2.0;
+
+                if end > start {
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: Rectangle {
+                                x: center.x - 0.5,
+                                y: start,
+                                width: 1.0,
+                                height: end - start,
+                            },
+                            ..Default::default()
+                        },
+                        style.outline,
+                    );
+                    let tick_width = GROUP_GUTTER_WIDTH / 2.0;
+                    renderer.fill_quad(
+                        renderer::Quad {
+                            bounds: Rectangle {
+                                x: if region.rtl {
+                                    center.x + 0.5 - tick_width
+                                } else {
+                                    center.x - 0.5
+                                },
+                                y: end - 1.0,
+                                width: tick_width,
+                                height: 1.0,
+                            },
+                            ..Default::default()
+                        },
+                        style.outline,
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl<Data, T, K, R, Message, Theme, Renderer> Table<'_, Data, T, K, R, Message, Theme, Renderer>
+where
+    Data: Tabular<T, K, R>,
+    T: Cell + Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+    Message: Clone,
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    // The status of the table with the cursor, for styling
+    fn status(&self, state: &State, cursor: mouse::Cursor, bounds: Rectangle) -> Status {
+        if self.on_edit.is_none() {
+            Status::Disabled
+        } else if cursor.position_in(bounds).is_some() {
+            Status::Hovered {
+                focused: state.is_focused,
+            }
+        } else if state.is_focused {
+            Status::Focused
+        } else {
+            Status::Unfocused
+        }
+    }
+
+    // The size of the content within a widget of the given size: the natural
+    // size of the grid and its gutters if it scrolls internally
+    fn content_size(&self, region: &Region, size: Size) -> Size {
+        if self.scrollable {
+            Size::new(
+                region.offset.x + region.total_raw_width(),
+                region.offset.y + region.total_raw_height(),
+            )
+        } else {
+            size
+        }
+    }
+
+    // How far the content can scroll within a widget of the given size
+    fn max_scroll(&self, region: &Region, size: Size) -> Vector {
+        let content = self.content_size(region, size);
+        let (horizontal, vertical) = (content.width > size.width, content.height > size.height);
+
+        // the content scrolls past the scrollbars covering its far edges
+        let horizontal = horizontal || vertical && content.width + SCROLLBAR_WIDTH > size.width;
+        let vertical = vertical || horizontal && content.height + SCROLLBAR_WIDTH > size.height;
+        let extra = |scrollbar: bool| if scrollbar { SCROLLBAR_WIDTH } else { 0.0 };
+
+        Vector::new(
+            (content.width + extra(vertical) - size.width).max(0.0),
+            (content.height + extra(horizontal) - size.height).max(0.0),
+        )
+    }
+
+    // The offset of the visible part of the content from its top left
+    // corner. Right-to-left layouts scroll from the right.
+    fn scroll_offset(&self, state: &State, size: Size) -> Vector {
+        if !self.scrollable {
+            return Vector::ZERO;
+        }
+
+        let max = self.max_scroll(&state.region, size);
+        let x = if state.region.rtl {
+            max.x - state.scroll.x
+        } else {
+            state.scroll.x
+        };
+        Vector::new(x.clamp(0.0, max.x), state.scroll.y.clamp(0.0, max.y))
+    }
+
+    // The cell under the given position relative to the content bounds, if
+    // it's over the body rather than a header, gutter or scrollbar
+    fn hovered_cell(
+        &self,
+        state: &State,
+        widget: Rectangle,
+        bounds: Rectangle,
+        pinned: &[Pinned],
+        position: Point,
+    ) -> Option<K> {
+        let local = position + (bounds.position() - widget.position());
+        let position = Pinned::unpin(pinned, position);
+
+        if self.scrollbar_at(state, widget.size(), local).is_some()
+            || state.region.mirror_x(position.x) < state.region.offset.x
+            || position.y < state.region.offset.y
+        {
+            return None;
+        }
+
+        Some(self.cell_at(&state.region, position))
+    }
+
+    // Scroll the content to the given offset of its visible part from its
+    // top left corner, within its limits. Returns whether the offset changed.
+    fn scroll_to_offset(&self, state: &mut State, size: Size, offset: Vector) -> bool {
+        let max = self.max_scroll(&state.region, size);
+        let (x, y) = (offset.x.clamp(0.0, max.x), offset.y.clamp(0.0, max.y));
+        let scroll = Vector::new(if state.region.rtl { max.x - x } else { x }, y);
+
+        let changed = scroll != state.scroll;
+        state.scroll = scroll;
+        changed
+    }
+
+    // Scroll the content just enough to show the given rectangle relative to
+    // it, or its leading corner if it doesn't fit. The frozen rows and columns
+    // cover the leading edges of the visible part of the content. Returns
+    // whether the offset changed.
+    fn scroll_into_view(&self, state: &mut State, size: Size, target: Rectangle) -> bool {
+        let region = &state.region;
+        let frozen_x = match self.frozen_columns.min(region.column_count) {
+            0 => None,
+            _ if region.rtl => None,
+            columns => region
+                .column_span(columns - 1)
+                .map(|(_, right)| (right, right)),
+        };
+        let frozen_y = match self.frozen_rows.min(region.row_count) {
+            0 => None,
+            rows => region
+                .row_span(rows - 1)
+                .map(|(_, bottom)| (bottom, bottom - region.offset.y)),
+        };
+
+        // the offset along an axis showing the target, given where the frozen
+        // lines end and how much they cover
+        let along =
+            |offset: f32, start: f32, length: f32, visible: f32, frozen: Option<(f32, f32)>| {
+                let (end, cover) = frozen.unwrap_or_default();
+                if start < end {
+                    offset
+                } else if start < offset + cover {
+                    start - cover
+                } else if start + length > offset + visible {
+                    (start + length - visible).min(start - cover)
+                } else {
+                    offset
+                }
+            };
+
+        let offset = self.scroll_offset(state, size);
+        let offset = Vector::new(
+            along(offset.x, target.x, target.width, size.width, frozen_x),
+            along(offset.y, target.y, target.height, size.height, frozen_y),
+        );
+        self.scroll_to_offset(state, size, offset)
+    }
+
+    // The bounds of the whole content, scrolled within the given bounds of
+    // the widget if it scrolls internally
+    fn content_bounds(&self, state: &State, bounds: Rectangle) -> Rectangle {
+        if !self.scrollable {
+            return bounds;
+        }
+
+        Rectangle::new(
+            bounds.position() - self.scroll_offset(state, bounds.size()),
+            self.content_size(&state.region, bounds.size()),
+        )
+    }
+
+    // The part of the viewport the content shows through
+    fn content_viewport(&self, bounds: Rectangle, viewport: &Rectangle) -> Rectangle {
+        if !self.scrollable {
+            return *viewport;
+        }
+
+        bounds
+            .intersection(viewport)
+            .unwrap_or(Rectangle::new(bounds.position(), Size::ZERO))
+    }
+
+    // The scrollbars of a table that scrolls internally, in a widget of the
+    // given size
+    fn scrollbars(&self, state: &State, size: Size) -> Vec<Scrollbar> {
+        if !self.scrollable {
+            return Vec::new();
+        }
+
+        let max = self.max_scroll(&state.region, size);
+        let offset = self.scroll_offset(state, size);
+        let (horizontal, vertical) = (max.x > 0.0, max.y > 0.0);
+        let mut scrollbars = Vec::new();
+
+        // the thumb is as long relative to the track as the visible part is
+        // relative to the content
+        let thumb = |track: f32, visible: f32, max: f32, offset: f32| {
+            let length = (track * visible / (visible + max))
+                .max(SCROLLBAR_MIN_THUMB)
+                .min(track);
+            ((track - length) * offset / max, length)
+        };
+
+        if horizontal {
+            let track = Rectangle {
+                x: 0.0,
+                y: size.height - SCROLLBAR_WIDTH,
+                width: size.width - if vertical { SCROLLBAR_WIDTH } else { 0.0 },
+                height: SCROLLBAR_WIDTH,
+            };
+            let (x, width) = thumb(track.width, size.width, max.x, offset.x);
+            scrollbars.push(Scrollbar {
+                axis: Axis::Column,
+                track,
+                thumb: Rectangle { x, width, ..track },
+            });
+        }
+        if vertical {
+            let track = Rectangle {
+                x: size.width - SCROLLBAR_WIDTH,
+                y: 0.0,
+                width: SCROLLBAR_WIDTH,
+                height: size.height - if horizontal { SCROLLBAR_WIDTH } else { 0.0 },
+            };
+            let (y, height) = thumb(track.height, size.height, max.y, offset.y);
+            scrollbars.push(Scrollbar {
+                axis: Axis::Row,
+                track,
+                thumb: Rectangle { y, height, ..track },
+            });
+        }
+
+        scrollbars
+    }
+
+    // The scrollbar under the given point relative to the widget, if any
+    fn scrollbar_at(&self, state: &State, size: Size, point: Point) -> Option<Scrollbar> {
+        self.scrollbars(state, size)
+            .into_iter()
+            .find(|scrollbar| scrollbar.track.contains(point))
+    }
+
+    // Scroll the content so the thumb of the scrollbar starts at the given
+    // position along its track. Returns whether the offset changed.
+    fn scroll_thumb_to(
+        &self,
+        state: &mut State,
+        size: Size,
+        scrollbar: &Scrollbar,
+        start: f32,
+    ) -> bool {
+        let max = self.max_scroll(&state.region, size);
+        let offset = self.scroll_offset(state, size);
+        let room = scrollbar.length(scrollbar.track) - scrollbar.length(scrollbar.thumb);
+        let fraction = if room > 0.0 {
+            ((start - scrollbar.start(scrollbar.track)) / room).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let offset = match scrollbar.axis {
+            Axis::Column => Vector::new(fraction * max.x, offset.y),
+            Axis::Row => Vector::new(offset.x, fraction * max.y),
+        };
+        self.scroll_to_offset(state, size, offset)
+    }
+
+    // Build the cells in view again if the table is lazy and they changed
+    fn update_window(
+        &self,
+        state: &State,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        shell: &mut Shell<'_, Message>,
+    ) {
+        if self.lazy {
+            let window = self.visible_cells(&state.region, bounds, viewport);
+            if self.data.internal().set_window(window) {
+                shell.invalidate_widgets();
+            }
+        }
+    }
+
+    // Fill the backgrounds given by the row background hook for the rows
+    // within the area, across its width. The rows of bands pinned to an edge
+    // are left to those bands.
+    fn draw_row_backgrounds(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        bounds: Rectangle,
+        area: Rectangle,
+        pinned: &[Pinned],
+    ) {
+        let Some(row_background) = &self.row_background else {
+            return;
+        };
+        let offset = Vector::new(bounds.x, bounds.y);
+        let Some(area) = area.intersection(&(region.grid(bounds.size()) + offset)) else {
+            return;
+        };
+
+        let first = region.find_cell(area.position() - offset).row;
+        let last = region
+            .find_cell(Point::new(area.x, area.y + area.height) - offset)
+            .row;
+
+        for row in (first..=last).filter(|&row| {
+            !self.data.is_row_hidden(row)
+                && !pinned
+                    .iter()
+                    .any(|band| band.edge.is_some() && band.rows.contains(&row))
+        }) {
+            let (Some((top, bottom)), Some(background)) =
+                (region.row_span(row), row_background(row))
+            else {
+                continue;
+            };
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: area.x,
+                        y: offset.y + top + region.spacing.height / 2.0,
+                        width: area.width,
+                        height: (bottom - top - region.spacing.height).max(0.0),
+                    },
+                    ..Default::default()
+                },
+                background,
+            );
+        }
+    }
+
+    // Fill the bounds of a cell with its own background, if it has one
+    fn draw_cell_background(&self, renderer: &mut Renderer, cell: K, bounds: Rectangle) {
+        if let Some(background) = self.data.get(cell).and_then(|cell| cell.background()) {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    ..Default::default()
+                },
+                background,
+            );
+        }
+    }
+
+    // Fill a cell with the background of its style, given its state and the
+    // selection shown
+    #[allow(clippy::too_many_arguments)]
+    fn draw_cell_style(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        cell: K,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+        status: Status,
+        (selection, active): (R, K),
+    ) {
+        let status = CellStatus {
+            selected: selection.normalize().contains(&cell),
+            active: active == cell,
+            hovered: cursor.is_over(bounds),
+            row: cell.y(),
+            column: cell.x(),
+            status,
+        };
+        let style = match &self.cell_style {
+            Some(cell_style) => cell_style(theme, status),
+            None => theme.cell_style(&self.class, status),
+        };
+
+        if let Some(background) = style.background {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    ..Default::default()
+                },
+                background,
+            );
+        }
+    }
+
+    // Draw the content of the table, scrolled within its bounds if it
+    // scrolls internally
+    #[allow(clippy::too_many_arguments)]
+    fn draw_content(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        defaults: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = self.content_bounds(state, layout.bounds());
+        let offset = Vector::new(bounds.x, bounds.y);
+
+        let status = self.status(state, cursor, layout.bounds());
+        let selection = self.shown_selection(state);
+        let style = Catalog::style(theme, &self.class, status);
+        let pinned = self.pinned(&state.region, bounds, viewport);
+        let defaults = &self.cell_defaults(defaults, &style);
+
+        // Draw children
+        if let Some(clipped_viewport) = bounds.intersection(viewport) {
+            self.draw_row_backgrounds(renderer, &state.region, bounds, clipped_viewport, &pinned);
+
+            let region = &state.region;
+            for (child, (state, layout)) in self
+                .children_in_view(tree, bounds, viewport, layout)
+                .filter(|((cell_ref, _), (_, layout))| {
+                    self.cell_bounds(region, *cell_ref, layout.bounds())
+                        .intersects(&clipped_viewport)
+                        && !self.data.is_row_hidden(cell_ref.y())
+                        && !self.is_covered(*cell_ref)
+                        && !pinned
+                            .iter()
+                            .any(|band| band.holds(cell_ref.x(), cell_ref.y()))
+                })
+            {
+                let cell_bounds = self.cell_bounds(region, child.0, layout.bounds());
+                let cursor = Pinned::cursor(&pinned, child.0.as_tuple(), cursor, bounds);
+                self.draw_cell_background(renderer, child.0, cell_bounds);
+                self.draw_cell_style(
+                    renderer,
+                    theme,
+                    child.0,
+                    cell_bounds,
+                    cursor,
+                    status,
+                    selection,
+                );
+                child.1.as_widget().draw(
+                    state,
+                    renderer,
+                    theme,
+                    defaults,
+                    layout,
+                    cursor,
+                    &clipped_viewport,
+                );
+
+                // FIXME: consider computing these at the layout pass instead and rendering simpler quads here.
+                if let Some(cell) = self.data.get(child.0) {
+                    if cell.has_borders() {
+                        cell.fill_border_quads(renderer, cell_bounds, self.scale_factor);
                     }
-                    Update::Binding(binding) => {
-                        fn apply_binding<Data, T, K, R, Message, Theme>(
-                            binding: Binding<Message>,
-                            _bounds: Rectangle,
-                            data: &Data,
-                            state: &mut State,
-                            on_edit: &dyn Fn(Action<K, R>) -> Message,
-                            on_instruction: Option<&dyn Fn(Instruction<K>) -> Message>,
-                            _clipboard: &mut dyn clipboard::Clipboard,
-                            shell: &mut Shell<'_, Message>,
-                        ) -> bool
-                        where
-                            Data: Tabular<T, K, R>,
-                            T: Default,
-                            K: Reference,
-                            R: ReferenceRange<K>,
-                            Message: Clone,
-                        {
-                            let mut action = |action| shell.publish(on_edit(action));
-                            match binding {
-                                Binding::Focus => {
-                                    state.interaction = Interaction::None;
-                                    state.focus();
-                                    shell.invalidate_layout();
-                                }
-                                Binding::ClickedOutside => {
-                                    state.interaction = Interaction::None;
-                                    state.unfocus();
-                                    shell.invalidate_layout();
-                                    return true;
-                                }
-                                Binding::Unfocus => {
-                                    state.interaction = Interaction::None;
-                                    state.unfocus();
-                                    shell.invalidate_layout();
-                                }
-                                Binding::Cut => {
-                                    if state.is_focused() {
-                                        if let Some(on_instruction) = on_instruction.as_ref() {
-                                            shell.publish(on_instruction(Instruction::Cut));
-                                        }
-                                    }
-                                }
-                                Binding::Copy => {
-                                    if state.is_focused() {
-                                        if let Some(on_instruction) = on_instruction.as_ref() {
-                                            shell.publish(on_instruction(Instruction::Copy));
-                                        }
-                                    }
-                                }
-                                Binding::Paste => {
-                                    if state.is_focused() {
-                                        if let Some(on_instruction) = on_instruction.as_ref() {
-                                            shell.publish(on_instruction(Instruction::Paste));
-                                        }
-                                    }
-                                }
-                                Binding::Custom(message) => {
-                                    shell.publish(message);
-                                }
-                                Binding::Delete => {
-                                    if state.is_focused() {
-                                        action(Edit::Delete.into());
-                                    }
-                                }
-                                Binding::StartEdit => {
-                                    if let Some(on_instruction) = on_instruction.as_ref() {
-                                        shell.publish(on_instruction(Instruction::Activate(
-                                            data.selection().start(),
-                                        )));
-                                    }
-                                }
-                                Binding::Enter => {
-                                    if state.is_focused() {
-                                        state.interaction = Interaction::None;
-                                        state.focus();
-                                        if let Some(on_instruction) = on_instruction.as_ref() {
-                                            shell.publish(on_instruction(Instruction::Activate(
-                                                data.selection().start(),
-                                            )));
-                                        }
-                                        shell.invalidate_layout();
-                                        shell.invalidate_widgets();
-                                    }
-                                }
-                                Binding::MoveSelection(motion) => {
-                                    if state.is_focused() {
-                                        action(Action::MoveSelection(motion))
-                                    }
-                                }
-                                Binding::ExpandSelection(motion) => {
-                                    if state.is_focused() {
-                                        action(Action::ExpandSelection(motion))
-                                    }
-                                }
-                                Binding::SelectAll => {
-                                    if state.is_focused() {
-                                        action(Action::SelectAll)
-                                    }
-                                }
-                            }
-                            false
+                }
+            }
+        }
+
+        // The area covered by the grid of cells, excluding gutters
+        let grid = state.region.grid(bounds.size()) + offset;
+
+        // Draw intermediate vertical lines using cumulative positions
+        if self.show_column_lines && self.columns.checked_sub(1).is_some() {
+            for (column, x) in state.region.column_dividers().enumerate().filter(|(_, x)| {
+                (viewport.x..=viewport.x + viewport.width).contains(&(bounds.x + x))
+            }) {
+                fill_gridline(
+                    renderer,
+                    Axis::Column,
+                    Rectangle {
+                        x: bounds.x + x - style.column_lines.width / 2.0,
+                        y: grid.y,
+                        width: style.column_lines.width,
+                        height: grid.height,
+                    },
+                    &self.column_divider_gaps(&state.region, column, bounds.y),
+                    style.column_lines.color,
+                    style.line_pattern,
+                    viewport,
+                    self.scale_factor,
+                );
+            }
+        }
+
+        // Draw intermediate horizontal lines using cumulative positions
+        if self.show_row_lines {
+            let dividers = &state.region.cumulative_y;
+            let dividers = &dividers[..dividers
+                .len()
+                .min(state.region.scaled_rows.len().saturating_sub(1))];
+            // only the dividers in view are looked at
+            let first = dividers.partition_point(|y| grid.y + y < viewport.y);
+            let last = dividers.partition_point(|y| grid.y + y <= viewport.y + viewport.height);
+            for (row, y) in dividers.iter().enumerate().take(last).skip(first) {
+                fill_gridline(
+                    renderer,
+                    Axis::Row,
+                    Rectangle {
+                        x: grid.x,
+                        y: grid.y + y - style.row_lines.width / 2.0,
+                        width: grid.width,
+                        height: style.row_lines.width,
+                    },
+                    &self.row_divider_gaps(&state.region, row, bounds.x),
+                    style.row_lines.color,
+                    style.line_pattern,
+                    viewport,
+                    self.scale_factor,
+                );
+            }
+        }
+
+        self.draw_row_groups(renderer, &state.region, bounds, &style);
+
+        if self.show_row_numbers {
+            self.draw_row_numbers(renderer, &state.region, bounds, viewport, &style);
+        }
+        if self.show_column_headers {
+            self.draw_column_headers(renderer, &state.region, bounds, viewport, &style);
+        }
+
+        // Draw outer border
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: snap_to_pixels(layout.bounds(), self.scale_factor),
+                border: Border {
+                    width: snap_length(style.border.width, self.scale_factor),
+                    ..style.border
+                },
+                ..Default::default()
+            },
+            Color::TRANSPARENT,
+        );
+
+        // the selection is kept within the widget and the viewport, so its
+        // stroke doesn't bleed over the widgets around the table
+        if let Some(visible) = layout.bounds().intersection(viewport) {
+            self.draw_selection(renderer, state, offset, Some(visible), &style);
+            self.draw_fill(renderer, state, offset, Some(visible), &style);
+        }
+
+        for band in &pinned {
+            self.draw_pinned(
+                band, &pinned, tree, renderer, theme, defaults, layout, cursor, viewport, &style,
+            );
+        }
+
+        let drop = match state.interaction {
+            Interaction::ReorderColumn { from, current_x } => Some((
+                Axis::Column,
+                from,
+                Self::drop_column(&state.region, current_x),
+            )),
+            Interaction::ReorderRow { from, current_y } => {
+                Some((Axis::Row, from, self.drop_row(&state.region, current_y)))
+            }
+            _ => None,
+        };
+        if let (true, Interaction::ResizeDivider(hit), Some(delta)) =
+            (self.resize_preview, &state.interaction, state.resize_delta)
+        {
+            self.draw_resize_preview(
+                renderer,
+                &state.region,
+                &pinned,
+                bounds,
+                viewport,
+                &style,
+                *hit,
+                delta,
+            );
+        }
+        if let Some((axis, from, to)) = drop {
+            self.draw_drop(
+                renderer,
+                &state.region,
+                &pinned,
+                bounds,
+                viewport,
+                &style,
+                axis,
+                from,
+                to,
+            );
+        }
+    }
+
+    // Draw a line where the divider being dragged would be dropped, moved by
+    // the raw delta from the size of its column or row when it was grabbed
+    #[allow(clippy::too_many_arguments)]
+    fn draw_resize_preview(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        pinned: &[Pinned],
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        style: &Style,
+        hit: DividerHit,
+        delta: f32,
+    ) {
+        let Some(clip) = bounds.intersection(viewport) else {
+            return;
+        };
+        let width = style.selection.stroke_width.max(2.0);
+        let moved = self.limit_size(hit.axis, hit.original_size + delta) - hit.original_size;
+
+        let line = match hit.axis {
+            Axis::Column => {
+                let Some((_, right)) = region.column_span(hit.index) else {
+                    return;
+                };
+                let shift = pinned
+                    .iter()
+                    .find(|band| {
+                        band.start && band.edge.is_none() && band.columns.contains(&hit.index)
+                    })
+                    .map_or(0.0, |band| band.shift.x);
+                let x = right - region.spacing.width / 2.0 + moved * region.scale_factor_x;
+
+                Rectangle {
+                    x: region.mirror_x(x) + shift - width / 2.0,
+                    y: 0.0,
+                    width,
+                    height: bounds.height,
+                }
+            }
+            Axis::Row => {
+                let Some((_, bottom)) = region.row_span(hit.index) else {
+                    return;
+                };
+                let shift = pinned
+                    .iter()
+                    .find(|band| !band.start && band.rows.contains(&hit.index))
+                    .map_or(0.0, |band| band.shift.y);
+                let y = bottom - region.spacing.height / 2.0 + moved * region.scale_factor_y;
+
+                Rectangle {
+                    x: 0.0,
+                    y: y + shift - width / 2.0,
+                    width: bounds.width,
+                    height: width,
+                }
+            }
+        };
+
+        renderer.with_layer(clip, |renderer| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: line + Vector::new(bounds.x, bounds.y),
+                    ..Default::default()
+                },
+                style.resize_preview,
+            );
+        });
+    }
+
+    // Draw a pinned band of rows on its own layer, over the body
+    #[allow(clippy::too_many_arguments)]
+    fn draw_pinned(
+        &self,
+        pinned: &Pinned,
+        bands: &[Pinned],
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        defaults: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        style: &Style,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = self.content_bounds(state, layout.bounds());
+        let translation = pinned.shift;
+        let band = pinned.bounds + Vector::new(bounds.x, bounds.y);
+
+        let Some(clip) = (band + translation).intersection(&bounds.expand(style.border.width))
+        else {
+            return;
+        };
+        let children = self.children_in_view(tree, bounds, viewport, layout);
+        let status = self.status(state, cursor, layout.bounds());
+        let selection = self.shown_selection(state);
+
+        renderer.with_layer(clip, |renderer| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: band + translation,
+                    ..Default::default()
+                },
+                style.pinned.background,
+            );
+
+            renderer.with_translation(translation, |renderer| {
+                let viewport = *viewport - translation;
+
+                // frozen columns carry the stripes of the rows scrolled past them
+                if pinned.edge.is_none() {
+                    if let Some(area) = band.intersection(&viewport) {
+                        self.draw_row_backgrounds(renderer, &state.region, bounds, area, bands);
+                    }
+                }
+
+                let region = &state.region;
+                for ((cell_ref, child), (state, layout)) in children.filter(|((cell_ref, _), _)| {
+                    pinned.holds(cell_ref.x(), cell_ref.y()) && !self.is_covered(*cell_ref)
+                }) {
+                    let cell_bounds = self.cell_bounds(region, *cell_ref, layout.bounds());
+                    let cursor = Pinned::cursor(bands, cell_ref.as_tuple(), cursor, bounds);
+                    self.draw_cell_background(renderer, *cell_ref, cell_bounds);
+                    self.draw_cell_style(
+                        renderer,
+                        theme,
+                        *cell_ref,
+                        cell_bounds,
+                        cursor,
+                        status,
+                        selection,
+                    );
+                    child
+                        .as_widget()
+                        .draw(state, renderer, theme, defaults, layout, cursor, &viewport);
+
+                    if let Some(cell) = self.data.get(*cell_ref) {
+                        if cell.has_borders() {
+                            cell.fill_border_quads(renderer, cell_bounds, self.scale_factor);
+                        }
+                    }
+                }
+
+                if self.show_row_lines {
+                    let grid = state.region.grid(bounds.size()) + Vector::new(bounds.x, bounds.y);
+
+                    for row in pinned.rows.clone() {
+                        let Some((_, y)) = state.region.row_span(row) else {
+                            continue;
+                        };
+                        if row + 1 >= self.data.row_count() {
+                            break;
+                        }
+                        if !(viewport.y..=viewport.y + viewport.height).contains(&(bounds.y + y)) {
+                            continue;
                         }
+                        fill_gridline(
+                            renderer,
+                            Axis::Row,
+                            Rectangle {
+                                x: grid.x,
+                                y: bounds.y + y - style.row_lines.width / 2.0,
+                                width: grid.width,
+                                height: style.row_lines.width,
+                            },
+                            &self.row_divider_gaps(&state.region, row, bounds.x),
+                            style.row_lines.color,
+                            style.line_pattern,
+                            &viewport,
+                            self.scale_factor,
+                        );
+                    }
+                }
+
+                if self.show_column_lines {
+                    for (column, x) in
+                        state.region.column_dividers().enumerate().filter(|(_, x)| {
+                            (viewport.x..=viewport.x + viewport.width).contains(&(bounds.x + x))
+                        })
+                    {
+                        fill_gridline(
+                            renderer,
+                            Axis::Column,
+                            Rectangle {
+                                x: bounds.x + x - style.column_lines.width / 2.0,
+                                y: band.y,
+                                width: style.column_lines.width,
+                                height: band.height,
+                            },
+                            &self.column_divider_gaps(&state.region, column, bounds.y),
+                            style.column_lines.color,
+                            style.line_pattern,
+                            &viewport,
+                            self.scale_factor,
+                        );
+                    }
+                }
+
+                self.draw_selection(
+                    renderer,
+                    state,
+                    Vector::new(bounds.x, bounds.y),
+                    Some(band),
+                    style,
+                );
+                self.draw_fill(
+                    renderer,
+                    state,
+                    Vector::new(bounds.x, bounds.y),
+                    Some(band),
+                    style,
+                );
+
+                if self.show_row_numbers {
+                    self.draw_row_numbers(renderer, &state.region, bounds, &viewport, style);
+                }
+                if pinned.start {
+                    self.draw_row_groups(renderer, &state.region, bounds, style);
+                    if self.show_column_headers {
+                        self.draw_column_headers(renderer, &state.region, bounds, &viewport, style);
+                    }
+                }
+            });
+
+            // Separate the band from the body
+            let band = band + translation;
+            let edge = pinned.edge.map(|edge| match edge {
+                Edge::Top => band.y + band.height,
+                Edge::Bottom => band.y,
+            });
+            if let Some(y) = edge {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: band.x,
+                            y: y - style.pinned.separator.width / 2.0,
+                            width: band.width,
+                            height: style.pinned.separator.width,
+                        },
+                        ..Default::default()
+                    },
+                    style.pinned.separator.color,
+                );
+            }
+            if pinned.start {
+                let x = if state.region.rtl {
+                    band.x
+                } else {
+                    band.x + band.width
+                };
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: Rectangle {
+                            x: x - style.pinned.separator.width / 2.0,
+                            y: band.y,
+                            width: style.pinned.separator.width,
+                            height: band.height,
+                        },
+                        ..Default::default()
+                    },
+                    style.pinned.separator.color,
+                );
+            }
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: snap_to_pixels(layout.bounds(), self.scale_factor),
+                    border: Border {
+                        width: snap_length(style.border.width, self.scale_factor),
+                        ..style.border
+                    },
+                    ..Default::default()
+                },
+                Color::TRANSPARENT,
+            );
+        });
+    }
+
+    // The width of the row number gutter, fitting the widest row number
+    fn row_number_width(&self, renderer: &Renderer) -> f32 {
+        use text::Paragraph;
+
+        if !self.show_row_numbers {
+            return 0.0;
+        }
+
+        let widest = self.data.row_count().max(1).to_string();
+        let paragraph = Renderer::Paragraph::with_text(text::Text {
+            content: widest.as_str(),
+            bounds: Size::INFINITY,
+            size: HEADER_TEXT_SIZE.into(),
+            line_height: text::LineHeight::default(),
+            font: renderer.default_font(),
+            horizontal_alignment: iced::alignment::Horizontal::Left,
+            vertical_alignment: iced::alignment::Vertical::Top,
+            shaping: text::Shaping::Basic,
+            wrapping: text::Wrapping::None,
+        });
+
+        paragraph.min_width().ceil() + 2.0 * ROW_NUMBER_PADDING
+    }
+
+    // Draw the gutter of row numbers left of the grid
+    fn draw_row_numbers(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        style: &Style,
+    ) {
+        let origin = Vector::new(bounds.x, bounds.y);
+        let left = self.group_gutter_width();
+        let gutter = region.mirror(Rectangle {
+            x: left,
+            y: 0.0,
+            width: region.row_number_width,
+            height: bounds.height,
+        }) + origin;
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: gutter,
+                ..Default::default()
+            },
+            style.header.background,
+        );
+
+        // only the rows from the first to the last in view are numbered
+        let from = viewport.y - bounds.y - region.offset.y;
+        let first = region.cumulative_y.partition_point(|&y| y < from);
+        let last = region
+            .cumulative_y
+            .partition_point(|&y| y < from + viewport.height);
+        for row in first..(last + 1).min(region.row_count) {
+            let Some((top, bottom)) = region.row_span(row) else {
+                continue;
+            };
+            if self.data.is_row_hidden(row) {
+                continue;
+            }
+
+            let label = Rectangle {
+                x: gutter.x,
+                y: bounds.y + top,
+                width: gutter.width,
+                height: bottom - top,
+            };
+            if !label.intersects(viewport) {
+                continue;
+            }
+
+            renderer.fill_text(
+                text::Text {
+                    content: (row + 1).to_string(),
+                    bounds: label.size(),
+                    size: HEADER_TEXT_SIZE.into(),
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: iced::alignment::Horizontal::Center,
+                    vertical_alignment: iced::alignment::Vertical::Center,
+                    shaping: text::Shaping::Basic,
+                    wrapping: text::Wrapping::None,
+                },
+                label.center(),
+                style.header.text,
+                label,
+            );
+
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: gutter.x,
+                        y: label.y + label.height - style.header.separator.width / 2.0,
+                        width: gutter.width,
+                        height: style.header.separator.width,
+                    },
+                    ..Default::default()
+                },
+                style.header.separator.color,
+            );
+        }
+
+        let edge = if region.rtl {
+            gutter.x
+        } else {
+            gutter.x + gutter.width
+        };
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: edge - style.header.separator.width / 2.0,
+                    y: gutter.y,
+                    width: style.header.separator.width,
+                    height: gutter.height,
+                },
+                ..Default::default()
+            },
+            style.header.separator.color,
+        );
+    }
+
+    // Keep a size a divider is resized to within the limits of its axis. The
+    // smallest size wins over the largest if they cross.
+    fn limit_size(&self, axis: Axis, size: f32) -> f32 {
+        let (min, max) = match axis {
+            Axis::Column => self.column_width_limits,
+            Axis::Row => self.row_height_limits,
+        };
+        size.min(max).max(min).max(0.0)
+    }
+
+    // The column or row of the active cell resized by the given number of
+    // steps, with its new size and the delta to it, unless it can't change
+    fn resize_by_steps(&self, axis: Axis, steps: f32) -> Option<(usize, f32, f32)> {
+        let active = self.data.active_cell();
+        let (index, sizes) = match axis {
+            Axis::Column => (active.x(), self.data.column_sizes()),
+            Axis::Row => (active.y(), self.data.row_sizes()),
+        };
+        let current = *sizes.get(index)?;
+        let size = self.limit_size(axis, current + steps * self.resize_step);
+
+        (size != current).then_some((index, size, size - current))
+    }
+
+    // The columns or rows resized along with the one of a divider: all the
+    // selected ones if it's one of them and they are selected whole, or just
+    // itself otherwise. Hidden rows are left alone.
+    fn resized_together(&self, hit: DividerHit) -> Vec<usize> {
+        let selection = self.data.selection().normalize();
+        let start = selection.start();
+        let end = selection.end().unwrap_or(start);
+
+        let (lines, across, last) = match hit.axis {
+            Axis::Column => (
+                start.x()..=end.x(),
+                start.y()..=end.y(),
+                self.last_selectable_row(),
+            ),
+            Axis::Row => (
+                start.y()..=end.y(),
+                start.x()..=end.x(),
+                self.data.column_count().checked_sub(1),
+            ),
+        };
+
+        let whole = last.is_some_and(|last| *across.start() == 0 && *across.end() >= last);
+        let lines = if whole && lines.contains(&hit.index) {
+            lines
+        } else {
+            hit.index..=hit.index
+        };
+
+        lines
+            .filter(|&index| hit.axis == Axis::Column || !self.data.is_row_hidden(index))
+            .collect()
+    }
+
+    // Resize a column or row of the region, along with those resized together
+    // with it, by the raw delta from its size when it was grabbed, and scale
+    // everything to the new sizes
+    fn resize_region(&self, state: &mut State, hit: DividerHit, raw_delta: f32, size: Size) {
+        let new_size = self.limit_size(hit.axis, hit.original_size + raw_delta);
+        for index in self.resized_together(hit) {
+            state.region.fix_size(hit.axis, index, new_size);
+        }
+
+        state
+            .region
+            .scale_to_bounds(self.content_size(&state.region, size), self.spacing);
+    }
+
+    // Size the rows to the natural height of their tallest cell at the width
+    // of its column, measuring the cells again only once the content or the
+    // column widths changed
+    fn fit_rows(&self, state: &mut State, children: &mut [Tree], renderer: &Renderer) {
+        let region = &state.region;
+        let measured = state
+            .measured_rows
+            .as_ref()
+            .is_some_and(|(widths, _)| *widths == region.scaled_columns);
+
+        if !measured {
+            let mut heights = vec![None::<f32>; region.row_count];
+            for ((cell_ref, cell), tree) in self.cells.iter().zip(children.iter_mut()) {
+                let (col, row) = (cell_ref.x(), cell_ref.y());
+                if col >= region.column_count
+                    || region.hidden_rows.get(row).copied().unwrap_or(true)
+                    || self.data.span_at(col, row).is_some()
+                {
+                    continue;
+                }
+
+                let width = (region.cell_size(cell_ref.index()).width
+                    - self.cell_padding.horizontal())
+                .max(0.0);
+                let limits = layout::Limits::new(Size::ZERO, Size::new(width, f32::INFINITY));
+                let height = cell
+                    .as_widget()
+                    .layout(tree, renderer, &limits)
+                    .size()
+                    .height
+                    + self.cell_padding.vertical();
+                if height.is_finite() {
+                    heights[row] = Some(heights[row].map_or(height, |tallest| tallest.max(height)));
+                }
+            }
+            state.measured_rows = Some((region.scaled_columns.clone(), heights));
+        }
+
+        if let Some((_, heights)) = &state.measured_rows {
+            state.region.fit_rows(heights);
+        }
+    }
+
+    // The column a column being dragged at the unpinned x would be dropped at
+    fn drop_column(region: &Region, x: f32) -> usize {
+        region.find_cell(Point::new(x, region.offset.y)).column
+    }
+
+    // The row a row being dragged at the unpinned y would be dropped at,
+    // never past the rows that can be selected
+    fn drop_row(&self, region: &Region, y: f32) -> usize {
+        let row = region.find_cell(Point::new(0.0, y)).row;
+        self.last_selectable_row().map_or(row, |last| row.min(last))
+    }
+
+    // Draw a column or row being dragged to a new position: a ghost over it
+    // and a line at the edge it would be dropped at
+    #[allow(clippy::too_many_arguments)]
+    fn draw_drop(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        pinned: &[Pinned],
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        style: &Style,
+        axis: Axis,
+        from: usize,
+        to: usize,
+    ) {
+        let Some(clip) = bounds.intersection(viewport) else {
+            return;
+        };
+        let origin = Vector::new(bounds.x, bounds.y);
+        let width = style.selection.stroke_width.max(2.0);
+
+        // the extent of a column or row across the widget, drawn where it's
+        // pinned if it's frozen
+        let extent = |index: usize| -> Option<Rectangle> {
+            match axis {
+                Axis::Column => {
+                    let (left, right) = region.column_span(index)?;
+                    let shift = pinned
+                        .iter()
+                        .find(|band| {
+                            band.start && band.edge.is_none() && band.columns.contains(&index)
+                        })
+                        .map_or(0.0, |band| band.shift.x);
+                    Some(
+                        region.mirror(Rectangle {
+                            x: left,
+                            y: 0.0,
+                            width: right - left,
+                            height: bounds.height,
+                        }) + Vector::new(shift, 0.0),
+                    )
+                }
+                Axis::Row => {
+                    let (top, bottom) = region.row_span(index)?;
+                    let shift = pinned
+                        .iter()
+                        .find(|band| !band.start && band.rows.contains(&index))
+                        .map_or(0.0, |band| band.shift.y);
+                    Some(Rectangle {
+                        x: 0.0,
+                        y: top + shift,
+                        width: bounds.width,
+                        height: bottom - top,
+                    })
+                }
+            }
+        };
+
+        renderer.with_layer(clip, |renderer| {
+            if let Some(ghost) = extent(from) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: ghost + origin,
+                        ..Default::default()
+                    },
+                    style.selection.fill,
+                );
+            }
+
+            let Some(target) = extent(to).filter(|_| to != from) else {
+                return;
+            };
+            // the moved line lands past the target in the direction it moves
+            let line = match (axis, to > from) {
+                (Axis::Column, after) => {
+                    let right = after != region.rtl;
+                    Rectangle {
+                        x: if right {
+                            target.x + target.width
+                        } else {
+                            target.x
+                        } - width / 2.0,
+                        width,
+                        ..target
+                    }
+                }
+                (Axis::Row, after) => Rectangle {
+                    y: if after {
+                        target.y + target.height
+                    } else {
+                        target.y
+                    } - width / 2.0,
+                    height: width,
+                    ..target
+                },
+            };
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: line + origin,
+                    ..Default::default()
+                },
+                style.selection.stroke,
+            );
+        });
+    }
+
+    // Draw the strip of column headers above the grid
+    fn draw_column_headers(
+        &self,
+        renderer: &mut Renderer,
+        region: &Region,
+        bounds: Rectangle,
+        viewport: &Rectangle,
+        style: &Style,
+    ) {
+        let origin = Vector::new(bounds.x, bounds.y);
+        let strip = region.mirror(Rectangle {
+            x: region.offset.x,
+            y: 0.0,
+            width: (bounds.width - region.offset.x).max(0.0),
+            height: region.offset.y,
+        }) + origin;
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: strip,
+                ..Default::default()
+            },
+            style.header.background,
+        );
+
+        for column in 0..region.column_count {
+            let Some((left, right)) = region.column_span(column) else {
+                continue;
+            };
+            let label = region.mirror(Rectangle {
+                x: left,
+                y: 0.0,
+                width: right - left,
+                height: region.offset.y,
+            }) + origin;
+            if !label.intersects(viewport) {
+                continue;
+            }
+
+            renderer.fill_text(
+                text::Text {
+                    content: self.data.column_name(column).into_owned(),
+                    bounds: label.size(),
+                    size: HEADER_TEXT_SIZE.into(),
+                    line_height: text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: iced::alignment::Horizontal::Center,
+                    vertical_alignment: iced::alignment::Vertical::Center,
+                    shaping: text::Shaping::Basic,
+                    wrapping: text::Wrapping::None,
+                },
+                label.center(),
+                style.header.text,
+                label,
+            );
+        }
+
+        for x in region.column_dividers() {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        x: bounds.x + x - style.header.separator.width / 2.0,
+                        y: strip.y,
+                        width: style.header.separator.width,
+                        height: strip.height,
+                    },
+                    ..Default::default()
+                },
+                style.header.separator.color,
+            );
+        }
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: Rectangle {
+                    x: strip.x,
+                    y: strip.y + strip.height - style.header.separator.width / 2.0,
+                    width: strip.width,
+                    height: style.header.separator.width,
+                },
+                ..Default::default()
+            },
+            style.header.separator.color,
+        );
+    }
+}
+
+impl<'a, Data, T, K, R, Message, Theme, Renderer> Widget<Message, Theme, Renderer>
+    for Table<'a, Data, T, K, R, Message, Theme, Renderer>
+where
+    Data: Tabular<T, K, R>,
+    T: Cell + Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+    Message: Clone + 'a,
+    Theme: Catalog,
+    Renderer: text::Renderer,
+{
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::from_data(self.data, self.spacing))
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let state = tree.state.downcast_mut::<State>();
+
+        // check if dimensions have changed, e.g. after inserting or removing rows,
+        // or if the sizes are out of date once a resize went through the data
+        let resizing = matches!(state.interaction, Interaction::ResizeDivider(_));
+        if self.data.row_count() != state.region.row_count
+            || self.data.column_count() != state.region.column_count
+            || (!resizing
+                && !state
+                    .region
+                    .has_sizes(self.data.column_sizes(), self.data.row_sizes()))
+        {
+            state.rebuild(self.data, self.spacing);
+        }
+
+        tree.diff_children(&self.cells.iter().map(|(_, el)| el).collect::<Vec<_>>());
+    }
+
+    fn children(&self) -> Vec<widget::Tree> {
+        self.cells.iter().map(|(_, el)| Tree::new(el)).collect()
+    }
+
+    fn layout(
+        &self,
+        tree: &mut widget::Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let state = tree.state.downcast_mut::<State>();
+        state.region.row_number_width = self.row_number_width(renderer);
+        state.region.offset = Vector::new(
+            self.group_gutter_width() + state.region.row_number_width,
+            self.column_header_height(),
+        );
+        state.region.rtl = self.right_to_left;
+        state.region.scale_mode = self.scale_mode;
+
+        // columns that fit their content are measured with unbounded widths
+        if state.region.column_sizing.contains(&Sizing::Auto) {
+            let mut natural = vec![None::<f32>; state.region.column_count];
+            for ((cell_ref, cell), tree) in self.cells.iter().zip(tree.children.iter_mut()) {
+                let (col, row) = (cell_ref.x(), cell_ref.y());
+                if !state.region.is_auto(col)
+                    || state.region.hidden_rows.get(row).copied().unwrap_or(true)
+                    || self.data.span_at(col, row).is_some()
+                {
+                    continue;
+                }
+
+                let Some(&raw) = state.region.raw_rows.get(row) else {
+                    continue;
+                };
+                let height = (raw - self.spacing.height - self.cell_padding.vertical()).max(0.0);
+                let limits = layout::Limits::new(Size::ZERO, Size::new(f32::INFINITY, height));
+                let width = cell
+                    .as_widget()
+                    .layout(tree, renderer, &limits)
+                    .size()
+                    .width
+                    + self.cell_padding.horizontal();
+                if width.is_finite() {
+                    natural[col] = Some(natural[col].map_or(width, |widest| widest.max(width)));
+                }
+            }
+            state.region.fit_columns(&natural);
+        }
+
+        let natural = |region: &Region| {
+            Size::new(
+                region.offset.x + region.total_raw_width(),
+                region.offset.y + region.total_raw_height(),
+            )
+        };
+        let mut size = limits.resolve(self.width, self.height, natural(&state.region));
+
+        let content = self.content_size(&state.region, size);
+        state.region.scale_to_bounds(content, self.spacing);
+
+        // the rows are measured once the widths of their cells are known, and
+        // the grid is laid out again at their heights. A row being resized
+        // keeps the size it's dragged to.
+        let resizing_row = matches!(
+            state.interaction,
+            Interaction::ResizeDivider(DividerHit {
+                axis: Axis::Row,
+                ..
+            })
+        );
+        if self.auto_row_height && !resizing_row {
+            self.fit_rows(state, &mut tree.children, renderer);
+            size = limits.resolve(self.width, self.height, natural(&state.region));
+            let content = self.content_size(&state.region, size);
+            state.region.scale_to_bounds(content, self.spacing);
+        }
+
+        if self.scrollable {
+            let offset = self.scroll_offset(state, size);
+            self.scroll_to_offset(state, size, offset);
+
+            // follow the active cell as the selection moves
+            let selection = self.data.selection();
+            let cell = self.data.active_cell();
+            // the active cell may cycle within the selection
+            let active = if cell != selection.start() {
+                cell
+            } else {
+                selection.end().unwrap_or(selection.start())
+            };
+            if state.active != Some(active.as_tuple()) {
+                state.active = Some(active.as_tuple());
+                let target = state
+                    .region
+                    .selection_bounds(self.data.snap_to_spans(active.as_range()));
+                self.scroll_into_view(state, size, target);
+            }
+        }
+        let scroll = self.scroll_offset(state, size);
+
+        let children: Vec<_> = self
+            .cells
+            .iter()
+            .zip(tree.children.iter_mut())
+            .map(|((cell_ref, cell), tree)| {
+                let (position, cell_size) = self.cell_area(&state.region, *cell_ref);
+
+                // the padding is clamped to cells smaller than it
+                let padding = self.cell_padding.fit(Size::ZERO, cell_size);
+                let content_size = Size::new(
+                    cell_size.width - padding.horizontal(),
+                    cell_size.height - padding.vertical(),
+                );
+
+                // cells narrower than their column are aligned within it
+                let cell_limits =
+                    layout::Limits::new(Size::new(0.0, content_size.height), content_size);
+                let node = cell.as_widget().layout(tree, renderer, &cell_limits);
+                let offset = self.content_offset(cell_ref.x(), cell_size, node.size().width);
+                node.move_to(position + offset - scroll)
+            })
+            .collect();
+
+        // kept to find the layouts of the cells in view without walking
+        // through all of them
+        let state = tree.state.downcast_mut::<State>();
+        state.nodes = children.clone();
+
+        layout::Node::with_children(size, children)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        defaults: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let style = Catalog::style(theme, &self.class, self.status(state, cursor, bounds));
+
+        // a transparent background, as in the default style, costs no quad
+        let transparent = matches!(style.background, Background::Color(color) if color.a == 0.0);
+        if !transparent {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: snap_to_pixels(bounds, self.scale_factor),
+                    border: Border {
+                        radius: style.border.radius,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                style.background,
+            );
+        }
+
+        if !self.scrollable {
+            return self.draw_content(tree, renderer, theme, defaults, layout, cursor, viewport);
+        }
+
+        let Some(visible) = bounds.intersection(viewport) else {
+            return;
+        };
+        // the content scrolled out of view can't be hovered
+        let cursor = if cursor.is_over(visible) {
+            cursor
+        } else {
+            mouse::Cursor::Unavailable
+        };
+
+        renderer.with_layer(visible, |renderer| {
+            self.draw_content(tree, renderer, theme, defaults, layout, cursor, &visible);
+        });
+
+        let origin = Vector::new(bounds.x, bounds.y);
+        renderer.with_layer(visible, |renderer| {
+            for scrollbar in self.scrollbars(state, bounds.size()) {
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scrollbar.track + origin,
+                        ..Default::default()
+                    },
+                    style.scrollbar.background,
+                );
+                renderer.fill_quad(
+                    renderer::Quad {
+                        bounds: scrollbar.thumb + origin,
+                        border: Border {
+                            radius: (SCROLLBAR_WIDTH / 2.0).into(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    style.scrollbar.thumb,
+                );
+            }
+        });
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, Renderer>> {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = self.content_bounds(state, layout.bounds());
+        // the overlays of pinned cells follow them as they're drawn, taking
+        // the whole table to be in view
+        let pinned = self.pinned(&state.region, bounds, &layout.bounds());
+
+        let overlays: Vec<_> = self
+            .cells
+            .iter_mut()
+            .zip(&mut tree.children)
+            .zip(layout.children())
+            .filter_map(|(((cell_ref, child), tree), layout)| {
+                let shift = pinned
+                    .iter()
+                    .rev()
+                    .find(|band| band.holds(cell_ref.x(), cell_ref.y()))
+                    .map_or(Vector::ZERO, |band| band.shift);
+                child
+                    .as_widget_mut()
+                    .overlay(tree, layout, renderer, translation + shift)
+            })
+            .collect();
+
+        (!overlays.is_empty()).then(|| overlay::Group::with_children(overlays).overlay())
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        let widget = layout.bounds();
+        let bounds = self.content_bounds(state, widget);
+        let viewport = &self.content_viewport(widget, viewport);
+        let pinned = self.pinned(&state.region, bounds, viewport);
+
+        if let Interaction::ReorderColumn { .. } | Interaction::ReorderRow { .. } =
+            state.interaction
+        {
+            return mouse::Interaction::Grabbing;
+        }
+        if self.scrollable
+            && (!cursor.is_over(*viewport)
+                || cursor.position_in(widget).is_some_and(|position| {
+                    self.scrollbar_at(state, widget.size(), position).is_some()
+                }))
+        {
+            return mouse::Interaction::default();
+        }
+
+        if let Some(cursor_position) = cursor.position_in(bounds) {
+            let cursor_position = Pinned::unpin(&pinned, cursor_position);
+
+            if matches!(state.interaction, Interaction::FillDrag { .. })
+                || self
+                    .fill_handle(&state.region, *self.data.selection())
+                    .expand(FILL_HANDLE_SIZE / 2.0)
+                    .contains(cursor_position)
+            {
+                return mouse::Interaction::Crosshair;
+            }
+
+            // Check if we're hovering over a divider
+            if let Some(divider_hit) = state.region.find_nearest_divider(cursor_position) {
+                return match divider_hit.axis {
+                    Axis::Row => mouse::Interaction::ResizingVertically,
+                    Axis::Column => mouse::Interaction::ResizingHorizontally,
+                };
+            }
+        }
+
+        // otherwise the cell under the cursor decides, e.g. a text input
+        self.cells
+            .iter()
+            .zip(&tree.children)
+            .zip(layout.children())
+            .filter(|(((cell_ref, _), _), _)| {
+                !self.data.is_row_hidden(cell_ref.y()) && !self.is_covered(*cell_ref)
+            })
+            .filter_map(|(((cell_ref, child), tree), layout)| {
+                let cursor = Pinned::cursor(&pinned, cell_ref.as_tuple(), cursor, bounds);
+                cursor.is_over(layout.bounds()).then(|| {
+                    child
+                        .as_widget()
+                        .mouse_interaction(tree, layout, cursor, viewport, renderer)
+                })
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: iced::Event,
+        layout: Layout<'_>,
+        raw_cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> iced::event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let widget = layout.bounds();
+        let bounds = self.content_bounds(state, widget);
+        let viewport = &self.content_viewport(widget, viewport);
+        let pinned = self.pinned(&state.region, bounds, viewport);
+
+        self.update_window(state, bounds, viewport, shell);
+
+        let requested = state
+            .select
+            .take()
+            .and_then(|range| self.requested_selection(range));
+        if let (Some(selection), Some(on_edit)) = (requested, self.on_edit.as_ref()) {
+            shell.publish(on_edit(Action::Select(selection)));
+        }
+
+        let filtered = if self.on_edit.as_ref().is_some() {
+            match &event {
+                iced::Event::Mouse(mouse_event) => {
+                    if matches!(mouse_event, mouse::Event::ButtonPressed(_))
+                        && raw_cursor.position_in(widget).is_none()
+                    {
+                        state.interaction = Interaction::None;
+                        state.unfocus();
+                        return event::Status::Ignored;
+                    }
+                    if matches!(mouse_event, mouse::Event::WheelScrolled { .. })
+                        && raw_cursor.position_in(widget).is_none()
+                    {
+                        return event::Status::Ignored;
+                    }
+                    Some(event.clone())
+                }
+                iced::Event::Touch(_) | iced::Event::Keyboard(_) => Some(event.clone()),
+                iced::Event::Window(iced::window::Event::RedrawRequested(_)) => Some(event.clone()),
+                _ => None,
+            }
+        } else {
+            eprintln!("Table is disabled. Enable it by calling `.on_action()`");
+            None
+        };
+
+        // The motion to publish once the cells submitted their edits
+        let mut submitted = None;
+
+        if let Some(filtered) = filtered {
+            #[cfg(feature = "debug-trace")]
+            let id = &self.id;
+            let on_edit_fn = self.on_edit.as_deref().unwrap();
+            let on_edit = &|action: Action<K, R>| {
+                trace!(id = ?id, ?action, "publish action");
+                on_edit_fn(action)
+            };
+            let on_instruction = self.on_instruction.as_deref().map(|on_instruction| {
+                move |instruction: Instruction<K>| {
+                    trace!(id = ?id, ?instruction, "publish instruction");
+                    on_instruction(instruction)
+                }
+            });
+
+            if let Some(update) = Update::from_event(
+                &filtered,
+                state,
+                bounds,
+                raw_cursor,
+                self.key_binding.as_deref(),
+            ) {
+                trace!(id = ?self.id, ?update, "update");
+
+                match update {
+                    Update::RedrawRequested => {
+                        if let Some(on_instruction) = on_instruction.as_ref() {
+                            for (cell, reason) in self.data.take_rejections() {
+                                shell.publish(on_instruction(Instruction::EditRejected {
+                                    cell,
+                                    reason,
+                                }));
+                            }
+                        }
+
+                        let dirty = self.data.internal().take_dirty();
+                        if dirty.sizes {
+                            state.rebuild(self.data, self.spacing);
+                            // a divider still being dragged keeps its size
+                            if let (false, Interaction::ResizeDivider(hit), Some(delta)) =
+                                (self.resize_preview, &state.interaction, state.resize_delta)
+                            {
+                                self.resize_region(state, *hit, delta, widget.size());
+                            }
+                            shell.invalidate_layout();
+                        } else if dirty.data {
+                            // the cells may measure differently
+                            state.measured_rows = None;
+                            shell.invalidate_layout();
+                        } else if dirty.selection {
+                            shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                        }
+                    }
+                    Update::Click(click) => match click.kind() {
+                        mouse::click::Kind::Single => {
+                            state.last_click = Some(click);
+                            state.drag_click = Some(click.kind());
+
+                            let local = click.position() + (bounds.position() - widget.position());
+                            if let Some(scrollbar) = self.scrollbar_at(state, widget.size(), local)
+                            {
+                                let along = scrollbar.along(local);
+                                let grab = if scrollbar.thumb.contains(local) {
+                                    along - scrollbar.start(scrollbar.thumb)
+                                } else {
+                                    // jump to the click, holding the thumb by its middle
+                                    let grab = scrollbar.length(scrollbar.thumb) / 2.0;
+                                    self.scroll_thumb_to(
+                                        state,
+                                        widget.size(),
+                                        &scrollbar,
+                                        along - grab,
+                                    );
+                                    shell.invalidate_layout();
+                                    grab
+                                };
+                                state.interaction = Interaction::Scroll {
+                                    axis: scrollbar.axis,
+                                    grab,
+                                };
+                                return event::Status::Captured;
+                            }
+
+                            let position = Pinned::unpin(&pinned, click.position());
+
+                            let divider = state.region.find_nearest_divider(position);
+                            trace!(id = ?self.id, ?position, ?divider, "divider hit");
+
+                            if self
+                                .fill_handle(&state.region, *self.data.selection())
+                                .expand(FILL_HANDLE_SIZE / 2.0)
+                                .contains(position)
+                            {
+                                let selection = self.data.selection().normalize();
+                                let end = selection.end().unwrap_or(selection.start());
+                                state.focus();
+                                state.interaction = Interaction::FillDrag {
+                                    current: end.as_tuple(),
+                                };
+                                return event::Status::Captured;
+                            } else if state.region.mirror_x(position.x) < self.group_gutter_width()
+                            {
+                                let row = state.region.find_cell(position).row;
+                                state.drag_click = None;
+
+                                if let Some(on_instruction) = on_instruction.as_ref() {
+                                    if self
+                                        .data
+                                        .row_groups()
+                                        .iter()
+                                        .any(|group| group.header() == row)
+                                    {
+                                        shell
+                                            .publish(on_instruction(Instruction::ToggleGroup(row)));
+                                    }
+                                }
+                                return event::Status::Captured;
+                            } else if let Some(divider_hit) = divider {
+                                // a filling row or column becomes fixed as soon as it is grabbed
+                                match divider_hit.axis {
+                                    Axis::Column => state.region.fix_column(divider_hit.index),
+                                    Axis::Row => state.region.fix_row(divider_hit.index),
+                                }
+                                state.region.scale_to_bounds(
+                                    self.content_size(&state.region, widget.size()),
+                                    self.spacing,
+                                );
+                                state.interaction = Interaction::ResizeDivider(divider_hit);
+                                state.focus();
+                                // ?
+                                return event::Status::Captured; // don't click through cells
+                            } else if state.region.mirror_x(position.x) < state.region.offset.x {
+                                let row = state.region.find_cell(position).row;
+
+                                if position.y >= state.region.offset.y
+                                    && self.is_selectable_row(row)
+                                {
+                                    state.focus();
+                                    state.interaction = Interaction::ReorderRow {
+                                        from: row,
+                                        current_y: position.y,
+                                    };
+                                    shell.publish(on_edit(Action::Select(R::new(
+                                        K::new(0, row),
+                                        Some(K::new(
+                                            self.data.column_count().saturating_sub(1),
+                                            row,
+                                        )),
+                                    ))));
+                                } else {
+                                    state.drag_click = None;
+                                }
+                                return event::Status::Captured;
+                            } else if position.y < state.region.offset.y {
+                                let column = state.region.find_cell(position).column;
+                                state.focus();
+                                // the header is clicked on release, unless it's dragged away
+                                state.interaction = Interaction::ReorderColumn {
+                                    from: column,
+                                    current_x: position.x,
+                                };
+
+                                if let Some(last_row) = self.last_selectable_row() {
+                                    shell.publish(on_edit(Action::Select(R::new(
+                                        K::new(column, 0),
+                                        Some(K::new(column, last_row)),
+                                    ))));
+                                }
+                                return event::Status::Captured;
+                            } else {
+                                let cell_ref = self.cell_at(&state.region, position);
+                                trace!(id = ?self.id, ?position, cell = %cell_ref, "find cell");
+
+                                if !self.is_selectable_row(cell_ref.y()) {
+                                    // let the children of the footer handle the click
+                                    state.drag_click = None;
+                                } else if state.modifiers.alt() {
+                                    state.focus();
+                                    state.interaction = Interaction::ReorderColumn {
+                                        from: cell_ref.x(),
+                                        current_x: position.x,
+                                    };
+                                    return event::Status::Captured;
+                                } else if state.modifiers.shift() && state.is_focused() {
+                                    // extend the selection from its anchor to the clicked cell
+                                    let range = self.data.snap_to_spans(R::new(
+                                        self.data.selection().start(),
+                                        Some(cell_ref),
+                                    ));
+                                    if range != *self.data.selection() {
+                                        shell.publish(on_edit(Action::Select(range)));
+                                    }
+                                    return event::Status::Captured;
+                                } else {
+                                    if !self.data.selection().contains(&cell_ref) {
+                                        state.focus();
+                                        shell.publish(on_edit(Action::Select(cell_ref.as_range())));
+                                    }
+                                    if self.passthrough {
+                                        // only the cell under the cursor is clicked
+                                        for ((child, state), child_layout) in self
+                                            .cells
+                                            .iter_mut()
+                                            .zip(tree.children.iter_mut())
+                                            .zip(layout.children())
+                                        {
+                                            let cursor = Pinned::cursor(
+                                                &pinned,
+                                                child.0.as_tuple(),
+                                                raw_cursor,
+                                                bounds,
+                                            );
+                                            if !cursor.is_over(child_layout.bounds()) {
+                                                continue;
+                                            }
+                                            child.1.as_widget_mut().on_event(
+                                                state,
+                                                event.clone(),
+                                                child_layout,
+                                                cursor,
+                                                renderer,
+                                                clipboard,
+                                                shell,
+                                                viewport,
+                                            );
+                                        }
+                                    }
+
+                                    // ?
+                                    return event::Status::Captured; // early to avoid second child update()
+                                }
+                            }
+                        }
+                        mouse::click::Kind::Double | mouse::click::Kind::Triple => {
+                            // for the next click to make a triple click
+                            state.last_click = Some(click);
+
+                            let local = click.position() + (bounds.position() - widget.position());
+                            if self.scrollbar_at(state, widget.size(), local).is_some() {
+                                return event::Status::Captured;
+                            }
+
+                            let position = Pinned::unpin(&pinned, click.position());
+
+                            if let Some(divider_hit) = state.region.find_nearest_divider(position) {
+                                state.interaction = Interaction::None;
+                                state.drag_click = None;
+                                if let Some(on_instruction) = on_instruction.as_ref() {
+                                    let (axis, index) = (divider_hit.axis, divider_hit.index);
+                                    // the texts shown in the column or row, to measure
+                                    let value = |cell| clipboard::value(self.data, cell);
+                                    let texts = match axis {
+                                        Axis::Column => (0..self.data.row_count())
+                                            .filter_map(|row| value(K::new(index, row)))
+                                            .collect(),
+                                        Axis::Row => (0..self.data.column_count())
+                                            .filter_map(|column| value(K::new(column, index)))
+                                            .collect(),
+                                    };
+                                    shell.publish(on_instruction(Instruction::AutoFit {
+                                        axis,
+                                        index,
+                                        texts,
+                                    }));
+                                }
+                                return event::Status::Captured;
+                            }
+                            if position.y < state.region.offset.y
+                                || state.region.mirror_x(position.x) < state.region.offset.x
+                            {
+                                // the headers and gutters have no cells to activate
+                                return event::Status::Captured;
+                            }
+                            let cell_ref = self.cell_at(&state.region, position);
+                            trace!(id = ?self.id, ?position, cell = %cell_ref, "find cell");
+
+                            let selectable = self.is_selectable_row(cell_ref.y());
+                            // a cell is only kept in the selection with the whole span it's in
+                            let clicked = self.data.snap_to_spans(cell_ref.as_range());
+                            if selectable && !self.data.selection().contains_range(&clicked) {
+                                shell.publish(on_edit(Action::Select(cell_ref.as_range())));
+                            }
+                            if let (true, Some(on_instruction)) =
+                                (selectable, on_instruction.as_ref())
+                            {
+                                shell.publish(on_instruction(Instruction::Activate(cell_ref)));
+                            }
+                            // only the clicked cell is clicked. Unless the first click
+                            // was passed through to it, it missed that click, so the
+                            // last click of three is repeated for it to be clicked
+                            // three times too, e.g. to select all its text.
+                            let missed_first = selectable && !self.passthrough;
+                            let presses = match click.kind() {
+                                mouse::click::Kind::Triple if missed_first => 2,
+                                _ => 1,
+                            };
+                            if let Some(((child, state), child_layout)) = self
+                                .cells
+                                .iter_mut()
+                                .zip(tree.children.iter_mut())
+                                .zip(layout.children())
+                                .find(|((child, _), _)| child.0 == cell_ref)
+                            {
+                                let cursor =
+                                    Pinned::cursor(&pinned, child.0.as_tuple(), raw_cursor, bounds);
+                                for _ in 0..presses {
+                                    child.1.as_widget_mut().on_event(
+                                        state,
+                                        event.clone(),
+                                        child_layout,
+                                        cursor,
+                                        renderer,
+                                        clipboard,
+                                        shell,
+                                        viewport,
+                                    );
+                                }
+                            }
+                            return event::Status::Captured;
+                        }
+                    },
+                    Update::Hover(position) => {
+                        if let Some(on_hover) = self.on_hover.as_ref() {
+                            let hovered = position.and_then(|position| {
+                                self.hovered_cell(state, widget, bounds, &pinned, position)
+                            });
+                            if hovered.map(|cell| cell.as_tuple()) != state.hovered {
+                                state.hovered = hovered.map(|cell| cell.as_tuple());
+                                shell.publish(on_hover(hovered));
+                            }
+                        }
+                    }
+                    Update::SecondaryClick(position) => {
+                        // only the cells of the body have a menu
+                        let Some(cell_ref) = self
+                            .hovered_cell(state, widget, bounds, &pinned, position)
+                            .filter(|cell| self.is_selectable_row(cell.y()))
+                        else {
+                            return event::Status::Captured;
+                        };
+
+                        state.focus();
+                        if !self.data.selection().normalize().contains(&cell_ref) {
+                            shell.publish(on_edit(Action::Select(cell_ref.as_range())));
+                        }
+                        if let (Some(on_instruction), Some(position)) =
+                            (on_instruction.as_ref(), raw_cursor.position())
+                        {
+                            shell.publish(on_instruction(Instruction::SecondaryClick {
+                                cell: cell_ref,
+                                position,
+                            }));
+                        }
+                        return event::Status::Captured;
+                    }
+                    Update::ModifiersChanged(modifiers) => {
+                        state.modifiers = modifiers;
+                    }
+                    Update::Scroll(delta) => {
+                        let offset = self.scroll_offset(state, widget.size());
+                        if self.scrollable
+                            && self.scroll_to_offset(state, widget.size(), offset - delta)
+                        {
+                            let bounds = self.content_bounds(state, widget);
+                            self.update_window(state, bounds, viewport, shell);
+                            shell.invalidate_layout();
+                            return event::Status::Captured;
+                        }
+                    }
+                    Update::Release => match state.interaction {
+                        Interaction::ResizeDivider(hit) => {
+                            if let Some(start) = state.last_click.map(|c| c.position()) {
+                                // the divider may be released past the edges
+                                if let Some(current) = raw_cursor
+                                    .position()
+                                    .map(|position| position - Vector::new(bounds.x, bounds.y))
+                                {
+                                    let start = Pinned::unpin(&pinned, start);
+                                    let current = Pinned::unpin(&pinned, current);
+                                    let raw_delta = match hit.axis {
+                                        Axis::Column => {
+                                            (current.x - start.x) / state.region.scale_factor_x
+                                        }
+                                        Axis::Row => {
+                                            (current.y - start.y) / state.region.scale_factor_y
+                                        }
+                                    };
+
+                                    let size =
+                                        self.limit_size(hit.axis, hit.original_size + raw_delta);
+
+                                    if self.resize_preview {
+                                        self.resize_region(state, hit, raw_delta, widget.size());
+                                    }
+                                    // the deltas are relative to the stored sizes, which
+                                    // filling or fitted ones may not be shown at
+                                    let sizes = match hit.axis {
+                                        Axis::Column => self.data.column_sizes(),
+                                        Axis::Row => self.data.row_sizes(),
+                                    };
+                                    for index in self.resized_together(hit) {
+                                        let delta =
+                                            size - sizes.get(index).copied().unwrap_or_default();
+
+                                        shell.publish(on_edit(Action::ResizeDivider(
+                                            hit.axis, index, delta,
+                                        )));
+                                        if let (true, Some(on_instruction)) =
+                                            (delta != 0.0, on_instruction.as_ref())
+                                        {
+                                            shell.publish(on_instruction(Instruction::Resized {
+                                                axis: hit.axis,
+                                                index,
+                                                size,
+                                            }));
+                                        }
+                                    }
+                                }
+                            }
+                            state.interaction = Interaction::None;
+                            state.drag_click = None;
+                            state.resize_delta = None;
+
+                            shell.invalidate_layout();
+                            shell.invalidate_widgets();
+                            // shell.capture_event();
+                            return event::Status::Captured;
+                        }
+                        Interaction::ReorderColumn { from, current_x } => {
+                            let to = Self::drop_column(&state.region, current_x);
+                            let header = state.last_click.is_some_and(|click| {
+                                Pinned::unpin(&pinned, click.position()).y < state.region.offset.y
+                            });
+
+                            if to != from {
+                                shell.publish(on_edit(Action::MoveColumn { from, to }));
+                            } else if let (true, Some(on_instruction)) =
+                                (header, on_instruction.as_ref())
+                            {
+                                shell.publish(on_instruction(Instruction::HeaderClicked(from)));
+                            }
+                            state.interaction = Interaction::None;
+                            state.drag_click = None;
+                            return event::Status::Captured;
+                        }
+                        Interaction::Scroll { .. } => {
+                            state.interaction = Interaction::None;
+                            state.drag_click = None;
+                            return event::Status::Captured;
+                        }
+                        Interaction::FillDrag { current } => {
+                            let source = self.data.selection().normalize();
+                            let target = self.fill_target(current);
+                            if target != source {
+                                shell.publish(on_edit(Action::Fill { source, target }));
+                            }
+                            state.interaction = Interaction::None;
+                            state.drag_click = None;
+                            return event::Status::Captured;
+                        }
+                        Interaction::ReorderRow { from, current_y } => {
+                            let to = self.drop_row(&state.region, current_y);
+                            if to != from {
+                                shell.publish(on_edit(Action::MoveRow { from, to }));
+                            }
+                            state.interaction = Interaction::None;
+                            state.drag_click = None;
+                            return event::Status::Captured;
+                        }
+                        Interaction::None => {
+                            state.drag_click = None;
+                        }
+                    },
+                    Update::Drag(raw_end) => {
+                        if let Some(start) = state.last_click.map(|c| c.position()) {
+                            match state.interaction {
+                                Interaction::ResizeDivider(hit) => {
+                                    // Convert pixel delta to raw delta using stored scale factor
+                                    let start = Pinned::unpin(&pinned, start);
+                                    let current = Pinned::unpin(
+                                        &pinned,
+                                        raw_end - Vector::new(bounds.x, bounds.y),
+                                    );
+                                    let raw_delta = match hit.axis {
+                                        Axis::Column => {
+                                            (current.x - start.x) / state.region.scale_factor_x
+                                        }
+                                        Axis::Row => {
+                                            (current.y - start.y) / state.region.scale_factor_y
+                                        }
+                                    };
+
+                                    state.resize_delta = Some(raw_delta);
+                                    if self.resize_preview {
+                                        shell
+                                            .request_redraw(iced::window::RedrawRequest::NextFrame);
+                                        return event::Status::Captured;
+                                    }
+
+                                    self.resize_region(state, hit, raw_delta, widget.size());
+
+                                    shell.invalidate_layout();
+                                    shell.invalidate_widgets();
+                                    // shell.capture_event();
+                                    return event::Status::Captured;
+                                }
+                                Interaction::ReorderColumn { from, .. } => {
+                                    let current = Pinned::unpin(
+                                        &pinned,
+                                        raw_end - Vector::new(bounds.x, bounds.y),
+                                    );
+                                    state.interaction = Interaction::ReorderColumn {
+                                        from,
+                                        current_x: current.x,
+                                    };
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                    return event::Status::Captured;
+                                }
+                                Interaction::Scroll { axis, grab } => {
+                                    let local = raw_end - widget.position();
+                                    let local = Point::new(local.x, local.y);
+                                    if let Some(scrollbar) = self
+                                        .scrollbars(state, widget.size())
+                                        .into_iter()
+                                        .find(|scrollbar| scrollbar.axis == axis)
+                                    {
+                                        let start = scrollbar.along(local) - grab;
+                                        if self.scroll_thumb_to(
+                                            state,
+                                            widget.size(),
+                                            &scrollbar,
+                                            start,
+                                        ) {
+                                            let bounds = self.content_bounds(state, widget);
+                                            self.update_window(state, bounds, viewport, shell);
+                                            shell.invalidate_layout();
+                                        }
+                                    }
+                                    return event::Status::Captured;
+                                }
+                                Interaction::FillDrag { .. } => {
+                                    let current = Pinned::unpin(
+                                        &pinned,
+                                        raw_end - Vector::new(bounds.x, bounds.y),
+                                    );
+                                    let (column, row) =
+                                        self.cell_at(&state.region, current).as_tuple();
+                                    state.interaction = Interaction::FillDrag {
+                                        current: (
+                                            column,
+                                            self.last_selectable_row()
+                                                .map_or(row, |last| row.min(last)),
+                                        ),
+                                    };
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                    return event::Status::Captured;
+                                }
+                                Interaction::ReorderRow { from, .. } => {
+                                    let current = Pinned::unpin(
+                                        &pinned,
+                                        raw_end - Vector::new(bounds.x, bounds.y),
+                                    );
+                                    state.interaction = Interaction::ReorderRow {
+                                        from,
+                                        current_y: current.y,
+                                    };
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                    return event::Status::Captured;
+                                }
+                                Interaction::None => {
+                                    // Only create a new selection if we've actually dragged to a different position
+                                    if let Some(end) = raw_cursor.position_in(bounds) {
+                                        if end != start {
+                                            let start = Pinned::unpin(&pinned, start);
+                                            let end = Pinned::unpin(&pinned, end);
+                                            let Some(last_row) = self.last_selectable_row() else {
+                                                return event::Status::Captured;
+                                            };
+                                            let range_start =
+                                                self.cell_at(&state.region, start).as_tuple();
+                                            let range_end =
+                                                self.cell_at(&state.region, end).as_tuple();
+                                            trace!(
+                                                id = ?self.id,
+                                                ?start,
+                                                ?end,
+                                                ?range_start,
+                                                ?range_end,
+                                                "find cell"
+                                            );
+                                            let range = self.data.snap_to_spans(
+                                                <R as ReferenceRange<K>>::new(
+                                                    K::new(
+                                                        range_start.0,
+                                                        range_start.1.min(last_row),
+                                                    ),
+                                                    Some(K::new(
+                                                        range_end.0,
+                                                        range_end.1.min(last_row),
+                                                    )),
+                                                )
+                                                .normalize(),
+                                            );
+
+                                            // the selection is only drawn, no
+                                            // cell moves
+                                            if range != *self.data.selection() {
+                                                shell.publish(on_edit(Action::Select(range)));
+                                                shell.request_redraw(
+                                                    iced::window::RedrawRequest::NextFrame,
+                                                );
+                                            }
+                                        }
+                                        // shell.capture_event();
+                                        return event::Status::Captured;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    Update::Binding(binding) => {
+                        if matches!(binding, Binding::Enter)
+                            && !state.is_focused()
+                            && self.has_focused_cell(&mut tree.children, layout, renderer)
+                        {
+                            submitted = Some(if state.modifiers.shift() {
+                                self.submit_motion.opposite()
+                            } else {
+                                self.submit_motion
+                            });
+                        }
+
+                        #[allow(clippy::too_many_arguments)]
+                        fn apply_binding<Data, T, K, R, Message>(
+                            binding: Binding<Message>,
+                            data: &Data,
+                            state: &mut State,
+                            on_edit: &dyn Fn(Action<K, R>) -> Message,
+                            on_instruction: Option<&dyn Fn(Instruction<K>) -> Message>,
+                            system_clipboard: bool,
+                            last_row: Option<usize>,
+                            clipboard: &mut dyn Clipboard,
+                            shell: &mut Shell<'_, Message>,
+                        ) -> bool
+                        where
+                            Data: Tabular<T, K, R>,
+                            T: Default,
+                            K: Reference,
+                            R: ReferenceRange<K>,
+                            Message: Clone,
+                        {
+                            let mut action = |action| shell.publish(on_edit(action));
+                            match binding {
+                                Binding::Focus => {
+                                    state.interaction = Interaction::None;
+                                    state.focus();
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                }
+                                Binding::ClickedOutside => {
+                                    state.interaction = Interaction::None;
+                                    state.unfocus();
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                    return true;
+                                }
+                                Binding::Unfocus => {
+                                    state.interaction = Interaction::None;
+                                    state.unfocus();
+                                    shell.request_redraw(iced::window::RedrawRequest::NextFrame);
+                                }
+                                Binding::Cut | Binding::Copy if system_clipboard => {
+                                    if state.is_focused() {
+                                        clipboard.write(
+                                            iced::advanced::clipboard::Kind::Standard,
+                                            clipboard::encode_values(data, data.selection()),
+                                        );
+                                        if matches!(binding, Binding::Cut) {
+                                            action(Edit::Delete.into());
+                                        }
+                                    }
+                                }
+                                Binding::Paste if system_clipboard => {
+                                    if let (true, Some(text)) = (
+                                        state.is_focused(),
+                                        clipboard.read(iced::advanced::clipboard::Kind::Standard),
+                                    ) {
+                                        action(
+                                            Edit::Paste {
+                                                anchor: data.selection().normalize().start(),
+                                                rows: clipboard::decode_tsv(&text),
+                                            }
+                                            .into(),
+                                        );
+                                    }
+                                }
+                                Binding::Cut => {
+                                    if state.is_focused() {
+                                        if let Some(on_instruction) = on_instruction.as_ref() {
+                                            shell.publish(on_instruction(Instruction::Cut));
+                                        }
+                                    }
+                                }
+                                Binding::Copy => {
+                                    if state.is_focused() {
+                                        if let Some(on_instruction) = on_instruction.as_ref() {
+                                            shell.publish(on_instruction(Instruction::Copy));
+                                        }
+                                    }
+                                }
+                                Binding::Paste => {
+                                    if state.is_focused() {
+                                        if let Some(on_instruction) = on_instruction.as_ref() {
+                                            shell.publish(on_instruction(Instruction::Paste));
+                                        }
+                                    }
+                                }
+                                Binding::Custom(message) => {
+                                    shell.publish(message);
+                                }
+                                Binding::Delete => {
+                                    if state.is_focused() {
+                                        action(Edit::Delete.into());
+                                    }
+                                }
+                                Binding::Type(text) => {
+                                    if state.is_focused() {
+                                        if let Some(on_instruction) = on_instruction.as_ref() {
+                                            shell.publish(on_instruction(
+                                                Instruction::ActivateWith(data.active_cell(), text),
+                                            ));
+                                        }
+                                    }
+                                }
+                                Binding::StartEdit => {
+                                    if let Some(on_instruction) = on_instruction.as_ref() {
+                                        shell.publish(on_instruction(Instruction::Activate(
+                                            data.active_cell(),
+                                        )));
+                                    }
+                                }
+                                Binding::Enter => {
+                                    if state.is_focused() {
+                                        state.interaction = Interaction::None;
+                                        state.focus();
+                                        if let Some(on_instruction) = on_instruction.as_ref() {
+                                            shell.publish(on_instruction(Instruction::Activate(
+                                                data.active_cell(),
+                                            )));
+                                        }
+                                        shell
+                                            .request_redraw(iced::window::RedrawRequest::NextFrame);
+                                        shell.invalidate_widgets();
+                                    }
+                                }
+                                Binding::MoveSelection(motion) => {
+                                    if state.is_focused() {
+                                        action(Action::MoveSelection(motion))
+                                    }
+                                }
+                                Binding::ExpandSelection(motion) => {
+                                    if state.is_focused() {
+                                        action(Action::ExpandSelection(motion))
+                                    }
+                                }
+                                Binding::SelectAll => {
+                                    if state.is_focused() {
+                                        action(Action::SelectAll)
+                                    }
+                                }
+                                Binding::SelectRow => {
+                                    if state.is_focused() {
+                                        // every row the selection covers, so a
+                                        // selection of several rows keeps them
+                                        let selection = data.selection().normalize();
+                                        let start = selection.start();
+                                        let end = selection.end().unwrap_or(start);
+                                        action(Action::Select(R::new(
+                                            K::new(0, start.y()),
+                                            Some(K::new(
+                                                data.column_count().saturating_sub(1),
+                                                end.y(),
+                                            )),
+                                        )));
+                                    }
+                                }
+                                // resized by the table itself
+                                Binding::Resize(..) => {}
+                                Binding::SelectColumn => {
+                                    if let (true, Some(last_row)) = (state.is_focused(), last_row) {
+                                        // down to the last row that can be
+                                        // selected, as a header click does
+                                        let selection = data.selection().normalize();
+                                        let start = selection.start();
+                                        let end = selection.end().unwrap_or(start);
+                                        action(Action::Select(R::new(
+                                            K::new(start.x(), 0),
+                                            Some(K::new(end.x(), last_row)),
+                                        )));
+                                    }
+                                }
+                            }
+                            false
+                        }
+
+                        let binding = if self.right_to_left {
+                            binding.mirror()
+                        } else {
+                            binding
+                        };
+                        let binding =
+                            binding.paged(self.page_rows(&state.region, bounds, viewport));
+                        trace!(id = ?self.id, ?binding, "binding");
+
+                        // keep Tab from also moving the focus to another widget
+                        let tabbed = state.is_focused()
+                            && matches!(
+                                binding,
+                                Binding::MoveSelection(Motion::Forward | Motion::Back)
+                            );
+
+                        if let Some(action) = self.wrapped_motion(&binding) {
+                            trace!(id = ?self.id, ?action, "binding wrapped");
+                            if state.is_focused() {
+                                shell.publish(on_edit(action));
+                            }
+                        } else if let Some(replacement) = self.motion_within_body(&binding) {
+                            trace!(id = ?self.id, ?replacement, "binding clamped to body");
+                            if let (true, Some(action)) = (state.is_focused(), replacement) {
+                                shell.publish(on_edit(action));
+                            }
+                        } else if let Binding::Resize(axis, steps) = binding {
+                            if let (true, Some((index, size, delta))) =
+                                (state.is_focused(), self.resize_by_steps(axis, steps))
+                            {
+                                shell.publish(on_edit(Action::ResizeDivider(axis, index, delta)));
+                                if let Some(on_instruction) = on_instruction.as_ref() {
+                                    shell.publish(on_instruction(Instruction::Resized {
+                                        axis,
+                                        index,
+                                        size,
+                                    }));
+                                }
+                            }
+                        } else if apply_binding::<Data, T, K, R, Message>(
+                            binding,
+                            self.data,
+                            state,
+                            on_edit,
+                            on_instruction
+                                .as_ref()
+                                .map(|f| f as &dyn Fn(Instruction<K>) -> Message),
+                            self.system_clipboard,
+                            self.last_selectable_row(),
+                            clipboard,
+                            shell,
+                        ) {
+                            // ?
+                            return event::Status::Ignored;
+                        }
+
+                        if tabbed {
+                            return event::Status::Captured;
+                        }
+                    }
+                }
+            }
+        }
+
+        // presses and scrolls over the table only reach the cell under the
+        // cursor, while the rest still reaches every cell, e.g. so that a
+        // drag within a cell ends wherever the button is released, and a
+        // press elsewhere unfocuses the cells
+        let pointed = raw_cursor.is_over(widget)
+            && matches!(
+                event,
+                iced::Event::Mouse(
+                    mouse::Event::ButtonPressed(_) | mouse::Event::WheelScrolled { .. }
+                ) | iced::Event::Touch(touch::Event::FingerPressed { .. })
+            );
+        for ((child, state), child_layout) in self
+            .cells
+            .iter_mut()
+            .zip(tree.children.iter_mut())
+            .zip(layout.children())
+        {
+            let cursor = Pinned::cursor(&pinned, child.0.as_tuple(), raw_cursor, bounds);
+            if pointed && !cursor.is_over(child_layout.bounds()) {
+                continue;
+            }
+            child.1.as_widget_mut().on_event(
+                state,
+                event.clone(),
+                child_layout,
+                cursor,
+                renderer,
+                clipboard,
+                shell,
+                viewport,
+            );
+        }
+
+        if let (Some(motion), Some(on_edit)) = (submitted, self.on_edit.as_deref()) {
+            let motion = motion.paged(self.page_rows(&state.region, bounds, viewport));
+            let binding = Binding::MoveSelection(motion);
+            let action = self
+                .wrapped_motion(&binding)
+                .map(Some)
+                .or_else(|| self.motion_within_body(&binding))
+                .unwrap_or(Some(Action::MoveSelection(motion)));
+            if let Some(action) = action {
+                shell.publish(on_edit(action));
+            }
+        }
+
+        event::Status::Ignored
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        operation.focusable(state, self.id.as_ref());
+        operation.custom(state, self.id.as_ref());
+
+        if let Some((x, y)) = state.reveal.take() {
+            if self.scrollable && self.data.column_count() > 0 && self.data.row_count() > 0 {
+                let cell = K::new(
+                    x.min(self.data.column_count() - 1),
+                    y.min(self.data.row_count() - 1),
+                );
+                let target = state
+                    .region
+                    .selection_bounds(self.data.snap_to_spans(cell.as_range()));
+                self.scroll_into_view(state, layout.bounds().size(), target);
+            }
+        }
+
+        operation.container(self.id.as_ref(), layout.bounds(), &mut |operation| {
+            self.cells
+                .iter()
+                .zip(&mut tree.children)
+                .zip(layout.children())
+                .for_each(|((child, state), child_layout)| {
+                    child
+                        .1
+                        .as_widget()
+                        .operate(state, child_layout, renderer, operation);
+                });
+        })
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    last_click: Option<mouse::Click>,
+    drag_click: Option<mouse::click::Kind>,
+    interaction: Interaction,
+    is_focused: bool,
+    modifiers: keyboard::Modifiers,
+    // How far the content is scrolled from its leading top corner, if the
+    // table scrolls internally
+    scroll: Vector,
+    // The active cell that the scrolling last followed
+    active: Option<(usize, usize)>,
+    // A cell to bring into view, requested by a `scroll_to` operation
+    reveal: Option<(usize, usize)>,
+    // The start and end of a selection requested by a `select` operation,
+    // published with the next event
+    select: Option<RawRange>,
+    // The cell under the cursor that was last published by `on_hover`
+    hovered: Option<(usize, usize)>,
+    // How far a divider being dragged was moved, as a raw size, laid over the
+    // sizes from the data until the resize is released
+    resize_delta: Option<f32>,
+    // The heights of the rows measured from their cells, along with the
+    // column widths they were measured at, until the content changes
+    measured_rows: Option<(Vec<f32>, Vec<Option<f32>>)>,
+    // The layouts of the cells, as last laid out
+    nodes: Vec<layout::Node>,
+
+    region: Region,
+}
+
+#[derive(Debug)]
+struct Region {
+    row_count: usize,
+    column_count: usize,
+    raw_rows: Vec<f32>,
+    raw_columns: Vec<f32>,
+    scaled_rows: Vec<f32>,
+    scaled_columns: Vec<f32>,
+    scale_factor_x: f32,
+    scale_factor_y: f32,
+    cumulative_x: Vec<f32>,
+    cumulative_y: Vec<f32>,
+    spacing: Size<f32>,
+    hidden_rows: Vec<bool>,
+    // The portion of the leftover height taken by each filling row
+    fill_rows: Vec<Option<u16>>,
+    // How each column is sized
+    column_sizing: Vec<Sizing>,
+    // Whether the rows were sized to their cells rather than by the data
+    fitted_rows: bool,
+    // The position of the grid of cells within the widget, leaving room for
+    // any gutters.
+    offset: Vector,
+    // The width of the row number gutter, right of the row group gutter
+    row_number_width: f32,
+    // Whether the grid is mirrored horizontally within the widget
+    rtl: bool,
+    // How the columns and rows are scaled to the bounds
+    scale_mode: ScaleMode,
+    // The width of the widget
+    width: f32,
+}
+
+impl Region {
+    const RESIZE_AREA: f32 = 4.0;
+
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        columns: &[f32],
+        rows: &[f32],
+        hidden_rows: Vec<bool>,
+        fill_rows: Vec<Option<u16>>,
+        column_sizing: Vec<Sizing>,
+        spacing: Size<f32>,
+        row_count: usize,
+        column_count: usize,
+    ) -> Self {
+        // sizes missing from ragged data fall back to the default sizes
+        let raw_columns = (0..column_count)
+            .map(|i| columns.get(i).copied().unwrap_or(DEFAULT_COLUMN_WIDTH) + spacing.width)
+            .collect();
+        let raw_rows = (0..row_count)
+            .map(|i| {
+                if hidden_rows.get(i).copied().unwrap_or(false) {
+                    0.0
+                } else {
+                    rows.get(i).copied().unwrap_or(DEFAULT_ROW_HEIGHT) + spacing.height
+                }
+            })
+            .collect();
+
+        Self {
+            row_count,
+            column_count,
+            raw_columns,
+            raw_rows,
+            scaled_columns: vec![0.0; column_count],
+            scaled_rows: vec![0.0; row_count],
+            cumulative_x: vec![0.0; column_count],
+            cumulative_y: vec![0.0; row_count],
+            scale_factor_x: 1.0,
+            scale_factor_y: 1.0,
+            spacing,
+            hidden_rows,
+            fill_rows,
+            column_sizing,
+            fitted_rows: false,
+            offset: Vector::ZERO,
+            row_number_width: 0.0,
+            rtl: false,
+            scale_mode: ScaleMode::Stretch,
+            width: 0.0,
+        }
+    }
+
+    // Whether the raw sizes are the given sizes of the data, with the spacing.
+    // Columns and rows that fit their content are measured rather than read.
+    fn has_sizes(&self, columns: &[f32], rows: &[f32]) -> bool {
+        let columns_match =
+            self.raw_columns
+                .iter()
+                .zip(columns)
+                .enumerate()
+                .all(|(column, (raw, width))| {
+                    self.is_auto(column) || *raw == width + self.spacing.width
+                });
+        let rows_match =
+            self.raw_rows
+                .iter()
+                .zip(rows)
+                .zip(&self.hidden_rows)
+                .all(|((raw, height), hidden)| {
+                    *hidden || self.fitted_rows || *raw == height + self.spacing.height
+                });
+
+        columns_match && rows_match
+    }
+
+    // Scale the grid to the given widget bounds, minus the grid offset
+    fn scale_to_bounds(&mut self, bounds: Size, spacing: Size<f32>) {
+        self.width = bounds.width;
+        let bounds = Size::new(
+            (bounds.width - self.offset.x).max(0.0),
+            (bounds.height - self.offset.y).max(0.0),
+        );
+        self.spacing = spacing;
+        let total_raw_width = self.total_raw_width();
+        let total_raw_height = self.total_raw_height();
+
+        // The axes that are kept at their own sizes
+        let (natural_x, natural_y) = match self.scale_mode {
+            ScaleMode::Stretch => (false, false),
+            ScaleMode::Natural => (true, true),
+            ScaleMode::FitWidth => (false, true),
+            ScaleMode::FitHeight => (true, false),
+        };
+        let scale_x = if natural_x {
+            1.0
+        } else {
+            bounds.width / total_raw_width
+        };
+        let scale_y = if natural_y {
+            1.0
+        } else {
+            bounds.height / total_raw_height
+        };
+
+        // Scale columns including spacing. If any column fills the table, the
+        // other columns keep their size and the filling columns share what is
+        // left.
+        let total_column_portion: u32 = (0..self.column_count)
+            .filter_map(|column| self.column_fill_portion(column))
+            .map(u32::from)
+            .sum();
+
+        if total_column_portion > 0 {
+            let fixed_width: f32 = self
+                .raw_columns
+                .iter()
+                .enumerate()
+                .filter(|&(column, _)| self.column_fill_portion(column).is_none())
+                .map(|(_, raw)| raw)
+                .sum();
+            let leftover = (bounds.width - fixed_width).max(0.0);
+
+            for column in 0..self.scaled_columns.len() {
+                self.scaled_columns[column] = match self.column_fill_portion(column) {
+                    Some(portion) => leftover * f32::from(portion) / total_column_portion as f32,
+                    None => self.raw_columns.get(column).copied().unwrap_or_default(),
+                };
+            }
+        } else {
+            for (scaled, raw) in self.scaled_columns.iter_mut().zip(self.raw_columns.iter()) {
+                *scaled = raw * scale_x;
+            }
+        }
+
+        // Scale rows including spacing. If any row fills the table, the fixed
+        // rows keep their size and the filling rows share what is left.
+        let total_portion: u32 = (0..self.row_count)
+            .filter_map(|row| self.fill_portion(row))
+            .map(u32::from)
+            .sum();
+
+        if total_portion > 0 {
+            let fixed_height: f32 = self
+                .raw_rows
+                .iter()
+                .enumerate()
+                .filter(|&(row, _)| self.fill_portion(row).is_none())
+                .map(|(_, raw)| raw)
+                .sum();
+            let leftover = (bounds.height - fixed_height).max(0.0);
+
+            for row in 0..self.scaled_rows.len() {
+                self.scaled_rows[row] = match self.fill_portion(row) {
+                    Some(portion) => leftover * f32::from(portion) / total_portion as f32,
+                    None => self.raw_rows.get(row).copied().unwrap_or_default(),
+                };
+            }
+        } else {
+            for (scaled, raw) in self.scaled_rows.iter_mut().zip(self.raw_rows.iter()) {
+                *scaled = raw * scale_y;
+            }
+        }
+
+        // Precompute cumulative positions
+        let mut x = 0.0;
+        for (i, width) in self.scaled_columns.iter().enumerate() {
+            x += width;
+            self.cumulative_x[i] = x;
+        }
+
+        let mut y = 0.0;
+        for (i, height) in self.scaled_rows.iter().enumerate() {
+            y += height;
+            self.cumulative_y[i] = y;
+        }
+
+        self.scale_factor_x = if total_column_portion > 0 {
+            1.0
+        } else {
+            scale_x
+        };
+        self.scale_factor_y = if total_portion > 0 { 1.0 } else { scale_y };
+    }
+
+    // The portion of the leftover height taken by a visible filling row
+    fn fill_portion(&self, row: usize) -> Option<u16> {
+        if self.hidden_rows.get(row).copied().unwrap_or(false) {
+            return None;
+        }
+        self.fill_rows.get(row).copied().flatten()
+    }
+
+    // Fix the height of a filling row at its current height, so that it can
+    // be resized
+    fn fix_row(&mut self, row: usize) {
+        if self.fill_portion(row).is_some() {
+            self.raw_rows[row] = self.scaled_rows[row];
+            self.fill_rows[row] = None;
+        }
+    }
+
+    // The portion of the leftover width taken by a filling column
+    fn column_fill_portion(&self, column: usize) -> Option<u16> {
+        match self.column_sizing.get(column) {
+            Some(Sizing::FillPortion(portion)) => Some(*portion),
+            _ => None,
+        }
+    }
+
+    // Whether a column takes the natural width of its content
+    fn is_auto(&self, column: usize) -> bool {
+        matches!(self.column_sizing.get(column), Some(Sizing::Auto))
+    }
+
+    // Size the columns that fit their content to the natural width of their
+    // widest cell, if any of their cells has one
+    fn fit_columns(&mut self, natural: &[Option<f32>]) {
+        for (column, width) in natural.iter().enumerate() {
+            if let (true, Some(width), Some(raw)) = (
+                self.is_auto(column),
+                width,
+                self.raw_columns.get_mut(column),
+            ) {
+                *raw = width + self.spacing.width;
+            }
+        }
+    }
+
+    // Size the visible rows to the given heights of their tallest cells, if
+    // any of their cells has one
+    fn fit_rows(&mut self, heights: &[Option<f32>]) {
+        for (row, height) in heights.iter().enumerate() {
+            let hidden = self.hidden_rows.get(row).copied().unwrap_or(false);
+            if let (false, Some(height), Some(raw)) = (hidden, height, self.raw_rows.get_mut(row)) {
+                *raw = height + self.spacing.height;
+            }
+        }
+        self.fitted_rows = true;
+    }
+
+    // Fix the width of a filling or fitted column at its current width, so
+    // that it can be resized
+    fn fix_column(&mut self, column: usize) {
+        if let Some(sizing) = self.column_sizing.get_mut(column) {
+            if matches!(sizing, Sizing::FillPortion(_)) {
+                self.raw_columns[column] = self.scaled_columns[column];
+            }
+            *sizing = Sizing::Fixed(self.raw_columns[column] - self.spacing.width);
+        }
+    }
+
+    // Fix a column or row at the given size, without the spacing
+    fn fix_size(&mut self, axis: Axis, index: usize, size: f32) {
+        // the raw sizes include the spacing, as when they're read from the data
+        match axis {
+            Axis::Column => {
+                if let Some(raw) = self.raw_columns.get_mut(index) {
+                    *raw = size + self.spacing.width;
+                }
+                if let Some(sizing) = self.column_sizing.get_mut(index) {
+                    *sizing = Sizing::Fixed(size);
+                }
+            }
+            Axis::Row => {
+                if let Some(raw) = self.raw_rows.get_mut(index) {
+                    *raw = size + self.spacing.height;
+                }
+                if let Some(fill) = self.fill_rows.get_mut(index) {
+                    *fill = None;
+                }
+            }
+        }
+    }
+
+    fn total_raw_width(&self) -> f32 {
+        self.raw_columns.iter().sum()
+    }
+
+    fn total_raw_height(&self) -> f32 {
+        self.raw_rows.iter().sum()
+    }
+
+    // Get actual cell size (without spacing)
+    fn cell_size(&self, cell: CellIndex) -> Size {
+        let width = self.scaled_columns.get(cell.column).unwrap_or(&0.0) - self.spacing.width;
+        let height = self.scaled_rows.get(cell.row).unwrap_or(&0.0) - self.spacing.height;
+
+        Size::new(width.max(0.0), height.max(0.0))
+    }
+
+    // Get cell position using precomputed cumulative positions
+    fn cell_position(&self, cell: CellIndex) -> Point {
+        let x = if cell.column == 0 {
+            &0.0
+        } else {
+            self.cumulative_x.get(cell.column - 1).unwrap_or(&0.0)
+        };
+        let y = if cell.row == 0 {
+            &0.0
+        } else {
+            self.cumulative_y.get(cell.row - 1).unwrap_or(&0.0)
+        };
+
+        let position = Point::new(
+            self.offset.x + x + self.spacing.width / 2.0,
+            self.offset.y + y + self.spacing.height / 2.0,
+        );
+
+        if self.rtl {
+            Point::new(
+                self.mirror_x(position.x) - self.cell_size(cell).width,
+                position.y,
+            )
+        } else {
+            position
+        }
+    }
+
+    // Get the bounds of a block of merged cells (without spacing)
+    fn merged_bounds(&self, row: usize, col: usize, rows: usize, columns: usize) -> Rectangle {
+        let last_col = (col + columns.max(1) - 1).min(self.column_count.saturating_sub(1));
+        let last_row = (row + rows.max(1) - 1).min(self.row_count.saturating_sub(1));
+
+        let (Some((left, _)), Some((_, right)), Some((top, _)), Some((_, bottom))) = (
+            self.column_span(col),
+            self.column_span(last_col),
+            self.row_span(row),
+            self.row_span(last_row),
+        ) else {
+            let cell = CellIndex::new(col, row);
+            return Rectangle::new(self.cell_position(cell), self.cell_size(cell));
+        };
+
+        self.mirror(Rectangle {
+            x: left + self.spacing.width / 2.0,
+            y: top + self.spacing.height / 2.0,
+            width: (right - left - self.spacing.width).max(0.0),
+            height: (bottom - top - self.spacing.height).max(0.0),
+        })
+    }
+
+    // Mirror an x coordinate relative to the widget in right-to-left layouts
+    fn mirror_x(&self, x: f32) -> f32 {
+        if self.rtl {
+            self.width - x
+        } else {
+            x
+        }
+    }
+
+    // Mirror a rectangle relative to the widget in right-to-left layouts
+    fn mirror(&self, rect: Rectangle) -> Rectangle {
+        Rectangle {
+            x: if self.rtl {
+                self.width - rect.x - rect.width
+            } else {
+                rect.x
+            },
+            ..rect
+        }
+    }
+
+    // The area covered by the grid of cells relative to the widget,
+    // excluding gutters
+    fn grid(&self, bounds: Size) -> Rectangle {
+        // only as far as the cells reach, if they don't fill the bounds
+        let width = self.cumulative_x.last().copied().unwrap_or(0.0);
+        let height = self.cumulative_y.last().copied().unwrap_or(0.0);
+
+        self.mirror(Rectangle {
+            x: self.offset.x,
+            y: self.offset.y,
+            width: (bounds.width - self.offset.x).max(0.0).min(width),
+            height: (bounds.height - self.offset.y).max(0.0).min(height),
+        })
+    }
+
+    // The x positions relative to the widget of the dividers between columns
+    fn column_dividers(&self) -> impl Iterator<Item = f32> + '_ {
+        self.cumulative_x
+            .iter()
+            .take(self.column_count.saturating_sub(1))
+            .map(|x| self.mirror_x(self.offset.x + x))
+    }
+
+    // Get the left and right of a column in widget bounds, before mirroring
+    fn column_span(&self, column: usize) -> Option<(f32, f32)> {
+        let right = *self.cumulative_x.get(column)?;
+        let left = match column {
+            0 => 0.0,
+            _ => self.cumulative_x[column - 1],
+        };
+
+        Some((self.offset.x + left, self.offset.x + right))
+    }
+
+    // Get the top and bottom of a row in widget bounds
+    fn row_span(&self, row: usize) -> Option<(f32, f32)> {
+        let bottom = *self.cumulative_y.get(row)?;
+        let top = match row {
+            0 => 0.0,
+            _ => self.cumulative_y[row - 1],
+        };
+
+        Some((self.offset.y + top, self.offset.y + bottom))
+    }
+
+    // Find cell indices for a given point in widget bounds
+    fn find_cell(&self, pos: Point) -> CellIndex {
+        let pos = Point::new(self.mirror_x(pos.x), pos.y) - self.offset;
+
+        fn find_index(pos: f32, cumulative: &[f32]) -> usize {
+            match cumulative
+                .binary_search_by(|cum| cum.partial_cmp(&pos).unwrap_or(std::cmp::Ordering::Equal))
+            {
+                Ok(idx) => idx,
+                Err(idx) => idx.min(cumulative.len().saturating_sub(1)),
+            }
+        }
+
+        CellIndex::new(
+            find_index(pos.x, &self.cumulative_x),
+            find_index(pos.y, &self.cumulative_y),
+        )
+    }
+
+    // Find the nearest divider to a given point
+    fn find_nearest_divider(&self, pos: Point) -> Option<DividerHit> {
+        fn find_nearest(
+            pos: f32,
+            cumulative: &[f32],
+            spacing: f32,
+            hidden: &[bool],
+        ) -> Option<usize> {
+            // Get the index where this position would be inserted
+            let idx = match cumulative
+                .binary_search_by(|cum| cum.partial_cmp(&pos).unwrap_or(std::cmp::Ordering::Equal))
+            {
+                Ok(idx) => idx,
+                Err(idx) => idx,
+            };
+
+            // Handle all bounds cases together
+            let candidates = if idx >= cumulative.len() {
+                cumulative.len().checked_sub(1).map(|i| vec![i])
+            } else if idx == 0 {
+                Some(vec![0])
+            } else {
+                Some(vec![idx - 1, idx])
+            }?;
+
+            candidates.into_iter().find_map(|i| {
+                if hidden.get(i).copied().unwrap_or(false) {
+                    return None;
+                }
+                let cum_pos = cumulative.get(i)?;
+
+                let divider_pos = cum_pos - spacing / 2.0;
+                ((pos - divider_pos).abs() <= Region::RESIZE_AREA).then_some(i)
+            })
+        }
+
+        let pos = Point::new(self.mirror_x(pos.x), pos.y) - self.offset;
+
+        // Try vertical dividers first, then horizontal
+        find_nearest(pos.x, &self.cumulative_x, self.spacing.width, &[])
+            .map(|idx| DividerHit {
+                axis: Axis::Column,
+                index: idx,
+                // A filling column is resized from its current width
+                original_size: match self.column_fill_portion(idx) {
+                    Some(_) => self.scaled_columns[idx],
+                    None => self.raw_columns[idx],
+                } - self.spacing.width,
+            })
+            .or_else(|| {
+                find_nearest(
+                    pos.y,
+                    &self.cumulative_y,
+                    self.spacing.height,
+                    &self.hidden_rows,
+                )
+                .map(|idx| DividerHit {
+                    axis: Axis::Row,
+                    index: idx,
+                    // A filling row is resized from its current height
+                    original_size: match self.fill_portion(idx) {
+                        Some(_) => self.scaled_rows[idx],
+                        None => self.raw_rows[idx],
+                    } - self.spacing.height,
+                })
+            })
+    }
+
+    /// Calculate the bounding rectangle for a selection range
+    pub fn selection_bounds<K: Reference, R: ReferenceRange<K>>(&self, selection: R) -> Rectangle {
+        let start = selection.start();
+        let end = selection.end().unwrap_or(selection.start());
+
+        // Get the minimum and maximum cell coordinates
+        let min_row = start.y().min(end.y());
+        let max_row = start.y().max(end.y());
+        let min_col = start.x().min(end.x());
+        let max_col = start.x().max(end.x());
+
+        // Get the top-left position using precomputed cumulative positions
+        let x = if min_col == 0 {
+            0.0
+        } else {
+            self.cumulative_x
+                .get(min_col.saturating_sub(1))
+                .copied()
+                .unwrap_or(0.0)
+        };
+
+        let y = if min_row == 0 {
+            0.0
+        } else {
+            self.cumulative_y
+                .get(min_row.saturating_sub(1))
+                .copied()
+                .unwrap_or(0.0)
+        };
+
+        // Calculate width and height using the difference in cumulative positions
+        if let Some(width) = self.cumulative_x.get(max_col) {
+            if let Some(height) = self.cumulative_y.get(max_row) {
+                self.mirror(Rectangle {
+                    x: self.offset.x + x + self.spacing.width / 2.0,
+                    y: self.offset.y + y + self.spacing.height / 2.0,
+                    width: *width - x - self.spacing.width / 2.0,
+                    height: *height - y - self.spacing.height / 2.0,
+                })
+            } else {
+                Rectangle::default()
+            }
+        } else {
+            Rectangle::default()
+        }
+    }
+}
+
+// A band of cells pinned to an edge of the visible part of the table
+#[derive(Debug, Clone)]
+struct Pinned {
+    // The rows of the band
+    rows: std::ops::Range<usize>,
+    // The columns of the band
+    columns: std::ops::Range<usize>,
+    // The edge of the visible bounds the rows of the band are pinned to
+    edge: Option<Edge>,
+    // Whether the columns of the band are pinned to the leading edge
+    start: bool,
+    // The natural bounds of the band, relative to the widget
+    bounds: Rectangle,
+    // The offset applied to the band to keep it pinned
+    shift: Vector,
+}
+
+impl Pinned {
+    // Whether the band, as drawn, contains the point relative to the widget
+    fn contains(&self, point: Point) -> bool {
+        (self.bounds + self.shift).contains(point)
+    }
+
+    // Whether the band holds the given cell
+    fn holds(&self, column: usize, row: usize) -> bool {
+        self.rows.contains(&row) && self.columns.contains(&column)
+    }
+
+    // The band drawn on top at the point relative to the widget, if any
+    fn topmost(pinned: &[Self], point: Point) -> Option<&Self> {
+        pinned.iter().rev().find(|band| band.contains(point))
+    }
+
+    // Map a point relative to the widget to its natural position, undoing
+    // the shift of the band the point lies on, if any
+    fn unpin(pinned: &[Self], point: Point) -> Point {
+        match Self::topmost(pinned, point) {
+            Some(band) => point - band.shift,
+            None => point,
+        }
+    }
+
+    // The cursor as seen by a child in the given cell, accounting for the
+    // bands being drawn over the body
+    fn cursor(
+        pinned: &[Self],
+        (column, row): (usize, usize),
+        cursor: mouse::Cursor,
+        bounds: Rectangle,
+    ) -> mouse::Cursor {
+        let Some(position) = cursor.position() else {
+            return cursor;
+        };
+
+        let relative = position - Vector::new(bounds.x, bounds.y);
+        let Some(band) = Self::topmost(pinned, relative) else {
+            // the cursor is over the body, which can't be seen by pinned cells
+            return if pinned.iter().any(|band| band.holds(column, row)) {
+                mouse::Cursor::Unavailable
+            } else {
+                cursor
+            };
+        };
+
+        if band.holds(column, row) {
+            mouse::Cursor::Available(position - band.shift)
+        } else {
+            mouse::Cursor::Unavailable
+        }
+    }
+
+    // The corner where a band of rows and a band of columns cross, pinned
+    // along both axes
+    fn corner(rows: &Self, columns: &Self) -> Self {
+        Self {
+            rows: rows.rows.clone(),
+            columns: columns.columns.clone(),
+            edge: rows.edge,
+            start: true,
+            bounds: Rectangle {
+                x: columns.bounds.x,
+                y: rows.bounds.y,
+                width: columns.bounds.width,
+                height: rows.bounds.height,
+            },
+            shift: Vector::new(columns.shift.x, rows.shift.y),
+        }
+    }
+}
+
+// A scrollbar of a table that scrolls internally, relative to the widget
+#[derive(Debug, Clone, Copy)]
+struct Scrollbar {
+    // The lines the scrollbar scrolls through: the columns for the horizontal
+    // scrollbar and the rows for the vertical one
+    axis: Axis,
+    track: Rectangle,
+    thumb: Rectangle,
+}
+
+impl Scrollbar {
+    // The position of a point along the scrollbar
+    fn along(&self, point: Point) -> f32 {
+        match self.axis {
+            Axis::Column => point.x,
+            Axis::Row => point.y,
+        }
+    }
+
+    // Where a part of the scrollbar starts along it
+    fn start(&self, part: Rectangle) -> f32 {
+        self.along(part.position())
+    }
+
+    // How long a part of the scrollbar is along it
+    fn length(&self, part: Rectangle) -> f32 {
+        match self.axis {
+            Axis::Column => part.width,
+            Axis::Row => part.height,
+        }
+    }
+}
+
+// An edge of the visible bounds that a band of rows is pinned to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Edge {
+    Top,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Default)]
+enum Interaction {
+    #[default]
+    None,
+    ResizeDivider(DividerHit),
+    // A column being dragged to a new position, with the unpinned x of the
+    // cursor relative to the widget
+    ReorderColumn {
+        from: usize,
+        current_x: f32,
+    },
+    // A row being dragged to a new position, with the unpinned y of the
+    // cursor relative to the widget
+    ReorderRow {
+        from: usize,
+        current_y: f32,
+    },
+    // A scrollbar thumb being dragged, with how far along the thumb it was
+    // grabbed
+    Scroll {
+        axis: Axis,
+        grab: f32,
+    },
+    // The fill handle of the selection being dragged, with the cell under
+    // the cursor
+    FillDrag {
+        current: (usize, usize),
+    },
+}
+
+impl State {
+    // Create a new State with the given column and row sizes
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        col_sizes: &[f32],
+        row_sizes: &[f32],
+        hidden_rows: Vec<bool>,
+        fill_rows: Vec<Option<u16>>,
+        column_sizing: Vec<Sizing>,
+        spacing: Size,
+        row_count: usize,
+        column_count: usize,
+    ) -> Self {
+        Self {
+            last_click: None,
+            drag_click: None,
+            interaction: Interaction::default(),
+            is_focused: false,
+            modifiers: keyboard::Modifiers::default(),
+            scroll: Vector::ZERO,
+            active: None,
+            reveal: None,
+            select: None,
+            hovered: None,
+            resize_delta: None,
+            measured_rows: None,
+            nodes: Vec::new(),
+            region: Region::new(
+                col_sizes,
+                row_sizes,
+                hidden_rows,
+                fill_rows,
+                column_sizing,
+                spacing,
+                row_count,
+                column_count,
+            ),
+        }
+    }
+
+    fn from_data<Data, T, K, R>(data: &Data, spacing: Size) -> Self
+    where
+        Data: Tabular<T, K, R>,
+        T: Default,
+        K: Reference,
+        R: ReferenceRange<K>,
+    {
+        Self::new(
+            data.column_sizes(),
+            data.row_sizes(),
+            (0..data.row_count())
+                .map(|row| data.is_row_hidden(row))
+                .collect(),
+            (0..data.row_count())
+                .map(|row| match data.row_height(row) {
+                    RowHeight::FillPortion(portion) => Some(portion),
+                    RowHeight::Fixed(_) => None,
+                })
+                .collect(),
+            (0..data.column_count())
+                .map(|column| data.column_sizing(column))
+                .collect(),
+            spacing,
+            data.row_count(),
+            data.column_count(),
+        )
+    }
+
+    // Rebuild the region from the data after it changed, keeping the rest of
+    // the state. An interaction with a column or row that was removed is
+    // cancelled.
+    fn rebuild<Data, T, K, R>(&mut self, data: &Data, spacing: Size)
+    where
+        Data: Tabular<T, K, R>,
+        T: Default,
+        K: Reference,
+        R: ReferenceRange<K>,
+    {
+        let Self { region, .. } = Self::from_data(data, spacing);
+        self.region = region;
+        self.measured_rows = None;
+
+        let (columns, rows) = (self.region.column_count, self.region.row_count);
+        let within = |(x, y): (usize, usize)| x < columns && y < rows;
+        let count = |axis| match axis {
+            Axis::Column => columns,
+            Axis::Row => rows,
+        };
+
+        self.interaction = match std::mem::take(&mut self.interaction) {
+            Interaction::ResizeDivider(hit) if hit.index >= count(hit.axis) => Interaction::None,
+            Interaction::ReorderColumn { from, .. } if from >= columns => Interaction::None,
+            Interaction::ReorderRow { from, .. } if from >= rows => Interaction::None,
+            Interaction::FillDrag { current } => Interaction::FillDrag {
+                current: (
+                    current.0.min(columns.saturating_sub(1)),
+                    current.1.min(rows.saturating_sub(1)),
+                ),
+            },
+            interaction => interaction,
+        };
+        if !matches!(self.interaction, Interaction::ResizeDivider(_)) {
+            self.resize_delta = None;
+        }
+
+        // a removed cell under the cursor is published again once it moves
+        self.hovered = self.hovered.filter(|&cell| within(cell));
+        self.reveal = self.reveal.filter(|&cell| within(cell));
+    }
+
+    /// Returns whether the [`Table`] is currently focused or not.
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    /// Focuses the [`Table`].
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    /// Unfocuses the [`Table`].
+    pub fn unfocus(&mut self) {
+        self.is_focused = false;
+        self.drag_click = None;
+        self.last_click = None;
+    }
+}
+
+impl operation::Focusable for State {
+    fn is_focused(&self) -> bool {
+        State::is_focused(self)
+    }
+
+    fn focus(&mut self) {
+        State::focus(self);
+    }
+
+    fn unfocus(&mut self) {
+        State::unfocus(self);
+    }
+}
+
+impl std::fmt::Display for Interaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interaction::None => write!(f, "None"),
+            Interaction::ResizeDivider(DividerHit {
+                axis,
+                index,
+                original_size,
+            }) => write!(
+                f,
+                "Resize({:?}, {}, {})",
+                axis,
+                index,
+                original_size.trunc() as i32
+            ),
+            Interaction::ReorderColumn { from, current_x } => {
+                write!(f, "ReorderColumn({}, {})", from, current_x.trunc() as i32)
+            }
+            Interaction::ReorderRow { from, current_y } => {
+                write!(f, "ReorderRow({}, {})", from, current_y.trunc() as i32)
+            }
+            Interaction::Scroll { axis, grab } => {
+                write!(f, "Scroll({:?}, {})", axis, grab.trunc() as i32)
+            }
+            Interaction::FillDrag { current } => write!(f, "FillDrag({:?})", current),
+        }
+    }
+}
+
+// Fill a gridline between columns or rows along the given rectangle, leaving
+// out the gaps along its length. The dashes of a patterned line are laid out
+// from the start of the line, so that they move along with it, and only
+// within the viewport.
+#[allow(clippy::too_many_arguments)]
+fn fill_gridline<Renderer: renderer::Renderer>(
+    renderer: &mut Renderer,
+    axis: Axis,
+    line: Rectangle,
+    gaps: &[(f32, f32)],
+    color: Color,
+    pattern: LinePattern,
+    viewport: &Rectangle,
+    scale_factor: f32,
+) {
+    let vertical = axis == Axis::Column;
+    let (start, end, thickness) = if vertical {
+        (line.y, line.y + line.height, line.width)
+    } else {
+        (line.x, line.x + line.width, line.height)
+    };
+    let (visible_start, visible_end) = if vertical {
+        (viewport.y, viewport.y + viewport.height)
+    } else {
+        (viewport.x, viewport.x + viewport.width)
+    };
+
+    let mut fill = |from: f32, to: f32| {
+        let bounds = if vertical {
+            Rectangle {
+                y: from,
+                height: to - from,
+                ..line
+            }
+        } else {
+            Rectangle {
+                x: from,
+                width: to - from,
+                ..line
+            }
+        };
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: snap_to_pixels(bounds, scale_factor),
+                ..Default::default()
+            },
+            color,
+        );
+    };
+
+    let mut gaps = gaps.to_vec();
+    gaps.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut from = start;
+    for (gap_start, gap_end) in gaps.into_iter().chain(std::iter::once((end, end))) {
+        let to = gap_start.min(end);
+        if to > from {
+            let dash = match pattern {
+                LinePattern::Solid => None,
+                LinePattern::Dashed { dash, gap } => Some((dash, gap)),
+                LinePattern::Dotted => Some((thickness.max(1.0), thickness.max(1.0))),
+            };
+
+            match dash.filter(|(dash, gap)| *dash > 0.0 && *gap > 0.0) {
+                None => fill(from, to),
+                Some((dash, gap)) => {
+                    let (from, to) = (from.max(visible_start), to.min(visible_end));
+                    let period = dash + gap;
+                    let mut at = start + ((from - start) / period).floor() * period;
+                    while at < to {
+                        let (dash_start, dash_end) = (at.max(from), (at + dash).min(to));
+                        if dash_end > dash_start {
+                            fill(dash_start, dash_end);
+                        }
+                        at += period;
+                    }
+                }
+            }
+        }
+        from = from.max(gap_end);
+    }
+}
+
+pub fn focus<Message>(id: impl Into<widget::Id>) -> iced::Task<Message>
+where
+    Message: Send + 'static,
+{
+    widget::operate(widget::operation::focusable::focus::<Message>(id.into()))
+}
+
+/// Produces a [`Task`](iced::Task) that scrolls the [`Table`] with the given
+/// [`Id`](widget::Id) until the cell at the given address is visible.
+///
+/// Addresses out of the table are clamped to its last row and column, and a
+/// cell that is already visible doesn't scroll the table at all. Only tables
+/// that scroll internally, see [`Table::scrollable`], are affected.
+pub fn scroll_to<Message>(id: impl Into<widget::Id>, address: impl Reference) -> iced::Task<Message>
+where
+    Message: Send + 'static,
+{
+    widget::operate(ScrollTo {
+        target: id.into(),
+        cell: address.as_tuple(),
+    })
+}
+
+/// Produces a [`Task`](iced::Task) that focuses the [`Table`] with the given
+/// [`Id`](widget::Id) and selects the given range, e.g. to jump to a cell
+/// found by a search.
+///
+/// The selection is published as an [`Action::Select`] through
+/// [`Table::on_action`] with the next event the table gets, clamped to its
+/// last row and column. The start of the range is brought into view like
+/// with [`scroll_to`].
+///
+/// An operation can't publish messages in iced, so until the selection is
+/// published the table draws it in place of the selection of its data, and
+/// it shows along with the focus as soon as the window is redrawn.
+pub fn select<K, Message>(
+    id: impl Into<widget::Id>,
+    range: impl ReferenceRange<K>,
+) -> iced::Task<Message>
+where
+    K: Reference,
+    Message: Send + 'static,
+{
+    widget::operate(Select {
+        target: id.into(),
+        range: (
+            range.start().as_tuple(),
+            range.end().map(|end| end.as_tuple()),
+        ),
+    })
+}
+
+// The start and end of a range, as columns and rows
+type RawRange = ((usize, usize), Option<(usize, usize)>);
+
+// Asks the table with the target id to focus and select a range, bringing
+// its start into view
+struct Select {
+    target: widget::Id,
+    range: RawRange,
+}
+
+impl<T> widget::Operation<T> for Select {
+    fn container(
+        &mut self,
+        _id: Option<&widget::Id>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn widget::Operation<T>),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn custom(&mut self, state: &mut dyn std::any::Any, id: Option<&widget::Id>) {
+        if id != Some(&self.target) {
+            return;
+        }
+
+        if let Some(state) = state.downcast_mut::<State>() {
+            state.focus();
+            state.select = Some(self.range);
+            state.reveal = Some(self.range.0);
+        }
+    }
+}
+
+// Asks the table with the target id to bring a cell into view
+struct ScrollTo {
+    target: widget::Id,
+    cell: (usize, usize),
+}
+
+impl<T> widget::Operation<T> for ScrollTo {
+    fn container(
+        &mut self,
+        _id: Option<&widget::Id>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn widget::Operation<T>),
+    ) {
+        operate_on_children(self);
+    }
+
+    fn custom(&mut self, state: &mut dyn std::any::Any, id: Option<&widget::Id>) {
+        if id != Some(&self.target) {
+            return;
+        }
+
+        if let Some(state) = state.downcast_mut::<State>() {
+            state.reveal = Some(self.cell);
+        }
+    }
+}
+
+impl<'a, Data, T, K, R, Message, Theme, Renderer>
+    From<Table<'a, Data, T, K, R, Message, Theme, Renderer>>
+    for Element<'a, Message, Theme, Renderer>
+where
+    Data: Tabular<T, K, R>,
+    T: Cell + Default,
+    K: Reference + 'a,
+    R: ReferenceRange<K> + 'a,
+    Renderer: text::Renderer + 'a,
+    Message: Clone + 'a,
+    Theme: Catalog + 'a,
+{
+    fn from(data: Table<'a, Data, T, K, R, Message, Theme, Renderer>) -> Self {
+        Self::new(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::advanced::clipboard;
+    use iced::widget::Space;
+    use iced::Theme;
+
+    use list::Content;
+
+    #[derive(Debug, Clone)]
+    enum Message {
+        Action(Action<Address, Range>),
+        Instruction(Instruction<Address>),
+        Hover(Option<Address>),
+    }
+
+    type TestTable<'a> = Table<'a, Content, list::Cell, Address, Range, Message, Theme, ()>;
+
+    // A grid of empty cells, 100 by 20 each
+    fn content(range: &str) -> Content {
+        Content::with_range(Range::parse(range).unwrap())
+    }
+
+    // A table of empty cells publishing everything it does
+    fn table(data: &Content) -> TestTable<'_> {
+        Table::new(data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+            .on_action(Message::Action)
+            .on_instruction(Message::Instruction)
+    }
+
+    fn range(a: &str, b: &str) -> Range {
+        Range::new(a.parse().unwrap(), Some(b.parse().unwrap()))
+    }
+
+    fn cell(a: &str) -> Range {
+        Range::new(a.parse().unwrap(), None)
+    }
+
+    fn press() -> iced::Event {
+        iced::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+    }
+
+    fn moved(position: Point) -> iced::Event {
+        iced::Event::Mouse(mouse::Event::CursorMoved { position })
+    }
+
+    fn release() -> iced::Event {
+        iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+    }
+
+    fn redraw() -> iced::Event {
+        iced::Event::Window(iced::window::Event::RedrawRequested(
+            std::time::Instant::now(),
+        ))
+    }
+
+    fn modifiers(modifiers: keyboard::Modifiers) -> iced::Event {
+        iced::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers))
+    }
+
+    fn key(named: keyboard::key::Named, modifiers: keyboard::Modifiers) -> iced::Event {
+        let key = keyboard::Key::Named(named);
+        iced::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: key.clone(),
+            modified_key: key,
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Standard,
+            modifiers,
+            text: None,
+        })
+    }
+
+    // What a table did with an event
+    struct Outcome {
+        status: event::Status,
+        messages: Vec<Message>,
+        // Whether the table asked to be laid out or drawn again
+        relayout: bool,
+        redraw: bool,
+    }
+
+    impl Outcome {
+        fn actions(&self) -> Vec<&Action<Address, Range>> {
+            self.messages
+                .iter()
+                .filter_map(|message| match message {
+                    Message::Action(action) => Some(action),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        fn instructions(&self) -> Vec<&Instruction<Address>> {
+            self.messages
+                .iter()
+                .filter_map(|message| match message {
+                    Message::Instruction(instruction) => Some(instruction),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        // Performs the published actions on the data, as an app would
+        fn perform(self, data: &mut Content) -> Self {
+            for action in self.actions() {
+                data.perform(action.clone());
+            }
+            self
+        }
+    }
+
+    // The widget tree of a table, kept across frames like a running app
+    // keeps it
+    struct Ui {
+        tree: Tree,
+        size: Size,
+    }
+
+    impl Ui {
+        fn new(size: Size) -> Self {
+            Self {
+                tree: Tree::empty(),
+                size,
+            }
+        }
+
+        fn layout(&mut self, table: TestTable<'_>) -> layout::Node {
+            self.layout_element(&Element::from(table))
+        }
+
+        fn layout_element(&mut self, element: &Element<'_, Message, Theme, ()>) -> layout::Node {
+            self.tree.diff(element);
+            element.as_widget().layout(
+                &mut self.tree,
+                &(),
+                &layout::Limits::new(Size::ZERO, self.size),
+            )
+        }
+
+        // Diffs and lays out the table, then runs the event through it with
+        // the cursor at the given position
+        fn event(&mut self, table: TestTable<'_>, event: iced::Event, cursor: Point) -> Outcome {
+            self.event_with(table, event, cursor, &mut clipboard::Null)
+        }
+
+        fn event_with(
+            &mut self,
+            table: TestTable<'_>,
+            event: iced::Event,
+            cursor: Point,
+            clipboard: &mut dyn Clipboard,
+        ) -> Outcome {
+            let mut element = Element::from(table);
+            let node = self.layout_element(&element);
+
+            let mut messages = Vec::new();
+            let mut shell = Shell::new(&mut messages);
+            let status = element.as_widget_mut().on_event(
+                &mut self.tree,
+                event,
+                Layout::new(&node),
+                mouse::Cursor::Available(cursor),
+                &(),
+                clipboard,
+                &mut shell,
+                &Rectangle::with_size(self.size),
+            );
+            let (relayout, redraw) = (shell.is_layout_invalid(), shell.redraw_request().is_some());
+            Outcome {
+                status,
+                messages,
+                relayout,
+                redraw,
+            }
+        }
+
+        fn mouse_interaction(&mut self, table: TestTable<'_>, cursor: Point) -> mouse::Interaction {
+            let element = Element::from(table);
+            let node = self.layout_element(&element);
+
+            element.as_widget().mouse_interaction(
+                &self.tree,
+                Layout::new(&node),
+                mouse::Cursor::Available(cursor),
+                &Rectangle::with_size(self.size),
+                &(),
+            )
+        }
+
+        fn state(&self) -> &State {
+            self.tree.state.downcast_ref::<State>()
+        }
+    }
+
+    #[test]
+    fn merged_cells_cover_their_span() {
+        let mut data = content("A1:D5");
+        data.merge_cells(range("B2", "D3"));
+
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        let node = ui.layout(table(&data));
+        let bounds: Vec<_> = node.children().iter().map(|child| child.bounds()).collect();
+
+        // the cells are laid out column by column, 5 rows each
+        assert_eq!(
+            bounds[6],
+            Rectangle::new(Point::new(100.0, 20.0), Size::new(300.0, 40.0))
+        );
+        for covered in [7, 11, 12, 16, 17] {
+            assert_eq!(bounds[covered].size(), Size::ZERO);
+        }
+        assert_eq!(
+            bounds[8],
+            Rectangle::new(Point::new(100.0, 60.0), Size::new(100.0, 20.0))
+        );
+
+        // the bottom-right cell of the span, clear of its dividers
+        let outcome = ui.event(table(&data), press(), Point::new(390.0, 50.0));
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::Select(selected)] if *selected == cell("B2")
+        ));
+        assert!(outcome.instructions().is_empty());
+    }
+
+    #[test]
+    fn shift_click_extends_the_selection() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+
+        ui.event(table(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        ui.event(
+            table(&data),
+            modifiers(keyboard::Modifiers::SHIFT),
+            Point::ORIGIN,
+        );
+        let outcome = ui
+            .event(table(&data), press(), Point::new(350.0, 70.0))
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::Select(selected)] if *selected == range("B2", "D4")
+        ));
+        assert_eq!(*data.selection(), range("B2", "D4"));
+    }
+
+    #[test]
+    fn shift_click_without_a_selection_selects_the_cell() {
+        let data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+
+        ui.event(
+            table(&data),
+            modifiers(keyboard::Modifiers::SHIFT),
+            Point::ORIGIN,
+        );
+        let outcome = ui.event(table(&data), press(), Point::new(350.0, 70.0));
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::Select(selected)] if *selected == cell("D4")
+        ));
+    }
+
+    #[test]
+    fn narrow_cells_follow_the_alignment_of_their_column() {
+        let mut data = content("A1:D1");
+        data.set_column_type(1, ColumnType::Integer);
+        data.set_column_type(2, ColumnType::Bool);
+        let narrow = |data| {
+            Table::new(data, |_, _| Space::new(20.0, Length::Fill).into())
+                .column_alignment(3, alignment::Horizontal::Center)
+        };
+        let xs = |node: layout::Node| -> Vec<f32> {
+            node.children()
+                .iter()
+                .map(|child| child.bounds().x)
+                .collect()
+        };
+
+        let mut ui = Ui::new(Size::new(400.0, 20.0));
+        assert_eq!(xs(ui.layout(narrow(&data))), [0.0, 180.0, 240.0, 340.0]);
+
+        // right to left, the columns and their alignments are mirrored
+        let node = ui.layout(narrow(&data).right_to_left(true));
+        assert_eq!(xs(node), [380.0, 200.0, 140.0, 40.0]);
+
+        // cells that fill their width are unaffected
+        let node = ui.layout(table(&data));
+        assert_eq!(xs(node), [0.0, 100.0, 200.0, 300.0]);
+    }
+
+    #[test]
+    fn state_follows_removed_rows_and_columns() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        ui.layout(table(&data));
+
+        data.perform(Action::RemoveRow(4));
+        data.perform(Action::RemoveColumn(0));
+        let node = ui.layout(table(&data));
+
+        let region = &ui.state().region;
+        assert_eq!((region.column_count, region.row_count), (3, 4));
+        assert_eq!(node.children().len(), 12);
+
+        for _ in 0..3 {
+            data.perform(Action::RemoveColumn(0));
+        }
+        let node = ui.layout(table(&data));
+
+        assert_eq!(ui.state().region.column_count, 0);
+        assert!(node.children().is_empty());
+    }
+
+    #[test]
+    fn double_click_on_a_divider_auto_fits() {
+        let mut data = content("A1:D5");
+        data.set_text_conversion(
+            |cell: &list::Cell| cell.content.clone(),
+            |text| list::Cell::from(text.to_string()),
+        );
+        data.insert("A2".parse::<Address>().unwrap(), "wide".to_string());
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        let divider = Point::new(100.0, 10.0);
+
+        ui.event(table(&data), press(), divider);
+        ui.event(table(&data), release(), divider);
+        let outcome = ui.event(table(&data), press(), divider);
+
+        assert!(matches!(
+            outcome.instructions()[..],
+            [Instruction::AutoFit {
+                axis: Axis::Column,
+                index: 0,
+                texts,
+            }] if texts == &["", "wide", "", "", ""]
+        ));
+        assert!(outcome.actions().is_empty());
+    }
+
+    #[test]
+    fn dragging_a_divider_stops_at_the_limits() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).min_column_width(30.0).max_row_height(50.0)
+        }
+
+        ui.event(view(&data), press(), Point::new(100.0, 10.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(-50.0, 10.0)),
+            Point::new(-50.0, 10.0),
+        );
+        let outcome = ui
+            .event(view(&data), release(), Point::new(-50.0, 10.0))
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::ResizeDivider(Axis::Column, 0, delta)] if *delta == -70.0
+        ));
+        assert_eq!(data.column_sizes()[0], 30.0);
+
+        ui.event(view(&data), press(), Point::new(60.0, 40.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(60.0, 500.0)),
+            Point::new(60.0, 500.0),
+        );
+        let outcome = ui
+            .event(view(&data), release(), Point::new(60.0, 500.0))
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::ResizeDivider(Axis::Row, 1, delta)] if *delta == 30.0
+        ));
+        assert_eq!(data.row_sizes()[1], 50.0);
+    }
+
+    #[test]
+    fn resizing_a_fitted_row_is_relative_to_its_stored_height() {
+        let mut data = content("A1:A2");
+        // the rows are fitted to cells 50 tall, but stored at 20
+        fn view(data: &Content) -> TestTable<'_> {
+            Table::new(data, |_, _| Space::new(Length::Fill, 50.0).into())
+                .on_action(Message::Action)
+                .auto_row_height(true)
+                .scale_mode(ScaleMode::Natural)
+        }
+        let mut ui = Ui::new(Size::new(100.0, 200.0));
+
+        ui.event(view(&data), press(), Point::new(50.0, 50.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(50.0, 60.0)),
+            Point::new(50.0, 60.0),
+        );
+        let outcome = ui
+            .event(view(&data), release(), Point::new(50.0, 60.0))
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::ResizeDivider(Axis::Row, 0, delta)] if *delta == 40.0
+        ));
+        assert_eq!(data.row_sizes()[0], 60.0);
+    }
+
+    #[test]
+    fn a_table_of_only_unselectable_footer_rows_selects_nothing() {
+        let data = content("A1:B2");
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data)
+                .show_column_headers(true)
+                .footer_rows(2)
+                .footer_selectable(false)
+        }
+        let none = keyboard::Modifiers::default();
+
+        // a click on a cell or a header, or a drag over the cells
+        let outcome =
+            Ui::new(Size::new(200.0, 60.0)).event(view(&data), press(), Point::new(50.0, 30.0));
+        assert!(outcome.actions().is_empty());
+        let outcome =
+            Ui::new(Size::new(200.0, 60.0)).event(view(&data), press(), Point::new(50.0, 5.0));
+        assert!(outcome.actions().is_empty());
+        let mut ui = Ui::new(Size::new(200.0, 60.0));
+        ui.event(view(&data), press(), Point::new(50.0, 30.0));
+        let outcome = ui.event(
+            view(&data),
+            moved(Point::new(150.0, 50.0)),
+            Point::new(150.0, 50.0),
+        );
+        assert!(outcome.actions().is_empty());
+        ui.event(view(&data), release(), Point::new(150.0, 50.0));
+
+        // nor do the keys move into the footer
+        ui.event(view(&data), press(), Point::new(50.0, 5.0));
+        ui.event(view(&data), release(), Point::new(50.0, 5.0));
+        for named in [keyboard::key::Named::ArrowDown, keyboard::key::Named::End] {
+            let outcome = ui.event(view(&data), key(named, none), Point::ORIGIN);
+            assert!(outcome.actions().is_empty(), "{named:?}");
+        }
+    }
+
+    #[test]
+    fn page_keys_move_by_the_rows_in_view() {
+        let mut data = content("A1:B100");
+        // wide enough for the vertical scrollbar
+        let mut ui = Ui::new(Size::new(220.0, 110.0));
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).scrollable(true)
+        }
+        let page_down = key(keyboard::key::Named::PageDown, keyboard::Modifiers::empty());
+        let page_up = key(keyboard::key::Named::PageUp, keyboard::Modifiers::empty());
+
+        ui.event(view(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        let outcome = ui
+            .event(view(&data), page_down.clone(), Point::ORIGIN)
+            .perform(&mut data);
+
+        // five and a half rows of 20 are in view
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::MoveSelection(Motion::PageDown(5))]
+        ));
+        assert_eq!(*data.selection(), cell("B7"));
+
+        for _ in 0..30 {
+            ui.event(view(&data), page_down.clone(), Point::ORIGIN)
+                .perform(&mut data);
+        }
+        assert_eq!(*data.selection(), cell("B100"));
+
+        for _ in 0..30 {
+            ui.event(view(&data), page_up.clone(), Point::ORIGIN)
+                .perform(&mut data);
+        }
+        assert_eq!(*data.selection(), cell("B1"));
+    }
+
+    #[test]
+    fn ctrl_shift_arrow_selects_to_the_edge_of_the_data() {
+        let mut data = content("A1:C10");
+        data.set_is_empty(|cell| cell.content.is_empty());
+        for y in 0..4 {
+            data.insert(Address::new(1, y), "x");
+        }
+        let mut ui = Ui::new(Size::new(300.0, 200.0));
+        let jump = keyboard::Modifiers::CTRL | keyboard::Modifiers::SHIFT;
+
+        ui.event(table(&data), press(), Point::new(150.0, 10.0))
+            .perform(&mut data);
+        let outcome = ui
+            .event(
+                table(&data),
+                key(keyboard::key::Named::ArrowDown, jump),
+                Point::ORIGIN,
+            )
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::ExpandSelection(Motion::DataDown)]
+        ));
+        assert_eq!(*data.selection(), range("B1", "B4"));
+
+        // the anchor stays put when the selection is reversed
+        data.select_cell(Address::new(1, 3));
+        ui.event(
+            table(&data),
+            key(keyboard::key::Named::ArrowUp, jump),
+            Point::ORIGIN,
+        )
+        .perform(&mut data);
+        assert_eq!(*data.selection(), range("B4", "B1"));
+    }
+
+    #[test]
+    fn tab_cycles_through_the_selection() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        let tab = |modifiers| key(keyboard::key::Named::Tab, modifiers);
+
+        ui.event(table(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        data.select_range(range("B2", "C3"));
+
+        let mut visited = Vec::new();
+        for _ in 0..5 {
+            let outcome = ui
+                .event(
+                    table(&data),
+                    tab(keyboard::Modifiers::empty()),
+                    Point::ORIGIN,
+                )
+                .perform(&mut data);
+            assert_eq!(outcome.status, event::Status::Captured);
+            visited.push(data.active_cell().to_string());
+        }
+        assert_eq!(visited, ["C2", "B3", "C3", "B2", "C2"]);
+        assert_eq!(*data.selection(), range("B2", "C3"));
+
+        ui.event(table(&data), tab(keyboard::Modifiers::SHIFT), Point::ORIGIN)
+            .perform(&mut data);
+        ui.event(table(&data), tab(keyboard::Modifiers::SHIFT), Point::ORIGIN)
+            .perform(&mut data);
+        assert_eq!(data.active_cell(), "C3".parse().unwrap());
+    }
+
+    #[test]
+    fn shift_space_selects_the_rows_of_the_selection() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        let shift_space = key(keyboard::key::Named::Space, keyboard::Modifiers::SHIFT);
+
+        ui.event(table(&data), press(), Point::new(250.0, 50.0))
+            .perform(&mut data);
+        let outcome = ui
+            .event(table(&data), shift_space.clone(), Point::ORIGIN)
+            .perform(&mut data);
+
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::Select(selected)] if *selected == range("A3", "D3")
+        ));
+
+        data.select_range(range("C4", "B2"));
+        ui.event(table(&data), shift_space, Point::ORIGIN)
+            .perform(&mut data);
+        assert_eq!(*data.selection(), range("A2", "D4"));
+    }
+
+    #[test]
+    fn clicking_a_row_number_selects_the_row() {
+        let data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(500.0, 100.0));
+        let view = || table(&data).show_row_numbers(true);
+
+        let node = ui.layout(view());
+        let gutter = node.children()[0].bounds().x;
+        assert!(gutter > 0.0);
+
+        let outcome = ui.event(view(), press(), Point::new(gutter / 2.0, 50.0));
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::Select(selected)] if *selected == range("A3", "D3")
+        ));
+    }
+
+    #[test]
+    fn hovering_publishes_once_per_cell() {
+        let data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        let view = || table(&data).on_hover(Message::Hover);
+        let hovered = |outcome: Outcome| -> Vec<Option<Address>> {
+            outcome
+                .messages
+                .into_iter()
+                .filter_map(|message| match message {
+                    Message::Hover(cell) => Some(cell),
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let mut moves = Vec::new();
+        for position in [
+            Point::new(150.0, 30.0),
+            Point::new(160.0, 35.0),
+            Point::new(250.0, 35.0),
+            Point::new(450.0, 35.0),
+        ] {
+            moves.extend(hovered(ui.event(view(), moved(position), position)));
+        }
+
+        assert_eq!(
+            moves,
+            [
+                Some("B2".parse().unwrap()),
+                Some("C2".parse().unwrap()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn resizing_a_selected_column_resizes_the_others() {
+        let mut data = content("A1:E5");
+        data.set_column_width(3, 60.0);
+        data.select_range(range("B1", "D5"));
+        // kept at their own sizes, so the divider follows the cursor
+        let mut ui = Ui::new(Size::new(600.0, 100.0));
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).scrollable(true)
+        }
+
+        ui.event(view(&data), press(), Point::new(300.0, 10.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(320.0, 10.0)),
+            Point::new(320.0, 10.0),
+        );
+        let outcome = ui
+            .event(view(&data), release(), Point::new(320.0, 10.0))
+            .perform(&mut data);
+
+        let deltas: Vec<_> = outcome
+            .actions()
+            .into_iter()
+            .map(|action| match action {
+                Action::ResizeDivider(Axis::Column, column, delta) => (*column, *delta),
+                action => panic!("unexpected {action:?}"),
+            })
+            .collect();
+        assert_eq!(deltas.len(), 3);
+        for column in 1..=3 {
+            assert!(deltas.contains(&(column, 120.0 - data_width(column))));
+        }
+        assert_eq!(data.column_sizes(), [100.0, 120.0, 120.0, 120.0, 100.0]);
+
+        fn data_width(column: usize) -> f32 {
+            [100.0, 100.0, 100.0, 60.0][column]
+        }
+    }
+
+    #[test]
+    fn state_follows_taken_rows_and_columns() {
+        let mut data = content("A1:C3");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        ui.layout(table(&data));
+
+        data.take_row(0);
+        data.take_column(2);
+        let node = ui.layout(table(&data));
+
+        let region = &ui.state().region;
+        assert_eq!((region.column_count, region.row_count), (2, 2));
+        assert_eq!(node.children().len(), 4);
+    }
+
+    #[test]
+    fn arrows_follow_the_wrap_policy_at_every_edge() {
+        use keyboard::key::Named::{ArrowDown, ArrowLeft, ArrowRight, ArrowUp};
+
+        let cases = [
+            // from, key, clamp, wrap row, wrap table
+            ("C2", ArrowRight, "C2", "A3", "A3"),
+            ("C3", ArrowRight, "C3", "C3", "A1"),
+            ("A2", ArrowLeft, "A2", "C1", "C1"),
+            ("A1", ArrowLeft, "A1", "A1", "C3"),
+            ("B3", ArrowDown, "B3", "C1", "C1"),
+            ("C3", ArrowDown, "C3", "C3", "A1"),
+            ("B1", ArrowUp, "B1", "A3", "A3"),
+            ("A1", ArrowUp, "A1", "A1", "C3"),
+        ];
+
+        for (index, wrap) in [Wrap::Clamp, Wrap::WrapRow, Wrap::WrapTable]
+            .into_iter()
+            .enumerate()
+        {
+            let mut data = content("A1:C3");
+            let mut ui = Ui::new(Size::new(300.0, 60.0));
+            ui.event(table(&data).wrap(wrap), press(), Point::new(150.0, 30.0))
+                .perform(&mut data);
+
+            for (from, named, clamp, wrap_row, wrap_table) in cases {
+                data.select_cell(from.parse().unwrap());
+                ui.event(
+                    table(&data).wrap(wrap),
+                    key(named, keyboard::Modifiers::empty()),
+                    Point::ORIGIN,
+                )
+                .perform(&mut data);
+
+                let expected = [clamp, wrap_row, wrap_table][index];
+                assert_eq!(
+                    *data.selection(),
+                    cell(expected),
+                    "{named:?} from {from} with {wrap:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn inserting_a_row_keeps_the_focus() {
+        let mut data = content("A1:C3");
+        let mut ui = Ui::new(Size::new(300.0, 100.0));
+
+        ui.event(table(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        ui.event(table(&data), release(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        assert!(ui.state().is_focused());
+        data.select_cell("B2".parse().unwrap());
+
+        data.perform(Action::InsertRow(0));
+        ui.layout(table(&data));
+        assert_eq!(ui.state().region.row_count, 4);
+        assert!(ui.state().is_focused());
+
+        let outcome = ui
+            .event(
+                table(&data),
+                key(
+                    keyboard::key::Named::ArrowDown,
+                    keyboard::Modifiers::empty(),
+                ),
+                Point::ORIGIN,
+            )
+            .perform(&mut data);
+        assert!(matches!(
+            outcome.actions()[..],
+            [Action::MoveSelection(Motion::Down)]
+        ));
+        assert_eq!(*data.selection(), cell("B4"));
+    }
+
+    #[test]
+    fn a_resized_column_keeps_its_width_when_the_content_is_dirty() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(600.0, 100.0));
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).scrollable(true)
+        }
+        let width = |node: &layout::Node| node.children()[0].bounds().width;
+
+        ui.event(view(&data), press(), Point::new(100.0, 10.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(140.0, 10.0)),
+            Point::new(140.0, 10.0),
+        );
+        // mid-drag, the dragged width is laid over the data
+        data.internal().set_dirty();
+        assert_eq!(width(&ui.layout(view(&data))), 140.0);
+
+        ui.event(view(&data), release(), Point::new(140.0, 10.0))
+            .perform(&mut data);
+        assert_eq!(data.column_sizes()[0], 140.0);
+
+        data.internal().set_dirty();
+        assert_eq!(width(&ui.layout(view(&data))), 140.0);
+        ui.event(
+            view(&data),
+            iced::Event::Window(iced::window::Event::RedrawRequested(
+                std::time::Instant::now(),
+            )),
+            Point::ORIGIN,
+        );
+        assert_eq!(width(&ui.layout(view(&data))), 140.0);
+
+        // the data has the last word, however the app clamps the resize
+        ui.event(view(&data), press(), Point::new(140.0, 10.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(180.0, 10.0)),
+            Point::new(180.0, 10.0),
+        );
+        ui.event(view(&data), release(), Point::new(180.0, 10.0));
+        data.set_column_width(0, 150.0);
+        assert_eq!(width(&ui.layout(view(&data))), 150.0);
+    }
+
+    // A renderer keeping the parts of the quads it fills that show, that is
+    // translated and clipped to their layers as a real renderer does, along
+    // with what they are filled with and the colors of the text it draws
+    #[derive(Default)]
+    struct QuadRenderer {
+        quads: Vec<Rectangle>,
+        fills: Vec<Background>,
+        text_colors: Vec<Color>,
+        layers: Vec<Rectangle>,
+        translations: Vec<Vector>,
+    }
+
+    impl Renderer for QuadRenderer {
+        fn start_layer(&mut self, bounds: Rectangle) {
+            let clip = match self.layers.last() {
+                Some(clip) => clip.intersection(&bounds).unwrap_or_default(),
+                None => bounds,
+            };
+            self.layers.push(clip);
+        }
+
+        fn end_layer(&mut self) {
+            self.layers.pop();
+        }
+
+        fn start_transformation(&mut self, transformation: iced::Transformation) {
+            let offset = self.translations.last().copied().unwrap_or_default();
+            self.translations
+                .push(offset + transformation.translation());
+        }
+
+        fn end_transformation(&mut self) {
+            self.translations.pop();
+        }
+
+        fn fill_quad(&mut self, quad: renderer::Quad, background: impl Into<Background>) {
+            let offset = self.translations.last().copied().unwrap_or_default();
+            let bounds = quad.bounds + offset;
+            let shown = match self.layers.last() {
+                Some(clip) => clip.intersection(&bounds),
+                None => Some(bounds),
+            };
+            if let Some(shown) = shown {
+                self.quads.push(shown);
+                self.fills.push(background.into());
+            }
+        }
+
+        fn clear(&mut self) {}
+    }
+
+    impl text::Renderer for QuadRenderer {
+        type Font = iced::Font;
+        type Paragraph = ();
+        type Editor = ();
+
+        const ICON_FONT: iced::Font = iced::Font::DEFAULT;
+        const CHECKMARK_ICON: char = '0';
+        const ARROW_DOWN_ICON: char = '0';
+
+        fn default_font(&self) -> iced::Font {
+            iced::Font::default()
+        }
+
+        fn default_size(&self) -> iced::Pixels {
+            iced::Pixels(16.0)
+        }
+
+        fn fill_paragraph(&mut self, _: &(), _: Point, color: Color, _: Rectangle) {
+            self.text_colors.push(color);
+        }
+
+        fn fill_editor(&mut self, _: &(), _: Point, _: Color, _: Rectangle) {}
+
+        fn fill_text(&mut self, _: text::Text, _: Point, _: Color, _: Rectangle) {}
+    }
+
+    // The quads drawn by a table laid out in the given bounds, of which only
+    // the viewport is in view
+    fn quads(
+        data: &Content,
+        bounds: Size,
+        viewport: Rectangle,
+        scrollable: bool,
+    ) -> Vec<Rectangle> {
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .scrollable(scrollable)
+                .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &viewport,
+        );
+        renderer.quads
+    }
+
+    #[test]
+    fn only_cells_with_a_background_are_filled() {
+        let plain = content("A1:C2");
+        let mut filled = plain.clone();
+        filled.insert(
+            "B2".parse::<Address>().unwrap(),
+            list::Cell {
+                background: Some(Background::Color(Color::from_rgb(1.0, 0.0, 0.0))),
+                ..list::Cell::default()
+            },
+        );
+        let bounds = Size::new(300.0, 40.0);
+        let view = Rectangle::with_size(bounds);
+        let count = |data: &Content, cell: Rectangle| {
+            self::quads(data, bounds, view, false)
+                .into_iter()
+                .filter(|quad| *quad == cell)
+                .count()
+        };
+        let cell = |x: f32, y: f32| Rectangle::new(Point::new(x, y), Size::new(100.0, 20.0));
+
+        assert_eq!(
+            count(&filled, cell(100.0, 20.0)),
+            count(&plain, cell(100.0, 20.0)) + 1
+        );
+        for other in [cell(0.0, 20.0), cell(200.0, 20.0), cell(100.0, 0.0)] {
+            assert_eq!(count(&filled, other), count(&plain, other));
+        }
+    }
+
+    #[test]
+    fn only_rows_given_a_background_are_filled() {
+        let data = content("A1:C4");
+        let bounds = Size::new(300.0, 80.0);
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .row_background(|row| {
+                    (row % 2 == 1).then_some(Background::Color(Color::from_rgb(0.9, 0.9, 0.9)))
+                })
+                .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(bounds),
+        );
+
+        let row =
+            |row: usize| Rectangle::new(Point::new(0.0, row as f32 * 20.0), Size::new(300.0, 20.0));
+        for (index, striped) in [(0, false), (1, true), (2, false), (3, true)] {
+            assert_eq!(renderer.quads.contains(&row(index)), striped, "row {index}");
+        }
+    }
+
+    #[test]
+    fn cell_borders_are_snapped_to_device_pixels() {
+        let mut data = content("A1:B1").with_column_widths(vec![33.3, 50.0]);
+        data.insert(
+            Address::new(1, 0),
+            list::Cell {
+                border: Some(Border {
+                    width: 1.0,
+                    ..Border::default()
+                }),
+                ..list::Cell::default()
+            },
+        );
+        let bounds = Size::new(83.3, 20.0);
+
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .scale_factor(1.25)
+                .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(bounds),
+        );
+
+        // the border of B1 starts on the device pixel nearest to 33.3
+        let border = renderer
+            .quads
+            .iter()
+            .find(|quad| (quad.x - 33.3).abs() < 0.5 && quad.width > 40.0)
+            .expect("border drawn");
+        assert_eq!(border.x, 42.0 / 1.25);
+        for edge in [border.x, border.y, border.width, border.height] {
+            let pixels = edge * 1.25;
+            assert!((pixels - pixels.round()).abs() < 1e-3, "{border:?}");
+        }
+    }
+
+    #[test]
+    fn a_shrunk_cell_is_filled_over_its_whole_cell() {
+        let mut data = content("A1:B1");
+        data.set_column_type(1, ColumnType::Integer);
+        let fill = Background::Color(Color::from_rgb(1.0, 0.0, 0.0));
+        data.insert(
+            Address::new(1, 0),
+            list::Cell {
+                content: "1".to_string(),
+                border: Some(Border::default().width(1.0)),
+                background: Some(fill),
+            },
+        );
+
+        // the text shrinks to its content, aligned right in the column
+        let element: Element<'_, Message, Theme, QuadRenderer> = Table::new(&data, |_, cell| {
+            iced::widget::text(cell.content.clone()).into()
+        })
+        .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let bounds = Size::new(200.0, 20.0);
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+        let child = Layout::new(&node).children().nth(1).unwrap().bounds();
+        assert!(child.width < 100.0);
+
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(bounds),
+        );
+
+        let whole = Rectangle::new(Point::new(100.0, 0.0), Size::new(100.0, 20.0));
+        assert!(renderer.quads.contains(&whole));
+        // the left border runs down the left edge of the cell
+        let left = Rectangle::new(whole.position(), Size::new(1.0, whole.height));
+        assert!(renderer.quads.contains(&left));
+    }
+
+    #[test]
+    fn frozen_rows_stay_at_the_top_of_the_viewport() {
+        let mut data = content("A1:C20");
+        let fill = Background::Color(Color::from_rgb(1.0, 0.0, 0.0));
+        for cell in ["B2", "C3", "B14"] {
+            data.insert(
+                cell.parse::<Address>().unwrap(),
+                list::Cell {
+                    background: Some(fill),
+                    ..list::Cell::default()
+                },
+            );
+        }
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .freeze_rows(3)
+                .into();
+        let mut tree = Tree::new(&element);
+        let node = element.as_widget().layout(
+            &mut tree,
+            &QuadRenderer::default(),
+            &layout::Limits::new(Size::ZERO, Size::new(300.0, 400.0)),
+        );
+
+        // the table is scrolled within a page, by the given height
+        let draw = |scrolled: f32| {
+            let mut renderer = QuadRenderer::default();
+            element.as_widget().draw(
+                &tree,
+                &mut renderer,
+                &Theme::Light,
+                &renderer::Style {
+                    text_color: Color::BLACK,
+                },
+                Layout::new(&node),
+                mouse::Cursor::Unavailable,
+                &Rectangle::new(Point::new(0.0, scrolled), Size::new(300.0, 100.0)),
+            );
+            renderer.quads
+        };
+        let cell = |x: f32, y: f32| Rectangle::new(Point::new(x, y), Size::new(100.0, 20.0));
+
+        let quads = draw(0.0);
+        assert!(quads.contains(&cell(100.0, 20.0)));
+        assert!(quads.contains(&cell(200.0, 40.0)));
+
+        // the frozen rows move down with the viewport, over the body
+        let quads = draw(200.0);
+        assert!(quads.contains(&cell(100.0, 220.0)));
+        assert!(quads.contains(&cell(200.0, 240.0)));
+        assert!(!quads.contains(&cell(100.0, 20.0)));
+        // while the body below them scrolls as usual
+        assert!(quads.contains(&cell(100.0, 260.0)));
+    }
+
+    #[test]
+    fn the_background_is_drawn_first_and_the_selection_last() {
+        let [background, stripe, cell, lines, selection, active] =
+            [0.1, 0.2, 0.3, 0.4, 0.5, 0.6].map(|shade| Color::from_rgb(shade, shade, shade));
+
+        let mut data = content("A1:C3");
+        data.insert(
+            Address::new(1, 1),
+            list::Cell {
+                background: Some(cell.into()),
+                ..list::Cell::default()
+            },
+        );
+        data.select_range(range("A1", "B2"));
+        let bounds = Size::new(300.0, 60.0);
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .row_background(move |row| (row == 2).then_some(stripe.into()))
+                .style(move |theme, status| {
+                    let mut style = theme::default(theme, status);
+                    style.background = background.into();
+                    style.row_lines.color = lines;
+                    style.column_lines.color = lines;
+                    style.selection.fill = selection;
+                    style.selection.active.fill = active;
+                    style
+                })
+                .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(bounds),
+        );
+
+        let drawn = |color: Color| {
+            let fills = &renderer.fills;
+            let first = fills.iter().position(|fill| *fill == color.into());
+            let last = fills.iter().rposition(|fill| *fill == color.into());
+            first.zip(last).expect("drawn")
+        };
+        let order = [background, stripe, cell, lines, active].map(drawn);
+        for pair in order.windows(2) {
+            // every quad of a part is drawn before any of the next part
+            assert!(pair[0].1 < pair[1].0, "{order:?}");
+        }
 
-                        let terminate = apply_binding::<Data, T, K, R, Message, Theme>(
-                            binding,
-                            layout.bounds(),
-                            self.data,
-                            state,
-                            on_edit,
-                            self.on_instruction.as_deref(),
-                            clipboard,
-                            shell,
-                        );
+        // the selected cells are filled along with the cells, over their own
+        // background
+        let (first, last) = drawn(selection);
+        assert!(order[1].1 < first && last < order[3].0, "{order:?}");
+        assert_eq!(renderer.fills[order[2].0 + 1], selection.into());
+    }
 
-                        if terminate {
-                            // ?
-                            return event::Status::Ignored;
-                        }
+    #[test]
+    fn the_default_cell_style_fills_the_selected_cells_but_the_active_one() {
+        let mut data = content("A1:C3");
+        // selected from the bottom right, which stays the active cell
+        data.select_range(range("C2", "A1"));
+        // a table without `on_edit` is disabled
+        let fill: Background = theme::default(&Theme::Light, Status::Disabled)
+            .selection
+            .fill
+            .into();
+
+        let draw = |cell_style: Option<fn(&Theme, CellStatus) -> CellStyle>| {
+            let mut table = Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into());
+            if let Some(cell_style) = cell_style {
+                table = table.cell_style(cell_style);
+            }
+            let element: Element<'_, Message, Theme, QuadRenderer> = table.into();
+            let mut tree = Tree::new(&element);
+            let mut renderer = QuadRenderer::default();
+            let bounds = Size::new(300.0, 60.0);
+            let node = element.as_widget().layout(
+                &mut tree,
+                &renderer,
+                &layout::Limits::new(Size::ZERO, bounds),
+            );
+            element.as_widget().draw(
+                &tree,
+                &mut renderer,
+                &Theme::Light,
+                &renderer::Style {
+                    text_color: Color::BLACK,
+                },
+                Layout::new(&node),
+                mouse::Cursor::Unavailable,
+                &Rectangle::with_size(bounds),
+            );
+            renderer
+                .quads
+                .into_iter()
+                .zip(renderer.fills)
+                .filter(|(_, background)| *background == fill)
+                .map(|(quad, _)| quad)
+                .collect::<Vec<_>>()
+        };
+
+        let filled = draw(None);
+        assert_eq!(filled.len(), 5);
+        assert!(!filled.contains(&Rectangle::new(
+            Point::new(200.0, 20.0),
+            Size::new(100.0, 20.0)
+        )));
+
+        // a style of their own replaces the fill
+        assert!(draw(Some(|_, _| CellStyle::default())).is_empty());
+        let kept = draw(Some(|theme, status| {
+            theme::default_cell(&theme::default(theme, status.status), status)
+        }));
+        assert_eq!(kept, filled);
+    }
+
+    #[test]
+    fn plain_text_cells_take_the_themed_text_color() {
+        let data = content("A1:B1");
+        let themed = Color::from_rgb(0.8, 0.1, 0.1);
+        let own = Color::from_rgb(0.1, 0.1, 0.8);
+
+        for (inherit, expected) in [(true, themed), (false, Color::BLACK)] {
+            // the second cell keeps a color of its own
+            let element: Element<'_, Message, Theme, QuadRenderer> =
+                Table::new(&data, move |cell: Address, _| {
+                    let text = iced::widget::text(cell.to_string());
+                    if cell.x() == 1 {
+                        text.color(own).into()
+                    } else {
+                        text.into()
                     }
-                }
+                })
+                .inherit_text_color(inherit)
+                .style(move |theme, status| Style {
+                    value: themed,
+                    ..theme::default(theme, status)
+                })
+                .into();
+            let mut tree = Tree::new(&element);
+            let mut renderer = QuadRenderer::default();
+            let bounds = Size::new(200.0, 20.0);
+            let node = element.as_widget().layout(
+                &mut tree,
+                &renderer,
+                &layout::Limits::new(Size::ZERO, bounds),
+            );
+            element.as_widget().draw(
+                &tree,
+                &mut renderer,
+                &Theme::Light,
+                &renderer::Style {
+                    text_color: Color::BLACK,
+                },
+                Layout::new(&node),
+                mouse::Cursor::Unavailable,
+                &Rectangle::with_size(bounds),
+            );
+
+            assert_eq!(renderer.text_colors, [expected, own]);
+        }
+    }
+
+    #[test]
+    fn no_quad_shows_outside_of_the_table() {
+        let short = content("A1:C3");
+        let long = content("A1:C10");
+        let bounds = Size::new(300.0, 60.0);
+        let view = Rectangle::with_size(bounds);
+
+        // including a selection running past the table, as an app may set
+        for selection in [
+            range("A1", "C3"),
+            range("C3", "C3"),
+            range("B2", "C3"),
+            range("B2", "E9"),
+            range("A8", "C10"),
+        ] {
+            let mut quads = Vec::new();
+            // the rows of the long table past the bottom are scrolled out of
+            // view
+            for (data, scrollable) in [(&short, false), (&long, true)] {
+                let mut data = data.clone();
+                data.select_range(selection);
+                quads.extend(self::quads(&data, bounds, view, scrollable));
+            }
+
+            assert!(!quads.is_empty());
+            for quad in quads {
+                assert!(
+                    view.intersection(&quad) == Some(quad),
+                    "{quad:?} shows outside of {view:?} for {selection}"
+                );
             }
         }
+    }
 
-        for ((child, state), child_layout) in self
-            .cells
-            .iter_mut()
-            .zip(tree.children.iter_mut())
-            .zip(layout.children())
+    // A clipboard holding what was last written to it
+    #[derive(Default)]
+    struct TestClipboard(Option<String>);
+
+    impl Clipboard for TestClipboard {
+        fn read(&self, _kind: clipboard::Kind) -> Option<String> {
+            self.0.clone()
+        }
+
+        fn write(&mut self, _kind: clipboard::Kind, contents: String) {
+            self.0 = Some(contents);
+        }
+    }
+
+    #[test]
+    fn copy_and_paste_address_the_right_cells() {
+        // each cell holds its own address, on more columns than rows
+        let mut data = content("A1:C2");
+        for cell in range("A1", "C2").iter() {
+            data.insert(cell, cell.to_string());
+        }
+        data.set_text_conversion(|cell| cell.content.clone(), |text| list::Cell::from(text));
+        let mut ui = Ui::new(Size::new(300.0, 40.0));
+        let mut clipboard = TestClipboard::default();
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).use_system_clipboard(true)
+        }
+        let command = |c: &str| {
+            let key = keyboard::Key::Character(c.into());
+            iced::Event::Keyboard(keyboard::Event::KeyPressed {
+                key: key.clone(),
+                modified_key: key,
+                physical_key: keyboard::key::Physical::Unidentified(
+                    keyboard::key::NativeCode::Unidentified,
+                ),
+                location: keyboard::Location::Standard,
+                modifiers: keyboard::Modifiers::COMMAND,
+                text: None,
+            })
+        };
+
+        ui.event(view(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        data.select_range(range("B1", "C2"));
+        ui.event_with(view(&data), command("c"), Point::ORIGIN, &mut clipboard);
+        assert_eq!(clipboard.0.as_deref(), Some("B1\tC1\nB2\tC2"));
+
+        data.select_cell("A1".parse().unwrap());
+        ui.event_with(view(&data), command("v"), Point::ORIGIN, &mut clipboard)
+            .perform(&mut data);
+        let text = |cell: &str| {
+            data.get(cell.parse::<Address>().unwrap())
+                .unwrap()
+                .content
+                .clone()
+        };
+        assert_eq!(
+            [["A1", "B1", "C1"], ["A2", "B2", "C2"]].map(|row| row.map(text)),
+            [["B1", "C1", "C1"], ["B2", "C2", "C2"]]
+        );
+    }
+
+    // A table whose rows don't all have every cell, and with a stray cell
+    // past its last column, yielded row by row
+    struct Ragged {
+        data: Content,
+        stray: list::Cell,
+    }
+
+    impl Ragged {
+        const CELLS: [&'static str; 7] = ["A1", "B1", "C1", "D1", "A2", "B2", "A3"];
+    }
+
+    impl Tabular<list::Cell, Address, Range> for Ragged {
+        fn from_range(range: &Range) -> Self {
+            Self {
+                data: Content::with_range(*range),
+                stray: list::Cell::default(),
+            }
+        }
+
+        fn range(&self) -> &Range {
+            self.data.range()
+        }
+
+        fn get(&self, cell: impl Into<Address>) -> Option<&list::Cell> {
+            self.data.get(cell)
+        }
+
+        fn get_mut(&mut self, cell: impl Into<Address>) -> Option<&mut list::Cell> {
+            self.data.get_mut(cell)
+        }
+
+        fn insert(&mut self, cell: impl Into<Address>, item: impl Into<list::Cell>) {
+            self.data.insert(cell, item);
+        }
+
+        // writes in place, leaving it to the edit to mark the cells dirty
+        fn write_cell_text(&mut self, cell: Address, text: &str) {
+            if let Some(item) = self.data.get_mut(cell) {
+                item.content = text.to_string();
+            }
+        }
+
+        fn row_count(&self) -> usize {
+            self.data.row_count()
+        }
+
+        fn column_count(&self) -> usize {
+            self.data.column_count()
+        }
+
+        fn column_sizes(&self) -> &[f32] {
+            self.data.column_sizes()
+        }
+
+        fn row_sizes(&self) -> &[f32] {
+            self.data.row_sizes()
+        }
+
+        fn column_sizes_mut(&mut self) -> &mut Vec<f32> {
+            self.data.column_sizes_mut()
+        }
+
+        fn row_sizes_mut(&mut self) -> &mut Vec<f32> {
+            self.data.row_sizes_mut()
+        }
+
+        fn internal(&self) -> &Internal {
+            self.data.internal()
+        }
+
+        fn iter(&self) -> Box<dyn Iterator<Item = (Address, &list::Cell)> + '_> {
+            Box::new(Self::CELLS.into_iter().map(|cell| {
+                let cell = cell.parse().unwrap();
+                (cell, self.data.get(cell).unwrap_or(&self.stray))
+            }))
+        }
+
+        fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (Address, &mut list::Cell)> + '_> {
+            self.data.iter_mut()
+        }
+
+        fn iter_relative(&self) -> Box<dyn Iterator<Item = (Address, &list::Cell)> + '_> {
+            self.iter()
+        }
+
+        fn iter_relative_mut(
+            &mut self,
+        ) -> Box<dyn Iterator<Item = (Address, &mut list::Cell)> + '_> {
+            self.data.iter_relative_mut()
+        }
+
+        fn with_reference(
+            &mut self,
+            cell: impl Into<Address>,
+            f: impl Fn(&Address, &mut list::Cell),
+        ) {
+            self.data.with_reference(cell, f);
+        }
+
+        fn with_reference_range(&mut self, range: &Range, f: impl Fn(&Address, &mut list::Cell)) {
+            self.data.with_reference_range(range, f);
+        }
+
+        fn select_cell(&mut self, cell: Address) {
+            self.data.select_cell(cell);
+        }
+
+        fn select_range(&mut self, range: Range) {
+            self.data.select_range(range);
+        }
+
+        fn select_all(&mut self) {
+            self.data.select_all();
+        }
+
+        fn selection(&self) -> &Range {
+            self.data.selection()
+        }
+
+        fn selection_mut<'a>(&'a mut self) -> &'a mut Range
+        where
+            Address: 'a,
         {
-            child.1.as_widget_mut().on_event(
-                state,
-                event.clone(),
-                child_layout,
-                raw_cursor,
-                renderer,
-                clipboard,
-                shell,
-                viewport,
+            self.data.selection_mut()
+        }
+    }
+
+    #[test]
+    fn a_lazy_table_builds_only_the_cells_in_view() {
+        type Built = std::rc::Rc<std::cell::RefCell<Vec<Address>>>;
+
+        fn view(data: &Content, built: Built) -> TestTable<'_> {
+            Table::lazy(data, move |cell, _| {
+                built.borrow_mut().push(cell);
+                Space::new(Length::Fill, Length::Fill).into()
+            })
+            .scrollable(true)
+            .on_action(Message::Action)
+        }
+
+        // 10 columns of 10,000 rows, 3 columns and 5 rows of which are in view
+        let data = content("A1:J10000");
+        let built = Built::default();
+        let mut ui = Ui::new(Size::new(300.0, 100.0));
+        let within = Point::new(150.0, 50.0);
+
+        // the cells in view are only known once the table has seen its bounds
+        ui.event(view(&data, built.clone()), redraw(), within);
+        assert!(built.borrow().is_empty());
+
+        let columns = 0..3 + LAZY_OVERSCAN;
+        let rows = 0..5 + LAZY_OVERSCAN;
+        ui.event(view(&data, built.clone()), redraw(), within);
+        let cells = built.take();
+        assert_eq!(cells.len(), columns.len() * rows.len());
+        assert!(cells
+            .iter()
+            .all(|cell| columns.contains(&cell.x()) && rows.contains(&cell.y())));
+
+        // scrolled down by 100 rows
+        let scroll = iced::Event::Mouse(mouse::Event::WheelScrolled {
+            delta: mouse::ScrollDelta::Pixels { x: 0.0, y: -2000.0 },
+        });
+        ui.event(view(&data, built.clone()), scroll, within);
+        built.take();
+
+        // the row ending right at the top of the view counts as in view
+        let rows = 99 - LAZY_OVERSCAN..105 + LAZY_OVERSCAN;
+        ui.event(view(&data, built.clone()), redraw(), within);
+        let cells = built.take();
+        assert_eq!(cells.len(), columns.len() * rows.len());
+        assert!(cells
+            .iter()
+            .all(|cell| columns.contains(&cell.x()) && rows.contains(&cell.y())));
+    }
+
+    #[test]
+    fn pasting_marks_the_cells_dirty() {
+        let mut data = Ragged::from_range(&range("A1", "C3"));
+        data.internal().take_dirty();
+
+        data.perform(Action::Edit(Edit::Paste {
+            anchor: Address::new(1, 1),
+            rows: vec![vec!["pasted".to_string()]],
+        }));
+        assert_eq!(data.get(Address::new(1, 1)).unwrap().content, "pasted");
+        assert!(data.internal().take_dirty().data);
+    }
+
+    #[test]
+    fn a_range_is_iterated_within_the_table() {
+        let mut data = Ragged::from_range(&range("A1", "C3"));
+        data.insert(Address::new(1, 1), list::Cell::from("b"));
+
+        // a range of the whole sheet doesn't go through every cell of it
+        let cells: Vec<_> = data
+            .iter_range(&range("B2", "XFD1048576"))
+            .map(|(cell, _)| cell.to_string())
+            .collect();
+        assert_eq!(cells, ["B2", "B3", "C2", "C3"]);
+
+        let (cell, item) = data.iter_range(&range("A1", "XFD1048576")).nth(4).unwrap();
+        assert_eq!((cell, item.content.as_str()), (Address::new(1, 1), "b"));
+    }
+
+    #[test]
+    fn ragged_cells_are_placed_by_their_reference() {
+        let data = Ragged::from_range(&range("A1", "C3"));
+        let element: Element<'_, Message, Theme, ()> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into()).into();
+        let mut tree = Tree::new(&element);
+        let node = element.as_widget().layout(
+            &mut tree,
+            &(),
+            &layout::Limits::new(Size::ZERO, Size::new(300.0, 60.0)),
+        );
+
+        let placed: Vec<_> = node
+            .children()
+            .iter()
+            .map(|child| {
+                let bounds = child.bounds();
+                (bounds.x, bounds.y, bounds.width, bounds.height)
+            })
+            .collect();
+        assert_eq!(
+            placed,
+            [
+                (0.0, 0.0, 100.0, 20.0),
+                (100.0, 0.0, 100.0, 20.0),
+                (200.0, 0.0, 100.0, 20.0),
+                // past the last column, so it takes no room
+                (300.0, 0.0, 0.0, 0.0),
+                (0.0, 20.0, 100.0, 20.0),
+                (100.0, 20.0, 100.0, 20.0),
+                (0.0, 40.0, 100.0, 20.0),
+            ]
+        );
+
+        // and drawn without looking for the missing cells
+        element.as_widget().draw(
+            &tree,
+            &mut (),
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Available(Point::new(250.0, 50.0)),
+            &Rectangle::with_size(Size::new(300.0, 60.0)),
+        );
+    }
+
+    #[test]
+    fn a_fixed_or_shrunk_table_doesnt_expand_in_a_row() {
+        let data = content("A1:C3");
+        let lay_out = |table: TestTable<'_>| {
+            let row: Element<'_, Message, Theme, ()> =
+                iced::widget::row![table, Space::with_width(Length::Fill)].into();
+            let mut tree = Tree::new(&row);
+            let node = row.as_widget().layout(
+                &mut tree,
+                &(),
+                &layout::Limits::new(Size::ZERO, Size::new(800.0, 100.0)),
+            );
+            let [table, space] = node.children() else {
+                panic!("expected two children");
+            };
+            (table.bounds().size(), space.bounds().width)
+        };
+
+        assert_eq!(
+            lay_out(table(&data).with_width(Length::Fixed(250.0))),
+            (Size::new(250.0, 100.0), 550.0)
+        );
+        assert_eq!(
+            lay_out(
+                table(&data)
+                    .with_width(Length::Shrink)
+                    .with_height(Length::Shrink)
+            ),
+            (Size::new(300.0, 60.0), 500.0)
+        );
+        // a filling table shares the row
+        assert_eq!(lay_out(table(&data)), (Size::new(400.0, 100.0), 400.0));
+    }
+
+    #[test]
+    fn each_scale_mode_lays_out_its_own_cumulative_sizes() {
+        let cases = [
+            (
+                ScaleMode::Stretch,
+                [200.0, 300.0],
+                [40.0, 80.0, 120.0],
+                (2.0, 2.0),
+            ),
+            (
+                ScaleMode::Natural,
+                [100.0, 150.0],
+                [20.0, 40.0, 60.0],
+                (1.0, 1.0),
+            ),
+            (
+                ScaleMode::FitWidth,
+                [200.0, 300.0],
+                [20.0, 40.0, 60.0],
+                (2.0, 1.0),
+            ),
+            (
+                ScaleMode::FitHeight,
+                [100.0, 150.0],
+                [40.0, 80.0, 120.0],
+                (1.0, 2.0),
+            ),
+        ];
+
+        for (scale_mode, x, y, scale) in cases {
+            let mut region = Region::new(
+                &[100.0, 50.0],
+                &[20.0, 20.0, 20.0],
+                Vec::new(),
+                Vec::new(),
+                Vec::new(),
+                Size::ZERO,
+                3,
+                2,
+            );
+            region.scale_mode = scale_mode;
+            region.scale_to_bounds(Size::new(300.0, 120.0), Size::ZERO);
+
+            assert_eq!(region.cumulative_x, x, "{scale_mode:?}");
+            assert_eq!(region.cumulative_y, y, "{scale_mode:?}");
+            assert_eq!(
+                (region.scale_factor_x, region.scale_factor_y),
+                scale,
+                "{scale_mode:?}"
             );
         }
+    }
 
-        event::Status::Ignored
+    #[test]
+    fn scaling_skips_missing_raw_sizes() {
+        let mut region = Region::new(
+            &[100.0, 100.0],
+            &[20.0, 20.0, 20.0],
+            Vec::new(),
+            vec![None, Some(1), None],
+            vec![Sizing::Fixed(100.0), Sizing::FillPortion(1)],
+            Size::ZERO,
+            3,
+            2,
+        );
+        // e.g. sizes not yet caught up with the counts
+        region.raw_rows.pop();
+        region.raw_columns.pop();
+        region.scale_to_bounds(Size::new(300.0, 100.0), Size::ZERO);
+
+        assert_eq!(region.scaled_columns, [100.0, 200.0]);
+        assert_eq!(region.scaled_rows, [20.0, 80.0, 0.0]);
     }
 
-    fn operate(
-        &self,
-        tree: &mut Tree,
-        layout: Layout<'_>,
-        renderer: &Renderer,
-        operation: &mut dyn widget::Operation,
-    ) {
-        let state = tree.state.downcast_mut::<State>();
-        operation.focusable(state, self.id.as_ref());
-        operation.custom(state, self.id.as_ref());
-        operation.container(self.id.as_ref(), layout.bounds(), &mut |operation| {
-            self.cells
-                .iter()
-                .zip(&mut tree.children)
-                .zip(layout.children())
-                .for_each(|((child, state), child_layout)| {
-                    child
-                        .1
-                        .as_widget()
-                        .operate(state, child_layout, renderer, operation);
-                });
-        })
+    #[test]
+    fn right_to_left_finds_the_mirrored_cells() {
+        let mut region = Region::new(
+            &[100.0, 100.0, 100.0],
+            &[20.0],
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            Size::ZERO,
+            1,
+            3,
+        );
+        region.rtl = true;
+        region.scale_to_bounds(Size::new(300.0, 20.0), Size::ZERO);
+
+        let columns = [1.0, 150.0, 299.0].map(|x| region.find_cell(Point::new(x, 10.0)).column);
+        assert_eq!(columns, [2, 1, 0]);
     }
-}
 
-#[derive(Debug)]
-struct State {
-    last_click: Option<mouse::Click>,
-    drag_click: Option<mouse::click::Kind>,
-    interaction: Interaction,
-    is_focused: bool,
+    #[test]
+    fn right_to_left_home_and_end_move_visually() {
+        let data = content("A1:C1");
+        let mut ui = Ui::new(Size::new(300.0, 20.0));
+        let rtl = |data| table(data).right_to_left(true);
+        ui.event(rtl(&data), press(), Point::new(150.0, 10.0));
 
-    region: Region,
-}
+        let none = keyboard::Modifiers::default();
+        for (named, motion) in [
+            (keyboard::key::Named::Home, Motion::End),
+            (keyboard::key::Named::End, Motion::Home),
+            (keyboard::key::Named::ArrowLeft, Motion::Right),
+        ] {
+            let outcome = ui.event(rtl(&data), key(named, none), Point::ORIGIN);
+            assert!(
+                matches!(
+                    outcome.actions()[..],
+                    [Action::MoveSelection(moved)] if *moved == motion
+                ),
+                "{named:?}"
+            );
+        }
+    }
 
-#[derive(Debug)]
-struct Region {
-    row_count: usize,
-    column_count: usize,
-    raw_rows: Vec<f32>,
-    raw_columns: Vec<f32>,
-    scaled_rows: Vec<f32>,
-    scaled_columns: Vec<f32>,
-    scale_factor_x: f32,
-    scale_factor_y: f32,
-    cumulative_x: Vec<f32>,
-    cumulative_y: Vec<f32>,
-    spacing: Size<f32>,
-}
+    #[test]
+    fn mixed_column_sizings_share_the_width() {
+        let mut data = content("A1:D2");
+        data.set_column_sizing(0, Sizing::Fixed(50.0));
+        data.set_column_sizing(1, Sizing::FillPortion(1));
+        data.set_column_sizing(2, Sizing::FillPortion(3));
+        data.set_column_width(3, 70.0);
+        let mut ui = Ui::new(Size::new(600.0, 40.0));
 
-impl Region {
-    const RESIZE_AREA: f32 = 4.0;
+        let node = ui.layout(table(&data));
+        // column by column, two rows each
+        let widths: Vec<_> = node
+            .children()
+            .iter()
+            .step_by(2)
+            .map(|child| child.bounds().width)
+            .collect();
+        assert_eq!(widths, [50.0, 120.0, 360.0, 70.0]);
 
-    fn new(
-        columns: &[f32],
-        rows: &[f32],
-        spacing: Size<f32>,
-        row_count: usize,
-        column_count: usize,
-    ) -> Self {
-        let raw_columns = columns.iter().map(|&w| w + spacing.width).collect();
-        let raw_rows = rows.iter().map(|&h| h + spacing.height).collect();
+        // dragging a filling column fixes it at the dragged width
+        ui.event(table(&data), press(), Point::new(170.0, 10.0));
+        ui.event(
+            table(&data),
+            moved(Point::new(200.0, 10.0)),
+            Point::new(200.0, 10.0),
+        );
+        ui.event(table(&data), release(), Point::new(200.0, 10.0))
+            .perform(&mut data);
+        assert!(matches!(data.column_sizing(1), Sizing::Fixed(width) if width == 150.0));
+    }
 
-        Self {
-            row_count,
-            column_count,
-            raw_columns,
-            raw_rows,
-            scaled_columns: vec![0.0; column_count],
-            scaled_rows: vec![0.0; row_count],
-            cumulative_x: vec![0.0; column_count],
-            cumulative_y: vec![0.0; row_count],
-            scale_factor_x: 1.0,
-            scale_factor_y: 1.0,
-            spacing,
+    #[test]
+    fn cell_padding_shrinks_the_children() {
+        let mut data = content("A1:B2");
+        data.set_column_width(1, 6.0);
+        let mut ui = Ui::new(Size::new(400.0, 40.0));
+        fn view(data: &Content, padding: Padding) -> TestTable<'_> {
+            table(data).scrollable(true).cell_padding(padding)
         }
+        let bounds = |node: layout::Node| -> Vec<_> {
+            node.children().iter().map(layout::Node::bounds).collect()
+        };
+
+        let plain = bounds(ui.layout(view(&data, Padding::ZERO)));
+        let padded = bounds(ui.layout(view(&data, Padding::from([2.0, 5.0]))));
+
+        assert_eq!(
+            plain[0],
+            Rectangle::new(Point::ORIGIN, Size::new(100.0, 20.0))
+        );
+        assert_eq!(
+            padded[0],
+            Rectangle::new(Point::new(5.0, 2.0), Size::new(90.0, 16.0))
+        );
+        assert_eq!(
+            padded[1],
+            Rectangle::new(Point::new(5.0, 22.0), Size::new(90.0, 16.0))
+        );
+        // a cell narrower than the padding leaves no negative size
+        assert_eq!(plain[2].width, 6.0);
+        assert_eq!(padded[2].width, 0.0);
+        assert!(padded[2].x >= plain[2].x && padded[2].x <= plain[2].x + 6.0);
+        assert_eq!(padded[2].height, 16.0);
+
+        // while clicks still cover the whole cell, padding included
+        let mut data = data;
+        ui.event(
+            view(&data, Padding::from([2.0, 5.0])),
+            press(),
+            Point::new(95.5, 30.0),
+        )
+        .perform(&mut data);
+        assert_eq!(*data.selection(), cell("A2"));
+    }
+
+    #[test]
+    fn status_follows_the_cursor_and_the_focus() {
+        let data = content("A1:B2");
+        let mut ui = Ui::new(Size::new(200.0, 40.0));
+        let bounds = Rectangle::with_size(Size::new(200.0, 40.0));
+        let over = mouse::Cursor::Available(Point::new(50.0, 10.0));
+        let away = mouse::Cursor::Available(Point::new(250.0, 10.0));
+        ui.layout(table(&data));
+
+        let status = |ui: &Ui, cursor| table(&data).status(ui.state(), cursor, bounds);
+        assert_eq!(status(&ui, away), Status::Unfocused);
+        assert_eq!(status(&ui, mouse::Cursor::Unavailable), Status::Unfocused);
+        assert_eq!(status(&ui, over), Status::Hovered { focused: false });
+
+        ui.event(table(&data), press(), Point::new(150.0, 30.0));
+        assert_eq!(status(&ui, away), Status::Focused);
+        assert_eq!(status(&ui, over), Status::Hovered { focused: true });
+
+        // a table that can't be edited is disabled, hovered or not
+        let disabled: TestTable<'_> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into());
+        assert_eq!(disabled.status(ui.state(), over, bounds), Status::Disabled);
     }
 
-    fn scale_to_bounds(&mut self, bounds: Size, spacing: Size<f32>) {
-        self.spacing = spacing;
-        let total_raw_width = self.total_raw_width();
-        let total_raw_height = self.total_raw_height();
+    // The cells that got each event, in order
+    type Log = std::rc::Rc<std::cell::RefCell<Vec<(Address, iced::Event)>>>;
 
-        // Scale columns including spacing
-        for (scaled, raw) in self.scaled_columns.iter_mut().zip(self.raw_columns.iter()) {
-            *scaled = (raw / total_raw_width) * bounds.width;
-        }
+    // A cell showing a pointer when hovered and logging the events it gets
+    struct Probe {
+        cell: Address,
+        log: Log,
+    }
 
-        // Scale rows including spacing
-        for (scaled, raw) in self.scaled_rows.iter_mut().zip(self.raw_rows.iter()) {
-            *scaled = (raw / total_raw_height) * bounds.height;
+    impl Widget<Message, Theme, ()> for Probe {
+        fn size(&self) -> Size<Length> {
+            Size::new(Length::Fill, Length::Fill)
         }
 
-        // Precompute cumulative positions
-        let mut x = 0.0;
-        for (i, width) in self.scaled_columns.iter().enumerate() {
-            x += width;
-            self.cumulative_x[i] = x;
+        fn layout(
+            &self,
+            _tree: &mut Tree,
+            _renderer: &(),
+            limits: &layout::Limits,
+        ) -> layout::Node {
+            layout::Node::new(limits.max())
         }
 
-        let mut y = 0.0;
-        for (i, height) in self.scaled_rows.iter().enumerate() {
-            y += height;
-            self.cumulative_y[i] = y;
+        fn draw(
+            &self,
+            _tree: &Tree,
+            _renderer: &mut (),
+            _theme: &Theme,
+            _style: &renderer::Style,
+            _layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+        ) {
         }
 
-        self.scale_factor_x = bounds.width / total_raw_width;
-        self.scale_factor_y = bounds.height / total_raw_height;
-    }
+        fn on_event(
+            &mut self,
+            _tree: &mut Tree,
+            event: iced::Event,
+            _layout: Layout<'_>,
+            _cursor: mouse::Cursor,
+            _renderer: &(),
+            _clipboard: &mut dyn Clipboard,
+            _shell: &mut Shell<'_, Message>,
+            _viewport: &Rectangle,
+        ) -> event::Status {
+            self.log.borrow_mut().push((self.cell, event));
+            event::Status::Ignored
+        }
 
-    fn total_raw_width(&self) -> f32 {
-        self.raw_columns.iter().sum()
+        fn mouse_interaction(
+            &self,
+            _tree: &Tree,
+            layout: Layout<'_>,
+            cursor: mouse::Cursor,
+            _viewport: &Rectangle,
+            _renderer: &(),
+        ) -> mouse::Interaction {
+            if cursor.is_over(layout.bounds()) {
+                mouse::Interaction::Pointer
+            } else {
+                mouse::Interaction::default()
+            }
+        }
     }
 
-    fn total_raw_height(&self) -> f32 {
-        self.raw_rows.iter().sum()
+    // A table of probes logging to the given log
+    fn probes<'a>(data: &'a Content, log: &Log) -> TestTable<'a> {
+        let log = log.clone();
+        Table::new(data, move |cell, _| {
+            Element::new(Probe {
+                cell,
+                log: log.clone(),
+            })
+        })
+        .on_action(Message::Action)
+        .on_instruction(Message::Instruction)
     }
 
-    // Get actual cell size (without spacing)
-    fn cell_size(&self, row: usize, col: usize) -> Size {
-        let width = self.scaled_columns.get(col).unwrap_or(&0.0) - self.spacing.width;
-        let height = self.scaled_rows.get(row).unwrap_or(&0.0) - self.spacing.height;
+    #[test]
+    fn the_cell_under_the_cursor_sets_the_mouse_interaction() {
+        let data = content("A1:C3");
+        let log = Log::default();
+        let mut ui = Ui::new(Size::new(300.0, 60.0));
 
-        Size::new(width.max(0.0), height.max(0.0))
+        let interaction =
+            |ui: &mut Ui, position| ui.mouse_interaction(probes(&data, &log), position);
+        assert_eq!(
+            interaction(&mut ui, Point::new(150.0, 30.0)),
+            mouse::Interaction::Pointer
+        );
+        // dividers come first
+        assert_eq!(
+            interaction(&mut ui, Point::new(101.0, 30.0)),
+            mouse::Interaction::ResizingHorizontally
+        );
+        assert_eq!(
+            interaction(&mut ui, Point::new(150.0, 41.0)),
+            mouse::Interaction::ResizingVertically
+        );
+        assert_eq!(
+            interaction(&mut ui, Point::new(350.0, 30.0)),
+            mouse::Interaction::default()
+        );
     }
 
-    // Get cell position using precomputed cumulative positions
-    fn cell_position(&self, row: usize, col: usize) -> Point {
-        let x = if col == 0 {
-            &0.0
-        } else {
-            self.cumulative_x
-                .get(col.checked_sub(1).unwrap_or(0))
-                .unwrap_or(&0.0)
-        };
-        let y = if row == 0 {
-            &0.0
-        } else {
-            self.cumulative_y
-                .get(row.checked_sub(1).unwrap_or(0))
-                .unwrap_or(&0.0)
+    #[test]
+    fn a_click_only_reaches_the_cell_under_the_cursor() {
+        let data = content("A1:C7");
+        let log = Log::default();
+
+        // a new tree each time, for the presses not to make a double click
+        let pressed = |table: TestTable<'_>, position| {
+            log.borrow_mut().clear();
+            Ui::new(Size::new(300.0, 140.0)).event(table, press(), position);
+            log.borrow()
+                .iter()
+                .filter(|(_, event)| *event == press())
+                .map(|(cell, _)| cell.to_string())
+                .collect::<Vec<_>>()
         };
 
-        Point::new(x + self.spacing.width / 2.0, y + self.spacing.height / 2.0)
+        let body = Point::new(150.0, 30.0);
+        assert_eq!(pressed(probes(&data, &log).passthrough(true), body), ["B2"]);
+        assert!(pressed(probes(&data, &log), body).is_empty());
+        // the cells of a footer that can't be selected handle their own clicks
+        let footer = probes(&data, &log).footer_rows(1).footer_selectable(false);
+        assert_eq!(pressed(footer, Point::new(150.0, 130.0)), ["B7"]);
     }
 
-    // Find cell indices for a given point in widget bounds
-    fn find_cell(&self, pos: Point) -> (usize, usize) {
-        fn find_index(pos: f32, cumulative: &[f32]) -> usize {
-            match cumulative
-                .binary_search_by(|cum| cum.partial_cmp(&pos).unwrap_or(std::cmp::Ordering::Equal))
-            {
-                Ok(idx) => idx,
-                Err(idx) => idx.min(cumulative.len().checked_sub(1).unwrap_or(0)),
+    #[test]
+    fn a_triple_click_clicks_the_cell_three_times() {
+        let data = content("A1:C7");
+        let log = Log::default();
+
+        for passthrough in [false, true] {
+            log.borrow_mut().clear();
+            let mut ui = Ui::new(Size::new(300.0, 140.0));
+            for _ in 0..3 {
+                let table = probes(&data, &log).passthrough(passthrough);
+                let outcome = ui.event(table, press(), Point::new(150.0, 30.0));
+                assert_eq!(outcome.status, event::Status::Captured);
             }
+
+            let pressed: Vec<_> = log
+                .borrow()
+                .iter()
+                .filter(|(_, event)| *event == press())
+                .map(|(cell, _)| cell.to_string())
+                .collect();
+            assert_eq!(pressed, ["B2"; 3], "passthrough: {passthrough}");
         }
+    }
 
-        let col = find_index(pos.x, &self.cumulative_x);
-        let row = find_index(pos.y, &self.cumulative_y);
+    #[test]
+    fn moving_the_selection_redraws_without_a_relayout() {
+        let mut data = content("A1:C3");
+        let mut ui = Ui::new(Size::new(300.0, 60.0));
+        ui.event(table(&data), press(), Point::new(150.0, 30.0))
+            .perform(&mut data);
+        ui.event(table(&data), redraw(), Point::ORIGIN);
+        let node = ui.layout(table(&data));
 
-        (col, row)
+        let right = key(
+            keyboard::key::Named::ArrowRight,
+            keyboard::Modifiers::empty(),
+        );
+        let outcome = ui
+            .event(table(&data), right, Point::ORIGIN)
+            .perform(&mut data);
+        assert!(!outcome.relayout);
+        assert_eq!(*data.selection(), cell("C2"));
+        let outcome = ui.event(table(&data), redraw(), Point::ORIGIN);
+        assert!(outcome.redraw && !outcome.relayout);
+
+        // the selection moves in the layout from before the key was pressed
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into()).into();
+        let mut renderer = QuadRenderer::default();
+        element.as_widget().draw(
+            &ui.tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(ui.size),
+        );
+        let c2 = Rectangle::new(Point::new(200.0, 20.0), Size::new(100.0, 20.0));
+        let b2 = Rectangle::new(Point::new(100.0, 20.0), Size::new(100.0, 20.0));
+        assert!(renderer.quads.contains(&c2));
+        assert!(!renderer.quads.contains(&b2));
     }
 
-    // Find the nearest divider to a given point
-    fn find_nearest_divider(&self, pos: Point) -> Option<DividerHit> {
-        fn find_nearest(
-            pos: f32,
-            cumulative: &[f32],
-            spacing: f32,
-            raw_sizes: &[f32],
-        ) -> Option<(usize, f32)> {
-            // Get the index where this position would be inserted
-            let idx = match cumulative
-                .binary_search_by(|cum| cum.partial_cmp(&pos).unwrap_or(std::cmp::Ordering::Equal))
-            {
-                Ok(idx) => idx,
-                Err(idx) => idx,
-            };
+    #[test]
+    fn a_selection_change_keeps_a_divider_drag() {
+        let mut data = content("A1:D5");
+        let mut ui = Ui::new(Size::new(600.0, 100.0));
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).scrollable(true)
+        }
+        let width = |node: &layout::Node| node.children()[0].bounds().width;
+        ui.event(view(&data), redraw(), Point::ORIGIN);
 
-            // Handle all bounds cases together
-            let candidates = if idx >= cumulative.len() {
-                cumulative.len().checked_sub(1).map(|i| vec![i])
-            } else if idx == 0 {
-                Some(vec![0])
-            } else {
-                Some(vec![idx - 1, idx])
-            }?;
+        ui.event(view(&data), press(), Point::new(100.0, 10.0));
+        ui.event(
+            view(&data),
+            moved(Point::new(140.0, 10.0)),
+            Point::new(140.0, 10.0),
+        );
+        // the app moves the selection mid-drag
+        data.select_cell("C3".parse().unwrap());
+        let outcome = ui.event(view(&data), redraw(), Point::new(140.0, 10.0));
+        assert!(outcome.redraw && !outcome.relayout);
+        assert!(matches!(
+            ui.state().interaction,
+            Interaction::ResizeDivider(_)
+        ));
+        assert_eq!(width(&ui.layout(view(&data))), 140.0);
 
-            candidates.into_iter().find_map(|i| {
-                let cum_pos = cumulative.get(i)?;
-                let raw_size = raw_sizes.get(i)?;
+        ui.event(view(&data), release(), Point::new(140.0, 10.0))
+            .perform(&mut data);
+        assert_eq!(data.column_sizes()[0], 140.0);
+    }
 
-                let divider_pos = cum_pos - spacing / 2.0;
-                ((pos - divider_pos).abs() <= Region::RESIZE_AREA)
-                    .then_some((i, raw_size - spacing))
-            })
+    #[test]
+    fn whole_rows_columns_and_the_table_are_selected_together() {
+        fn view(data: &Content) -> TestTable<'_> {
+            table(data).show_column_headers(true).show_row_numbers(true)
         }
+        let ctrl = keyboard::Modifiers::CTRL;
+        let shift = keyboard::Modifiers::SHIFT;
+        let select_all = iced::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Character("a".into()),
+            modified_key: keyboard::Key::Character("a".into()),
+            physical_key: keyboard::key::Physical::Unidentified(
+                keyboard::key::NativeCode::Unidentified,
+            ),
+            location: keyboard::Location::Standard,
+            modifiers: keyboard::Modifiers::COMMAND,
+            text: None,
+        });
 
-        // Try vertical dividers first, then horizontal
-        find_nearest(
-            pos.x,
-            &self.cumulative_x,
-            self.spacing.width,
-            &self.raw_columns,
+        let mut data = content("A1:C3");
+        let mut ui = Ui::new(Size::new(400.0, 100.0));
+        ui.layout(view(&data));
+        // sized to fit the cells past the gutters, so they aren't stretched
+        let offset = ui.state().region.offset;
+        ui.size = Size::new(offset.x + 300.0, offset.y + 60.0);
+        let gutter = Point::new(offset.x / 2.0, offset.y + 30.0);
+        let header = Point::new(offset.x + 150.0, offset.y / 2.0);
+        let click = |ui: &mut Ui, data: &mut Content, position| {
+            let mut clicked = ui.event(view(data), press(), position);
+            clicked
+                .messages
+                .extend(ui.event(view(data), release(), position).messages);
+            clicked.perform(data);
+        };
+
+        // a click on the number of the second row selects the row
+        click(&mut ui, &mut data, gutter);
+        assert_eq!(*data.selection(), range("A2", "C2"));
+
+        // the columns of a whole row are all the columns
+        ui.event(view(&data), key(keyboard::key::Named::Space, ctrl), gutter)
+            .perform(&mut data);
+        assert_eq!(*data.selection(), range("A1", "C3"));
+
+        // a click on the header of the second column selects the column
+        click(&mut ui, &mut data, header);
+        assert_eq!(*data.selection(), range("B1", "B3"));
+
+        // which stays whole when expanded
+        ui.event(
+            view(&data),
+            key(keyboard::key::Named::ArrowRight, shift),
+            header,
         )
-        .map(|(idx, original_size)| DividerHit {
-            axis: Axis::Column,
-            index: idx,
-            original_size,
-        })
-        .or_else(|| {
-            find_nearest(
-                pos.y,
-                &self.cumulative_y,
-                self.spacing.height,
-                &self.raw_rows,
+        .perform(&mut data);
+        assert_eq!(*data.selection(), range("B1", "C3"));
+
+        // the rows of whole columns are all the rows
+        ui.event(view(&data), key(keyboard::key::Named::Space, shift), header)
+            .perform(&mut data);
+        assert_eq!(*data.selection(), range("A1", "C3"));
+
+        // select all from a single cell takes the whole table, whose rows
+        // and columns are the whole table again
+        data.select_cell(Address::new(1, 1));
+        let outcome = ui.event(view(&data), select_all, header);
+        assert!(matches!(outcome.actions()[..], [Action::SelectAll]));
+        outcome.perform(&mut data);
+        assert_eq!(*data.selection(), range("A1", "C3"));
+        for modifiers in [ctrl, shift] {
+            ui.event(
+                view(&data),
+                key(keyboard::key::Named::Space, modifiers),
+                header,
             )
-            .map(|(idx, original_size)| DividerHit {
-                axis: Axis::Row,
-                index: idx,
-                original_size,
-            })
-        })
+            .perform(&mut data);
+            assert_eq!(*data.selection(), range("A1", "C3"));
+        }
     }
 
-    /// Calculate the bounding rectangle for a selection range
-    pub fn selection_bounds<K: Reference, R: ReferenceRange<K>>(&self, selection: R) -> Rectangle {
-        let start = selection.start();
-        let end = selection.end().unwrap_or(selection.start());
+    #[test]
+    fn the_scroll_to_task_brings_the_cell_into_view() {
+        use iced::futures::{executor, StreamExt};
 
-        // Get the minimum and maximum cell coordinates
-        let min_row = start.y().min(end.y());
-        let max_row = start.y().max(end.y());
-        let min_col = start.x().min(end.x());
-        let max_col = start.x().max(end.x());
+        let data = content("A1:J100");
+        let id = widget::Id::new("table");
+        let mut ui = Ui::new(Size::new(300.0, 100.0));
+        let view = |data| table(data).id(id.clone()).scrollable(true);
 
-        // Get the top-left position using precomputed cumulative positions
-        let x = if min_col == 0 {
-            0.0
-        } else {
-            self.cumulative_x
-                .get(min_col.saturating_sub(1))
-                .copied()
-                .unwrap_or(0.0)
-        };
+        let mut scroll_to = |address: Address| {
+            let task = super::scroll_to::<Message>(id.clone(), address);
+            let mut actions = iced_runtime::task::into_stream(task).unwrap();
+            let Some(iced_runtime::Action::Widget(mut operation)) =
+                executor::block_on(actions.next())
+            else {
+                panic!("not an operation");
+            };
 
-        let y = if min_row == 0 {
-            0.0
-        } else {
-            self.cumulative_y
-                .get(min_row.saturating_sub(1))
-                .copied()
-                .unwrap_or(0.0)
+            let element = Element::from(view(&data));
+            let node = ui.layout_element(&element);
+            element
+                .as_widget()
+                .operate(&mut ui.tree, Layout::new(&node), &(), operation.as_mut());
+            drop(element);
+            ui.state().scroll
         };
 
-        // Calculate width and height using the difference in cumulative positions
-        if let Some(width) = self.cumulative_x.get(max_col) {
-            if let Some(height) = self.cumulative_y.get(max_row) {
-                Rectangle {
-                    x: x + self.spacing.width / 2.0,
-                    y: y + self.spacing.height / 2.0,
-                    width: *width - x - self.spacing.width / 2.0,
-                    height: *height - y - self.spacing.height / 2.0,
-                }
-            } else {
-                Rectangle::default()
-            }
-        } else {
-            Rectangle::default()
-        }
-    }
-}
+        // F51 is brought in at the bottom right corner
+        let scrolled = scroll_to("F51".parse().unwrap());
+        assert_eq!(scrolled, Vector::new(600.0 - 300.0, 1020.0 - 100.0));
 
-#[derive(Debug, Clone, Default)]
-enum Interaction {
-    #[default]
-    None,
-    ResizeDivider(DividerHit),
-}
+        // a cell in view doesn't scroll the table
+        assert_eq!(scroll_to("E50".parse().unwrap()), scrolled);
 
-impl State {
-    // Create a new State with the given column and row sizes
-    fn new(
-        col_sizes: &[f32],
-        row_sizes: &[f32],
-        spacing: Size,
-        row_count: usize,
-        column_count: usize,
-    ) -> Self {
-        Self {
-            last_click: None,
-            drag_click: None,
-            interaction: Interaction::default(),
-            is_focused: false,
-            region: Region::new(col_sizes, row_sizes, spacing, row_count, column_count),
-        }
-    }
+        // a cell past the table is clamped to its last cell
+        let last = Vector::new(1000.0 - 300.0, 2000.0 - 100.0);
+        assert_eq!(scroll_to("ZZ9999".parse().unwrap()), last);
 
-    fn from_data<Data, T, K, R>(data: &Data, spacing: Size) -> Self
-    where
-        Data: Tabular<T, K, R>,
-        T: Default,
-        K: Reference,
-        R: ReferenceRange<K>,
-    {
-        Self::new(
-            data.column_sizes(),
-            data.row_sizes(),
-            spacing,
-            data.row_count(),
-            data.column_count(),
-        )
+        // and back up to the first one
+        assert_eq!(scroll_to("A1".parse().unwrap()), Vector::ZERO);
     }
 
-    /// Returns whether the [`Table`] is currently focused or not.
-    pub fn is_focused(&self) -> bool {
-        self.is_focused
-    }
+    #[test]
+    fn the_select_task_focuses_the_table_and_publishes_the_selection() {
+        use iced::futures::{executor, StreamExt};
 
-    /// Focuses the [`Table`].
-    pub fn focus(&mut self) {
-        self.is_focused = true;
-    }
+        let data = content("A1:C3");
+        let id = widget::Id::new("table");
+        let mut ui = Ui::new(Size::new(300.0, 60.0));
+        let view = |data| table(data).id(id.clone());
 
-    /// Unfocuses the [`Table`].
-    pub fn unfocus(&mut self) {
-        self.is_focused = false;
-        self.drag_click = None;
-        self.last_click = None;
-    }
-}
+        for (range, selected) in [
+            (range("B2", "C3"), range("B2", "C3")),
+            (range("B2", "F9"), range("B2", "C3")),
+        ] {
+            let task = select::<Address, Message>(id.clone(), range);
+            let mut actions = iced_runtime::task::into_stream(task).unwrap();
+            let Some(iced_runtime::Action::Widget(mut operation)) =
+                executor::block_on(actions.next())
+            else {
+                panic!("not an operation");
+            };
 
-impl operation::Focusable for State {
-    fn is_focused(&self) -> bool {
-        State::is_focused(self)
-    }
+            let element = Element::from(view(&data));
+            let node = ui.layout_element(&element);
+            ui.tree.state.downcast_mut::<State>().unfocus();
+            element
+                .as_widget()
+                .operate(&mut ui.tree, Layout::new(&node), &(), operation.as_mut());
+            assert!(ui.state().is_focused());
 
-    fn focus(&mut self) {
-        State::focus(self);
+            let outcome = ui.event(view(&data), redraw(), Point::ORIGIN);
+            assert!(matches!(
+                outcome.actions()[..],
+                [Action::Select(range)] if *range == selected
+            ));
+        }
     }
 
-    fn unfocus(&mut self) {
-        State::unfocus(self);
+    #[test]
+    fn a_selected_range_is_drawn_before_it_is_published() {
+        use iced::futures::{executor, StreamExt};
+
+        let data = content("A1:C3");
+        let id = widget::Id::new("table");
+        let red = Background::Color(Color::from_rgb(1.0, 0.0, 0.0));
+        let bounds = Size::new(300.0, 60.0);
+
+        let element: Element<'_, Message, Theme, QuadRenderer> =
+            Table::new(&data, |_, _| Space::new(Length::Fill, Length::Fill).into())
+                .id(id.clone())
+                .on_action(Message::Action)
+                .cell_style(move |_, status| CellStyle {
+                    background: status.selected.then_some(red),
+                })
+                .into();
+        let mut tree = Tree::new(&element);
+        let mut renderer = QuadRenderer::default();
+        let node = element.as_widget().layout(
+            &mut tree,
+            &renderer,
+            &layout::Limits::new(Size::ZERO, bounds),
+        );
+
+        let task = select::<Address, Message>(id, range("B2", "F9"));
+        let mut actions = iced_runtime::task::into_stream(task).unwrap();
+        let Some(iced_runtime::Action::Widget(mut operation)) = executor::block_on(actions.next())
+        else {
+            panic!("not an operation");
+        };
+        element
+            .as_widget()
+            .operate(&mut tree, Layout::new(&node), &renderer, operation.as_mut());
+
+        element.as_widget().draw(
+            &tree,
+            &mut renderer,
+            &Theme::Light,
+            &renderer::Style {
+                text_color: Color::BLACK,
+            },
+            Layout::new(&node),
+            mouse::Cursor::Unavailable,
+            &Rectangle::with_size(bounds),
+        );
+
+        // the cells of the clamped range, rather than A1 of the data
+        let filled: Vec<_> = renderer
+            .quads
+            .iter()
+            .zip(&renderer.fills)
+            .filter(|(_, background)| **background == red)
+            .map(|(quad, _)| quad.position())
+            .collect();
+        assert_eq!(
+            filled,
+            [
+                Point::new(100.0, 20.0),
+                Point::new(100.0, 40.0),
+                Point::new(200.0, 20.0),
+                Point::new(200.0, 40.0),
+            ]
+        );
     }
-}
 
-impl std::fmt::Display for Interaction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Interaction::None => write!(f, "None"),
-            Interaction::ResizeDivider(DividerHit {
-                axis,
-                index,
-                original_size,
-            }) => write!(
-                f,
-                "Resize({:?}, {}, {})",
-                axis,
-                index,
-                original_size.trunc() as i32
-            ),
+    #[test]
+    fn submitting_an_edit_activates_then_moves_the_selection() {
+        // A table of editors, submitting with the address of their cell
+        fn editor(data: &Content) -> TestTable<'_> {
+            Table::new(data, |address: Address, _| {
+                iced::widget::text_input("", "")
+                    .on_submit(Message::Hover(Some(address)))
+                    .id(iced::widget::text_input::Id::new(address.to_string()))
+                    .into()
+            })
+            .on_action(Message::Action)
+            .on_instruction(Message::Instruction)
         }
-    }
-}
+        let none = keyboard::Modifiers::default();
 
-pub fn focus<Message>(id: impl Into<widget::Id>) -> iced::Task<Message>
-where
-    Message: Send + 'static,
-{
-    widget::operate(widget::operation::focusable::focus::<Message>(id.into()))
-}
+        for (held, moved) in [(none, "A3"), (keyboard::Modifiers::SHIFT, "A1")] {
+            let mut data = content("A1:A3");
+            let mut ui = Ui::new(Size::new(100.0, 60.0));
+            ui.event(editor(&data), press(), Point::new(50.0, 30.0))
+                .perform(&mut data);
 
-impl<'a, Data, T, K, R, Message, Theme, Renderer>
-    From<Table<'a, Data, T, K, R, Message, Theme, Renderer>>
-    for Element<'a, Message, Theme, Renderer>
-where
-    Data: Tabular<T, K, R>,
-    T: Cell + Default,
-    K: Reference + 'a,
-    R: ReferenceRange<K> + 'a,
-    Renderer: renderer::Renderer + 'a,
-    Message: Clone + 'a,
-    Theme: Catalog + 'a,
-{
-    fn from(data: Table<'a, Data, T, K, R, Message, Theme, Renderer>) -> Self {
-        Self::new(data)
+            // Enter in the table activates the cell, and the app focuses its
+            // editor
+            let outcome = ui.event(
+                editor(&data),
+                key(keyboard::key::Named::Enter, none),
+                Point::ORIGIN,
+            );
+            assert!(matches!(
+                outcome.instructions()[..],
+                [Instruction::Activate(cell)] if *cell == "A2".parse::<Address>().unwrap()
+            ));
+            let element = Element::from(editor(&data));
+            let node = ui.layout_element(&element);
+            element.as_widget().operate(
+                &mut ui.tree,
+                Layout::new(&node),
+                &(),
+                &mut operation::focusable::focus(widget::Id::new("A2")),
+            );
+            drop(element);
+            assert!(!ui.state().is_focused());
+
+            // Enter in the editor submits the edit, then the selection moves
+            ui.event(editor(&data), modifiers(held), Point::ORIGIN);
+            let outcome = ui.event(
+                editor(&data),
+                key(keyboard::key::Named::Enter, held),
+                Point::ORIGIN,
+            );
+            assert!(matches!(
+                &outcome.messages[..],
+                [
+                    Message::Hover(Some(submitted)),
+                    Message::Action(Action::MoveSelection(_)),
+                ] if *submitted == "A2".parse().unwrap()
+            ));
+            outcome.perform(&mut data);
+            assert_eq!(*data.selection(), cell(moved));
+        }
     }
 }
@@ -0,0 +1,123 @@
+//! Describe the cells of a table as text, for assistive technology such as
+//! screen readers.
+//!
+//! iced has no accessibility tree of its own, so the app takes a [`Snapshot`]
+//! of what the table shows and hands it to whatever the platform uses.
+use super::{clipboard, Reference, ReferenceRange, Tabular};
+
+/// A description of the cells of a table, as they are shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<K> {
+    /// The number of columns in the table.
+    pub columns: usize,
+    /// The number of rows in the table.
+    pub rows: usize,
+    /// The active cell of the selection.
+    pub active: K,
+    /// The cells described, row by row.
+    pub cells: Vec<CellDescription<K>>,
+}
+
+/// A description of a single cell of a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDescription<K> {
+    /// The cell, or the anchor of the merged cells it stands for.
+    pub cell: K,
+    /// The name of the column of the cell, see [`Tabular::column_name`].
+    pub column: String,
+    /// The text shown in the cell: its [`Tabular::display`] text, or
+    /// otherwise its [`Tabular::read_cell_text`].
+    pub text: String,
+    /// Whether the cell is selected.
+    pub selected: bool,
+}
+
+/// Take a [`Snapshot`] of the cells of a range, e.g. the cells in view.
+///
+/// The parts of the range outside of the table are ignored, and so are hidden
+/// rows and the cells covered by merged cells other than their anchor.
+pub fn snapshot<Data, T, K, R>(data: &Data, range: &R) -> Snapshot<K>
+where
+    Data: Tabular<T, K, R>,
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let range = range.normalize();
+    let start = range.start();
+    let end = range.end().unwrap_or(start);
+    let selection = data.selection().normalize();
+
+    let mut cells = Vec::new();
+    for y in start.y()..(end.y() + 1).min(data.row_count()) {
+        if data.is_row_hidden(y) {
+            continue;
+        }
+        for x in start.x()..(end.x() + 1).min(data.column_count()) {
+            let cell = K::new(x, y);
+            if data.span_at(x, y).is_some_and(|span| span.anchor != cell) {
+                continue;
+            }
+            cells.push(CellDescription {
+                cell,
+                column: data.column_name(x).into_owned(),
+                text: clipboard::value(data, cell).unwrap_or_default(),
+                selected: selection.contains(&cell),
+            });
+        }
+    }
+
+    Snapshot {
+        columns: data.column_count(),
+        rows: data.row_count(),
+        active: data.active_cell(),
+        cells,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list::{Cell, Content};
+    use crate::{Address, Range};
+
+    #[test]
+    fn describes_the_text_shown_in_the_cells() {
+        let mut data = Content::<Cell>::with_range(Range::parse("A1:C2").unwrap());
+        for (x, text) in ["1", "2", "=A1+B1"].into_iter().enumerate() {
+            data.insert(Address::new(x, 0), Cell::from(text));
+        }
+        data.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        data.set_display_text(|_, cell| (cell.content == "=A1+B1").then(|| "3".to_string()));
+        data.merge_cells(Range::parse("A2:B2").unwrap());
+        data.select_range(Range::parse("B1:C1").unwrap());
+
+        let snapshot = snapshot(&data, &Range::parse("A1:D9").unwrap());
+        assert_eq!((snapshot.columns, snapshot.rows), (3, 2));
+        assert_eq!(snapshot.active, Address::new(1, 0));
+
+        let cells: Vec<_> = snapshot
+            .cells
+            .iter()
+            .map(|cell| {
+                (
+                    cell.cell,
+                    cell.column.as_str(),
+                    cell.text.as_str(),
+                    cell.selected,
+                )
+            })
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                (Address::new(0, 0), "A", "1", false),
+                (Address::new(1, 0), "B", "2", true),
+                (Address::new(2, 0), "C", "3", true),
+                // B2 is covered by A2
+                (Address::new(0, 1), "A", "", false),
+                (Address::new(2, 1), "C", "", false),
+            ]
+        );
+    }
+}
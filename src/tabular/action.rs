@@ -1,3 +1,6 @@
+use iced::advanced::graphics::core::SmolStr;
+use iced::Point;
+
 use super::update::Motion;
 use super::{Axis, Reference, ReferenceRange};
 
@@ -13,12 +16,88 @@ pub enum Action<K: Reference = Address, R: ReferenceRange<K> = Range> {
     ExpandSelection(Motion),
     /// Select a range of cells.
     Select(R),
-    /// Select the entire table
+    /// Select the [`Tabular::used_range`] of the table, or the entire table
+    /// if the used range is already selected.
+    ///
+    /// [`Tabular::used_range`]: super::Tabular::used_range
     SelectAll,
     /// Edit the table
-    Edit(Edit),
-    /// Resize a divider with index `usize` to the given `f32` value.
+    Edit(Edit<K>),
+    /// Sort the rows of the table by the given column.
+    Sort {
+        column: usize,
+        ascending: bool,
+    },
+    /// Resize a divider with index `usize` by the given `f32` delta.
+    ///
+    /// The delta is relative to the size of the column or row in
+    /// [`Tabular::column_sizes`] or [`Tabular::row_sizes`], even if it's shown
+    /// at another size, and the new size is clamped at zero. A row with a
+    /// [`RowHeight::FillPortion`] height, or a column that isn't
+    /// [`Sizing::Fixed`], is first fixed at that size.
+    ///
+    /// Dragging the divider of one of several whole selected columns or rows
+    /// resizes all of them to the same size, with one action for each.
+    ///
+    /// [`RowHeight::FillPortion`]: super::RowHeight::FillPortion
+    /// [`Tabular::column_sizes`]: super::Tabular::column_sizes
+    /// [`Tabular::row_sizes`]: super::Tabular::row_sizes
+    /// [`Sizing::Fixed`]: super::Sizing::Fixed
     ResizeDivider(Axis, usize, f32),
+    /// Insert an empty row before the given row. An index past the last row
+    /// appends the row.
+    InsertRow(usize),
+    /// Insert an empty column before the given column. An index past the
+    /// last column appends the column.
+    InsertColumn(usize),
+    /// Remove the given row.
+    RemoveRow(usize),
+    /// Remove the given column.
+    RemoveColumn(usize),
+    /// Move the column at `from` so that it ends up at index `to`.
+    MoveColumn {
+        from: usize,
+        to: usize,
+    },
+    /// Move the row at `from` so that it ends up at index `to`.
+    MoveRow {
+        from: usize,
+        to: usize,
+    },
+    /// Set the width of the given column, e.g. to fit it to its content.
+    ///
+    /// A column that isn't [`Sizing::Fixed`] is fixed at the new width.
+    ///
+    /// [`Sizing::Fixed`]: super::Sizing::Fixed
+    SetColumnWidth(usize, f32),
+    /// Set the height of the given row, e.g. to fit it to its content.
+    ///
+    /// A row with a [`RowHeight::FillPortion`] height is fixed at the new
+    /// height.
+    ///
+    /// [`RowHeight::FillPortion`]: super::RowHeight::FillPortion
+    SetRowHeight(usize, f32),
+    /// Fill the `target` range with copies of the `source` block, e.g. by
+    /// dragging the fill handle of the selection, then select the `target`.
+    ///
+    /// The `target` either contains the `source` and extends it along one
+    /// axis, or lies within it; the cells of the `source` left out of the
+    /// `target` are cleared.
+    ///
+    /// The cells are copied as text, through [`Tabular::read_cell_text`] and
+    /// [`Tabular::write_cell_text`]. Data without text, e.g. a
+    /// [`list::Content`] without a [`set_text_conversion`], copies nothing,
+    /// though the cells left out are still cleared and the `target` is still
+    /// selected.
+    ///
+    /// [`Tabular::read_cell_text`]: super::Tabular::read_cell_text
+    /// [`Tabular::write_cell_text`]: super::Tabular::write_cell_text
+    /// [`list::Content`]: crate::list::Content
+    /// [`set_text_conversion`]: crate::list::Content::set_text_conversion
+    Fill {
+        source: R,
+        target: R,
+    },
 
     _Phantom(K), // marker for K
 }
@@ -27,17 +106,32 @@ impl<K: Reference, R: ReferenceRange<K>> Action<K, R> {
     pub fn is_edit(&self) -> bool {
         matches!(self, Self::Edit(_))
     }
+
+    /// Returns whether the action only changes the selection.
+    pub fn is_selection(&self) -> bool {
+        matches!(
+            self,
+            Self::MoveSelection(_) | Self::ExpandSelection(_) | Self::Select(_) | Self::SelectAll
+        )
+    }
 }
 
 /// An edit action that can be performed on a [`Table`].
 #[derive(Debug, Clone)]
-pub enum Edit {
+pub enum Edit<K: Reference = Address> {
     /// Delete the selected cells.
     Delete,
+    /// Write rows of text to the cells from the `anchor` rightwards and
+    /// downwards, e.g. pasted from the clipboard.
+    ///
+    /// Text that falls outside the table is dropped. If the selection starts
+    /// at the `anchor` and is a whole number of times the size of the block
+    /// of text, the block is repeated to fill the selection.
+    Paste { anchor: K, rows: Vec<Vec<String>> },
 }
 
-impl<K: Reference, R: ReferenceRange<K>> From<Edit> for Action<K, R> {
-    fn from(edit: Edit) -> Self {
+impl<K: Reference, R: ReferenceRange<K>> From<Edit<K>> for Action<K, R> {
+    fn from(edit: Edit<K>) -> Self {
         Self::Edit(edit)
     }
 }
@@ -48,12 +142,56 @@ impl<K: Reference, R: ReferenceRange<K>> From<Edit> for Action<K, R> {
 /// [`Table`] widget.
 #[derive(Debug, Clone)]
 pub enum Instruction<K: Reference = Address> {
-    /// The app should paste the clipboard contents.
+    /// The app should paste the clipboard contents, e.g. as an
+    /// [`Edit::Paste`] of the rows decoded by [`decode_tsv`].
+    ///
+    /// [`decode_tsv`]: crate::clipboard::decode_tsv
     Paste,
     /// The app should cut the selection.
     Cut,
-    /// The app should copy the selection.
+    /// The app should copy the selection, e.g. as the text encoded by
+    /// [`encode_tsv`].
+    ///
+    /// [`encode_tsv`]: crate::clipboard::encode_tsv
     Copy,
     /// The app should activate the given cell, such as focusing it.
     Activate(K),
+    /// The user typed the given text on the given cell, and the app should
+    /// activate the cell with its content replaced by the text.
+    ActivateWith(K, SmolStr),
+    /// The user toggled the row groups whose header is the given row.
+    ToggleGroup(usize),
+    /// An edit to the given cell was rejected by the data's validator, or
+    /// because its text doesn't convert to the [`ColumnType`] of the column.
+    ///
+    /// [`ColumnType`]: crate::ColumnType
+    EditRejected { cell: K, reason: String },
+    /// The user clicked the header of the given column.
+    HeaderClicked(usize),
+    /// The user right-clicked the given cell, at the given position in the
+    /// window, e.g. to open a context menu there.
+    ///
+    /// The cell is selected first if it was outside of the selection.
+    SecondaryClick { cell: K, position: Point },
+    /// The user finished resizing the given column or row to the given
+    /// size, e.g. for the app to remember it.
+    ///
+    /// It follows the [`Action::ResizeDivider`] of the resize, unless the
+    /// size didn't change.
+    Resized { axis: Axis, index: usize, size: f32 },
+    /// The user double-clicked the divider after the given column or row.
+    ///
+    /// The app, which knows how to measure its content, should fit the
+    /// column or row to it with [`Action::SetColumnWidth`] or
+    /// [`Action::SetRowHeight`]. The texts shown in its cells, as given by
+    /// [`Tabular::display`] or otherwise [`Tabular::read_cell_text`], are
+    /// handed over to be measured.
+    ///
+    /// [`Tabular::display`]: super::Tabular::display
+    /// [`Tabular::read_cell_text`]: super::Tabular::read_cell_text
+    AutoFit {
+        axis: Axis,
+        index: usize,
+        texts: Vec<String>,
+    },
 }
@@ -0,0 +1,237 @@
+//! Encode and decode the text of cells as it travels through the clipboard.
+//!
+//! Spreadsheets exchange cells as tab-separated text, with the cells of a row
+//! separated by tabs and the rows by line breaks. A cell holding a tab, a line
+//! break or a leading quote is wrapped in quotes, with its own quotes doubled,
+//! the way Excel and Google Sheets do it.
+use super::{Reference, ReferenceRange, Tabular};
+
+/// Encode the text of the given cells over a range as tab-separated text.
+///
+/// Cells outside of the range are ignored, and the parts of the range without
+/// a cell are left empty. The rows are separated by `\n`, without a trailing
+/// line break.
+pub fn encode_tsv<'a, K, R>(cells: impl IntoIterator<Item = (K, &'a str)>, range: &R) -> String
+where
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let range = range.normalize();
+    let start = range.start();
+    let mut grid = vec![vec![""; range.width()]; range.height()];
+
+    for (cell, text) in cells {
+        if range.contains(&cell) {
+            grid[cell.y() - start.y()][cell.x() - start.x()] = text;
+        }
+    }
+
+    grid.into_iter()
+        .map(|row| row.into_iter().map(quote).collect::<Vec<_>>().join("\t"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Encode the values shown in the cells of a range as tab-separated text.
+///
+/// Each cell is copied as its [`Tabular::display`] text, e.g. the result of
+/// a formula, or otherwise as its [`Tabular::read_cell_text`], so that it
+/// pastes elsewhere as the value rather than what computes it.
+pub fn encode_values<Data, T, K, R>(data: &Data, range: &R) -> String
+where
+    Data: Tabular<T, K, R>,
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let range = range.normalize();
+    let cells = range
+        .iter()
+        .map(|cell| (cell, value(data, cell).unwrap_or_default()))
+        .collect::<Vec<_>>();
+
+    encode_tsv(
+        cells.iter().map(|(cell, text)| (*cell, text.as_str())),
+        &range,
+    )
+}
+
+// The text shown in a cell: its display text, or otherwise its text
+pub(crate) fn value<Data, T, K, R>(data: &Data, cell: K) -> Option<String>
+where
+    Data: Tabular<T, K, R>,
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    data.display(cell)
+        .map(|text| text.into_owned())
+        .or_else(|| data.read_cell_text(cell))
+}
+
+/// Decode tab-separated text into rows of cells.
+///
+/// Rows may be separated by `\n` or `\r\n`, and a trailing line break is
+/// ignored. A cell starting with a quote is unquoted if it is closed by a
+/// quote right before a tab, a line break or the end of the text, and is
+/// otherwise taken as it is. Empty text decodes to a single empty cell.
+pub fn decode_tsv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut rest = text;
+
+    loop {
+        let (cell, after) = unquote(rest).unwrap_or_else(|| {
+            let end = rest.find(['\t', '\n']).unwrap_or(rest.len());
+            let cell = &rest[..end];
+            (
+                cell.strip_suffix('\r').unwrap_or(cell).to_string(),
+                &rest[end..],
+            )
+        });
+        row.push(cell);
+
+        let after = after.strip_prefix('\r').unwrap_or(after);
+        if let Some(after) = after.strip_prefix('\t') {
+            rest = after;
+        } else if let Some(after) = after.strip_prefix('\n') {
+            rows.push(std::mem::take(&mut row));
+            if after.is_empty() {
+                break;
+            }
+            rest = after;
+        } else {
+            rows.push(row);
+            break;
+        }
+    }
+
+    rows
+}
+
+// Quote a cell if it would otherwise be split or unquoted by decode_tsv
+fn quote(text: &str) -> String {
+    if text.contains(['\t', '\n', '\r']) || text.starts_with('"') {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// Read a quoted cell from the start of the text, returning it with the rest
+// of the text, if the cell is closed right before a tab, line break or the end
+fn unquote(text: &str) -> Option<(String, &str)> {
+    let mut chars = text.strip_prefix('"')?.chars();
+    let mut cell = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '"' {
+            cell.push(c);
+            continue;
+        }
+
+        let rest = chars.as_str();
+        if rest.starts_with('"') {
+            chars.next();
+            cell.push('"');
+        } else if rest.is_empty() || rest.starts_with(['\t', '\n']) || rest.starts_with("\r\n") {
+            return Some((cell, rest));
+        } else {
+            return None;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Address, Range};
+
+    fn rows(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_excel_samples() {
+        // rows end with CRLF, the last one included, and line breaks within
+        // a cell are quoted LFs
+        let text = "Name\tNote\r\nAda\t\"first\nsecond\"\r\n\t\"say \"\"hi\"\"\"\r\n";
+
+        assert_eq!(
+            decode_tsv(text),
+            rows(&[
+                &["Name", "Note"],
+                &["Ada", "first\nsecond"],
+                &["", "say \"hi\""]
+            ])
+        );
+    }
+
+    #[test]
+    fn decodes_google_sheets_samples() {
+        // rows end with LF, without a trailing one
+        let text = "1\t2\t3\n\"a\tb\"\t\t\"\"\"quoted\"\"\"";
+
+        assert_eq!(
+            decode_tsv(text),
+            rows(&[&["1", "2", "3"], &["a\tb", "", "\"quoted\""]])
+        );
+    }
+
+    #[test]
+    fn decodes_line_breaks_and_stray_quotes() {
+        assert_eq!(decode_tsv(""), rows(&[&[""]]));
+        assert_eq!(decode_tsv("a\n"), rows(&[&["a"]]));
+        assert_eq!(decode_tsv("a\r\nb\r\n"), rows(&[&["a"], &["b"]]));
+        // an empty row in the middle is kept
+        assert_eq!(decode_tsv("a\n\nb"), rows(&[&["a"], &[""], &["b"]]));
+        // quotes that don't wrap the whole cell are kept as they are
+        assert_eq!(decode_tsv("\"a\"b\tc\"d\""), rows(&[&["\"a\"b", "c\"d\""]]));
+    }
+
+    #[test]
+    fn encodes_the_range_with_quoting() {
+        let cells = [
+            (Address::new(0, 0), "a"),
+            (Address::new(1, 0), "tab\there"),
+            (Address::new(1, 1), "two\nlines"),
+            (Address::new(0, 1), "\"quoted\""),
+            // outside of the range
+            (Address::new(5, 5), "ignored"),
+        ];
+        let range = Range::parse("A1:C2").unwrap();
+
+        let text = encode_tsv(cells, &range);
+        assert_eq!(
+            text,
+            "a\t\"tab\there\"\t\n\"\"\"quoted\"\"\"\t\"two\nlines\"\t"
+        );
+        assert_eq!(
+            decode_tsv(&text),
+            rows(&[&["a", "tab\there", ""], &["\"quoted\"", "two\nlines", ""]])
+        );
+    }
+
+    #[test]
+    fn encodes_the_values_shown_in_the_cells() {
+        use crate::list::{Cell, Content};
+
+        let mut data = Content::<Cell>::with_range(Range::parse("A1:C2").unwrap());
+        for (x, text) in ["1", "2", "=A1+B1"].into_iter().enumerate() {
+            data.insert(Address::new(x, 0), Cell::from(text));
+        }
+        data.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        let range = Range::parse("A1:C2").unwrap();
+
+        // without a display text, the text of the cells is copied
+        assert_eq!(encode_values(&data, &range), "1\t2\t=A1+B1\n\t\t");
+
+        // the formula is copied as its result, the other cells as they are
+        data.set_display_text(|_, cell| (cell.content == "=A1+B1").then(|| "3".to_string()));
+        assert_eq!(encode_values(&data, &range), "1\t2\t3\n\t\t");
+    }
+}
@@ -0,0 +1,122 @@
+//! Data type hints for the columns of a table.
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+use iced::alignment;
+
+/// A function that converts some text into the canonical text of a custom
+/// [`ColumnType`], or explains why it can't.
+pub type Parser = Arc<dyn Fn(&str) -> Result<String, String> + Send + Sync>;
+
+/// The kind of data held by a column of a table.
+///
+/// Column types are hints: cells keep storing whatever the table stores, but
+/// incoming text (e.g. a paste or an import) is converted through
+/// [`ColumnType::parse`] and the default alignment follows the type.
+#[derive(Clone, Default)]
+pub enum ColumnType {
+    /// Free-form text.
+    #[default]
+    Text,
+    /// Whole numbers.
+    Integer,
+    /// Floating point numbers.
+    Float,
+    /// `TRUE` or `FALSE`.
+    Bool,
+    /// Text converted by a custom [`Parser`].
+    Custom(Parser),
+}
+
+impl ColumnType {
+    /// Creates a custom [`ColumnType`] from the given parser.
+    pub fn custom(parser: impl Fn(&str) -> Result<String, String> + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(parser))
+    }
+
+    /// Converts the given text into the canonical text for this type.
+    ///
+    /// Empty text is always accepted as-is.
+    pub fn parse(&self, input: &str) -> Result<String, String> {
+        let trimmed = input.trim();
+
+        if trimmed.is_empty() {
+            return Ok(String::new());
+        }
+
+        match self {
+            Self::Text => Ok(input.to_string()),
+            Self::Integer => trimmed
+                .parse::<i64>()
+                .map(|n| n.to_string())
+                .map_err(|_| format!("\"{}\" is not an integer", input)),
+            Self::Float => trimmed
+                .parse::<f64>()
+                .map(|n| n.to_string())
+                .map_err(|_| format!("\"{}\" is not a number", input)),
+            Self::Bool => match trimmed.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok("TRUE".to_string()),
+                "false" | "no" | "0" => Ok("FALSE".to_string()),
+                _ => Err(format!("\"{}\" is not a boolean", input)),
+            },
+            Self::Custom(parser) => parser(input),
+        }
+    }
+
+    /// Compares two values of this type, e.g. to sort a column.
+    ///
+    /// Numbers and booleans are compared by value and text is compared as
+    /// is. Values that don't parse as the type sort after those that do, and
+    /// empty values sort last.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (a, b) = (a.trim(), b.trim());
+
+        match (a.is_empty(), b.is_empty()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+
+        fn by<V: PartialOrd>(a: Option<V>, b: Option<V>, fallback: Ordering) -> Ordering {
+            match (a, b) {
+                (Some(a), Some(b)) => a.partial_cmp(&b).unwrap_or(fallback),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => fallback,
+            }
+        }
+
+        match self {
+            Self::Text => a.cmp(b),
+            Self::Integer => by(a.parse::<i64>().ok(), b.parse::<i64>().ok(), a.cmp(b)),
+            Self::Float => by(a.parse::<f64>().ok(), b.parse::<f64>().ok(), a.cmp(b)),
+            Self::Bool | Self::Custom(_) => by(self.parse(a).ok(), self.parse(b).ok(), a.cmp(b)),
+        }
+    }
+
+    /// The default horizontal alignment of values of this type.
+    ///
+    /// Numbers are right-aligned, booleans centered, and everything else
+    /// left-aligned.
+    pub fn alignment(&self) -> alignment::Horizontal {
+        match self {
+            Self::Integer | Self::Float => alignment::Horizontal::Right,
+            Self::Bool => alignment::Horizontal::Center,
+            Self::Text | Self::Custom(_) => alignment::Horizontal::Left,
+        }
+    }
+}
+
+impl fmt::Debug for ColumnType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text => write!(f, "Text"),
+            Self::Integer => write!(f, "Integer"),
+            Self::Float => write!(f, "Float"),
+            Self::Bool => write!(f, "Bool"),
+            Self::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
@@ -1,11 +1,17 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
 use std::slice::SliceIndex;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Mutex, PoisonError};
 
 use super::action::Edit;
+use super::column::ColumnType;
 use super::update::Motion;
-use super::{Action, Axis, Reference, ReferenceRange};
+use super::{to_column_name, Action, Axis, Reference, ReferenceRange};
 
+mod history;
 pub mod list;
+pub mod records;
+pub mod rows;
 
 pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
     /// Create a new table.
@@ -23,6 +29,87 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
         usize: SliceIndex<[Vec<T>]>;
     /// Insert an item at the given cell.
     fn insert(&mut self, cell: impl Into<K>, item: impl Into<T>);
+    /// The text shown for the given cell, if it differs from the raw value
+    /// (e.g. the computed result of a formula).
+    ///
+    /// It's the text copied by [`encode_values`], e.g. when the table uses
+    /// the system clipboard, measured by the app on an
+    /// [`Instruction::AutoFit`] and described in an accessibility
+    /// [`snapshot`].
+    ///
+    /// Returns `None` by default, meaning the raw value should be used.
+    ///
+    /// [`encode_values`]: super::clipboard::encode_values
+    /// [`Instruction::AutoFit`]: super::Instruction::AutoFit
+    /// [`snapshot`]: super::accessibility::snapshot
+    fn display(&self, _cell: K) -> Option<Cow<'_, str>> {
+        None
+    }
+
+    /// The text of the given cell, e.g. to copy it to the clipboard.
+    ///
+    /// Returns `None` by default, meaning the cell has no text.
+    fn read_cell_text(&self, _cell: K) -> Option<String> {
+        None
+    }
+
+    /// Write some text to the given cell, e.g. pasted from the clipboard.
+    ///
+    /// Does nothing by default.
+    fn write_cell_text(&mut self, _cell: K, _text: &str) {}
+
+    /// Drain the edits that were rejected since the last call, along with
+    /// the reason they were rejected.
+    ///
+    /// The widget emits an [`Instruction::EditRejected`] for each of these.
+    ///
+    /// [`Instruction::EditRejected`]: super::Instruction::EditRejected
+    fn take_rejections(&self) -> Vec<(K, String)> {
+        Vec::new()
+    }
+
+    /// Whether the given cell holds nothing, as far as the data-edge motions
+    /// (e.g. [`Motion::DataDown`]) are concerned.
+    ///
+    /// Returns `false` by default, so those motions go to the edge of the
+    /// table.
+    fn is_cell_empty(&self, _cell: K) -> bool {
+        false
+    }
+
+    /// The smallest range holding every cell that isn't empty according to
+    /// [`Tabular::is_cell_empty`], or `None` if all of them are.
+    ///
+    /// As no cell is empty by default, this is the whole table by default.
+    fn used_range(&self) -> Option<R> {
+        let filled = |&(x, y): &(usize, usize)| !self.is_cell_empty(K::new(x, y));
+        let columns = self.column_count();
+        let mut cells = (0..self.row_count())
+            .flat_map(|y| (0..columns).map(move |x| (x, y)))
+            .filter(filled);
+
+        let (x, y) = cells.next()?;
+        let (left, right, bottom) = cells.fold((x, x, y), |(left, right, _), (x, y)| {
+            (left.min(x), right.max(x), y)
+        });
+
+        Some(R::new(K::new(left, y), Some(K::new(right, bottom))))
+    }
+
+    /// The [`ColumnType`] of the given column.
+    ///
+    /// Defaults to [`ColumnType::Text`] for every column.
+    fn column_type(&self, _column: usize) -> &ColumnType {
+        static TEXT: ColumnType = ColumnType::Text;
+        &TEXT
+    }
+
+    /// The name shown in the header of the given column.
+    ///
+    /// Defaults to the letters of the column, as in `A`, `B`, ..., `AA`.
+    fn column_name(&self, column: usize) -> Cow<'_, str> {
+        Cow::Owned(to_column_name(column))
+    }
 
     /// The number of rows in the table.
     fn row_count(&self) -> usize;
@@ -37,6 +124,110 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
     /// A mutable reference to the sizes of the rows.
     fn row_sizes_mut(&mut self) -> &mut Vec<f32>;
 
+    /// The [`Sizing`] of the given column.
+    ///
+    /// Defaults to a fixed width at the size of the column in
+    /// [`Tabular::column_sizes`].
+    fn column_sizing(&self, column: usize) -> Sizing {
+        Sizing::Fixed(self.column_sizes().get(column).copied().unwrap_or_default())
+    }
+
+    /// Fix the width of a column that fills the table or fits its content at
+    /// its size in [`Tabular::column_sizes`]. Called when the column is
+    /// resized.
+    fn fix_column_width(&mut self, _column: usize) {}
+
+    /// The [`RowHeight`] of the given row.
+    ///
+    /// Defaults to a fixed height at the size of the row in
+    /// [`Tabular::row_sizes`].
+    fn row_height(&self, row: usize) -> RowHeight {
+        RowHeight::Fixed(self.row_sizes().get(row).copied().unwrap_or_default())
+    }
+
+    /// Fix the height of a row that fills the table at its size in
+    /// [`Tabular::row_sizes`]. Called when the row is resized.
+    fn fix_row_height(&mut self, _row: usize) {}
+
+    /// The row groups of the table, used to collapse detail rows under their
+    /// header row.
+    fn row_groups(&self) -> &[RowGroup] {
+        &[]
+    }
+
+    /// Whether the given row is hidden, e.g. because it belongs to a collapsed
+    /// [`RowGroup`].
+    ///
+    /// Hidden rows are skipped in layout and by keyboard navigation, but they
+    /// are still part of any selection spanning them, so copying or deleting
+    /// such a selection affects the hidden rows too.
+    fn is_row_hidden(&self, row: usize) -> bool {
+        self.row_groups()
+            .iter()
+            .any(|group| group.collapsed && group.hides(row))
+    }
+
+    /// The blocks of merged cells in the table.
+    ///
+    /// Each [`CellSpan`] is laid out as a single cell covering the whole
+    /// block, showing the item at its anchor; the other cells of the block
+    /// are hidden.
+    fn spans(&self) -> &[CellSpan<K>] {
+        &[]
+    }
+
+    /// The merged [`CellSpan`] containing the given cell, if any.
+    fn span_at(&self, column: usize, row: usize) -> Option<&CellSpan<K>> {
+        self.spans().iter().find(|span| span.contains(column, row))
+    }
+
+    /// Grow a range until it fully includes every merged [`CellSpan`] it
+    /// touches, keeping the direction of the range.
+    fn snap_to_spans(&self, range: R) -> R {
+        let start = range.start();
+        let end = range.end().unwrap_or(start);
+        let (mut left, mut top) = (start.x().min(end.x()), start.y().min(end.y()));
+        let (mut right, mut bottom) = (start.x().max(end.x()), start.y().max(end.y()));
+
+        // growing over one span can make the range touch another
+        let mut grown = false;
+        loop {
+            let mut changed = false;
+            for span in self.spans() {
+                let (x, y) = span.anchor.as_tuple();
+                let (x2, y2) = span.end().as_tuple();
+                let touches = x <= right && left <= x2 && y <= bottom && top <= y2;
+
+                if touches && (x < left || y < top || x2 > right || y2 > bottom) {
+                    (left, top) = (left.min(x), top.min(y));
+                    (right, bottom) = (right.max(x2), bottom.max(y2));
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+            grown = true;
+        }
+
+        if !grown {
+            return range;
+        }
+
+        let (start_x, end_x) = if start.x() <= end.x() {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        let (start_y, end_y) = if start.y() <= end.y() {
+            (top, bottom)
+        } else {
+            (bottom, top)
+        };
+
+        R::new(K::new(start_x, start_y), Some(K::new(end_x, end_y)))
+    }
+
     /// A reference to the internal state of the widget (the impure bits?)
     fn internal(&self) -> &Internal;
 
@@ -49,12 +240,56 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
     /// A mutable iterator over the items in the table, relative to their position in the source sheet.
     fn iter_relative_mut(&mut self) -> Box<dyn Iterator<Item = (K, &mut T)> + '_>;
 
+    /// An iterator over the items in the given range of the table.
+    ///
+    /// The parts of the range outside of the table are skipped. The default
+    /// implementation looks up every cell of the range within the table, as
+    /// the iterator is advanced.
+    fn iter_range<'a>(&'a self, range: &R) -> Box<dyn Iterator<Item = (K, &'a T)> + 'a>
+    where
+        K: 'a,
+    {
+        let (columns, rows) = clamp_range(range, self.column_count(), self.row_count());
+        Box::new(columns.flat_map(move |x| {
+            rows.clone().filter_map(move |y| {
+                let cell = K::new(x, y);
+                self.get(cell).map(|item| (cell, item))
+            })
+        }))
+    }
+
+    /// A mutable iterator over the items in the given range of the table.
+    ///
+    /// The parts of the range outside of the table are skipped. The default
+    /// implementation goes through the whole table, keeping the items in the
+    /// range.
+    fn iter_range_mut<'a>(&'a mut self, range: &R) -> Box<dyn Iterator<Item = (K, &'a mut T)> + 'a>
+    where
+        K: 'a,
+        R: 'a,
+    {
+        let range = range.normalize();
+        Box::new(
+            self.iter_mut()
+                .filter(move |(cell, _)| range.contains(cell)),
+        )
+    }
+
     /// Apply a function to the item at the given cell
     fn with_reference(&mut self, cell: impl Into<K>, f: impl Fn(&K, &mut T));
 
     /// Apply a function to the items in a given range
     fn with_reference_range(&mut self, range: &R, f: impl Fn(&K, &mut T));
 
+    /// Reset the items in the given range to their default, e.g. to delete
+    /// the selection.
+    ///
+    /// The parts of the range outside of the table are skipped. The default
+    /// implementation goes through [`Tabular::with_reference_range`].
+    fn clear_range(&mut self, range: &R) {
+        self.with_reference_range(&range.normalize(), |_, item| *item = T::default());
+    }
+
     /// Select the given cell.
     fn select_cell(&mut self, cell: K);
     /// Select the given range.
@@ -68,42 +303,91 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
     where
         K: 'a;
 
+    /// The cell of the selection that is edited on activation and that
+    /// [`Motion::Forward`] and [`Motion::Back`] cycle through.
+    ///
+    /// Defaults to the start of the selection.
+    fn active_cell(&self) -> K {
+        self.selection().start()
+    }
+    /// Make the given cell of the selection the active one.
+    ///
+    /// Selects just the given cell by default, for data that doesn't keep
+    /// track of an active cell.
+    fn set_active_cell(&mut self, cell: K) {
+        self.select_range(self.snap_to_spans(R::new(cell, None)));
+    }
+
     /// Perform a grid [`Action`].
+    ///
+    /// See [`apply_action`] for the default behavior.
     fn perform(&mut self, action: Action<K, R>) {
-        match action {
-            // editing actions will trigger a recalculation.
-            Action::Edit(edit) => match edit {
-                Edit::Delete => self.selection_mut().iter().for_each(|cell| {
-                    self.get_mut(cell).map(|item| *item = T::default());
-                }),
-            },
-            Action::Select(range) => self.select_range(range),
-            Action::SelectAll => self.select_all(),
-            Action::MoveSelection(motion) => self.move_selection(motion),
-            Action::ExpandSelection(motion) => self.expand_selection(motion),
-            Action::ResizeDivider(axis, index, size) => {
-                let size = size.clamp(0.0, f32::INFINITY);
-                match axis {
-                    Axis::Column => {
-                        self.column_sizes_mut()
-                            .get_mut(index)
-                            .map(|col| *col += size);
-                    }
-                    Axis::Row => {
-                        self.row_sizes_mut().get_mut(index).map(|row| *row += size);
-                    }
-                }
-            }
-            Action::_Phantom(_) => {}
-        }
+        apply_action(self, action);
     }
 
+    /// Sort the rows of the table by the given column, keeping rows with
+    /// equal values in their current order.
+    ///
+    /// Implementations should move the sizes of the rows along with them and
+    /// keep the selection on the same data. The default implementation
+    /// doesn't know how to order items and does nothing.
+    fn sort_rows(&mut self, _column: usize, _ascending: bool) {}
+
+    /// Insert an empty row before the given row, or after the last row if the
+    /// index is past it.
+    ///
+    /// Implementations should shift the selection along with the data, so
+    /// that it keeps pointing at the same cells. The default implementation
+    /// does nothing.
+    fn insert_row(&mut self, _row: usize) {}
+
+    /// Insert an empty column before the given column, or after the last
+    /// column if the index is past it.
+    ///
+    /// Implementations should shift the selection along with the data, so
+    /// that it keeps pointing at the same cells. The default implementation
+    /// does nothing.
+    fn insert_column(&mut self, _column: usize) {}
+
+    /// Remove the given row, if it exists.
+    ///
+    /// Implementations should shift the selection along with the data and
+    /// keep it within the table. The default implementation does nothing.
+    fn remove_row(&mut self, _row: usize) {}
+
+    /// Remove the given column, if it exists.
+    ///
+    /// Implementations should shift the selection along with the data and
+    /// keep it within the table. The default implementation does nothing.
+    fn remove_column(&mut self, _column: usize) {}
+
+    /// Move the column at `from` so that it ends up at index `to`, shifting
+    /// the columns in between, if both exist.
+    ///
+    /// Implementations should move the column's width along with it and keep
+    /// the selection on the same data. The default implementation does
+    /// nothing.
+    fn move_column(&mut self, _from: usize, _to: usize) {}
+
+    /// Move the row at `from` so that it ends up at index `to`, shifting the
+    /// rows in between, if both exist.
+    ///
+    /// Implementations should move the row's height along with it and keep
+    /// the selection on the same data. The default implementation does
+    /// nothing.
+    fn move_row(&mut self, _from: usize, _to: usize) {}
+
     /// Do something with the selected cells.
     fn with_selection<O>(&self, f: impl FnOnce(&R) -> O) -> O {
-        f(&self.selection())
+        f(self.selection())
     }
 
     /// Moves the active cell within or beyond the current selection based on the motion
+    ///
+    /// [`Motion::Home`] and [`Motion::End`] move a single cell (or merged
+    /// span) to the first and last columns of its row, and collapse a
+    /// selection of several cells to the first and last columns of the
+    /// selection instead.
     fn move_selection(&mut self, motion: Motion) {
         let (start_col, start_row) = self.selection().start().as_tuple();
         let (end_col, end_row) = self
@@ -120,17 +404,26 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
         let max_col = self.column_count().saturating_sub(1);
         let max_row = self.row_count().saturating_sub(1);
 
+        // A selection of just a merged span moves like a single cell, leaving
+        // from the far side of the span
+        let span = self.span_at(start_col, start_row).copied().filter(|span| {
+            (first_col, first_row) == span.anchor.as_tuple()
+                && (last_col, last_row) == span.end().as_tuple()
+        });
+        let single = self.selection().end().is_none() || span.is_some();
+        let (far_col, far_row) = span.map_or((start_col, start_row), |span| span.end().as_tuple());
+
         // Handle tab behavior for single-cell selections
         let motion = match motion {
             Motion::Forward => {
-                if self.selection().end().is_none() {
+                if single {
                     Motion::Right
                 } else {
                     Motion::Forward
                 }
             }
             Motion::Back => {
-                if self.selection().end().is_none() {
+                if single {
                     Motion::Left
                 } else {
                     Motion::Back
@@ -140,66 +433,129 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
         };
 
         let new_cell = match motion {
-            Motion::Forward => {
-                let num_cols = last_col - first_col + 1;
-                let next_col = (start_col - first_col + 1) % num_cols + first_col;
-                let next_row = if next_col == first_col {
-                    start_row.saturating_add(1)
-                } else {
-                    start_row
-                };
-                let wrapped_row = if next_row > last_row {
-                    first_row
-                } else {
-                    next_row
-                };
-                K::new(next_col.min(max_col), wrapped_row.min(max_row))
-            }
-            Motion::Back => {
-                let prev_col = if start_col == first_col {
-                    last_col
-                } else {
-                    start_col.saturating_sub(1)
-                };
-                let prev_row = if prev_col == last_col && start_row == first_row {
-                    last_row
-                } else if prev_col == last_col {
-                    start_row.saturating_sub(1)
-                } else {
-                    start_row
-                };
-                let wrapped_row = if prev_row < first_row {
-                    last_row
-                } else {
-                    prev_row
+            // Cycle the active cell through a selection of several cells, row by
+            // row, keeping the selection as is
+            Motion::Forward | Motion::Back => {
+                let step = |(col, row): (usize, usize)| {
+                    if motion == Motion::Forward {
+                        let num_cols = last_col - first_col + 1;
+                        let next_col = (col - first_col + 1) % num_cols + first_col;
+                        let next_row = if next_col == first_col {
+                            row.saturating_add(1)
+                        } else {
+                            row
+                        };
+                        let wrapped_row = if next_row > last_row {
+                            first_row
+                        } else {
+                            next_row
+                        };
+                        (next_col, wrapped_row)
+                    } else {
+                        let prev_col = if col == first_col {
+                            last_col
+                        } else {
+                            col.saturating_sub(1)
+                        };
+                        let prev_row = if prev_col == last_col && row == first_row {
+                            last_row
+                        } else if prev_col == last_col {
+                            row.saturating_sub(1)
+                        } else {
+                            row
+                        };
+                        let wrapped_row = if prev_row < first_row {
+                            last_row
+                        } else {
+                            prev_row
+                        };
+                        (prev_col, wrapped_row)
+                    }
                 };
-                K::new(prev_col.min(max_col), wrapped_row.min(max_row))
+
+                // the cells hidden under merged spans are skipped
+                let count = (last_col - first_col + 1) * (last_row - first_row + 1);
+                let mut cell = step(self.active_cell().as_tuple());
+                for _ in 0..count {
+                    if !self
+                        .span_at(cell.0, cell.1)
+                        .is_some_and(|span| span.covers(cell.0, cell.1))
+                    {
+                        break;
+                    }
+                    cell = step(cell);
+                }
+
+                self.set_active_cell(K::new(cell.0.min(max_col), cell.1.min(max_row)));
+                return;
             }
             Motion::Up => K::new(start_col.min(max_col), start_row.saturating_sub(1)),
             Motion::Down => K::new(
                 start_col.min(max_col),
-                start_row.saturating_add(1).min(max_row),
+                far_row.saturating_add(1).min(max_row),
             ),
             Motion::Right => K::new(
-                start_col.saturating_add(1).min(max_col),
+                far_col.saturating_add(1).min(max_col),
                 start_row.min(max_row),
             ),
             Motion::Left => K::new(start_col.saturating_sub(1), start_row.min(max_row)),
-            Motion::Home => K::new(first_col.min(max_col), start_row.min(max_row)),
-            Motion::End => K::new(last_col.min(max_col), start_row.min(max_row)),
+            Motion::Home => {
+                let col = if single { 0 } else { first_col.min(max_col) };
+                K::new(col, start_row.min(max_row))
+            }
+            Motion::End => {
+                let col = if single {
+                    max_col
+                } else {
+                    last_col.min(max_col)
+                };
+                K::new(col, start_row.min(max_row))
+            }
             Motion::DocumentStart => K::new(0, 0),
             Motion::DocumentEnd => K::new(max_col, max_row),
+            Motion::DataLeft | Motion::DataRight | Motion::DataUp | Motion::DataDown => {
+                let (x, y) =
+                    self.data_edge((start_col.min(max_col), start_row.min(max_row)), motion);
+                K::new(x, y)
+            }
+            Motion::PageUp(rows) => K::new(start_col.min(max_col), start_row.saturating_sub(rows)),
+            Motion::PageDown(rows) => K::new(
+                start_col.min(max_col),
+                start_row
+                    .saturating_add(rows)
+                    .max(far_row.saturating_add(1))
+                    .min(max_row),
+            ),
+        };
+
+        // Jump over hidden rows in the direction of the motion
+        let new_cell = match motion {
+            Motion::Up | Motion::Down | Motion::PageUp(_) | Motion::PageDown(_) => {
+                K::new(new_cell.x(), self.visible_row(new_cell.y(), motion))
+            }
+            _ => new_cell,
         };
 
+        // Land on the anchor of a merged span
+        let new_cell = self
+            .span_at(new_cell.x(), new_cell.y())
+            .map_or(new_cell, |span| span.anchor);
+
         // Update the region's selection state with the new active cell
-        self.select_range(R::new(new_cell, None));
+        self.select_range(self.snap_to_spans(R::new(new_cell, None)));
     }
 
     /// Expands the current selection in the specified direction
+    ///
+    /// The start of the selection stays put while its end moves, so
+    /// [`Motion::Home`] and [`Motion::End`] extend it to the first and last
+    /// columns, and the document motions to the corners of the table.
+    /// [`Motion::Forward`] and [`Motion::Back`] expand like [`Motion::Right`]
+    /// and [`Motion::Left`].
     fn expand_selection(&mut self, motion: Motion) {
-        let (add_x, add_y): (i16, i16) = match motion {
-            Motion::Left => (-1, 0),
-            Motion::Right => (1, 0),
+        let (dx, dy): (isize, isize) = match motion {
+            Motion::Left | Motion::Back => (-1, 0),
+            Motion::Right | Motion::Forward => (1, 0),
             Motion::Up => (0, -1),
             Motion::Down => (0, 1),
             _ => (0, 0),
@@ -213,23 +569,452 @@ pub trait Tabular<T: Default, K: Reference, R: ReferenceRange<K>> {
         let end = self.selection().end().unwrap_or(start);
 
         // Calculate new end position while keeping within grid bounds
-        let new_y = ((end.y() as i16).saturating_add(add_y))
-            .max(0)
-            .min(max_row as i16) as usize;
-        let new_x = ((end.x() as i16).saturating_add(add_x))
-            .max(0)
-            .min(max_col as i16) as usize;
+        let moved = end.saturating_offset(dx, dy);
+        let (new_x, new_y) = (moved.x().min(max_col), moved.y().min(max_row));
+
+        let (new_x, new_y) = match motion {
+            Motion::DataLeft | Motion::DataRight | Motion::DataUp | Motion::DataDown => {
+                self.data_edge((end.x().min(max_col), end.y().min(max_row)), motion)
+            }
+            Motion::Home => (0, new_y),
+            Motion::End => (max_col, new_y),
+            Motion::DocumentStart => (0, 0),
+            Motion::DocumentEnd => (max_col, max_row),
+            _ => (new_x, new_y),
+        };
+        let new_y = match motion {
+            Motion::PageUp(rows) => end.y().saturating_sub(rows),
+            Motion::PageDown(rows) => end.y().saturating_add(rows).min(max_row),
+            _ => new_y,
+        };
+        let new_y = match motion {
+            Motion::Up | Motion::Down | Motion::PageUp(_) | Motion::PageDown(_) => {
+                self.visible_row(new_y, motion)
+            }
+            _ => new_y,
+        };
 
         let new_end = K::new(new_x, new_y);
 
-        self.select_range(R::new(start, Some(new_end)))
+        self.select_range(self.snap_to_spans(R::new(start, Some(new_end))))
+    }
+
+    /// The cell a data-edge `motion` lands on from the given cell: the last
+    /// filled cell of a run of filled cells, or else the next filled cell, or
+    /// else the edge of the table. Hidden rows are skipped.
+    fn data_edge(&self, from: (usize, usize), motion: Motion) -> (usize, usize) {
+        let (dx, dy): (isize, isize) = match motion {
+            Motion::DataLeft => (-1, 0),
+            Motion::DataRight => (1, 0),
+            Motion::DataUp => (0, -1),
+            Motion::DataDown => (0, 1),
+            _ => return from,
+        };
+
+        let max_col = self.column_count().saturating_sub(1);
+        let max_row = self.row_count().saturating_sub(1);
+
+        // The next cell in the direction of the motion, if any
+        let step = |(x, y): (usize, usize)| {
+            let x = x.checked_add_signed(dx).filter(|&x| x <= max_col)?;
+            let mut y = y.checked_add_signed(dy).filter(|&y| y <= max_row)?;
+            while self.is_row_hidden(y) {
+                y = y.checked_add_signed(dy).filter(|&y| y <= max_row)?;
+            }
+            Some((x, y))
+        };
+        let filled = |(x, y): (usize, usize)| !self.is_cell_empty(K::new(x, y));
+
+        let Some(mut cell) = step(from) else {
+            return from;
+        };
+
+        if filled(from) && filled(cell) {
+            // run to the end of the block of filled cells
+            while let Some(next) = step(cell).filter(|&next| filled(next)) {
+                cell = next;
+            }
+        } else {
+            // skip the empty cells up to the next filled one
+            while !filled(cell) {
+                match step(cell) {
+                    Some(next) => cell = next,
+                    None => break,
+                }
+            }
+        }
+
+        cell
+    }
+
+    /// The nearest visible row from `row` in the direction of the vertical
+    /// `motion`, or the nearest one in the opposite direction if there are no
+    /// visible rows left that way.
+    fn visible_row(&self, row: usize, motion: Motion) -> usize {
+        let max_row = self.row_count().saturating_sub(1);
+        let down = (row..=max_row).find(|&y| !self.is_row_hidden(y));
+        let up = (0..=row.min(max_row))
+            .rev()
+            .find(|&y| !self.is_row_hidden(y));
+
+        match motion {
+            Motion::Down | Motion::PageDown(_) => down.or(up),
+            _ => up.or(down),
+        }
+        .unwrap_or(row)
+    }
+}
+
+// The columns and rows of a range that fall inside a table of the given size
+fn clamp_range<K: Reference, R: ReferenceRange<K>>(
+    range: &R,
+    columns: usize,
+    rows: usize,
+) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+    let range = range.normalize();
+    let start = range.start();
+    let end = range.end().unwrap_or(start);
+
+    (
+        start.x().min(columns)..(end.x() + 1).min(columns),
+        start.y().min(rows)..(end.y() + 1).min(rows),
+    )
+}
+
+/// Perform a grid [`Action`] on some [`Tabular`] data: the default behavior
+/// of [`Tabular::perform`], for implementations that override it to do
+/// something more.
+pub fn apply_action<T, K, R, D>(data: &mut D, action: Action<K, R>)
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+    D: Tabular<T, K, R> + ?Sized,
+{
+    match action {
+        // editing actions will trigger a recalculation.
+        Action::Edit(edit) => match edit {
+            Edit::Delete => {
+                let selection = *data.selection();
+                data.clear_range(&selection);
+                data.internal().set_data_dirty();
+            }
+            Edit::Paste { anchor, rows } => {
+                let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+                let height = rows.len();
+                if width == 0 {
+                    return;
+                }
+
+                // a selection from the anchor that fits the block a whole
+                // number of times is filled with copies of it
+                let selection = data.selection().normalize();
+                let (selected_width, selected_height) = (selection.width(), selection.height());
+                let (across, down) = if selection.start() == anchor
+                    && selected_width % width == 0
+                    && selected_height % height == 0
+                {
+                    (selected_width / width, selected_height / height)
+                } else {
+                    (1, 1)
+                };
+
+                let columns = (anchor.x() + width * across).min(data.column_count());
+                let row_count = (anchor.y() + height * down).min(data.row_count());
+
+                for y in anchor.y()..row_count {
+                    let row = &rows[(y - anchor.y()) % height];
+                    for x in anchor.x()..columns {
+                        if let Some(text) = row.get((x - anchor.x()) % width) {
+                            data.write_cell_text(K::new(x, y), text);
+                        }
+                    }
+                }
+                data.internal().set_data_dirty();
+            }
+        },
+        Action::Select(range) => data.select_range(data.snap_to_spans(range)),
+        Action::SelectAll => {
+            // a second press selects everything, past the used range
+            let used = data.used_range().map(|range| data.snap_to_spans(range));
+            match used {
+                Some(used) if data.selection().normalize() != used.normalize() => {
+                    data.select_range(used)
+                }
+                _ => data.select_all(),
+            }
+        }
+        Action::MoveSelection(motion) => data.move_selection(motion),
+        Action::ExpandSelection(motion) => data.expand_selection(motion),
+        Action::Sort { column, ascending } => data.sort_rows(column, ascending),
+        Action::InsertRow(row) => data.insert_row(row),
+        Action::InsertColumn(column) => data.insert_column(column),
+        Action::RemoveRow(row) => data.remove_row(row),
+        Action::RemoveColumn(column) => data.remove_column(column),
+        Action::MoveColumn { from, to } => data.move_column(from, to),
+        Action::MoveRow { from, to } => data.move_row(from, to),
+        Action::ResizeDivider(axis, index, delta) => {
+            match axis {
+                Axis::Column => {
+                    if !matches!(data.column_sizing(index), Sizing::Fixed(_)) {
+                        data.fix_column_width(index);
+                    }
+                    if let Some(col) = data.column_sizes_mut().get_mut(index) {
+                        *col = (*col + delta).max(0.0);
+                    }
+                }
+                Axis::Row => {
+                    if matches!(data.row_height(index), RowHeight::FillPortion(_)) {
+                        data.fix_row_height(index);
+                    }
+                    if let Some(row) = data.row_sizes_mut().get_mut(index) {
+                        *row = (*row + delta).max(0.0);
+                    }
+                }
+            }
+            data.internal().set_sizes_dirty();
+        }
+        Action::SetColumnWidth(column, width) => {
+            if !matches!(data.column_sizing(column), Sizing::Fixed(_)) {
+                data.fix_column_width(column);
+            }
+            if let Some(col) = data.column_sizes_mut().get_mut(column) {
+                *col = width.max(0.0);
+            }
+            data.internal().set_sizes_dirty();
+        }
+        Action::SetRowHeight(row, height) => {
+            if matches!(data.row_height(row), RowHeight::FillPortion(_)) {
+                data.fix_row_height(row);
+            }
+            if let Some(size) = data.row_sizes_mut().get_mut(row) {
+                *size = height.max(0.0);
+            }
+            data.internal().set_sizes_dirty();
+        }
+        Action::Fill { source, target } => {
+            let (source, target) = (source.normalize(), target.normalize());
+            let start = source.start();
+            let (width, height) = (source.width(), source.height());
+
+            for cell in source.iter().filter(|cell| !target.contains(cell)) {
+                data.clear_range(&cell.as_range());
+            }
+
+            // the block repeats in both directions away from the source
+            let wrap = |index: usize, start: usize, length: usize| {
+                start + (index as isize - start as isize).rem_euclid(length as isize) as usize
+            };
+            for cell in target.iter().filter(|cell| !source.contains(cell)) {
+                let from = K::new(
+                    wrap(cell.x(), start.x(), width),
+                    wrap(cell.y(), start.y(), height),
+                );
+                if let Some(text) = data.read_cell_text(from) {
+                    data.write_cell_text(cell, &text);
+                }
+            }
+            data.internal().set_data_dirty();
+
+            data.select_range(data.snap_to_spans(target));
+        }
+        Action::_Phantom(_) => {}
+    }
+}
+
+/// The height of a row in a [`Table`](crate::Table).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    /// A fixed height. If no row fills the table, fixed rows are scaled
+    /// together to the height of the table.
+    Fixed(f32),
+    /// A portion of the height left over by the fixed rows, shared with the
+    /// other filling rows.
+    FillPortion(u16),
+}
+
+impl From<f32> for RowHeight {
+    fn from(height: f32) -> Self {
+        Self::Fixed(height)
+    }
+}
+
+/// The width of a column in a [`Table`](crate::Table).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sizing {
+    /// A fixed width. If no column fills the table, fixed columns are scaled
+    /// together to the width of the table.
+    Fixed(f32),
+    /// A portion of the width left over by the other columns, shared with the
+    /// other filling columns.
+    FillPortion(u16),
+    /// The natural width of the widest cell in the column, or its size in
+    /// [`Tabular::column_sizes`] if none of its cells has one.
+    Auto,
+}
+
+impl From<f32> for Sizing {
+    fn from(width: f32) -> Self {
+        Self::Fixed(width)
+    }
+}
+
+/// A group of rows that can be collapsed under its header row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowGroup {
+    /// The rows of the group. The first row is the header of the group and
+    /// stays visible when the group is collapsed.
+    pub rows: std::ops::Range<usize>,
+    /// The outline level of the group, starting at 1 for outermost groups.
+    pub level: usize,
+    /// Whether the group is collapsed.
+    pub collapsed: bool,
+}
+
+impl RowGroup {
+    /// The header row of the group.
+    pub fn header(&self) -> usize {
+        self.rows.start
+    }
+
+    /// Whether the group hides the given row when collapsed.
+    pub fn hides(&self, row: usize) -> bool {
+        row > self.rows.start && row < self.rows.end
+    }
+}
+
+/// A block of cells merged into the cell at its top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellSpan<K> {
+    /// The top-left cell of the span, which holds its item.
+    pub anchor: K,
+    /// The number of columns spanned.
+    pub columns: usize,
+    /// The number of rows spanned.
+    pub rows: usize,
+}
+
+impl<K: Reference> CellSpan<K> {
+    /// Creates a new [`CellSpan`], spanning at least one cell each way.
+    pub fn new(anchor: K, columns: usize, rows: usize) -> Self {
+        Self {
+            anchor,
+            columns: columns.max(1),
+            rows: rows.max(1),
+        }
+    }
+
+    /// The bottom-right cell of the span.
+    pub fn end(&self) -> K {
+        K::new(
+            self.anchor.x() + self.columns.max(1) - 1,
+            self.anchor.y() + self.rows.max(1) - 1,
+        )
+    }
+
+    /// Whether the span contains the given cell.
+    pub fn contains(&self, column: usize, row: usize) -> bool {
+        let end = self.end();
+        (self.anchor.x()..=end.x()).contains(&column) && (self.anchor.y()..=end.y()).contains(&row)
+    }
+
+    /// Whether the given cell is hidden under the span, i.e. it is in the
+    /// span but isn't its anchor.
+    pub fn covers(&self, column: usize, row: usize) -> bool {
+        self.contains(column, row) && (column, row) != self.anchor.as_tuple()
+    }
+}
+
+/// The cells in view of a lazy [`Table`](crate::Table), as last seen by the
+/// widget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellWindow {
+    /// The columns in view.
+    pub columns: std::ops::Range<usize>,
+    /// The rows in view.
+    pub rows: std::ops::Range<usize>,
+    /// The rows pinned in view wherever the table is scrolled to.
+    pub pinned_rows: Vec<std::ops::Range<usize>>,
+    /// The columns pinned in view wherever the table is scrolled to.
+    pub pinned_columns: Vec<std::ops::Range<usize>>,
+}
+
+impl CellWindow {
+    /// Whether the given cell is in view.
+    pub fn contains(&self, column: usize, row: usize) -> bool {
+        (self.columns.contains(&column)
+            || self
+                .pinned_columns
+                .iter()
+                .any(|columns| columns.contains(&column)))
+            && (self.rows.contains(&row) || self.pinned_rows.iter().any(|rows| rows.contains(&row)))
+    }
+
+    /// The cells in view, column by column.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        fn merge(window: &std::ops::Range<usize>, pinned: &[std::ops::Range<usize>]) -> Vec<usize> {
+            let mut indices: Vec<usize> = pinned
+                .iter()
+                .flat_map(|range| range.clone())
+                .chain(window.clone())
+                .collect();
+            indices.sort_unstable();
+            indices.dedup();
+            indices
+        }
+
+        let rows = merge(&self.rows, &self.pinned_rows);
+        merge(&self.columns, &self.pinned_columns)
+            .into_iter()
+            .flat_map(move |column| rows.clone().into_iter().map(move |row| (column, row)))
+    }
+}
+
+/// What changed in the `Tabular` content since the widget last caught up
+/// with it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dirty {
+    /// The cells changed, and may be laid out differently.
+    pub data: bool,
+    /// The sizes of the rows or columns changed, or the rows that are
+    /// hidden.
+    pub sizes: bool,
+    /// The selection or the active cell changed, which only need drawing.
+    pub selection: bool,
+}
+
+impl Dirty {
+    /// Everything changed.
+    pub const ALL: Self = Self {
+        data: true,
+        sizes: true,
+        selection: true,
+    };
+
+    /// Whether anything changed.
+    pub fn any(self) -> bool {
+        self.data || self.sizes || self.selection
+    }
+
+    fn bits(self) -> u8 {
+        u8::from(self.data) | u8::from(self.sizes) << 1 | u8::from(self.selection) << 2
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            data: bits & 1 != 0,
+            sizes: bits & 1 << 1 != 0,
+            selection: bits & 1 << 2 != 0,
+        }
     }
 }
 
 pub struct Internal {
-    /// Whether the `Tabular` content is dirty and needs to be rebuilt by the
-    /// widget.
-    is_dirty: RefCell<bool>,
+    /// What the widget needs to catch up with in the `Tabular` content, as
+    /// the bits of a [`Dirty`].
+    dirty: AtomicU8,
+    /// The cells in view of a lazy widget.
+    window: Mutex<Option<CellWindow>>,
 }
 
 impl Clone for Internal {
@@ -239,26 +1024,85 @@ impl Clone for Internal {
 }
 
 impl Internal {
-    /// Mark the content as dirty.
+    /// Mark all of the content as dirty.
     pub fn set_dirty(&self) {
-        *self.is_dirty.borrow_mut() = true;
+        self.mark(Dirty::ALL);
+    }
+
+    /// Mark the cells as dirty.
+    pub fn set_data_dirty(&self) {
+        self.mark(Dirty {
+            data: true,
+            ..Dirty::default()
+        });
+    }
+
+    /// Mark the sizes of the rows and columns as dirty.
+    pub fn set_sizes_dirty(&self) {
+        self.mark(Dirty {
+            sizes: true,
+            ..Dirty::default()
+        });
+    }
+
+    /// Mark the selection as dirty.
+    pub fn set_selection_dirty(&self) {
+        self.mark(Dirty {
+            selection: true,
+            ..Dirty::default()
+        });
     }
 
     /// Mark the content as clean.
     pub fn set_clean(&self) {
-        *self.is_dirty.borrow_mut() = false;
+        self.dirty.store(0, Ordering::Relaxed);
     }
 
-    /// Check if the content is dirty.
+    /// Check if any of the content is dirty.
     pub fn is_dirty(&self) -> bool {
-        *self.is_dirty.borrow()
+        self.dirty().any()
+    }
+
+    /// What of the content is dirty.
+    pub fn dirty(&self) -> Dirty {
+        Dirty::from_bits(self.dirty.load(Ordering::Relaxed))
+    }
+
+    /// What of the content is dirty, marking it clean at once so that
+    /// nothing marked in between is lost.
+    pub fn take_dirty(&self) -> Dirty {
+        Dirty::from_bits(self.dirty.swap(0, Ordering::Relaxed))
+    }
+
+    fn mark(&self, dirty: Dirty) {
+        self.dirty.fetch_or(dirty.bits(), Ordering::Relaxed);
+    }
+
+    /// The cells in view of a lazy widget, if it has been drawn.
+    pub fn window(&self) -> Option<CellWindow> {
+        self.window
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Set the cells in view. Returns whether the window changed.
+    pub fn set_window(&self, window: CellWindow) -> bool {
+        let mut current = self.window.lock().unwrap_or_else(PoisonError::into_inner);
+        if current.as_ref() == Some(&window) {
+            false
+        } else {
+            *current = Some(window);
+            true
+        }
     }
 }
 
 impl Default for Internal {
     fn default() -> Self {
         Self {
-            is_dirty: RefCell::new(true),
+            dirty: AtomicU8::new(Dirty::ALL.bits()),
+            window: Mutex::new(None),
         }
     }
 }
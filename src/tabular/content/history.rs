@@ -0,0 +1,600 @@
+//! The undo history of a [`list::Content`](super::list::Content) or a
+//! [`rows::Content`](super::rows::Content).
+use std::collections::VecDeque;
+
+use iced::time::{Duration, Instant};
+
+use super::{
+    apply_action, CellSpan, ColumnType, Reference, ReferenceRange, RowGroup, Sizing, Tabular,
+};
+use crate::tabular::action::Edit;
+use crate::{Action, Axis};
+
+/// How long consecutive deletes keep coalescing into a single undo step.
+pub(crate) const COALESCE_WINDOW: Duration = Duration::from_millis(500);
+
+/// A change recorded in the history, with the values before and after it.
+#[derive(Clone)]
+pub(crate) enum Change<T, K> {
+    /// An item written to a cell.
+    Cell { cell: K, before: T, after: T },
+    /// The width of a column, along with its sizing if it isn't fixed.
+    ColumnWidth {
+        column: usize,
+        before: (f32, Option<Sizing>),
+        after: (f32, Option<Sizing>),
+    },
+    /// The height of a row, along with the portion of the table it fills.
+    RowHeight {
+        row: usize,
+        before: (f32, Option<u16>),
+        after: (f32, Option<u16>),
+    },
+    /// An empty row inserted at the given index.
+    InsertRow { row: usize },
+    /// An empty column inserted at the given index.
+    InsertColumn { column: usize },
+    /// A row removed from the given index, with everything needed to put it
+    /// back.
+    RemoveRow {
+        row: usize,
+        items: Vec<T>,
+        size: (f32, Option<u16>),
+        groups: Vec<RowGroup>,
+        spans: Vec<CellSpan<K>>,
+    },
+    /// A column removed from the given index, with everything needed to put
+    /// it back.
+    RemoveColumn {
+        column: usize,
+        items: Vec<T>,
+        width: (f32, Option<Sizing>),
+        column_type: Option<ColumnType>,
+        spans: Vec<CellSpan<K>>,
+    },
+    /// A column moved from one index to another, with the merged cells
+    /// before the move.
+    MoveColumn {
+        from: usize,
+        to: usize,
+        spans: Vec<CellSpan<K>>,
+    },
+    /// A row moved from one index to another, with the groups and merged
+    /// cells before the move.
+    MoveRow {
+        from: usize,
+        to: usize,
+        groups: Vec<RowGroup>,
+        spans: Vec<CellSpan<K>>,
+    },
+    /// Two rows exchanged, along with their heights.
+    SwapRows { a: usize, b: usize },
+    /// Two columns exchanged, along with their widths and types.
+    SwapColumns { a: usize, b: usize },
+    /// The rows reordered, the new row `i` being the old row `order[i]`,
+    /// with the merged cells before the reorder.
+    Reorder {
+        order: Vec<usize>,
+        spans: Vec<CellSpan<K>>,
+    },
+}
+
+/// The kinds of undo steps, as far as coalescing is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Kind {
+    /// Cells cleared by a delete.
+    Delete,
+    /// Anything else, which never coalesces.
+    Other,
+}
+
+/// A single undo step.
+#[derive(Clone)]
+pub(crate) struct Entry<T, K> {
+    pub(crate) changes: Vec<Change<T, K>>,
+    kind: Kind,
+    at: Instant,
+}
+
+impl<T, K: Reference> Entry<T, K> {
+    // Fold a later change into this step, keeping the earliest value of a cell
+    fn merge(&mut self, change: Change<T, K>) {
+        let Change::Cell {
+            cell,
+            before,
+            after,
+        } = change
+        else {
+            self.changes.push(change);
+            return;
+        };
+
+        for existing in &mut self.changes {
+            if let Change::Cell {
+                cell: existing,
+                after: existing_after,
+                ..
+            } = existing
+            {
+                if *existing == cell {
+                    *existing_after = after;
+                    return;
+                }
+            }
+        }
+
+        self.changes.push(Change::Cell {
+            cell,
+            before,
+            after,
+        });
+    }
+}
+
+/// A bounded history of undo and redo steps.
+#[derive(Clone)]
+pub(crate) struct History<T, K> {
+    capacity: usize,
+    /// Copies an item, so the history doesn't need `T: Clone` everywhere.
+    pub(crate) snapshot: fn(&T) -> T,
+    /// Tells the time steps are recorded at, to coalesce quick deletes.
+    pub(crate) clock: fn() -> Instant,
+    pub(crate) undo: VecDeque<Entry<T, K>>,
+    pub(crate) redo: Vec<Entry<T, K>>,
+    // The step the changes of a single action are gathered in, if any
+    group: Option<Entry<T, K>>,
+}
+
+impl<T, K: Reference> History<T, K> {
+    pub(crate) fn new(capacity: usize, snapshot: fn(&T) -> T) -> Self {
+        Self {
+            capacity,
+            snapshot,
+            clock: Instant::now,
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            group: None,
+        }
+    }
+
+    /// Gather the changes recorded until [`History::end`] into one step.
+    pub(crate) fn begin(&mut self) {
+        self.group = Some(Entry {
+            changes: Vec::new(),
+            kind: Kind::Other,
+            at: (self.clock)(),
+        });
+    }
+
+    /// Record the changes gathered since [`History::begin`] as one step.
+    pub(crate) fn end(&mut self) {
+        if let Some(group) = self.group.take() {
+            self.record(group.kind, group.changes);
+        }
+    }
+
+    /// Record a new undo step, dropping the redo steps and the oldest steps
+    /// beyond the capacity.
+    pub(crate) fn record(&mut self, kind: Kind, changes: Vec<Change<T, K>>) {
+        if changes.is_empty() || self.capacity == 0 {
+            return;
+        }
+
+        if let Some(group) = &mut self.group {
+            for change in changes {
+                group.merge(change);
+            }
+            return;
+        }
+
+        self.redo.clear();
+        let now = (self.clock)();
+
+        if let Some(last) = self.undo.back_mut() {
+            if kind == Kind::Delete
+                && last.kind == Kind::Delete
+                && now.duration_since(last.at) < COALESCE_WINDOW
+            {
+                for change in changes {
+                    last.merge(change);
+                }
+                last.at = now;
+                return;
+            }
+        }
+
+        self.undo.push_back(Entry {
+            changes,
+            kind,
+            at: now,
+        });
+        while self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+}
+
+/// A layout of the items of a table, with the edits the [`History`] records
+/// and replays.
+///
+/// None of the edits record anything themselves. Each keeps the sizes, row
+/// groups, merged cells and selection in step with the items it moves.
+pub(crate) trait Layout<T: Default, K: Reference, R: ReferenceRange<K>>:
+    Tabular<T, K, R>
+{
+    /// The history of the table, if it keeps one.
+    fn history(&mut self) -> &mut Option<History<T, K>>;
+
+    /// Insert an empty row at the given index, shifting everything below it.
+    fn insert_row_at(&mut self, row: usize);
+
+    /// Insert an empty column at the given index, shifting everything right
+    /// of it.
+    fn insert_column_at(&mut self, column: usize);
+
+    /// Remove the row at the given index, shifting everything below it.
+    fn remove_row_at(&mut self, row: usize);
+
+    /// Remove the column at the given index, shifting everything right of
+    /// it.
+    fn remove_column_at(&mut self, column: usize);
+
+    /// Move the column at `from` to `to`, shifting the columns in between.
+    fn move_column_at(&mut self, from: usize, to: usize);
+
+    /// Move the row at `from` to `to`, shifting the rows in between.
+    fn move_row_at(&mut self, from: usize, to: usize);
+
+    /// Exchange two rows, leaving the row groups and merged cells where they
+    /// are.
+    fn swap_rows_at(&mut self, a: usize, b: usize);
+
+    /// Exchange two columns, leaving the merged cells where they are.
+    fn swap_columns_at(&mut self, a: usize, b: usize);
+
+    /// Reorder the rows so that the new row `i` is the old row `order[i]`,
+    /// returning where each old row ended up.
+    fn permute_rows(&mut self, order: &[usize]) -> Vec<usize>;
+
+    /// The width of a column along with its sizing if it isn't fixed.
+    fn column_size(&self, column: usize) -> Option<(f32, Option<Sizing>)>;
+
+    /// Set the width of a column along with its sizing if it isn't fixed.
+    fn set_column_size(&mut self, column: usize, size: (f32, Option<Sizing>));
+
+    /// The height of a row along with the portion of the table it fills.
+    fn row_size(&self, row: usize) -> Option<(f32, Option<u16>)>;
+
+    /// Set the height of a row along with the portion of the table it fills.
+    fn set_row_size(&mut self, row: usize, size: (f32, Option<u16>));
+
+    /// Fill an empty row inserted in place of a removed one with its items.
+    fn restore_row(&mut self, row: usize, items: Vec<T>);
+
+    /// Fill an empty column inserted in place of a removed one with its
+    /// items and type.
+    fn restore_column(&mut self, column: usize, items: Vec<T>, column_type: Option<ColumnType>);
+
+    /// Put back the row groups and merged cells from before a change.
+    fn restore_outline(&mut self, groups: Option<&[RowGroup]>, spans: &[CellSpan<K>]);
+}
+
+/// Perform an [`Action`], recording what it changes in the history of the
+/// table as a single step.
+pub(crate) fn perform<T, K, R>(data: &mut impl Layout<T, K, R>, action: Action<K, R>)
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let Some(snapshot) = data.history().as_ref().map(|history| history.snapshot) else {
+        return apply_action(data, action);
+    };
+
+    match action {
+        Action::Edit(Edit::Delete) => {
+            let before: Vec<(K, T)> = data
+                .iter_range(data.selection())
+                .map(|(cell, item)| (cell, snapshot(item)))
+                .collect();
+
+            apply_action(data, action);
+
+            let changes = cell_changes(data, snapshot, before);
+            record(data, Kind::Delete, changes);
+        }
+        Action::Fill { source, target } => {
+            let (source, target) = (source.normalize(), target.normalize());
+            let before: Vec<(K, T)> = source
+                .iter()
+                .chain(target.iter().filter(|cell| !source.contains(cell)))
+                .filter_map(|cell| Some((cell, snapshot(data.get(cell)?))))
+                .collect();
+
+            // the cells written by the fill make up a single step
+            let history = data.history().take();
+            apply_action(data, action);
+            *data.history() = history;
+
+            let changes = cell_changes(data, snapshot, before);
+            record(data, Kind::Other, changes);
+        }
+        Action::ResizeDivider(Axis::Column, column, _) | Action::SetColumnWidth(column, _) => {
+            let before = data.column_size(column);
+            apply_action(data, action);
+            let after = data.column_size(column);
+
+            if let (Some(before), Some(after)) = (before, after) {
+                record(
+                    data,
+                    Kind::Other,
+                    vec![Change::ColumnWidth {
+                        column,
+                        before,
+                        after,
+                    }],
+                );
+            }
+        }
+        Action::ResizeDivider(Axis::Row, row, _) | Action::SetRowHeight(row, _) => {
+            let before = data.row_size(row);
+            apply_action(data, action);
+            let after = data.row_size(row);
+
+            if let (Some(before), Some(after)) = (before, after) {
+                record(
+                    data,
+                    Kind::Other,
+                    vec![Change::RowHeight { row, before, after }],
+                );
+            }
+        }
+        action => {
+            // everything written by one action is undone at once, e.g. the
+            // cells of a paste
+            if let Some(history) = data.history() {
+                history.begin();
+            }
+            apply_action(data, action);
+            if let Some(history) = data.history() {
+                history.end();
+            }
+        }
+    }
+}
+
+// The changes from the given items to the ones now in their cells
+fn cell_changes<T, K, R>(
+    data: &impl Layout<T, K, R>,
+    snapshot: fn(&T) -> T,
+    before: Vec<(K, T)>,
+) -> Vec<Change<T, K>>
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    before
+        .into_iter()
+        .filter_map(|(cell, before)| {
+            let after = snapshot(data.get(cell)?);
+            Some(Change::Cell {
+                cell,
+                before,
+                after,
+            })
+        })
+        .collect()
+}
+
+/// Record the changes as a new step in the history of the table, if it
+/// keeps one.
+pub(crate) fn record<T, K, R>(
+    data: &mut impl Layout<T, K, R>,
+    kind: Kind,
+    changes: Vec<Change<T, K>>,
+) where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    if let Some(history) = data.history() {
+        history.record(kind, changes);
+    }
+}
+
+/// Undo the last recorded step, returning the range it affected.
+pub(crate) fn undo<T, K, R>(data: &mut impl Layout<T, K, R>) -> Option<R>
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let entry = data.history().as_mut()?.undo.pop_back()?;
+
+    for change in entry.changes.iter().rev() {
+        apply_change(data, change, false);
+    }
+    let range = affected_range(data, &entry.changes);
+
+    data.history().as_mut()?.redo.push(entry);
+    data.internal().set_dirty();
+    Some(range)
+}
+
+/// Redo the last undone step, returning the range it affected.
+pub(crate) fn redo<T, K, R>(data: &mut impl Layout<T, K, R>) -> Option<R>
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let entry = data.history().as_mut()?.redo.pop()?;
+
+    for change in &entry.changes {
+        apply_change(data, change, true);
+    }
+    let range = affected_range(data, &entry.changes);
+
+    data.history().as_mut()?.undo.push_back(entry);
+    data.internal().set_dirty();
+    Some(range)
+}
+
+// Restore a recorded change to its value after (or before) it was made
+fn apply_change<T, K, R>(data: &mut impl Layout<T, K, R>, change: &Change<T, K>, forward: bool)
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let Some(snapshot) = data.history().as_ref().map(|history| history.snapshot) else {
+        return;
+    };
+
+    match change {
+        Change::Cell {
+            cell,
+            before,
+            after,
+        } => {
+            if let Some(item) = data.get_mut(*cell) {
+                *item = snapshot(if forward { after } else { before });
+            }
+        }
+        Change::ColumnWidth {
+            column,
+            before,
+            after,
+        } => {
+            data.set_column_size(*column, if forward { *after } else { *before });
+        }
+        Change::RowHeight { row, before, after } => {
+            data.set_row_size(*row, if forward { *after } else { *before });
+        }
+        Change::InsertRow { row } => {
+            if forward {
+                data.insert_row_at(*row);
+            } else {
+                data.remove_row_at(*row);
+            }
+        }
+        Change::InsertColumn { column } => {
+            if forward {
+                data.insert_column_at(*column);
+            } else {
+                data.remove_column_at(*column);
+            }
+        }
+        Change::RemoveRow {
+            row,
+            items,
+            size,
+            groups,
+            spans,
+        } => {
+            if forward {
+                data.remove_row_at(*row);
+            } else {
+                data.insert_row_at(*row);
+                data.restore_row(*row, items.iter().map(snapshot).collect());
+                data.set_row_size(*row, *size);
+                data.restore_outline(Some(groups), spans);
+            }
+        }
+        Change::RemoveColumn {
+            column,
+            items,
+            width,
+            column_type,
+            spans,
+        } => {
+            if forward {
+                data.remove_column_at(*column);
+            } else {
+                data.insert_column_at(*column);
+                data.restore_column(
+                    *column,
+                    items.iter().map(snapshot).collect(),
+                    column_type.clone(),
+                );
+                data.set_column_size(*column, *width);
+                data.restore_outline(None, spans);
+            }
+        }
+        Change::MoveColumn { from, to, spans } => {
+            if forward {
+                data.move_column_at(*from, *to);
+            } else {
+                data.move_column_at(*to, *from);
+                data.restore_outline(None, spans);
+            }
+        }
+        Change::MoveRow {
+            from,
+            to,
+            groups,
+            spans,
+        } => {
+            if forward {
+                data.move_row_at(*from, *to);
+            } else {
+                data.move_row_at(*to, *from);
+                data.restore_outline(Some(groups), spans);
+            }
+        }
+        // a swap undoes itself
+        Change::SwapRows { a, b } => data.swap_rows_at(*a, *b),
+        Change::SwapColumns { a, b } => data.swap_columns_at(*a, *b),
+        Change::Reorder { order, spans } => {
+            if forward {
+                data.permute_rows(order);
+            } else {
+                let mut inverse = vec![0; order.len()];
+                for (new, &old) in order.iter().enumerate() {
+                    inverse[old] = new;
+                }
+                data.permute_rows(&inverse);
+                // the merged cells unmerged by the sort are merged again
+                data.restore_outline(None, spans);
+            }
+        }
+    }
+}
+
+// The smallest range covering the given changes
+fn affected_range<T, K, R>(data: &impl Layout<T, K, R>, changes: &[Change<T, K>]) -> R
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let last_column = data.column_count().saturating_sub(1);
+    let last_row = data.row_count().saturating_sub(1);
+
+    let (mut first, mut last) = ((usize::MAX, usize::MAX), (0, 0));
+    for change in changes {
+        let (start, end) = match change {
+            Change::Cell { cell, .. } => (cell.as_tuple(), cell.as_tuple()),
+            Change::ColumnWidth { column, .. } => ((*column, 0), (*column, last_row)),
+            Change::RowHeight { row, .. } => ((0, *row), (last_column, *row)),
+            Change::InsertRow { row } => ((0, *row), (last_column, *row)),
+            Change::InsertColumn { column } => ((*column, 0), (*column, last_row)),
+            Change::RemoveRow { row, .. } => ((0, *row), (last_column, *row)),
+            Change::RemoveColumn { column, .. } => ((*column, 0), (*column, last_row)),
+            Change::MoveColumn { from, to, .. } => ((*from.min(to), 0), (*from.max(to), last_row)),
+            Change::MoveRow { from, to, .. } => ((0, *from.min(to)), (last_column, *from.max(to))),
+            Change::SwapRows { a, b } => ((0, *a.min(b)), (last_column, *a.max(b))),
+            Change::SwapColumns { a, b } => ((*a.min(b), 0), (*a.max(b), last_row)),
+            Change::Reorder { .. } => ((0, 0), (last_column, last_row)),
+        };
+        first = (first.0.min(start.0), first.1.min(start.1));
+        last = (last.0.max(end.0), last.1.max(end.1));
+    }
+
+    if first == last {
+        R::new(K::new(first.0, first.1), None)
+    } else {
+        R::new(K::new(first.0, first.1), Some(K::new(last.0, last.1)))
+    }
+}
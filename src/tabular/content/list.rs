@@ -1,25 +1,323 @@
 // //! A default implementation of `Tabular` in a `Vec<Vec<T>>` format.
-use super::{Internal, Reference, ReferenceRange, Tabular};
-use crate::{Address, Range};
-use iced::advanced::{Renderer, renderer};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use super::history::{self, Change, History, Kind, Layout};
+use super::{
+    clamp_range, CellSpan, ColumnType, Internal, Reference, ReferenceRange, RowGroup, RowHeight,
+    Sizing, Tabular,
+};
+use crate::tabular::{snap_length, snap_to_pixels};
+use crate::{Action, Address, Range};
+use iced::advanced::{renderer, Renderer};
+use iced::Background;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+/// The width of columns added to grow the grid, unless configured otherwise.
+pub(crate) const DEFAULT_COLUMN_WIDTH: f32 = 100.0;
+/// The height of rows added to grow the grid, unless configured otherwise.
+pub(crate) const DEFAULT_ROW_HEIGHT: f32 = 20.0;
+
+/// Where the line at `index` ends up once the line at `from` is moved to `to`.
+pub(super) fn moved_index(index: usize, from: usize, to: usize) -> usize {
+    if index == from {
+        to
+    } else if from < to && (from + 1..=to).contains(&index) {
+        index - 1
+    } else if to < from && (to..from).contains(&index) {
+        index + 1
+    } else {
+        index
+    }
+}
+
+/// Shift or grow the row groups and merged cells for an empty row inserted
+/// at `row`.
+pub(super) fn insert_row_in_outline<K: Reference>(
+    groups: &mut [RowGroup],
+    spans: &mut [CellSpan<K>],
+    row: usize,
+) {
+    for group in groups {
+        if group.rows.start >= row {
+            group.rows = group.rows.start + 1..group.rows.end + 1;
+        } else if group.rows.end > row {
+            group.rows.end += 1;
+        }
+    }
+    for span in spans {
+        let (x, y) = span.anchor.as_tuple();
+        if y >= row {
+            span.anchor = K::new(x, y + 1);
+        } else if span.end().y() >= row {
+            span.rows += 1;
+        }
+    }
+}
+
+/// Shift or shrink the row groups and merged cells for the row removed at
+/// `row`, dropping the groups headed by it and the spans left as one cell.
+pub(super) fn remove_row_from_outline<K: Reference>(
+    groups: &mut Vec<RowGroup>,
+    spans: &mut Vec<CellSpan<K>>,
+    row: usize,
+) {
+    groups.retain(|group| group.rows.start != row);
+    for group in groups {
+        if group.rows.start > row {
+            group.rows = group.rows.start - 1..group.rows.end - 1;
+        } else if group.rows.end > row {
+            group.rows.end -= 1;
+        }
+    }
+    spans.retain(|span| span.anchor.y() != row || span.rows > 1);
+    for span in spans.iter_mut() {
+        let (x, y) = span.anchor.as_tuple();
+        if y > row {
+            span.anchor = K::new(x, y - 1);
+        } else if span.end().y() >= row {
+            span.rows -= 1;
+        }
+    }
+    spans.retain(|span| span.rows > 1 || span.columns > 1);
+}
+
+/// Shift or grow the merged cells for an empty column inserted at `column`.
+pub(super) fn insert_column_in_spans<K: Reference>(spans: &mut [CellSpan<K>], column: usize) {
+    for span in spans {
+        let (x, y) = span.anchor.as_tuple();
+        if x >= column {
+            span.anchor = K::new(x + 1, y);
+        } else if span.end().x() >= column {
+            span.columns += 1;
+        }
+    }
+}
+
+/// Shift or shrink the merged cells for the column removed at `column`,
+/// dropping the spans left as one cell.
+pub(super) fn remove_column_from_spans<K: Reference>(spans: &mut Vec<CellSpan<K>>, column: usize) {
+    spans.retain(|span| span.anchor.x() != column || span.columns > 1);
+    for span in spans.iter_mut() {
+        let (x, y) = span.anchor.as_tuple();
+        if x > column {
+            span.anchor = K::new(x - 1, y);
+        } else if span.end().x() >= column {
+            span.columns -= 1;
+        }
+    }
+    spans.retain(|span| span.rows > 1 || span.columns > 1);
+}
+
+/// Move the merged cells along with the column moved from `from` to `to`,
+/// unmerging the spans that the move would tear apart.
+pub(super) fn move_column_in_spans<K: Reference>(
+    spans: &mut Vec<CellSpan<K>>,
+    from: usize,
+    to: usize,
+) {
+    spans.retain_mut(|span| {
+        let (x, y) = span.anchor.as_tuple();
+        let moved = (x..x + span.columns).map(|x| moved_index(x, from, to));
+        let (first, last) = moved.fold((usize::MAX, 0), |(first, last), x| {
+            (first.min(x), last.max(x))
+        });
+        span.anchor = K::new(first, y);
+        last - first + 1 == span.columns
+    });
+}
+
+/// Move the row groups and merged cells along with the row moved from `from`
+/// to `to`, dropping those that the move would tear apart.
+pub(super) fn move_row_in_outline<K: Reference>(
+    groups: &mut Vec<RowGroup>,
+    spans: &mut Vec<CellSpan<K>>,
+    from: usize,
+    to: usize,
+) {
+    // the rows of a group or span must stay together, or it's dropped
+    let moved = |rows: std::ops::Range<usize>| {
+        let len = rows.len();
+        let (first, last) = rows
+            .map(|y| moved_index(y, from, to))
+            .fold((usize::MAX, 0), |(first, last), y| {
+                (first.min(y), last.max(y))
+            });
+        (last + 1 - first == len).then_some(first..last + 1)
+    };
+    groups.retain_mut(|group| {
+        moved(group.rows.clone())
+            .map(|rows| group.rows = rows)
+            .is_some()
+    });
+    spans.retain_mut(|span| {
+        let (x, y) = span.anchor.as_tuple();
+        moved(y..y + span.rows)
+            .map(|rows| span.anchor = K::new(x, rows.start))
+            .is_some()
+    });
+}
+
+/// Move the merged cells within a row to where their rows ended up after a
+/// sort, unmerging the taller ones which can't survive it.
+pub(super) fn permute_spans<K: Reference>(spans: &mut Vec<CellSpan<K>>, moved: &[usize]) {
+    spans.retain(|span| span.rows == 1);
+    for span in spans {
+        let (x, y) = span.anchor.as_tuple();
+        span.anchor = K::new(x, moved.get(y).copied().unwrap_or(y));
+    }
+}
+
+/// Add a row group, replacing the one with the same header row and level.
+pub(super) fn set_row_group(
+    groups: &mut Vec<RowGroup>,
+    rows: std::ops::Range<usize>,
+    level: usize,
+) {
+    let level = level.max(1);
+    groups.retain(|group| group.header() != rows.start || group.level != level);
+    groups.push(RowGroup {
+        rows,
+        level,
+        collapsed: false,
+    });
+    groups.sort_by_key(|group| (group.level, group.rows.start));
+}
+
+/// Add a merged span over a range, unmerging the spans overlapping it.
+pub(super) fn merge_span<K: Reference, R: ReferenceRange<K>>(
+    spans: &mut Vec<CellSpan<K>>,
+    range: R,
+) {
+    let range = range.normalize();
+    let start = range.start();
+    let end = range.end().unwrap_or(start);
+    let span = CellSpan::new(start, range.width(), range.height());
+
+    spans.retain(|other| {
+        let (x, y) = other.anchor.as_tuple();
+        let (x2, y2) = other.end().as_tuple();
+        x > end.x() || x2 < start.x() || y > end.y() || y2 < start.y()
+    });
+    if span.columns > 1 || span.rows > 1 {
+        spans.push(span);
+    }
+}
+
+/// A function that checks whether an item may be written to a cell.
+pub type Validator<T, K> = Arc<dyn Fn(K, &T) -> Result<(), String> + Send + Sync>;
+
+/// A function that orders two items of a column with the given type.
+pub type Comparator<T> = Arc<dyn Fn(&ColumnType, &T, &T) -> Ordering + Send + Sync>;
+
+/// A function that checks whether an item counts as empty.
+pub type Emptiness<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+/// A function that gives the text of an item.
+pub type ToText<T> = Arc<dyn Fn(&T) -> String + Send + Sync>;
+
+/// A function that makes an item out of some text.
+pub type FromText<T> = Arc<dyn Fn(&str) -> T + Send + Sync>;
+
+/// A function that gives the text shown for the item of a cell, if it isn't
+/// the item itself.
+pub type DisplayText<T, K> = Arc<dyn Fn(K, &T) -> Option<String> + Send + Sync>;
+
 #[derive(Clone)]
 pub struct Content<T = Cell, K: Reference = Address, R: ReferenceRange<K> = Range> {
     columns: Vec<Vec<T>>,
     selection: R,
+    // The active cell of the selection, if it isn't the start of it
+    active: Option<K>,
     col_widths: Vec<f32>,
+    // The sizing of the columns that aren't fixed at their width
+    col_sizing: Vec<Option<Sizing>>,
     row_heights: Vec<f32>,
+    row_fills: Vec<Option<u16>>,
+    // The sizes of the columns and rows added to grow the grid
+    default_width: f32,
+    default_height: f32,
     range: R,
     internal: Internal,
+    validator: Option<Validator<T, K>>,
+    comparator: Option<Comparator<T>>,
+    emptiness: Option<Emptiness<T>>,
+    text: Option<(ToText<T>, FromText<T>)>,
+    display_text: Option<DisplayText<T, K>>,
+    column_types: Vec<ColumnType>,
+    row_groups: Vec<RowGroup>,
+    spans: Vec<CellSpan<K>>,
+    history: Option<History<T, K>>,
+    rejections: Rejections<K>,
     _phantom: std::marker::PhantomData<K>,
 }
 
-#[derive(Default, Clone)]
+// A `Content` can be shared with other threads, e.g. to export it in the
+// background
+const _: fn() = || {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<Content<Cell>>();
+};
+
+/// The edits turned down by the validator of a [`Content`], or whose text
+/// doesn't convert to the column type, until they are taken by the widget.
+pub(super) struct Rejections<K>(Mutex<Vec<(K, String)>>);
+
+impl<K> Rejections<K> {
+    pub(super) fn push(&self, cell: K, reason: String) {
+        self.lock().push((cell, reason));
+    }
+
+    pub(super) fn take(&self) -> Vec<(K, String)> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(K, String)>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K: Clone> Clone for Rejections<K> {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.lock().clone()))
+    }
+}
+
+impl<K> Default for Rejections<K> {
+    fn default() -> Self {
+        Self(Mutex::default())
+    }
+}
+
+/// The parts of a [`Content`] shared with the other layouts.
+pub(super) struct Parts<T, K, R> {
+    pub(super) columns: Vec<Vec<T>>,
+    pub(super) selection: R,
+    pub(super) active: Option<K>,
+    pub(super) col_widths: Vec<f32>,
+    pub(super) row_heights: Vec<f32>,
+    pub(super) range: R,
+    pub(super) validator: Option<Validator<T, K>>,
+    pub(super) comparator: Option<Comparator<T>>,
+    pub(super) text: Option<(ToText<T>, FromText<T>)>,
+    pub(super) column_types: Vec<ColumnType>,
+    pub(super) row_groups: Vec<RowGroup>,
+    pub(super) spans: Vec<CellSpan<K>>,
+    pub(super) history: Option<History<T, K>>,
+    pub(super) rejections: Rejections<K>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Cell<T = String> {
     pub content: T,
+    #[cfg_attr(feature = "serde", serde(default, with = "border"))]
     pub border: Option<iced::Border>,
+    #[cfg_attr(feature = "serde", serde(default, with = "background"))]
+    pub background: Option<Background>,
 }
 
 impl<T> From<T> for Cell<T> {
@@ -27,25 +325,39 @@ impl<T> From<T> for Cell<T> {
         Self {
             content,
             border: None,
+            background: None,
         }
     }
 }
+impl From<&str> for Cell<String> {
+    fn from(content: &str) -> Self {
+        content.to_owned().into()
+    }
+}
+
 impl crate::tabular::Cell for Cell {
     fn has_borders(&self) -> bool {
         self.border.is_some()
     }
 
+    fn background(&self) -> Option<Background> {
+        self.background
+    }
+
     fn fill_border_quads<R: Renderer>(
         &self,
         renderer: &mut R,
         bounds: iced::Rectangle,
-        scaling: f32,
+        scale_factor: f32,
     ) {
         if let Some(border) = &self.border {
             renderer.fill_quad(
                 renderer::Quad {
-                    bounds: bounds * scaling,
-                    border: *border,
+                    bounds: snap_to_pixels(bounds, scale_factor),
+                    border: iced::Border {
+                        width: snap_length(border.width, scale_factor),
+                        ..*border
+                    },
                     ..Default::default()
                 },
                 iced::Color::TRANSPARENT,
@@ -62,10 +374,25 @@ where
         Self {
             columns: Vec::new(),
             selection: R::default(),
+            active: None,
             col_widths: vec![],
             row_heights: vec![],
-            range: range.clone(),
+            row_fills: Vec::new(),
+            col_sizing: Vec::new(),
+            default_width: DEFAULT_COLUMN_WIDTH,
+            default_height: DEFAULT_ROW_HEIGHT,
+            range: *range,
             internal: Internal::default(),
+            validator: None,
+            comparator: None,
+            emptiness: None,
+            text: None,
+            display_text: None,
+            column_types: Vec::new(),
+            row_groups: Vec::new(),
+            spans: Vec::new(),
+            history: None,
+            rejections: Rejections::default(),
             _phantom: std::marker::PhantomData,
         }
     }
@@ -85,7 +412,17 @@ where
     }
 
     fn insert(&mut self, cell: impl Into<K>, item: impl Into<T>) {
-        let (x, y) = cell.into().as_tuple();
+        let cell = cell.into();
+        let item = item.into();
+
+        if let Some(validator) = &self.validator {
+            if let Err(reason) = validator(cell, &item) {
+                self.rejections.push(cell, reason);
+                return;
+            }
+        }
+
+        let (x, y) = cell.as_tuple();
 
         // Find the max dimensions needed
         let needed_cols = (x + 1).max(self.column_count());
@@ -94,8 +431,162 @@ where
         // Ensure we have a uniform grid
         self.ensure_uniform_grid(needed_rows, needed_cols);
 
+        if let Some(history) = &mut self.history {
+            let change = Change::Cell {
+                cell,
+                before: (history.snapshot)(&self.columns[x][y]),
+                after: (history.snapshot)(&item),
+            };
+            history.record(Kind::Other, vec![change]);
+        }
+
         // Now we can safely insert
-        self.columns[x][y] = item.into();
+        self.columns[x][y] = item;
+        self.internal.set_data_dirty();
+    }
+
+    fn insert_row(&mut self, row: usize) {
+        let row = row.min(self.row_count());
+        self.insert_row_at(row);
+        self.record(Kind::Other, vec![Change::InsertRow { row }]);
+    }
+
+    fn insert_column(&mut self, column: usize) {
+        let column = column.min(self.column_count());
+        self.insert_column_at(column);
+        self.record(Kind::Other, vec![Change::InsertColumn { column }]);
+    }
+
+    fn remove_row(&mut self, row: usize) {
+        self.take_row(row);
+    }
+
+    fn remove_column(&mut self, column: usize) {
+        self.take_column(column);
+    }
+
+    fn move_column(&mut self, from: usize, to: usize) {
+        let count = self.column_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let spans = self.spans.clone();
+        self.move_column_at(from, to);
+        self.record(Kind::Other, vec![Change::MoveColumn { from, to, spans }]);
+    }
+
+    fn move_row(&mut self, from: usize, to: usize) {
+        let count = self.row_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let (groups, spans) = (self.row_groups.clone(), self.spans.clone());
+        self.move_row_at(from, to);
+        self.record(
+            Kind::Other,
+            vec![Change::MoveRow {
+                from,
+                to,
+                groups,
+                spans,
+            }],
+        );
+    }
+
+    fn perform(&mut self, action: Action<K, R>) {
+        history::perform(self, action);
+    }
+
+    fn row_groups(&self) -> &[RowGroup] {
+        &self.row_groups
+    }
+
+    fn spans(&self) -> &[CellSpan<K>] {
+        &self.spans
+    }
+
+    fn column_sizing(&self, column: usize) -> Sizing {
+        match self.col_sizing.get(column).copied().flatten() {
+            Some(sizing) => sizing,
+            None => Sizing::Fixed(self.col_widths.get(column).copied().unwrap_or_default()),
+        }
+    }
+
+    fn fix_column_width(&mut self, column: usize) {
+        if let Some(sizing) = self.col_sizing.get_mut(column) {
+            *sizing = None;
+            self.internal.set_sizes_dirty();
+        }
+    }
+
+    fn row_height(&self, row: usize) -> RowHeight {
+        match self.row_fills.get(row).copied().flatten() {
+            Some(portion) => RowHeight::FillPortion(portion),
+            None => RowHeight::Fixed(self.row_heights.get(row).copied().unwrap_or_default()),
+        }
+    }
+
+    fn sort_rows(&mut self, column: usize, ascending: bool) {
+        let column_type = self.column_type(column).clone();
+        if let Some(comparator) = self.comparator.clone() {
+            self.sort_by(column, ascending, |a, b| comparator(&column_type, a, b));
+        } else if let Some((to_text, _)) = self.text.clone() {
+            // the text of the items is compared as values of the column type
+            self.sort_by(column, ascending, |a, b| {
+                column_type.compare(&to_text(a), &to_text(b))
+            });
+        }
+    }
+
+    fn fix_row_height(&mut self, row: usize) {
+        if let Some(fill) = self.row_fills.get_mut(row) {
+            *fill = None;
+            self.internal.set_sizes_dirty();
+        }
+    }
+
+    fn display(&self, cell: K) -> Option<Cow<'_, str>> {
+        let display_text = self.display_text.as_ref()?;
+        self.get(cell)
+            .and_then(|item| display_text(cell, item))
+            .map(Cow::Owned)
+    }
+
+    fn read_cell_text(&self, cell: K) -> Option<String> {
+        let (to_text, _) = self.text.as_ref()?;
+        self.display(cell)
+            .map(|text| text.into_owned())
+            .or_else(|| self.get(cell).map(|item| to_text(item)))
+    }
+
+    fn write_cell_text(&mut self, cell: K, text: &str) {
+        let Some((_, from_text)) = self.text.clone() else {
+            return;
+        };
+
+        // text that doesn't convert to the column type is rejected
+        match self.column_type(cell.x()).parse(text) {
+            Ok(parsed) => self.insert(cell, from_text(&parsed)),
+            Err(reason) => self.rejections.push(cell, reason),
+        }
+    }
+
+    fn is_cell_empty(&self, cell: K) -> bool {
+        self.emptiness
+            .as_ref()
+            .zip(self.get(cell))
+            .is_some_and(|(is_empty, item)| is_empty(item))
+    }
+
+    fn column_type(&self, column: usize) -> &ColumnType {
+        static TEXT: ColumnType = ColumnType::Text;
+        self.column_types.get(column).unwrap_or(&TEXT)
+    }
+
+    fn take_rejections(&self) -> Vec<(K, String)> {
+        self.rejections.take()
     }
 
     fn row_count(&self) -> usize {
@@ -170,6 +661,43 @@ where
         })
     }
 
+    fn iter_range<'a>(&'a self, range: &R) -> Box<dyn Iterator<Item = (K, &'a T)> + 'a>
+    where
+        K: 'a,
+    {
+        let (columns, rows) = clamp_range(range, self.column_count(), self.row_count());
+        Box::new(
+            self.columns[columns.clone()]
+                .iter()
+                .zip(columns)
+                .flat_map(move |(items, x)| {
+                    items[rows.clone()]
+                        .iter()
+                        .zip(rows.clone())
+                        .map(move |(item, y)| (K::new(x, y), item))
+                }),
+        )
+    }
+
+    fn iter_range_mut<'a>(&'a mut self, range: &R) -> Box<dyn Iterator<Item = (K, &'a mut T)> + 'a>
+    where
+        K: 'a,
+        R: 'a,
+    {
+        let (columns, rows) = clamp_range(range, self.column_count(), self.row_count());
+        Box::new(
+            self.columns[columns.clone()]
+                .iter_mut()
+                .zip(columns)
+                .flat_map(move |(items, x)| {
+                    items[rows.clone()]
+                        .iter_mut()
+                        .zip(rows.clone())
+                        .map(move |(item, y)| (K::new(x, y), item))
+                }),
+        )
+    }
+
     fn selection(&self) -> &R {
         &self.selection
     }
@@ -178,21 +706,46 @@ where
     where
         K: 'a,
     {
+        self.internal.set_selection_dirty();
         &mut self.selection
     }
 
     fn select_cell(&mut self, cell: K) {
         self.selection = cell.as_range();
+        self.active = None;
+        self.internal.set_selection_dirty();
     }
 
     fn select_range(&mut self, range: R) {
         self.selection = range;
+        self.active = None;
+        self.internal.set_selection_dirty();
     }
 
     fn select_all(&mut self) {
-        let start = K::from((0, 0));
-        let end = K::from((self.column_count(), self.row_count()));
+        let start = K::new(0, 0);
+        let end = K::new(
+            self.column_count().saturating_sub(1),
+            self.row_count().saturating_sub(1),
+        );
         self.selection = R::new(start, Some(end));
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn active_cell(&self) -> K {
+        self.active
+            .filter(|cell| self.selection.normalize().contains(cell))
+            .unwrap_or(self.selection.start())
+    }
+
+    fn set_active_cell(&mut self, cell: K) {
+        if self.selection.normalize().contains(&cell) {
+            self.active = Some(cell);
+            self.internal.set_selection_dirty();
+        } else {
+            self.select_range(self.snap_to_spans(R::new(cell, None)));
+        }
     }
 
     fn with_reference(&mut self, cell: impl Into<K>, f: impl Fn(&K, &mut T)) {
@@ -203,11 +756,8 @@ where
     }
 
     fn with_reference_range(&mut self, range: &R, f: impl Fn(&K, &mut T)) {
-        let normalized = range.normalize();
-        for cell in normalized.iter() {
-            if let Some(value) = self.get_mut(cell) {
-                f(&cell, value);
-            }
+        for (cell, value) in self.iter_range_mut(range) {
+            f(&cell, value);
         }
     }
 }
@@ -231,144 +781,1145 @@ impl<T, K: Reference, R: ReferenceRange<K>> Content<T, K, R> {
         }
     }
 
-    pub fn with_range(range: R) -> Self
-    where
-        T: Default,
-    {
-        // Normalize the range to get consistent dimensions
-        let normalized = range.normalize();
-        let start = normalized.start();
-        let end = normalized.end().unwrap_or(start);
-
-        // Calculate dimensions (add 1 since end is inclusive)
-        let cols = end.x().saturating_sub(start.x()) + 1;
-        let rows = end.y().saturating_sub(start.y()) + 1;
-
-        // Create the grid with default values
-        let mut columns = Vec::with_capacity(cols);
-        for _ in 0..cols {
-            let mut column = Vec::with_capacity(rows);
-            column.resize_with(rows, T::default);
-            columns.push(column);
+    /// Set the width of the columns and the height of the rows added to grow
+    /// the grid, which are 100 and 20 by default.
+    pub fn with_default_cell_size(self, width: f32, height: f32) -> Self {
+        Self {
+            default_width: width,
+            default_height: height,
+            ..self
         }
+    }
 
+    /// Keep an undo history of up to `capacity` steps.
+    ///
+    /// Inserts, deletes, resizes, sorts and inserted, removed, moved and
+    /// swapped rows and columns are recorded, while selection changes are
+    /// not. Everything written by a
+    /// single action, e.g. a paste, is undone at once, and so are
+    /// consecutive deletes made in quick succession.
+    pub fn with_history(self, capacity: usize) -> Self
+    where
+        T: Clone,
+    {
         Self {
-            columns,
-            selection: R::default(),
-            col_widths: vec![100.0; cols],
-            row_heights: vec![20.0; rows],
-            range,
-            internal: Internal::default(),
-            _phantom: std::marker::PhantomData,
+            history: Some(History::new(capacity, T::clone)),
+            ..self
         }
     }
 
-    fn ensure_uniform_grid(&mut self, rows: usize, cols: usize)
+    /// Whether there is anything to undo.
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.undo.is_empty())
+    }
+
+    /// Whether there is anything to redo.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.redo.is_empty())
+    }
+
+    /// Undo the last recorded step. Returns the range it affected, so that it
+    /// can be selected again.
+    pub fn undo(&mut self) -> Option<R>
     where
         T: Default,
     {
-        // First ensure we have enough column capacity
-        if self.columns.len() < cols {
-            self.columns.resize_with(cols, || Vec::with_capacity(rows));
-            self.col_widths.resize(cols, 100.0);
-        }
-
-        // Ensure each column has the right number of rows
-        for column in &mut self.columns {
-            column.resize_with(rows, T::default);
-        }
+        history::undo(self)
+    }
 
-        // Update row heights array
-        self.row_heights.resize(rows, 20.0);
+    /// Redo the last undone step. Returns the range it affected, so that it
+    /// can be selected again.
+    pub fn redo(&mut self) -> Option<R>
+    where
+        T: Default,
+    {
+        history::redo(self)
     }
 
-    /// Set the height for a specific row
-    pub fn set_row_height(&mut self, row: usize, height: f32) {
-        if row < self.row_heights.len() {
-            self.row_heights[row] = height;
+    /// Forget every undo and redo step.
+    pub fn clear_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.undo.clear();
+            history.redo.clear();
         }
     }
 
-    /// Set the width for a specific column
-    pub fn set_column_width(&mut self, col: usize, width: f32) {
-        if col < self.col_widths.len() {
-            self.col_widths[col] = width;
+    fn record(&mut self, kind: Kind, changes: Vec<Change<T, K>>) {
+        if let Some(history) = &mut self.history {
+            history.record(kind, changes);
         }
     }
 }
 
-struct ContentIterator<'a, T, K: Reference> {
-    columns: &'a Vec<Vec<T>>,
-    col: usize,
-    row: usize,
-    offset_col: usize,
-    offset_row: usize,
-    _phantom: std::marker::PhantomData<K>,
-}
-
-struct ContentIteratorMut<'a, T, K: Reference> {
-    columns: &'a mut Vec<Vec<T>>,
-    col: usize,
-    row: usize,
-    offset_col: usize,
-    offset_row: usize,
-    _phantom: std::marker::PhantomData<K>,
-}
+impl<T: Default, K: Reference, R: ReferenceRange<K>> Layout<T, K, R> for Content<T, K, R> {
+    fn history(&mut self) -> &mut Option<History<T, K>> {
+        &mut self.history
+    }
 
-impl<'a, T, K: Reference> Iterator for ContentIterator<'a, T, K> {
-    type Item = (K, &'a T);
+    fn insert_row_at(&mut self, row: usize) {
+        let rows = self.columns.first().map_or(0, Vec::len);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.col >= self.columns.len() {
-            return None;
+        // a grid without columns has no rows to grow
+        if self.columns.is_empty() {
+            self.columns.push(Vec::new());
+            self.col_widths.resize(1, self.default_width);
         }
 
-        let current_col = &self.columns[self.col];
-        if self.row >= current_col.len() {
-            self.col += 1;
-            self.row = 0;
-            return self.next();
+        for column in &mut self.columns {
+            column.insert(row.min(column.len()), T::default());
+        }
+        self.row_heights
+            .insert(row.min(self.row_heights.len()), self.default_height);
+        if row < self.row_fills.len() {
+            self.row_fills.insert(row, None);
         }
 
-        let item = &current_col[self.row];
-        let cell_ref = K::new(self.col + self.offset_col, self.row + self.offset_row);
-        self.row += 1;
-        Some((cell_ref, item))
+        insert_row_in_outline(&mut self.row_groups, &mut self.spans, row);
+
+        // appending a row moves no cells
+        let shift = |cell: K| {
+            if cell.y() >= row && row < rows {
+                K::new(cell.x(), cell.y() + 1)
+            } else {
+                cell
+            }
+        };
+        self.selection = R::new(
+            shift(self.selection.start()),
+            self.selection.end().map(shift),
+        );
+        self.active = self.active.map(shift);
+        self.internal.set_dirty();
     }
-}
 
-impl<'a, T, K: Reference> Iterator for ContentIteratorMut<'a, T, K> {
-    type Item = (K, &'a mut T);
+    fn insert_column_at(&mut self, column: usize) {
+        let (rows, columns) = (self.columns.first().map_or(0, Vec::len), self.columns.len());
+        let mut items = Vec::with_capacity(rows);
+        items.resize_with(rows, T::default);
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.col >= self.columns.len() {
-            return None;
+        self.columns.insert(column.min(self.columns.len()), items);
+        self.col_widths
+            .insert(column.min(self.col_widths.len()), self.default_width);
+        if column < self.col_sizing.len() {
+            self.col_sizing.insert(column, None);
         }
-
-        let current_col = &mut self.columns[self.col];
-        if self.row >= current_col.len() {
-            self.col += 1;
-            self.row = 0;
-            return self.next();
+        if column < self.column_types.len() {
+            self.column_types.insert(column, ColumnType::Text);
         }
 
-        // Create the cell reference
-        let cell_ref = K::new(self.col + self.offset_col, self.row + self.offset_row);
-        self.row += 1;
+        insert_column_in_spans(&mut self.spans, column);
 
-        // SAFETY: We know this reference is unique because we're iterating through the Vec mutably
-        // and we increment the indices before returning
-        let item = unsafe {
-            let ptr = &mut self.columns[self.col][self.row - 1] as *mut T;
-            &mut *ptr
+        // appending a column moves no cells
+        let shift = |cell: K| {
+            if cell.x() >= column && column < columns {
+                K::new(cell.x() + 1, cell.y())
+            } else {
+                cell
+            }
         };
-
-        Some((cell_ref, item))
+        self.selection = R::new(
+            shift(self.selection.start()),
+            self.selection.end().map(shift),
+        );
+        self.active = self.active.map(shift);
+        self.internal.set_dirty();
     }
-}
 
-impl<T, K: Reference, R: ReferenceRange<K>> Default for Content<T, K, R>
-where
+    fn remove_row_at(&mut self, row: usize) {
+        for column in &mut self.columns {
+            if row < column.len() {
+                column.remove(row);
+            }
+        }
+        if row < self.row_heights.len() {
+            self.row_heights.remove(row);
+        }
+        if row < self.row_fills.len() {
+            self.row_fills.remove(row);
+        }
+
+        remove_row_from_outline(&mut self.row_groups, &mut self.spans, row);
+
+        let last = self.columns.first().map_or(0, Vec::len).saturating_sub(1);
+        let shift = |cell: K| {
+            let y = if cell.y() > row {
+                cell.y() - 1
+            } else {
+                cell.y()
+            };
+            K::new(cell.x(), y.min(last))
+        };
+        self.selection = R::new(
+            shift(self.selection.start()),
+            self.selection.end().map(shift),
+        );
+        self.active = self.active.map(shift);
+        self.internal.set_dirty();
+    }
+
+    fn remove_column_at(&mut self, column: usize) {
+        if column < self.columns.len() {
+            self.columns.remove(column);
+        }
+        if column < self.col_widths.len() {
+            self.col_widths.remove(column);
+        }
+        if column < self.col_sizing.len() {
+            self.col_sizing.remove(column);
+        }
+        if column < self.column_types.len() {
+            self.column_types.remove(column);
+        }
+
+        // without columns, there are no rows either
+        if self.columns.is_empty() {
+            self.row_heights.clear();
+            self.row_fills.clear();
+            self.row_groups.clear();
+        }
+
+        remove_column_from_spans(&mut self.spans, column);
+
+        let last = self.columns.len().saturating_sub(1);
+        let shift = |cell: K| {
+            let x = if cell.x() > column {
+                cell.x() - 1
+            } else {
+                cell.x()
+            };
+            K::new(x.min(last), cell.y())
+        };
+        self.selection = R::new(
+            shift(self.selection.start()),
+            self.selection.end().map(shift),
+        );
+        self.active = self.active.map(shift);
+        self.internal.set_dirty();
+    }
+
+    fn move_column_at(&mut self, from: usize, to: usize) {
+        let count = self.columns.len();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+        if self.col_widths.len() < count {
+            self.col_widths.resize(count, self.default_width);
+        }
+        let width = self.col_widths.remove(from);
+        self.col_widths.insert(to, width);
+        if self.col_sizing.len() > from.min(to) {
+            self.col_sizing
+                .resize(count.max(self.col_sizing.len()), None);
+            let sizing = self.col_sizing.remove(from);
+            self.col_sizing.insert(to, sizing);
+        }
+        if self.column_types.len() > from.min(to) {
+            self.column_types
+                .resize(count.max(self.column_types.len()), ColumnType::Text);
+            let column_type = self.column_types.remove(from);
+            self.column_types.insert(to, column_type);
+        }
+
+        move_column_in_spans(&mut self.spans, from, to);
+
+        let follow = |cell: K| K::new(moved_index(cell.x(), from, to), cell.y());
+        self.selection = R::new(
+            follow(self.selection.start()),
+            self.selection.end().map(follow),
+        );
+        self.active = self.active.map(follow);
+        self.internal.set_dirty();
+    }
+
+    fn move_row_at(&mut self, from: usize, to: usize) {
+        let count = self.columns.first().map_or(0, Vec::len);
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        for column in &mut self.columns {
+            let item = column.remove(from);
+            column.insert(to, item);
+        }
+        if self.row_heights.len() < count {
+            self.row_heights.resize(count, self.default_height);
+        }
+        let height = self.row_heights.remove(from);
+        self.row_heights.insert(to, height);
+        if self.row_fills.len() > from.min(to) {
+            self.row_fills.resize(count.max(self.row_fills.len()), None);
+            let fill = self.row_fills.remove(from);
+            self.row_fills.insert(to, fill);
+        }
+
+        move_row_in_outline(&mut self.row_groups, &mut self.spans, from, to);
+
+        let follow = |cell: K| K::new(cell.x(), moved_index(cell.y(), from, to));
+        self.selection = R::new(
+            follow(self.selection.start()),
+            self.selection.end().map(follow),
+        );
+        self.active = self.active.map(follow);
+        self.internal.set_dirty();
+    }
+
+    fn swap_rows_at(&mut self, a: usize, b: usize) {
+        let rows = self.columns.first().map_or(0, Vec::len);
+        if a == b || a >= rows || b >= rows {
+            return;
+        }
+
+        for column in &mut self.columns {
+            column.swap(a, b);
+        }
+        if self.row_heights.len() < rows {
+            self.row_heights.resize(rows, self.default_height);
+        }
+        self.row_heights.swap(a, b);
+        if self.row_fills.len() > a.min(b) {
+            self.row_fills.resize(rows.max(self.row_fills.len()), None);
+            self.row_fills.swap(a, b);
+        }
+        self.internal.set_dirty();
+    }
+
+    fn swap_columns_at(&mut self, a: usize, b: usize) {
+        let columns = self.columns.len();
+        if a == b || a >= columns || b >= columns {
+            return;
+        }
+
+        self.columns.swap(a, b);
+        if self.col_widths.len() < columns {
+            self.col_widths.resize(columns, self.default_width);
+        }
+        self.col_widths.swap(a, b);
+        if self.col_sizing.len() > a.min(b) {
+            self.col_sizing
+                .resize(columns.max(self.col_sizing.len()), None);
+            self.col_sizing.swap(a, b);
+        }
+        if self.column_types.len() > a.min(b) {
+            self.column_types
+                .resize(columns.max(self.column_types.len()), ColumnType::Text);
+            self.column_types.swap(a, b);
+        }
+        self.internal.set_dirty();
+    }
+
+    fn permute_rows(&mut self, order: &[usize]) -> Vec<usize> {
+        // a list too short for the order is filled up with defaults first,
+        // and the items past the order stay where they are
+        fn permute<V: Default>(items: &mut Vec<V>, order: &[usize]) {
+            if items.len() < order.len() {
+                items.resize_with(order.len(), V::default);
+            }
+            let rest = items.split_off(order.len());
+            let mut old = std::mem::take(items);
+            items.extend(order.iter().map(|&i| std::mem::take(&mut old[i])));
+            items.extend(rest);
+        }
+
+        for items in &mut self.columns {
+            permute(items, order);
+        }
+        if self.row_heights.len() < order.len() {
+            self.row_heights.resize(order.len(), self.default_height);
+        }
+        permute(&mut self.row_heights, order);
+        if !self.row_fills.is_empty() {
+            permute(&mut self.row_fills, order);
+        }
+
+        let mut moved = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            moved[old] = new;
+        }
+
+        permute_spans(&mut self.spans, &moved);
+        moved
+    }
+
+    fn column_size(&self, column: usize) -> Option<(f32, Option<Sizing>)> {
+        let width = *self.col_widths.get(column)?;
+        Some((width, self.col_sizing.get(column).copied().flatten()))
+    }
+
+    fn set_column_size(&mut self, column: usize, (width, sizing): (f32, Option<Sizing>)) {
+        if let Some(size) = self.col_widths.get_mut(column) {
+            *size = width;
+        }
+        if sizing.is_some() && self.col_sizing.len() <= column {
+            self.col_sizing.resize(column + 1, None);
+        }
+        if let Some(col_sizing) = self.col_sizing.get_mut(column) {
+            *col_sizing = sizing;
+        }
+    }
+
+    fn row_size(&self, row: usize) -> Option<(f32, Option<u16>)> {
+        let height = *self.row_heights.get(row)?;
+        Some((height, self.row_fills.get(row).copied().flatten()))
+    }
+
+    fn set_row_size(&mut self, row: usize, (height, fill): (f32, Option<u16>)) {
+        if let Some(size) = self.row_heights.get_mut(row) {
+            *size = height;
+        }
+        if fill.is_some() && self.row_fills.len() <= row {
+            self.row_fills.resize(row + 1, None);
+        }
+        if let Some(portion) = self.row_fills.get_mut(row) {
+            *portion = fill;
+        }
+    }
+
+    fn restore_row(&mut self, row: usize, items: Vec<T>) {
+        for (column, item) in self.columns.iter_mut().zip(items) {
+            column[row] = item;
+        }
+    }
+
+    fn restore_column(&mut self, column: usize, items: Vec<T>, column_type: Option<ColumnType>) {
+        // the table may have lost its rows along with its last column
+        let rows = items.len().max(self.row_count());
+        self.ensure_uniform_grid(rows, self.columns.len());
+        for (cell, item) in self.columns[column].iter_mut().zip(items) {
+            *cell = item;
+        }
+        if let Some(column_type) = column_type {
+            self.set_column_type(column, column_type);
+        }
+    }
+
+    fn restore_outline(&mut self, groups: Option<&[RowGroup]>, spans: &[CellSpan<K>]) {
+        if let Some(groups) = groups {
+            self.row_groups = groups.to_vec();
+        }
+        self.spans = spans.to_vec();
+    }
+}
+
+impl<T, K: Reference, R: ReferenceRange<K>> Content<T, K, R> {
+    pub fn with_range(range: R) -> Self
+    where
+        T: Default,
+    {
+        let (cols, rows) = (range.width(), range.height());
+
+        // Create the grid with default values
+        let mut columns = Vec::with_capacity(cols);
+        for _ in 0..cols {
+            let mut column = Vec::with_capacity(rows);
+            column.resize_with(rows, T::default);
+            columns.push(column);
+        }
+
+        Self {
+            columns,
+            selection: R::default(),
+            active: None,
+            col_widths: vec![DEFAULT_COLUMN_WIDTH; cols],
+            row_heights: vec![DEFAULT_ROW_HEIGHT; rows],
+            row_fills: Vec::new(),
+            col_sizing: Vec::new(),
+            default_width: DEFAULT_COLUMN_WIDTH,
+            default_height: DEFAULT_ROW_HEIGHT,
+            range,
+            internal: Internal::default(),
+            validator: None,
+            comparator: None,
+            emptiness: None,
+            text: None,
+            display_text: None,
+            column_types: Vec::new(),
+            row_groups: Vec::new(),
+            spans: Vec::new(),
+            history: None,
+            rejections: Rejections::default(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    // Take the content apart, to lay it out differently
+    pub(super) fn into_parts(self) -> Parts<T, K, R> {
+        Parts {
+            columns: self.columns,
+            selection: self.selection,
+            active: self.active,
+            col_widths: self.col_widths,
+            row_heights: self.row_heights,
+            range: self.range,
+            validator: self.validator,
+            comparator: self.comparator,
+            text: self.text,
+            column_types: self.column_types,
+            row_groups: self.row_groups,
+            spans: self.spans,
+            history: self.history,
+            rejections: self.rejections,
+        }
+    }
+
+    // Put the content back together, with nothing else set
+    pub(super) fn from_parts(parts: Parts<T, K, R>) -> Self
+    where
+        T: Default,
+    {
+        Self {
+            columns: parts.columns,
+            selection: parts.selection,
+            active: parts.active,
+            col_widths: parts.col_widths,
+            row_heights: parts.row_heights,
+            validator: parts.validator,
+            comparator: parts.comparator,
+            text: parts.text,
+            column_types: parts.column_types,
+            row_groups: parts.row_groups,
+            spans: parts.spans,
+            history: parts.history,
+            rejections: parts.rejections,
+            ..Self::from_range(&parts.range)
+        }
+    }
+
+    fn ensure_uniform_grid(&mut self, rows: usize, cols: usize)
+    where
+        T: Default,
+    {
+        // First ensure we have enough column capacity
+        if self.columns.len() < cols {
+            self.columns.resize_with(cols, || Vec::with_capacity(rows));
+            self.col_widths.resize(cols, self.default_width);
+        }
+
+        // Ensure each column has the right number of rows
+        for column in &mut self.columns {
+            column.resize_with(rows, T::default);
+        }
+
+        // Update row heights array
+        self.row_heights.resize(rows, self.default_height);
+    }
+
+    /// Set the validator consulted before an item is inserted.
+    ///
+    /// Items that fail validation are not written; the rejection is kept
+    /// until it is drained by [`Tabular::take_rejections`].
+    pub fn set_validator(
+        &mut self,
+        validator: impl Fn(K, &T) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.validator = Some(Arc::new(validator));
+    }
+
+    /// Remove the validator, if any.
+    pub fn clear_validator(&mut self) {
+        self.validator = None;
+    }
+
+    /// Set the comparator used to sort the rows on [`Action::Sort`].
+    ///
+    /// Without a comparator, the text of the items, see
+    /// [`Content::set_text_conversion`], is compared with
+    /// [`ColumnType::compare`]. Without either, sorting does nothing since
+    /// items have no ordering of their own.
+    ///
+    /// [`Action::Sort`]: crate::Action::Sort
+    pub fn set_comparator(
+        &mut self,
+        comparator: impl Fn(&ColumnType, &T, &T) -> Ordering + Send + Sync + 'static,
+    ) {
+        self.comparator = Some(Arc::new(comparator));
+    }
+
+    /// Set the check for empty items, where the data-edge motions (e.g.
+    /// [`Motion::DataDown`]) stop.
+    ///
+    /// Without it, every cell counts as filled.
+    ///
+    /// [`Motion::DataDown`]: crate::Motion::DataDown
+    pub fn set_is_empty(&mut self, is_empty: impl Fn(&T) -> bool + Send + Sync + 'static) {
+        self.emptiness = Some(Arc::new(is_empty));
+    }
+
+    /// Set how items are converted to and from text, e.g. to copy them to
+    /// and paste them from the clipboard.
+    ///
+    /// Without it, cells have no text and text can't be written to them.
+    pub fn set_text_conversion(
+        &mut self,
+        to_text: impl Fn(&T) -> String + Send + Sync + 'static,
+        from_text: impl Fn(&str) -> T + Send + Sync + 'static,
+    ) {
+        self.text = Some((Arc::new(to_text), Arc::new(from_text)));
+    }
+
+    /// Set the text shown for the items, when it isn't their own text, e.g.
+    /// the result of a formula.
+    ///
+    /// The function is given each item with its cell, and returns `None` to
+    /// show the item itself. See [`Tabular::display`].
+    pub fn set_display_text(
+        &mut self,
+        display_text: impl Fn(K, &T) -> Option<String> + Send + Sync + 'static,
+    ) {
+        self.display_text = Some(Arc::new(display_text));
+    }
+
+    /// Sort the rows by the given column with a comparator, keeping rows
+    /// with equal values in their current order.
+    ///
+    /// The row sizes move along with the rows. A selection within a single
+    /// row follows the row; a selection spanning several rows is collapsed
+    /// to its first cell, which follows its row. Merged cells within a row
+    /// move with it, while those spanning several rows are unmerged.
+    pub fn sort_by(
+        &mut self,
+        column: usize,
+        ascending: bool,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) where
+        T: Default,
+    {
+        let Some(keys) = self.columns.get(column) else {
+            return;
+        };
+
+        // the stable sort keeps equal rows in order in both directions
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        if ascending {
+            order.sort_by(|&a, &b| compare(&keys[a], &keys[b]));
+        } else {
+            order.sort_by(|&a, &b| compare(&keys[b], &keys[a]));
+        }
+
+        let spans = self.spans.clone();
+        let moved = self.permute_rows(&order);
+        self.record(Kind::Other, vec![Change::Reorder { order, spans }]);
+
+        let start = self.selection.start();
+        let end = self.selection.end().unwrap_or(start);
+        let follow = |cell: K| K::new(cell.x(), moved.get(cell.y()).copied().unwrap_or(cell.y()));
+        self.selection = if start.y() == end.y() {
+            R::new(follow(start), self.selection.end().map(follow))
+        } else {
+            follow(start).as_range()
+        };
+        self.active = None;
+
+        self.internal.set_dirty();
+    }
+
+    /// Set the [`ColumnType`] of a specific column.
+    pub fn set_column_type(&mut self, col: usize, column_type: ColumnType) {
+        if col >= self.column_types.len() {
+            self.column_types.resize(col + 1, ColumnType::Text);
+        }
+        self.column_types[col] = column_type;
+    }
+
+    /// Insert some text at the given cell, converted through the
+    /// [`ColumnType`] of its column.
+    ///
+    /// If the text can't be converted it is inserted unchanged and the
+    /// reason is returned.
+    pub fn insert_parsed(&mut self, cell: impl Into<K>, text: &str) -> Result<(), String>
+    where
+        T: Default,
+        String: Into<T>,
+    {
+        let cell = cell.into();
+
+        match self.column_type(cell.x()).parse(text) {
+            Ok(parsed) => {
+                self.insert(cell, parsed);
+                Ok(())
+            }
+            Err(reason) => {
+                self.insert(cell, text.to_string());
+                Err(reason)
+            }
+        }
+    }
+
+    /// Group the given rows under their first row at the given outline level.
+    ///
+    /// A group with the same header row and level is replaced.
+    pub fn set_row_group(&mut self, rows: std::ops::Range<usize>, level: usize) {
+        set_row_group(&mut self.row_groups, rows, level);
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Remove every row group.
+    pub fn clear_row_groups(&mut self) {
+        self.row_groups.clear();
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Collapse or expand the groups whose header is the given row.
+    pub fn set_row_group_collapsed(&mut self, header: usize, collapsed: bool) {
+        for group in &mut self.row_groups {
+            if group.header() == header {
+                group.collapsed = collapsed;
+            }
+        }
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Toggle the groups whose header is the given row.
+    pub fn toggle_row_group(&mut self, header: usize) {
+        let collapsed = self
+            .row_groups
+            .iter()
+            .any(|group| group.header() == header && !group.collapsed);
+        self.set_row_group_collapsed(header, collapsed);
+    }
+
+    /// Merge the cells of a range into its top-left cell.
+    ///
+    /// Any merged spans overlapping the range are unmerged first. Merging a
+    /// single cell only unmerges it.
+    pub fn merge_cells(&mut self, range: R) {
+        merge_span(&mut self.spans, range);
+        self.internal.set_data_dirty();
+    }
+
+    /// Unmerge the span containing the given cell, if any.
+    pub fn unmerge_cells(&mut self, cell: K) {
+        self.spans.retain(|span| !span.contains(cell.x(), cell.y()));
+        self.internal.set_data_dirty();
+    }
+
+    /// Set the height for a specific row
+    ///
+    /// A [`RowHeight::Fixed`] height replaces the size of the row, while a
+    /// filling row keeps its size to fall back on once it is resized.
+    pub fn set_row_height(&mut self, row: usize, height: impl Into<RowHeight>) {
+        if row >= self.row_heights.len() {
+            return;
+        }
+        if self.row_fills.len() <= row {
+            self.row_fills.resize(row + 1, None);
+        }
+
+        match height.into() {
+            RowHeight::Fixed(height) => {
+                self.row_heights[row] = height;
+                self.row_fills[row] = None;
+            }
+            RowHeight::FillPortion(portion) => self.row_fills[row] = Some(portion),
+        }
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Set the width for a specific column
+    pub fn set_column_width(&mut self, col: usize, width: f32) {
+        if col < self.col_widths.len() {
+            self.col_widths[col] = width;
+            self.internal.set_sizes_dirty();
+        }
+    }
+
+    /// Set the [`Sizing`] of a specific column
+    ///
+    /// A [`Sizing::Fixed`] width replaces the size of the column, while a
+    /// filling or fitted column keeps its size to fall back on once it is
+    /// resized.
+    pub fn set_column_sizing(&mut self, column: usize, sizing: impl Into<Sizing>)
+    where
+        T: Default,
+    {
+        if column >= self.col_widths.len() {
+            return;
+        }
+
+        match sizing.into() {
+            Sizing::Fixed(width) => self.set_column_size(column, (width, None)),
+            sizing => self.set_column_size(column, (self.col_widths[column], Some(sizing))),
+        }
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Insert an empty row of the given height before row `at`, or after the
+    /// last row if `at` is past it.
+    ///
+    /// Like [`Tabular::insert_row`], the rows below move down along with the
+    /// selection, and the insertion is a single undo step.
+    pub fn insert_row_with_height(&mut self, at: usize, height: f32)
+    where
+        T: Default,
+    {
+        let row = at.min(self.row_count());
+        self.insert_row_at(row);
+        self.set_row_size(row, (height, None));
+        self.record(
+            Kind::Other,
+            vec![
+                Change::InsertRow { row },
+                Change::RowHeight {
+                    row,
+                    before: (self.default_height, None),
+                    after: (height, None),
+                },
+            ],
+        );
+    }
+
+    /// Insert an empty column of the given width before column `at`, or after
+    /// the last column if `at` is past it.
+    ///
+    /// Like [`Tabular::insert_column`], the columns to the right move along
+    /// with the selection, and the insertion is a single undo step.
+    pub fn insert_column_with_width(&mut self, at: usize, width: f32)
+    where
+        T: Default,
+    {
+        let column = at.min(self.column_count());
+        self.insert_column_at(column);
+        if let Some(size) = self.col_widths.get_mut(column) {
+            *size = width;
+        }
+        self.record(
+            Kind::Other,
+            vec![
+                Change::InsertColumn { column },
+                Change::ColumnWidth {
+                    column,
+                    before: (self.default_width, None),
+                    after: (width, None),
+                },
+            ],
+        );
+    }
+
+    /// Remove the row at the given index, returning its items from the first
+    /// column to the last.
+    ///
+    /// Like [`Tabular::remove_row`], the rows below move up along with the
+    /// selection, and the removal is a single undo step. Nothing is removed
+    /// if the row is outside of the table.
+    pub fn take_row(&mut self, row: usize) -> Vec<T>
+    where
+        T: Default,
+    {
+        if row >= self.row_count() {
+            return Vec::new();
+        }
+
+        if let Some(snapshot) = self.history.as_ref().map(|history| history.snapshot) {
+            let change = Change::RemoveRow {
+                row,
+                items: self
+                    .columns
+                    .iter()
+                    .map(|column| snapshot(&column[row]))
+                    .collect(),
+                size: self.row_size(row).unwrap_or((self.default_height, None)),
+                groups: self.row_groups.clone(),
+                spans: self.spans.clone(),
+            };
+            self.record(Kind::Other, vec![change]);
+        }
+
+        let items = self
+            .columns
+            .iter_mut()
+            .map(|column| std::mem::take(&mut column[row]))
+            .collect();
+        self.remove_row_at(row);
+        items
+    }
+
+    /// Remove the column at the given index, returning its items from the
+    /// first row to the last.
+    ///
+    /// Like [`Tabular::remove_column`], the columns to the right move left
+    /// along with the selection, and the removal is a single undo step.
+    /// Nothing is removed if the column is outside of the table.
+    pub fn take_column(&mut self, column: usize) -> Vec<T>
+    where
+        T: Default,
+    {
+        if column >= self.column_count() {
+            return Vec::new();
+        }
+
+        if let Some(snapshot) = self.history.as_ref().map(|history| history.snapshot) {
+            let change = Change::RemoveColumn {
+                column,
+                items: self.columns[column].iter().map(snapshot).collect(),
+                width: self
+                    .column_size(column)
+                    .unwrap_or((self.default_width, None)),
+                column_type: self.column_types.get(column).cloned(),
+                spans: self.spans.clone(),
+            };
+            self.record(Kind::Other, vec![change]);
+        }
+
+        let items = std::mem::take(&mut self.columns[column]);
+        self.remove_column_at(column);
+        items
+    }
+
+    /// Grow or shrink the grid to exactly the given number of rows and
+    /// columns.
+    ///
+    /// Added cells hold default items and added lines have the default sizes
+    /// set by [`Content::with_default_cell_size`]. Shrinking drops the items
+    /// and sizes of the lines past the new edges, along with the row groups
+    /// and merged cells that no longer fit, and moves the selection within
+    /// the new bounds. The resize isn't recorded in the undo history.
+    pub fn resize(&mut self, rows: usize, columns: usize)
+    where
+        T: Default,
+    {
+        // without columns or rows, the grid is empty
+        let (rows, columns) = if rows == 0 || columns == 0 {
+            (0, 0)
+        } else {
+            (rows, columns)
+        };
+
+        self.columns.resize_with(columns, Vec::new);
+        for column in &mut self.columns {
+            column.resize_with(rows, T::default);
+        }
+        self.col_widths.resize(columns, self.default_width);
+        self.col_sizing.truncate(columns);
+        self.row_heights.resize(rows, self.default_height);
+        self.row_fills.truncate(rows);
+        self.column_types.truncate(columns);
+
+        self.row_groups.retain(|group| group.rows.start < rows);
+        for group in &mut self.row_groups {
+            group.rows.end = group.rows.end.min(rows);
+        }
+        self.spans.retain(|span| {
+            let end = span.end();
+            end.x() < columns && end.y() < rows
+        });
+
+        let (last_column, last_row) = (columns.saturating_sub(1), rows.saturating_sub(1));
+        let clamp = |cell: K| K::new(cell.x().min(last_column), cell.y().min(last_row));
+        self.selection = R::new(
+            clamp(self.selection.start()),
+            self.selection.end().map(clamp),
+        );
+        self.active = self.active.map(clamp);
+        self.internal.set_dirty();
+    }
+
+    /// Exchange two rows, along with their heights.
+    ///
+    /// The selection, row groups and merged cells are left where they are,
+    /// as they refer to positions rather than to items, and the swap is a
+    /// single undo step. Swapping a row with itself or with a row outside of
+    /// the table does nothing.
+    pub fn swap_rows(&mut self, a: usize, b: usize)
+    where
+        T: Default,
+    {
+        let rows = self.row_count();
+        if a == b || a >= rows || b >= rows {
+            return;
+        }
+
+        self.swap_rows_at(a, b);
+        self.record(Kind::Other, vec![Change::SwapRows { a, b }]);
+    }
+
+    /// Exchange two columns, along with their widths and types.
+    ///
+    /// The selection and merged cells are left where they are, as they refer
+    /// to positions rather than to items, and the swap is a single undo step.
+    /// Swapping a column with itself or with a column outside of the table
+    /// does nothing.
+    pub fn swap_columns(&mut self, a: usize, b: usize)
+    where
+        T: Default,
+    {
+        let columns = self.column_count();
+        if a == b || a >= columns || b >= columns {
+            return;
+        }
+
+        self.swap_columns_at(a, b);
+        self.record(Kind::Other, vec![Change::SwapColumns { a, b }]);
+    }
+
+    /// Turn every item into another type, keeping the sizes, range,
+    /// selection, column types, row groups and merged cells.
+    ///
+    /// The function is given the item along with its cell, offset by the
+    /// start of the range as in [`Tabular::iter_relative`]. The validator,
+    /// comparator, emptiness check, text conversions and undo history all
+    /// work on the old type, so they are dropped.
+    pub fn map<U>(self, mut f: impl FnMut(K, T) -> U) -> Content<U, K, R> {
+        match self.try_map(|cell, item| Ok::<_, std::convert::Infallible>(f(cell, item))) {
+            Ok(content) => content,
+            Err((_, never)) => match never {},
+        }
+    }
+
+    /// Like [`Content::map`], but stops at the first item that can't be
+    /// turned into the other type, returning its cell with the error.
+    pub fn try_map<U, E>(
+        self,
+        mut f: impl FnMut(K, T) -> Result<U, E>,
+    ) -> Result<Content<U, K, R>, (K, E)> {
+        let (dx, dy) = (self.range.start().x(), self.range.start().y());
+        let columns = self
+            .columns
+            .into_iter()
+            .enumerate()
+            .map(|(x, column)| {
+                column
+                    .into_iter()
+                    .enumerate()
+                    .map(|(y, item)| {
+                        let cell = K::new(x + dx, y + dy);
+                        f(cell, item).map_err(|error| (cell, error))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.internal.set_dirty();
+        Ok(Content {
+            columns,
+            selection: self.selection,
+            active: self.active,
+            col_widths: self.col_widths,
+            row_heights: self.row_heights,
+            row_fills: self.row_fills,
+            col_sizing: self.col_sizing,
+            default_width: self.default_width,
+            default_height: self.default_height,
+            range: self.range,
+            internal: self.internal,
+            validator: None,
+            comparator: None,
+            emptiness: None,
+            text: None,
+            display_text: None,
+            column_types: self.column_types,
+            row_groups: self.row_groups,
+            spans: self.spans,
+            history: None,
+            rejections: self.rejections,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// The items of a row, from the first column to the last.
+    ///
+    /// Yields nothing if the row is outside of the table.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &T> + '_ {
+        self.columns.iter().filter_map(move |column| column.get(y))
+    }
+
+    /// The mutable items of a row, from the first column to the last.
+    ///
+    /// Yields nothing if the row is outside of the table.
+    pub fn row_mut(&mut self, y: usize) -> impl Iterator<Item = &mut T> + '_ {
+        self.columns
+            .iter_mut()
+            .filter_map(move |column| column.get_mut(y))
+    }
+
+    /// The items of a column, from the first row to the last.
+    pub fn column(&self, x: usize) -> Option<&[T]> {
+        self.columns.get(x).map(Vec::as_slice)
+    }
+
+    /// The mutable items of a column, from the first row to the last.
+    pub fn column_mut(&mut self, x: usize) -> Option<&mut [T]> {
+        self.columns.get_mut(x).map(Vec::as_mut_slice)
+    }
+}
+
+struct ContentIterator<'a, T, K: Reference> {
+    columns: &'a Vec<Vec<T>>,
+    col: usize,
+    row: usize,
+    offset_col: usize,
+    offset_row: usize,
+    _phantom: std::marker::PhantomData<K>,
+}
+
+struct ContentIteratorMut<'a, T, K: Reference> {
+    columns: &'a mut Vec<Vec<T>>,
+    col: usize,
+    row: usize,
+    offset_col: usize,
+    offset_row: usize,
+    _phantom: std::marker::PhantomData<K>,
+}
+
+impl<'a, T, K: Reference> Iterator for ContentIterator<'a, T, K> {
+    type Item = (K, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.col >= self.columns.len() {
+            return None;
+        }
+
+        let current_col = &self.columns[self.col];
+        if self.row >= current_col.len() {
+            self.col += 1;
+            self.row = 0;
+            return self.next();
+        }
+
+        let item = &current_col[self.row];
+        let cell_ref = K::new(self.col + self.offset_col, self.row + self.offset_row);
+        self.row += 1;
+        Some((cell_ref, item))
+    }
+}
+
+impl<'a, T, K: Reference> Iterator for ContentIteratorMut<'a, T, K> {
+    type Item = (K, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.col >= self.columns.len() {
+            return None;
+        }
+
+        let current_col = &mut self.columns[self.col];
+        if self.row >= current_col.len() {
+            self.col += 1;
+            self.row = 0;
+            return self.next();
+        }
+
+        // Create the cell reference
+        let cell_ref = K::new(self.col + self.offset_col, self.row + self.offset_row);
+        self.row += 1;
+
+        // SAFETY: We know this reference is unique because we're iterating through the Vec mutably
+        // and we increment the indices before returning
+        let item = unsafe {
+            let ptr = &mut self.columns[self.col][self.row - 1] as *mut T;
+            &mut *ptr
+        };
+
+        Some((cell_ref, item))
+    }
+}
+
+impl<T, K: Reference, R: ReferenceRange<K>> Default for Content<T, K, R>
+where
     T: Default,
 {
     fn default() -> Self {
@@ -415,15 +1966,175 @@ impl<'de, T: Deserialize<'de>, K: Reference, R: ReferenceRange<K> + Deserialize<
         Ok(Content {
             columns: helper.items,
             selection: helper.selection,
+            active: None,
             col_widths: helper.col_widths,
             row_heights: helper.row_heights,
+            row_fills: Vec::new(),
+            col_sizing: Vec::new(),
+            default_width: DEFAULT_COLUMN_WIDTH,
+            default_height: DEFAULT_ROW_HEIGHT,
             range: helper.range,
             internal: Internal::default(),
+            validator: None,
+            comparator: None,
+            emptiness: None,
+            text: None,
+            display_text: None,
+            column_types: Vec::new(),
+            row_groups: Vec::new(),
+            spans: Vec::new(),
+            history: None,
+            rejections: Rejections::default(),
             _phantom: std::marker::PhantomData,
         })
     }
 }
 
+// Cell backgrounds serialized as an optional RGBA color, since iced has no
+// serde support of its own. Gradients are not kept.
+#[cfg(feature = "serde")]
+mod background {
+    use iced::{Background, Color};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(background: &Option<Background>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let color = match background {
+            Some(Background::Color(color)) => Some([color.r, color.g, color.b, color.a]),
+            _ => None,
+        };
+        color.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Background>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let color = Option::<[f32; 4]>::deserialize(deserializer)?;
+        Ok(color.map(|[r, g, b, a]| Background::Color(Color::from_rgba(r, g, b, a))))
+    }
+}
+
+// Cell borders serialized through a mirror of their fields, for the same
+// reason.
+#[cfg(feature = "serde")]
+mod border {
+    use iced::{Border, Color};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    struct Mirror {
+        #[serde(default)]
+        color: [f32; 4],
+        #[serde(default)]
+        width: f32,
+        // top left, top right, bottom right and bottom left
+        #[serde(default)]
+        radius: [f32; 4],
+    }
+
+    pub fn serialize<S>(border: &Option<Border>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        border
+            .map(|border| Mirror {
+                color: [
+                    border.color.r,
+                    border.color.g,
+                    border.color.b,
+                    border.color.a,
+                ],
+                width: border.width,
+                radius: border.radius.into(),
+            })
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Border>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mirror = Option::<Mirror>::deserialize(deserializer)?;
+        Ok(mirror.map(
+            |Mirror {
+                 color,
+                 width,
+                 radius,
+             }| {
+                let [r, g, b, a] = color;
+                let [top_left, top_right, bottom_right, bottom_left] = radius;
+                Border {
+                    color: Color::from_rgba(r, g, b, a),
+                    width,
+                    radius: iced::border::Radius {
+                        top_left,
+                        top_right,
+                        bottom_right,
+                        bottom_left,
+                    },
+                }
+            },
+        ))
+    }
+}
+
+/// The rows and columns of cells shown by the [`Debug`](fmt::Debug) output of
+/// a [`Content`], so that a large grid doesn't flood it.
+const DEBUG_PREVIEW: usize = 8;
+
+impl<T: fmt::Debug, K: Reference, R: ReferenceRange<K>> fmt::Debug for Content<T, K, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the top-left cells, row by row
+        struct Preview<'a, T>(&'a [Vec<T>], usize);
+
+        impl<T: fmt::Debug> fmt::Debug for Preview<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let Self(columns, rows) = *self;
+                let mut list = f.debug_list();
+                for y in 0..rows.min(DEBUG_PREVIEW) {
+                    list.entry(&Row(columns, y));
+                }
+                if rows > DEBUG_PREVIEW {
+                    list.entry(&format_args!(".."));
+                }
+                list.finish()
+            }
+        }
+
+        struct Row<'a, T>(&'a [Vec<T>], usize);
+
+        impl<T: fmt::Debug> fmt::Debug for Row<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let Self(columns, y) = *self;
+                let mut list = f.debug_list();
+                for column in columns.iter().take(DEBUG_PREVIEW) {
+                    // a column cut short has nothing to show in the row
+                    match column.get(y) {
+                        Some(item) => list.entry(item),
+                        None => list.entry(&format_args!("_")),
+                    };
+                }
+                if columns.len() > DEBUG_PREVIEW {
+                    list.entry(&format_args!(".."));
+                }
+                list.finish()
+            }
+        }
+
+        let rows = self.columns.first().map_or(0, Vec::len);
+        f.debug_struct("Content")
+            .field("columns", &self.columns.len())
+            .field("rows", &rows)
+            .field("selection", &self.selection)
+            .field("active", &self.active)
+            .field("cells", &Preview(&self.columns, rows))
+            .finish_non_exhaustive()
+    }
+}
+
 impl<T: PartialEq, K: Reference, R: ReferenceRange<K>> PartialEq for Content<T, K, R> {
     // Ignore internal
     fn eq(&self, other: &Self) -> bool {
@@ -433,3 +2144,841 @@ impl<T: PartialEq, K: Reference, R: ReferenceRange<K>> PartialEq for Content<T,
             && self.row_heights == other.row_heights
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::history::COALESCE_WINDOW;
+    use super::*;
+    use crate::tabular::action::Edit;
+    use crate::{Axis, Motion};
+    use iced::time::{Duration, Instant};
+
+    // A grid with the given rows of text
+    fn content(rows: &[&[&str]]) -> Content {
+        let mut content = Content::default();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, text) in row.iter().enumerate() {
+                content.insert(Address::new(x, y), *text);
+            }
+        }
+        content
+    }
+
+    // A grid with the given rows of text, which text can be pasted into
+    fn text_content(rows: &[&[&str]]) -> Content {
+        let mut content = content(rows);
+        content.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        content
+    }
+
+    // The rows of text of a grid
+    fn texts(content: &Content) -> Vec<Vec<&str>> {
+        (0..content.row_count())
+            .map(|y| content.row(y).map(|cell| cell.content.as_str()).collect())
+            .collect()
+    }
+
+    fn range(a: &str, b: &str) -> Range {
+        Range::new(a.parse().unwrap(), Some(b.parse().unwrap()))
+    }
+
+    fn paste(anchor: &str, rows: &[&[&str]]) -> Action {
+        Action::Edit(Edit::Paste {
+            anchor: anchor.parse().unwrap(),
+            rows: rows
+                .iter()
+                .map(|row| row.iter().map(|text| text.to_string()).collect())
+                .collect(),
+        })
+    }
+
+    #[test]
+    fn resizing_fixed_and_filling_rows_clamps_the_same_way() {
+        let mut data = content(&[&["a"], &["b"]]);
+        data.set_row_height(1, RowHeight::FillPortion(1));
+
+        for row in [0, 1] {
+            data.perform(Action::ResizeDivider(Axis::Row, row, 5.0));
+            assert_eq!(data.row_sizes()[row], DEFAULT_ROW_HEIGHT + 5.0);
+            data.perform(Action::ResizeDivider(Axis::Row, row, -100.0));
+            assert_eq!(data.row_sizes()[row], 0.0);
+            assert!(matches!(data.row_height(row), RowHeight::Fixed(_)));
+        }
+    }
+
+    #[test]
+    fn undo_restores_a_deleted_range_and_redo_deletes_it_again() {
+        let mut data = content(&[&["a", "b"], &["c", "d"], &["e", "f"]]).with_history(8);
+
+        data.perform(Action::ResizeDivider(Axis::Column, 1, 25.0));
+        data.perform(Action::Select(range("A2", "B3")));
+        data.perform(Action::Edit(Edit::Delete));
+        assert_eq!(texts(&data), [["a", "b"], ["", ""], ["", ""]]);
+
+        assert_eq!(data.undo(), Some(range("A2", "B3")));
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"], ["e", "f"]]);
+        assert_eq!(data.column_sizes(), [100.0, 125.0]);
+
+        assert_eq!(data.undo(), Some(range("B1", "B3")));
+        assert_eq!(data.column_sizes(), [100.0, 100.0]);
+        assert!(!data.can_undo());
+
+        data.redo();
+        assert_eq!(data.column_sizes(), [100.0, 125.0]);
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"], ["e", "f"]]);
+
+        data.redo();
+        assert_eq!(texts(&data), [["a", "b"], ["", ""], ["", ""]]);
+        assert!(!data.can_redo());
+    }
+
+    thread_local! {
+        // The time told to the histories of the tests, moved by `wait`
+        static NOW: std::cell::Cell<Instant> = std::cell::Cell::new(Instant::now());
+    }
+
+    fn clock() -> Instant {
+        NOW.with(std::cell::Cell::get)
+    }
+
+    fn wait(duration: Duration) {
+        NOW.with(|now| now.set(now.get() + duration));
+    }
+
+    #[test]
+    fn quick_deletes_coalesce() {
+        let mut data = content(&[&["a", "b", "c"]]).with_history(8);
+        data.history.as_mut().unwrap().clock = clock;
+        let delete = |data: &mut Content, x| {
+            data.select_cell(Address::new(x, 0));
+            data.perform(Action::Edit(Edit::Delete));
+        };
+
+        delete(&mut data, 0);
+        wait(COALESCE_WINDOW - Duration::from_millis(1));
+        delete(&mut data, 2);
+        // the window starts over from the last delete
+        wait(COALESCE_WINDOW - Duration::from_millis(1));
+        delete(&mut data, 1);
+        assert_eq!(texts(&data), [["", "", ""]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b", "c"]]);
+        assert!(!data.can_undo());
+    }
+
+    #[test]
+    fn slow_deletes_are_separate_steps() {
+        let mut data = content(&[&["a", "b", "c"]]).with_history(8);
+        data.history.as_mut().unwrap().clock = clock;
+        let delete = |data: &mut Content, x| {
+            data.select_cell(Address::new(x, 0));
+            data.perform(Action::Edit(Edit::Delete));
+        };
+
+        delete(&mut data, 0);
+        wait(COALESCE_WINDOW);
+        delete(&mut data, 2);
+        assert_eq!(texts(&data), [["", "b", ""]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["", "b", "c"]]);
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b", "c"]]);
+        assert!(!data.can_undo());
+    }
+
+    #[test]
+    fn each_paste_is_its_own_step() {
+        let mut data = text_content(&[&["", "", ""], &["", "", ""]]).with_history(8);
+
+        data.perform(paste("A1", &[&["a", "b"], &["c", "d"]]));
+        data.perform(paste("C1", &[&["e"]]));
+        assert_eq!(texts(&data), [["a", "b", "e"], ["c", "d", ""]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b", ""], ["c", "d", ""]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["", "", ""], ["", "", ""]]);
+        assert!(!data.can_undo());
+    }
+
+    #[test]
+    fn inserts_and_deletes_dont_coalesce() {
+        let mut data = content(&[&["a", "b"]]).with_history(8);
+
+        data.insert(Address::new(0, 0), "x");
+        data.insert(Address::new(1, 0), "y");
+        data.perform(Action::Edit(Edit::Delete));
+        assert_eq!(texts(&data), [["", "y"]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["x", "y"]]);
+        data.undo();
+        assert_eq!(texts(&data), [["x", "b"]]);
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b"]]);
+    }
+
+    #[test]
+    fn insert_rows_at_the_start_middle_and_end() {
+        let mut data = content(&[&["a"], &["b"]]);
+        data.set_row_height(1, 30.0);
+        data.select_cell(Address::new(0, 1));
+
+        data.perform(Action::InsertRow(0));
+        assert_eq!(texts(&data), [[""], ["a"], ["b"]]);
+        assert_eq!(data.row_sizes(), [20.0, 20.0, 30.0]);
+        assert_eq!(*data.selection(), Range::new(Address::new(0, 2), None));
+
+        data.perform(Action::InsertRow(2));
+        assert_eq!(texts(&data), [[""], ["a"], [""], ["b"]]);
+        assert_eq!(data.row_sizes(), [20.0, 20.0, 20.0, 30.0]);
+        assert_eq!(*data.selection(), Range::new(Address::new(0, 3), None));
+
+        data.perform(Action::InsertRow(4));
+        data.perform(Action::InsertRow(99));
+        assert_eq!(texts(&data), [[""], ["a"], [""], ["b"], [""], [""]]);
+        assert_eq!(data.row_sizes().len(), 6);
+        assert_eq!(*data.selection(), Range::new(Address::new(0, 3), None));
+    }
+
+    #[test]
+    fn insert_columns_at_the_start_middle_and_end() {
+        let mut data = content(&[&["a", "b"]]);
+        data.set_column_width(1, 50.0);
+        data.select_range(range("A1", "B1"));
+
+        data.perform(Action::InsertColumn(0));
+        assert_eq!(texts(&data), [["", "a", "b"]]);
+        assert_eq!(data.column_sizes(), [100.0, 100.0, 50.0]);
+        assert_eq!(*data.selection(), range("B1", "C1"));
+
+        data.perform(Action::InsertColumn(2));
+        assert_eq!(texts(&data), [["", "a", "", "b"]]);
+        assert_eq!(data.column_sizes(), [100.0, 100.0, 100.0, 50.0]);
+
+        data.perform(Action::InsertColumn(4));
+        assert_eq!(texts(&data), [["", "a", "", "b", ""]]);
+        assert_eq!(data.column_sizes().len(), 5);
+    }
+
+    #[test]
+    fn moving_a_row_up_and_down_keeps_its_cells_and_height() {
+        let mut data = content(&[&["a", "1"], &["b", "2"], &["c", "3"], &["d", "4"]]);
+        data.set_row_height(2, 40.0);
+        let h = DEFAULT_ROW_HEIGHT;
+
+        data.perform(Action::MoveRow { from: 2, to: 0 });
+        assert_eq!(
+            texts(&data),
+            [["c", "3"], ["a", "1"], ["b", "2"], ["d", "4"]]
+        );
+        assert_eq!(data.row_sizes(), [40.0, h, h, h]);
+
+        data.perform(Action::MoveRow { from: 0, to: 3 });
+        assert_eq!(
+            texts(&data),
+            [["a", "1"], ["b", "2"], ["d", "4"], ["c", "3"]]
+        );
+        assert_eq!(data.row_sizes(), [h, h, h, 40.0]);
+        assert_eq!((data.column_count(), data.row_count()), (2, 4));
+    }
+
+    #[test]
+    fn page_motions_land_a_page_away_and_clamp() {
+        let mut data = Content::with_range(range("A1", "C100"));
+        let row = |data: &Content| data.active_cell().y();
+
+        data.select_cell(Address::new(1, 0));
+        data.perform(Action::MoveSelection(Motion::PageDown(30)));
+        assert_eq!(row(&data), 30);
+        data.perform(Action::MoveSelection(Motion::PageDown(30)));
+        assert_eq!(row(&data), 60);
+        data.perform(Action::MoveSelection(Motion::PageDown(30)));
+        assert_eq!(row(&data), 90);
+        data.perform(Action::MoveSelection(Motion::PageDown(30)));
+        assert_eq!(row(&data), 99);
+        assert_eq!(data.active_cell().x(), 1);
+
+        data.perform(Action::MoveSelection(Motion::PageUp(40)));
+        assert_eq!(row(&data), 59);
+        data.perform(Action::MoveSelection(Motion::PageUp(100)));
+        assert_eq!(row(&data), 0);
+
+        data.perform(Action::ExpandSelection(Motion::PageDown(10)));
+        data.perform(Action::ExpandSelection(Motion::PageDown(10)));
+        assert_eq!(*data.selection(), range("B1", "B21"));
+        data.perform(Action::ExpandSelection(Motion::PageUp(50)));
+        assert_eq!(*data.selection(), range("B1", "B1"));
+    }
+
+    #[test]
+    fn paste_is_clamped_to_the_table() {
+        let mut data = text_content(&[&["", "", ""], &["", "", ""], &["", "", ""]]);
+
+        data.perform(paste("B2", &[&["a", "b", "c"], &["d", "e", "f"], &["g"]]));
+        assert_eq!(texts(&data), [["", "", ""], ["", "a", "b"], ["", "d", "e"]]);
+
+        data.perform(paste("C3", &[&["x", "y"], &["z"]]));
+        assert_eq!(texts(&data), [["", "", ""], ["", "a", "b"], ["", "d", "x"]]);
+        assert_eq!((data.column_count(), data.row_count()), (3, 3));
+    }
+
+    #[test]
+    fn pasted_text_that_doesnt_convert_is_rejected() {
+        let mut data = text_content(&[&["1", "a"], &["2", "b"]]);
+        data.set_column_type(0, ColumnType::Integer);
+
+        data.perform(paste("A1", &[&["x", "c"], &["3", "d"]]));
+        assert_eq!(texts(&data), [["1", "c"], ["3", "d"]]);
+
+        let rejections = data.take_rejections();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].0, Address::new(0, 0));
+        assert!(data.take_rejections().is_empty());
+    }
+
+    #[test]
+    fn paste_tiles_a_selection_that_fits_the_block() {
+        let mut data = text_content(&[&["", "", "", ""], &["", "", "", ""], &["", "", "", ""]]);
+
+        data.select_range(range("A1", "D2"));
+        data.perform(paste("A1", &[&["a", "b"]]));
+        assert_eq!(
+            texts(&data),
+            [["a", "b", "a", "b"], ["a", "b", "a", "b"], ["", "", "", ""]]
+        );
+
+        // a selection that doesn't fit the block a whole number of times
+        // takes a single copy
+        data.select_range(range("A1", "C3"));
+        data.perform(paste("A1", &[&["x", "y"]]));
+        assert_eq!(
+            texts(&data),
+            [["x", "y", "a", "b"], ["a", "b", "a", "b"], ["", "", "", ""]]
+        );
+
+        // as does a selection that doesn't start at the anchor
+        data.select_range(range("A1", "D1"));
+        data.perform(paste("B3", &[&["z"]]));
+        assert_eq!(
+            texts(&data),
+            [
+                ["x", "y", "a", "b"],
+                ["a", "b", "a", "b"],
+                ["", "z", "", ""]
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn cells_round_trip_with_and_without_borders() {
+        let bordered = Cell {
+            content: String::from("a"),
+            border: Some(iced::Border {
+                color: iced::Color::from_rgba(0.25, 0.5, 0.75, 1.0),
+                width: 2.0,
+                radius: iced::border::Radius {
+                    top_left: 1.0,
+                    top_right: 2.0,
+                    bottom_right: 3.0,
+                    bottom_left: 4.0,
+                },
+            }),
+            background: Some(Background::Color(iced::Color::from_rgba(
+                1.0, 0.0, 0.0, 0.5,
+            ))),
+        };
+        let plain = Cell::from(String::from("b"));
+
+        for cell in [bordered, plain] {
+            let json = serde_json::to_string(&cell).unwrap();
+            assert_eq!(serde_json::from_str::<Cell>(&json).unwrap(), cell);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn cells_tolerate_missing_and_unknown_fields() {
+        let cell: Cell = serde_json::from_str(r#"{"content":"a","extra":1}"#).unwrap();
+        assert_eq!(cell, Cell::from(String::from("a")));
+
+        // border fields left out take their defaults
+        let cell: Cell =
+            serde_json::from_str(r#"{"content":"a","border":{"width":1.0,"unknown":true}}"#)
+                .unwrap();
+        assert_eq!(
+            cell.border,
+            Some(iced::Border {
+                width: 1.0,
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.0),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn rows_and_columns_of_a_non_square_table() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"]]);
+
+        let row = |data: &Content, y| -> Vec<String> {
+            data.row(y).map(|cell| cell.content.clone()).collect()
+        };
+        assert_eq!(row(&data, 0), ["a", "b", "c"]);
+        assert_eq!(row(&data, 1), ["d", "e", "f"]);
+        assert_eq!(data.row(2).count(), 0);
+
+        let column = data.column(2).unwrap();
+        assert_eq!(column.len(), 2);
+        assert_eq!((&*column[0].content, &*column[1].content), ("c", "f"));
+        assert!(data.column(3).is_none());
+
+        for cell in data.row_mut(1) {
+            cell.content.make_ascii_uppercase();
+        }
+        data.column_mut(0).unwrap()[0].content.push('!');
+        assert_eq!(texts(&data), [["a!", "b", "c"], ["D", "E", "F"]]);
+    }
+
+    #[test]
+    fn insert_with_sizes_at_the_edges() {
+        let mut data = content(&[&["a", "b"], &["c", "d"]]).with_history(8);
+        data.select_range(range("B1", "B2"));
+        data.internal().take_dirty();
+
+        data.insert_row_with_height(0, 40.0);
+        assert_eq!(texts(&data), [["", ""], ["a", "b"], ["c", "d"]]);
+        assert_eq!(data.row_sizes(), [40.0, 20.0, 20.0]);
+        assert_eq!(*data.selection(), range("B2", "B3"));
+        assert!(data.internal().take_dirty().data);
+
+        // past the end appends
+        data.insert_row_with_height(99, 10.0);
+        data.insert_column_with_width(99, 60.0);
+        assert_eq!(
+            texts(&data),
+            [["", "", ""], ["a", "b", ""], ["c", "d", ""], ["", "", ""]]
+        );
+        assert_eq!(data.row_sizes(), [40.0, 20.0, 20.0, 10.0]);
+        assert_eq!(data.column_sizes(), [100.0, 100.0, 60.0]);
+        assert_eq!(*data.selection(), range("B2", "B3"));
+
+        data.insert_column_with_width(0, 30.0);
+        assert_eq!(data.column_sizes(), [30.0, 100.0, 100.0, 60.0]);
+        assert_eq!(*data.selection(), range("C2", "C3"));
+
+        // each insertion is a single step
+        for _ in 0..4 {
+            data.undo();
+        }
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"]]);
+        assert_eq!(data.row_sizes(), [20.0, 20.0]);
+        assert_eq!(data.column_sizes(), [100.0, 100.0]);
+        assert!(!data.can_undo());
+    }
+
+    #[test]
+    fn insert_into_an_empty_table() {
+        let mut data = Content::<Cell>::default();
+
+        data.insert_row_with_height(3, 25.0);
+        // the row needs a column to hold its cell
+        assert_eq!((data.column_count(), data.row_count()), (1, 1));
+        assert_eq!(data.row_sizes(), [25.0]);
+        assert_eq!(data.column_sizes(), [100.0]);
+
+        data.insert_column_with_width(3, 50.0);
+        assert_eq!((data.column_count(), data.row_count()), (2, 1));
+        assert_eq!(data.column_sizes(), [100.0, 50.0]);
+    }
+
+    #[test]
+    fn take_rows_and_columns() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"]]);
+        data.set_row_height(1, 30.0);
+        data.set_column_width(2, 50.0);
+        data.select_range(range("C2", "C2"));
+
+        let taken = data.take_row(1);
+        assert_eq!(
+            taken.iter().map(|cell| &*cell.content).collect::<Vec<_>>(),
+            ["d", "e", "f"]
+        );
+        assert_eq!(texts(&data), [["a", "b", "c"]]);
+        assert_eq!(data.row_sizes(), [20.0]);
+        assert_eq!(*data.selection(), range("C1", "C1"));
+
+        let taken = data.take_column(2);
+        assert_eq!(taken, [Cell::from(String::from("c"))]);
+        assert_eq!(texts(&data), [["a", "b"]]);
+        assert_eq!(data.column_sizes(), [100.0, 100.0]);
+        assert_eq!(*data.selection(), range("B1", "B1"));
+
+        // outside of the table
+        assert!(data.take_row(1).is_empty());
+        assert!(data.take_column(2).is_empty());
+        assert_eq!(texts(&data), [["a", "b"]]);
+
+        let mut empty = Content::<Cell>::default();
+        assert!(empty.take_row(0).is_empty());
+        assert!(empty.take_column(0).is_empty());
+    }
+
+    #[test]
+    fn swapping_twice_restores_the_table() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"], &["g", "h", "i"]]);
+        data.set_row_height(0, 30.0);
+        data.set_column_width(2, 50.0);
+        data.select_range(range("A1", "B2"));
+        let (items, rows, columns) = (
+            texts(&data).concat().join(""),
+            data.row_sizes().to_vec(),
+            data.column_sizes().to_vec(),
+        );
+
+        for a in 0..4 {
+            for b in 0..4 {
+                data.swap_rows(a, b);
+                data.swap_rows(a, b);
+                data.swap_columns(a, b);
+                data.swap_columns(b, a);
+
+                assert_eq!(texts(&data).concat().join(""), items);
+                assert_eq!(data.row_sizes(), rows);
+                assert_eq!(data.column_sizes(), columns);
+            }
+        }
+
+        data.swap_rows(0, 2);
+        data.swap_columns(2, 1);
+        assert_eq!(
+            texts(&data),
+            [["g", "i", "h"], ["d", "f", "e"], ["a", "c", "b"]]
+        );
+        assert_eq!(data.row_sizes(), [20.0, 20.0, 30.0]);
+        assert_eq!(data.column_sizes(), [100.0, 50.0, 100.0]);
+        assert_eq!(*data.selection(), range("A1", "B2"));
+    }
+
+    #[test]
+    fn undo_swaps_back_and_redo_swaps_again() {
+        let mut data = content(&[&["a", "b"], &["c", "d"]]).with_history(8);
+        data.set_column_width(1, 50.0);
+
+        data.swap_rows(0, 1);
+        data.swap_columns(0, 1);
+        // nothing to record
+        data.swap_rows(1, 1);
+        data.swap_columns(0, 2);
+        assert_eq!(texts(&data), [["d", "c"], ["b", "a"]]);
+        assert_eq!(data.column_sizes(), [50.0, 100.0]);
+
+        assert_eq!(data.undo(), Some(range("A1", "B2")));
+        assert_eq!(texts(&data), [["c", "d"], ["a", "b"]]);
+        assert_eq!(data.column_sizes(), [100.0, 50.0]);
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"]]);
+        assert!(!data.can_undo());
+
+        data.redo();
+        data.redo();
+        assert_eq!(texts(&data), [["d", "c"], ["b", "a"]]);
+        assert_eq!(data.column_sizes(), [50.0, 100.0]);
+    }
+
+    #[test]
+    fn map_parses_a_sheet_of_numbers() {
+        let mut data = Content::<Cell>::with_range(range("B2", "C3"));
+        for (x, y, text) in [(0, 0, "1"), (1, 0, "2"), (0, 1, "3"), (1, 1, "x")] {
+            data.insert(Address::new(x, y), text);
+        }
+        data.set_column_width(1, 50.0);
+        data.select_range(range("A1", "B1"));
+
+        // the cells are offset by the start of the range
+        let error = data
+            .clone()
+            .try_map(|_, cell| cell.content.parse::<i32>())
+            .unwrap_err();
+        assert_eq!(error.0, "C3".parse().unwrap());
+
+        let mut cells = Vec::new();
+        let numbers = data.map(|cell, item| {
+            cells.push(cell.to_string());
+            item.content.parse::<i32>().unwrap_or(-1)
+        });
+
+        assert_eq!(cells, ["B2", "B3", "C2", "C3"]);
+        assert_eq!(numbers.row(0).collect::<Vec<_>>(), [&1, &2]);
+        assert_eq!(numbers.row(1).collect::<Vec<_>>(), [&3, &-1]);
+        assert_eq!(numbers.column_sizes(), [100.0, 50.0]);
+        assert_eq!(*numbers.selection(), range("A1", "B1"));
+        assert_eq!(*numbers.range(), range("B2", "C3"));
+    }
+
+    #[test]
+    fn resize_grows_shrinks_and_keeps_the_same_size() {
+        let mut data = content(&[&["a", "b"], &["c", "d"]]).with_default_cell_size(60.0, 30.0);
+        data.set_column_width(1, 50.0);
+        data.select_range(range("B2", "B2"));
+
+        data.resize(2, 2);
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"]]);
+        assert_eq!(data.column_sizes(), [100.0, 50.0]);
+
+        data.resize(3, 3);
+        assert_eq!(texts(&data), [["a", "b", ""], ["c", "d", ""], ["", "", ""]]);
+        assert_eq!(data.column_sizes(), [100.0, 50.0, 60.0]);
+        assert_eq!(data.row_sizes(), [20.0, 20.0, 30.0]);
+        assert_eq!(*data.selection(), range("B2", "B2"));
+
+        data.select_range(range("A1", "C3"));
+        data.resize(1, 2);
+        assert_eq!(texts(&data), [["a", "b"]]);
+        assert_eq!(data.column_sizes(), [100.0, 50.0]);
+        assert_eq!(data.row_sizes(), [20.0]);
+        assert_eq!(*data.selection(), range("A1", "B1"));
+
+        data.resize(0, 5);
+        assert_eq!((data.column_count(), data.row_count()), (0, 0));
+    }
+
+    #[test]
+    fn clear_range_skips_the_parts_outside_of_the_table() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"]]);
+
+        data.clear_range(&range("Z9", "B2"));
+        assert_eq!(texts(&data), [["a", "b", "c"], ["d", "", ""]]);
+
+        data.clear_range(&range("A1", "A5"));
+        assert_eq!(texts(&data), [["", "b", "c"], ["", "", ""]]);
+
+        // entirely outside
+        data.clear_range(&range("D3", "E4"));
+        assert_eq!(texts(&data), [["", "b", "c"], ["", "", ""]]);
+    }
+
+    #[test]
+    fn expanding_the_selection_past_an_i16_of_columns() {
+        let mut data = Content::<Cell>::with_range(range("A1", "AXZZ1"));
+        let last = data.column_count() - 1;
+        assert!(last > i16::MAX as usize);
+        data.select_cell(Address::new(i16::MAX as usize, 0));
+
+        data.expand_selection(Motion::Right);
+        assert_eq!(
+            data.selection().end(),
+            Some(Address::new(i16::MAX as usize + 1, 0))
+        );
+
+        data.select_cell(Address::new(last, 0));
+        data.expand_selection(Motion::Right);
+        assert_eq!(data.selection().end(), Some(Address::new(last, 0)));
+        data.expand_selection(Motion::Left);
+        assert_eq!(data.selection().end(), Some(Address::new(last - 1, 0)));
+    }
+
+    #[test]
+    fn home_and_end_from_a_cell_and_from_a_range() {
+        let mut data = Content::<Cell>::with_range(range("A1", "E3"));
+
+        data.select_cell(Address::new(2, 1));
+        data.move_selection(Motion::Home);
+        assert_eq!(*data.selection(), Range::new(Address::new(0, 1), None));
+        data.move_selection(Motion::End);
+        assert_eq!(*data.selection(), Range::new(Address::new(4, 1), None));
+
+        // a range moves to its own first and last columns, on the row of its
+        // start
+        data.select_range(range("D3", "B2"));
+        data.move_selection(Motion::Home);
+        assert_eq!(*data.selection(), Range::new(Address::new(1, 2), None));
+
+        data.select_range(range("B2", "D3"));
+        data.move_selection(Motion::End);
+        assert_eq!(*data.selection(), Range::new(Address::new(3, 1), None));
+    }
+
+    #[test]
+    fn expanding_to_the_ends_of_the_row_and_the_table() {
+        let mut data = Content::<Cell>::with_range(range("A1", "E4"));
+        let expand = |data: &mut Content, motion| {
+            data.select_cell(Address::new(2, 1));
+            data.expand_selection(motion);
+            *data.selection()
+        };
+
+        assert_eq!(expand(&mut data, Motion::Home), range("C2", "A2"));
+        assert_eq!(expand(&mut data, Motion::End), range("C2", "E2"));
+        assert_eq!(expand(&mut data, Motion::DocumentStart), range("C2", "A1"));
+        assert_eq!(expand(&mut data, Motion::DocumentEnd), range("C2", "E4"));
+
+        // the anchor stays put as the end moves back and forth
+        data.select_cell(Address::new(2, 1));
+        data.expand_selection(Motion::Down);
+        data.expand_selection(Motion::End);
+        assert_eq!(*data.selection(), range("C2", "E3"));
+        data.expand_selection(Motion::Home);
+        assert_eq!(*data.selection(), range("C2", "A3"));
+    }
+
+    #[test]
+    fn forward_and_back_expand_like_right_and_left() {
+        let mut data = Content::<Cell>::with_range(range("A1", "D2"));
+
+        data.select_range(range("B1", "B2"));
+        data.expand_selection(Motion::Forward);
+        assert_eq!(*data.selection(), range("B1", "C2"));
+        data.expand_selection(Motion::Forward);
+        data.expand_selection(Motion::Forward);
+        assert_eq!(*data.selection(), range("B1", "D2"));
+
+        data.expand_selection(Motion::Back);
+        assert_eq!(*data.selection(), range("B1", "C2"));
+        data.expand_selection(Motion::Back);
+        data.expand_selection(Motion::Back);
+        data.expand_selection(Motion::Back);
+        assert_eq!(*data.selection(), range("B1", "A2"));
+    }
+
+    #[test]
+    fn select_all_takes_the_used_range_first() {
+        let mut data = content(&[
+            &["", "", "", ""],
+            &["", "a", "", ""],
+            &["", "", "b", ""],
+            &["", "", "", ""],
+            &["", "", "", ""],
+        ]);
+
+        // every cell counts as filled without an emptiness check
+        assert_eq!(data.used_range(), Some(range("A1", "D5")));
+
+        data.set_is_empty(|cell| cell.content.is_empty());
+        assert_eq!(data.used_range(), Some(range("B2", "C3")));
+
+        data.perform(Action::SelectAll);
+        assert_eq!(*data.selection(), range("B2", "C3"));
+        data.perform(Action::SelectAll);
+        assert_eq!(*data.selection(), range("A1", "D5"));
+        // and back to the used range
+        data.perform(Action::SelectAll);
+        assert_eq!(*data.selection(), range("B2", "C3"));
+
+        data.clear_range(&range("A1", "D5"));
+        assert_eq!(data.used_range(), None);
+        data.perform(Action::SelectAll);
+        assert_eq!(*data.selection(), range("A1", "D5"));
+    }
+
+    #[test]
+    fn debug_shows_the_corner_of_a_large_table() {
+        let mut large = Content::<usize>::default();
+        for x in 0..20 {
+            for y in 0..50 {
+                large.insert(Address::new(x, y), 100 * x + y);
+            }
+        }
+        let debug = format!("{large:?}");
+        assert!(debug.starts_with("Content { columns: 20, rows: 50, "));
+        let rows: Vec<String> = (0..8)
+            .map(|y| {
+                let cells: Vec<_> = (0..8).map(|x| (100 * x + y).to_string()).collect();
+                format!("[{}, ..]", cells.join(", "))
+            })
+            .collect();
+        assert!(debug.ends_with(&format!("cells: [{}, ..], .. }}", rows.join(", "))));
+
+        // however large it is
+        let mut huge = Content::<usize>::default();
+        huge.insert(Address::new(499, 499), 0usize);
+        let debug = format!("{huge:?}");
+        assert!(debug.starts_with("Content { columns: 500, rows: 500, "));
+        assert!(debug.len() < 500);
+
+        let mut small = Content::<usize>::default();
+        small.insert(Address::new(0, 0), 1usize);
+        small.insert(Address::new(1, 0), 2usize);
+        assert!(format!("{small:?}").ends_with("cells: [[1, 2]], .. }"));
+
+        // even if its columns don't all have every row
+        small.insert(Address::new(0, 1), 3usize);
+        small.columns[1].pop();
+        assert!(format!("{small:?}").ends_with("cells: [[1, 2], [3, _]], .. }"));
+    }
+
+    #[test]
+    fn sorting_by_a_number_column_keeps_equal_rows_in_order() {
+        let mut content = text_content(&[
+            &["10", "a"],
+            &["9", "b"],
+            &["10", "c"],
+            &["", "d"],
+            &["x", "e"],
+            &["9", "f"],
+        ]);
+        content.set_column_type(0, ColumnType::Integer);
+        content.set_row_height(1, 40.0);
+        let letters =
+            |content: &Content| -> String { texts(content).iter().map(|row| row[1]).collect() };
+
+        content.perform(Action::Sort {
+            column: 0,
+            ascending: true,
+        });
+        // numbers by value, then what isn't a number, then empty cells
+        assert_eq!(letters(&content), "bfaced");
+        // the rows keep their heights
+        assert_eq!(content.row_sizes()[0], 40.0);
+
+        content.perform(Action::Sort {
+            column: 0,
+            ascending: false,
+        });
+        assert_eq!(letters(&content), "deacbf");
+    }
+
+    #[test]
+    fn undoing_a_sort_restores_the_rows_and_merged_cells() {
+        let mut content = text_content(&[&["3", "a"], &["1", "b"], &["2", "c"]]).with_history(8);
+        content.merge_cells(range("B1", "B2"));
+        content.merge_cells(range("A3", "B3"));
+        let before: Vec<String> = texts(&content)
+            .concat()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let spans = content.spans().to_vec();
+
+        content.perform(Action::Sort {
+            column: 0,
+            ascending: true,
+        });
+        assert_eq!(texts(&content).concat(), ["1", "b", "2", "c", "3", "a"]);
+        // the merged cells within a row move with it, the taller ones don't
+        // survive the sort
+        assert_eq!(content.spans().len(), 1);
+        assert_eq!(content.spans()[0].anchor, "A2".parse().unwrap());
+
+        content.undo();
+        assert_eq!(texts(&content).concat(), before);
+        assert_eq!(content.spans(), spans);
+    }
+
+    #[test]
+    fn sorting_with_fewer_row_heights_than_rows() {
+        let mut content = text_content(&[&["b"], &["c"], &["a"]]).with_row_heights(vec![30.0]);
+        content.set_comparator(|_, a, b| a.content.cmp(&b.content));
+
+        content.perform(Action::Sort {
+            column: 0,
+            ascending: true,
+        });
+        assert_eq!(texts(&content).concat(), ["a", "b", "c"]);
+        assert_eq!(content.row_sizes()[1], 30.0);
+        assert_eq!(content.row_count(), 3);
+    }
+}
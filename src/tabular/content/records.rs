@@ -0,0 +1,506 @@
+//! An implementation of `Tabular` over a slice of records, one per row.
+//!
+//! Each column is described by a [`Column`], which reads its text out of a
+//! record and, optionally, writes text back into it. The items of the table
+//! are the records themselves, so every cell of a row hands out the same
+//! record; use [`Records::cell_text`] to build the cell of a column.
+use std::borrow::Cow;
+
+use super::list::{Rejections, DEFAULT_COLUMN_WIDTH, DEFAULT_ROW_HEIGHT};
+use super::{ColumnType, Internal, Reference, ReferenceRange, Tabular};
+use crate::{Address, Range};
+
+/// A column of [`Records`], reading and writing one field of a record.
+pub struct Column<Rec> {
+    /// The name shown in the header of the column.
+    pub name: String,
+    /// Reads the text of the column out of a record.
+    pub get: fn(&Rec) -> String,
+    /// Writes some text to the column of a record, if it can be edited.
+    pub set: Option<fn(&mut Rec, &str)>,
+    /// The type of the column, which text must convert to to be written.
+    /// Text that doesn't is rejected, see [`Tabular::take_rejections`].
+    pub column_type: ColumnType,
+    /// The width of the column.
+    pub width: f32,
+}
+
+impl<Rec> Column<Rec> {
+    /// Create a read-only column with the given name.
+    pub fn new(name: impl Into<String>, get: fn(&Rec) -> String) -> Self {
+        Self {
+            name: name.into(),
+            get,
+            set: None,
+            column_type: ColumnType::Text,
+            width: DEFAULT_COLUMN_WIDTH,
+        }
+    }
+
+    /// Make the column editable, writing text to records with `set`.
+    pub fn with_setter(self, set: fn(&mut Rec, &str)) -> Self {
+        Self {
+            set: Some(set),
+            ..self
+        }
+    }
+
+    /// Set the [`ColumnType`] of the column.
+    pub fn with_type(self, column_type: ColumnType) -> Self {
+        Self {
+            column_type,
+            ..self
+        }
+    }
+
+    /// Set the width of the column.
+    pub fn with_width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+}
+
+impl<Rec> Clone for Column<Rec> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            get: self.get,
+            set: self.set,
+            column_type: self.column_type.clone(),
+            width: self.width,
+        }
+    }
+}
+
+// The records, which can only be written to unless they're shared
+enum Source<'a, Rec> {
+    Shared(&'a [Rec]),
+    Exclusive(&'a mut [Rec]),
+    Owned(Vec<Rec>),
+}
+
+impl<Rec> Source<'_, Rec> {
+    fn get(&self) -> &[Rec] {
+        match self {
+            Self::Shared(records) => records,
+            Self::Exclusive(records) => records,
+            Self::Owned(records) => records,
+        }
+    }
+
+    fn get_mut(&mut self) -> Option<&mut [Rec]> {
+        match self {
+            Self::Shared(_) => None,
+            Self::Exclusive(records) => Some(records),
+            Self::Owned(records) => Some(records),
+        }
+    }
+}
+
+/// A table over borrowed records, with a row per record and a column per
+/// [`Column`].
+///
+/// Records borrowed with [`Records::new`] are read-only, while those borrowed
+/// with [`Records::new_mut`] or owned through [`Records::owned`] take pastes,
+/// fills and deletes through the setters of their columns. Rows and columns
+/// can't be inserted, removed or moved, as the records and columns are fixed.
+///
+/// The trait requires items to implement [`Default`], which records must do
+/// as well, even though they're never replaced by a default. To be shown in
+/// a [`Table`](crate::Table), they must also implement [`Cell`](crate::Cell).
+pub struct Records<'a, Rec, K: Reference = Address, R: ReferenceRange<K> = Range> {
+    records: Source<'a, Rec>,
+    columns: Vec<Column<Rec>>,
+    selection: R,
+    // The active cell of the selection, if it isn't the start of it
+    active: Option<K>,
+    col_widths: Vec<f32>,
+    row_heights: Vec<f32>,
+    range: R,
+    internal: Internal,
+    rejections: Rejections<K>,
+}
+
+impl<'a, Rec, K: Reference, R: ReferenceRange<K>> Records<'a, Rec, K, R> {
+    /// Show the given records, read-only.
+    pub fn new(records: &'a [Rec], columns: Vec<Column<Rec>>) -> Self {
+        Self::with_source(Source::Shared(records), columns)
+    }
+
+    /// Show the given records, writing edits to them through the setters of
+    /// the columns.
+    pub fn new_mut(records: &'a mut [Rec], columns: Vec<Column<Rec>>) -> Self {
+        Self::with_source(Source::Exclusive(records), columns)
+    }
+
+    /// Show the given records, writing edits to them through the setters of
+    /// the columns. This lets an app keep the table in its state.
+    pub fn owned(records: Vec<Rec>, columns: Vec<Column<Rec>>) -> Self {
+        Self::with_source(Source::Owned(records), columns)
+    }
+
+    fn with_source(records: Source<'a, Rec>, columns: Vec<Column<Rec>>) -> Self {
+        let rows = records.get().len();
+        let end = K::new(columns.len().saturating_sub(1), rows.saturating_sub(1));
+
+        Self {
+            col_widths: columns.iter().map(|column| column.width).collect(),
+            row_heights: vec![DEFAULT_ROW_HEIGHT; rows],
+            records,
+            columns,
+            selection: R::default(),
+            active: None,
+            range: R::new(K::new(0, 0), Some(end)),
+            internal: Internal::default(),
+            rejections: Rejections::default(),
+        }
+    }
+
+    /// The records shown by the table.
+    pub fn records(&self) -> &[Rec] {
+        self.records.get()
+    }
+
+    /// The columns of the table.
+    pub fn columns(&self) -> &[Column<Rec>] {
+        &self.columns
+    }
+
+    /// A function giving the text of a cell out of its record, to build the
+    /// cells of a [`Table`](crate::Table) with.
+    pub fn cell_text(&self) -> impl Fn(K, &Rec) -> String + 'static
+    where
+        Rec: 'static,
+    {
+        let getters: Vec<_> = self.columns.iter().map(|column| column.get).collect();
+        move |cell, record| {
+            getters
+                .get(cell.x())
+                .map(|get| get(record))
+                .unwrap_or_default()
+        }
+    }
+
+    /// Set the row heights for the grid.
+    pub fn with_row_heights(self, sizes: Vec<f32>) -> Self {
+        Self {
+            row_heights: sizes,
+            ..self
+        }
+    }
+}
+
+impl<Rec, K: Reference, R: ReferenceRange<K>> Tabular<Rec, K, R> for Records<'_, Rec, K, R>
+where
+    Rec: Default,
+{
+    fn from_range(range: &R) -> Self {
+        Self {
+            range: *range,
+            ..Self::new(&[], Vec::new())
+        }
+    }
+
+    fn range(&self) -> &R {
+        &self.range
+    }
+
+    fn get(&self, cell: impl Into<K>) -> Option<&Rec> {
+        let (x, y) = cell.into().as_tuple();
+        if x < self.columns.len() {
+            self.records.get().get(y)
+        } else {
+            None
+        }
+    }
+
+    /// Always `None`, as a cell is only a part of its record; edits go
+    /// through [`Tabular::write_cell_text`] instead.
+    fn get_mut(&mut self, _cell: impl Into<K>) -> Option<&mut Rec> {
+        None
+    }
+
+    /// Write the text of the cell's column of `item` to the cell's record.
+    fn insert(&mut self, cell: impl Into<K>, item: impl Into<Rec>) {
+        let cell = cell.into();
+        if let Some(column) = self.columns.get(cell.x()) {
+            let text = (column.get)(&item.into());
+            self.write_cell_text(cell, &text);
+        }
+    }
+
+    fn display(&self, cell: K) -> Option<Cow<'_, str>> {
+        self.read_cell_text(cell).map(Cow::Owned)
+    }
+
+    fn read_cell_text(&self, cell: K) -> Option<String> {
+        let column = self.columns.get(cell.x())?;
+        self.records.get().get(cell.y()).map(column.get)
+    }
+
+    fn write_cell_text(&mut self, cell: K, text: &str) {
+        let (x, y) = cell.as_tuple();
+        let Some(column) = self.columns.get(x) else {
+            return;
+        };
+        let (Some(set), Some(record)) = (
+            column.set,
+            self.records
+                .get_mut()
+                .and_then(|records| records.get_mut(y)),
+        ) else {
+            return;
+        };
+
+        // text that doesn't convert to the column type is rejected
+        match column.column_type.parse(text) {
+            Ok(text) => {
+                set(record, &text);
+                self.internal.set_data_dirty();
+            }
+            Err(reason) => self.rejections.push(cell, reason),
+        }
+    }
+
+    fn take_rejections(&self) -> Vec<(K, String)> {
+        self.rejections.take()
+    }
+
+    fn column_type(&self, column: usize) -> &ColumnType {
+        static TEXT: ColumnType = ColumnType::Text;
+        self.columns
+            .get(column)
+            .map_or(&TEXT, |column| &column.column_type)
+    }
+
+    fn column_name(&self, column: usize) -> Cow<'_, str> {
+        match self.columns.get(column) {
+            Some(column) => Cow::Borrowed(&column.name),
+            None => Cow::Owned(crate::to_column_name(column)),
+        }
+    }
+
+    fn row_count(&self) -> usize {
+        if self.columns.is_empty() {
+            0
+        } else {
+            self.records.get().len()
+        }
+    }
+
+    fn column_count(&self) -> usize {
+        self.columns.len()
+    }
+
+    fn column_sizes(&self) -> &[f32] {
+        &self.col_widths
+    }
+
+    fn row_sizes(&self) -> &[f32] {
+        &self.row_heights
+    }
+
+    fn column_sizes_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.col_widths
+    }
+
+    fn row_sizes_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.row_heights
+    }
+
+    fn internal(&self) -> &Internal {
+        &self.internal
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, &Rec)> + '_> {
+        Box::new(iter(self.records.get(), self.columns.len(), (0, 0)))
+    }
+
+    /// Yields nothing, for the same reason as [`Tabular::get_mut`].
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (K, &mut Rec)> + '_> {
+        Box::new(std::iter::empty())
+    }
+
+    fn iter_relative(&self) -> Box<dyn Iterator<Item = (K, &Rec)> + '_> {
+        let offset = self.range.start().as_tuple();
+        Box::new(iter(self.records.get(), self.columns.len(), offset))
+    }
+
+    fn iter_relative_mut(&mut self) -> Box<dyn Iterator<Item = (K, &mut Rec)> + '_> {
+        Box::new(std::iter::empty())
+    }
+
+    fn selection(&self) -> &R {
+        &self.selection
+    }
+
+    fn selection_mut<'b>(&'b mut self) -> &'b mut R
+    where
+        K: 'b,
+    {
+        self.internal.set_selection_dirty();
+        &mut self.selection
+    }
+
+    fn select_cell(&mut self, cell: K) {
+        self.selection = cell.as_range();
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn select_range(&mut self, range: R) {
+        self.selection = range;
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn select_all(&mut self) {
+        let start = K::new(0, 0);
+        let end = K::new(
+            self.column_count().saturating_sub(1),
+            self.row_count().saturating_sub(1),
+        );
+        self.selection = R::new(start, Some(end));
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn active_cell(&self) -> K {
+        self.active
+            .filter(|cell| self.selection.normalize().contains(cell))
+            .unwrap_or(self.selection.start())
+    }
+
+    fn set_active_cell(&mut self, cell: K) {
+        if self.selection.normalize().contains(&cell) {
+            self.active = Some(cell);
+            self.internal.set_selection_dirty();
+        } else {
+            self.select_cell(cell);
+        }
+    }
+
+    fn with_reference(&mut self, _cell: impl Into<K>, _f: impl Fn(&K, &mut Rec)) {}
+
+    fn with_reference_range(&mut self, _range: &R, _f: impl Fn(&K, &mut Rec)) {}
+
+    /// Clear the cells one field at a time through the setters of their
+    /// columns, rather than a record at a time.
+    fn clear_range(&mut self, range: &R) {
+        for cell in range.normalize().iter() {
+            self.write_cell_text(cell, "");
+        }
+    }
+}
+
+// Give every cell of a row its record, a row at a time
+fn iter<Rec, K: Reference>(
+    records: &[Rec],
+    columns: usize,
+    (x, y): (usize, usize),
+) -> impl Iterator<Item = (K, &Rec)> {
+    records.iter().enumerate().flat_map(move |(row, record)| {
+        (0..columns).map(move |column| (K::new(column + x, row + y), record))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, Clone, PartialEq)]
+    struct Person {
+        name: String,
+        age: u32,
+    }
+
+    fn people() -> Vec<Person> {
+        vec![
+            Person {
+                name: "Ada".into(),
+                age: 36,
+            },
+            Person {
+                name: "Alan".into(),
+                age: 41,
+            },
+        ]
+    }
+
+    fn columns() -> Vec<Column<Person>> {
+        vec![
+            Column::new("Name", |person: &Person| person.name.clone())
+                .with_setter(|person, text| person.name = text.to_string()),
+            Column::new("Age", |person: &Person| person.age.to_string())
+                .with_setter(|person, text| person.age = text.parse().unwrap_or_default())
+                .with_type(ColumnType::Integer),
+        ]
+    }
+
+    #[test]
+    fn shared_records_ignore_writes() {
+        let people = people();
+        let mut data: Records<'_, Person> = Records::new(&people, columns());
+
+        data.write_cell_text(Address::new(0, 0), "Grace");
+        data.insert(Address::new(1, 1), Person::default());
+        data.clear_range(&Range::new(Address::new(0, 0), Some(Address::new(1, 1))));
+
+        assert_eq!(data.records(), people);
+        assert!(data.take_rejections().is_empty());
+    }
+
+    #[test]
+    fn writes_go_through_the_setters_of_the_columns() {
+        let mut people = people();
+        let mut columns = columns();
+        columns.push(Column::new("Initial", |person: &Person| {
+            person.name.chars().take(1).collect()
+        }));
+        let mut data: Records<'_, Person> = Records::new_mut(&mut people, columns);
+
+        data.write_cell_text(Address::new(0, 0), "Grace");
+        data.write_cell_text(Address::new(1, 1), " 42 ");
+        // the last column has no setter
+        data.write_cell_text(Address::new(2, 1), "X");
+
+        assert_eq!(
+            data.read_cell_text(Address::new(2, 0)).as_deref(),
+            Some("G")
+        );
+        assert_eq!(
+            data.read_cell_text(Address::new(2, 1)).as_deref(),
+            Some("A")
+        );
+        drop(data);
+        assert_eq!(people[0].name, "Grace");
+        assert_eq!(people[1].age, 42);
+    }
+
+    #[test]
+    fn text_that_doesnt_convert_is_rejected() {
+        let mut data: Records<'_, Person> = Records::owned(people(), columns());
+
+        data.write_cell_text(Address::new(1, 0), "old");
+
+        assert_eq!(data.records()[0].age, 36);
+        let rejections = data.take_rejections();
+        assert_eq!(rejections.len(), 1);
+        assert_eq!(rejections[0].0, Address::new(1, 0));
+        assert!(data.take_rejections().is_empty());
+    }
+
+    #[test]
+    fn cells_outside_of_the_table_have_no_record() {
+        let data: Records<'_, Person> = Records::owned(people(), columns());
+
+        assert_eq!(
+            data.get(Address::new(1, 1)).map(|person| person.age),
+            Some(41)
+        );
+        assert!(data.get(Address::new(2, 0)).is_none());
+        assert!(data.get(Address::new(0, 2)).is_none());
+        assert!(data.read_cell_text(Address::new(2, 0)).is_none());
+        assert!(data.read_cell_text(Address::new(0, 2)).is_none());
+        assert_eq!((data.column_count(), data.row_count()), (2, 2));
+    }
+}
@@ -0,0 +1,1255 @@
+//! An implementation of `Tabular` in a row-major `Vec<Vec<T>>` format.
+//!
+//! This suits tables that are mostly read and written a row at a time, like
+//! when building the elements of a row, exporting or pasting rows. It has
+//! the validation, sorting, column types, row groups, merged cells and undo
+//! history of [`list::Content`], while the column sizings, filling rows,
+//! emptiness checks and display text are left out.
+//!
+//! [`list::Content`]: super::list::Content
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use super::history::{self, Change, History, Kind, Layout};
+use super::list::{
+    self, insert_column_in_spans, insert_row_in_outline, merge_span, move_column_in_spans,
+    move_row_in_outline, moved_index, permute_spans, remove_column_from_spans,
+    remove_row_from_outline, set_row_group, Cell, Comparator, FromText, Rejections, ToText,
+    Validator, DEFAULT_COLUMN_WIDTH, DEFAULT_ROW_HEIGHT,
+};
+use super::{
+    clamp_range, CellSpan, ColumnType, Internal, Reference, ReferenceRange, RowGroup, Sizing,
+    Tabular,
+};
+use crate::{Action, Address, Range};
+
+#[derive(Clone)]
+pub struct Content<T = Cell, K: Reference = Address, R: ReferenceRange<K> = Range> {
+    rows: Vec<Vec<T>>,
+    selection: R,
+    // The active cell of the selection, if it isn't the start of it
+    active: Option<K>,
+    col_widths: Vec<f32>,
+    row_heights: Vec<f32>,
+    range: R,
+    internal: Internal,
+    validator: Option<Validator<T, K>>,
+    comparator: Option<Comparator<T>>,
+    text: Option<(ToText<T>, FromText<T>)>,
+    column_types: Vec<ColumnType>,
+    row_groups: Vec<RowGroup>,
+    spans: Vec<CellSpan<K>>,
+    history: Option<History<T, K>>,
+    rejections: Rejections<K>,
+}
+
+impl<T, K: Reference, R: ReferenceRange<K>> Tabular<T, K, R> for Content<T, K, R>
+where
+    T: Default,
+{
+    fn from_range(range: &R) -> Self {
+        Self {
+            rows: Vec::new(),
+            selection: R::default(),
+            active: None,
+            col_widths: vec![],
+            row_heights: vec![],
+            range: *range,
+            internal: Internal::default(),
+            validator: None,
+            comparator: None,
+            text: None,
+            column_types: Vec::new(),
+            row_groups: Vec::new(),
+            spans: Vec::new(),
+            history: None,
+            rejections: Rejections::default(),
+        }
+    }
+
+    fn range(&self) -> &R {
+        &self.range
+    }
+
+    fn get(&self, cell: impl Into<K>) -> Option<&T> {
+        let (x, y) = cell.into().as_tuple();
+        self.rows.get(y).and_then(|row| row.get(x))
+    }
+
+    fn get_mut(&mut self, cell: impl Into<K>) -> Option<&mut T> {
+        let (x, y) = cell.into().as_tuple();
+        self.rows.get_mut(y).and_then(|row| row.get_mut(x))
+    }
+
+    fn insert(&mut self, cell: impl Into<K>, item: impl Into<T>) {
+        let cell = cell.into();
+        let item = item.into();
+
+        if let Some(validator) = &self.validator {
+            if let Err(reason) = validator(cell, &item) {
+                self.rejections.push(cell, reason);
+                return;
+            }
+        }
+
+        let (x, y) = cell.as_tuple();
+
+        // growing the grid touches every row, so leave it be when possible
+        if x >= self.column_count() || y >= self.row_count() {
+            let needed_cols = (x + 1).max(self.column_count());
+            let needed_rows = (y + 1).max(self.row_count());
+            self.ensure_uniform_grid(needed_rows, needed_cols);
+        }
+
+        if let Some(history) = &mut self.history {
+            let change = Change::Cell {
+                cell,
+                before: (history.snapshot)(&self.rows[y][x]),
+                after: (history.snapshot)(&item),
+            };
+            history.record(Kind::Other, vec![change]);
+        }
+
+        self.rows[y][x] = item;
+        self.internal.set_data_dirty();
+    }
+
+    fn insert_row(&mut self, row: usize) {
+        let row = row.min(self.row_count());
+        self.insert_row_at(row);
+        self.record(Kind::Other, vec![Change::InsertRow { row }]);
+    }
+
+    fn insert_column(&mut self, column: usize) {
+        let column = column.min(self.column_count());
+        self.insert_column_at(column);
+        self.record(Kind::Other, vec![Change::InsertColumn { column }]);
+    }
+
+    fn remove_row(&mut self, row: usize) {
+        if row >= self.row_count() {
+            return;
+        }
+
+        if let Some(snapshot) = self.history.as_ref().map(|history| history.snapshot) {
+            let change = Change::RemoveRow {
+                row,
+                items: self.rows[row].iter().map(snapshot).collect(),
+                size: self.row_size(row).unwrap_or((DEFAULT_ROW_HEIGHT, None)),
+                groups: self.row_groups.clone(),
+                spans: self.spans.clone(),
+            };
+            self.record(Kind::Other, vec![change]);
+        }
+
+        self.remove_row_at(row);
+    }
+
+    fn remove_column(&mut self, column: usize) {
+        if column >= self.column_count() {
+            return;
+        }
+
+        if let Some(snapshot) = self.history.as_ref().map(|history| history.snapshot) {
+            let change = Change::RemoveColumn {
+                column,
+                items: self
+                    .rows
+                    .iter()
+                    .map(|items| snapshot(&items[column]))
+                    .collect(),
+                width: self
+                    .column_size(column)
+                    .unwrap_or((DEFAULT_COLUMN_WIDTH, None)),
+                column_type: self.column_types.get(column).cloned(),
+                spans: self.spans.clone(),
+            };
+            self.record(Kind::Other, vec![change]);
+        }
+
+        self.remove_column_at(column);
+    }
+
+    fn move_column(&mut self, from: usize, to: usize) {
+        let count = self.column_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let spans = self.spans.clone();
+        self.move_column_at(from, to);
+        self.record(Kind::Other, vec![Change::MoveColumn { from, to, spans }]);
+    }
+
+    fn move_row(&mut self, from: usize, to: usize) {
+        let count = self.row_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let (groups, spans) = (self.row_groups.clone(), self.spans.clone());
+        self.move_row_at(from, to);
+        self.record(
+            Kind::Other,
+            vec![Change::MoveRow {
+                from,
+                to,
+                groups,
+                spans,
+            }],
+        );
+    }
+
+    fn perform(&mut self, action: Action<K, R>) {
+        history::perform(self, action);
+    }
+
+    fn row_groups(&self) -> &[RowGroup] {
+        &self.row_groups
+    }
+
+    fn spans(&self) -> &[CellSpan<K>] {
+        &self.spans
+    }
+
+    fn sort_rows(&mut self, column: usize, ascending: bool) {
+        let column_type = self.column_type(column).clone();
+        if let Some(comparator) = self.comparator.clone() {
+            self.sort_by(column, ascending, |a, b| comparator(&column_type, a, b));
+        } else if let Some((to_text, _)) = self.text.clone() {
+            // the text of the items is compared as values of the column type
+            self.sort_by(column, ascending, |a, b| {
+                column_type.compare(&to_text(a), &to_text(b))
+            });
+        }
+    }
+
+    fn read_cell_text(&self, cell: K) -> Option<String> {
+        let (to_text, _) = self.text.as_ref()?;
+        self.display(cell)
+            .map(|text| text.into_owned())
+            .or_else(|| self.get(cell).map(|item| to_text(item)))
+    }
+
+    fn write_cell_text(&mut self, cell: K, text: &str) {
+        let Some((_, from_text)) = self.text.clone() else {
+            return;
+        };
+
+        // text that doesn't convert to the column type is rejected
+        match self.column_type(cell.x()).parse(text) {
+            Ok(parsed) => self.insert(cell, from_text(&parsed)),
+            Err(reason) => self.rejections.push(cell, reason),
+        }
+    }
+
+    fn column_type(&self, column: usize) -> &ColumnType {
+        static TEXT: ColumnType = ColumnType::Text;
+        self.column_types.get(column).unwrap_or(&TEXT)
+    }
+
+    fn take_rejections(&self) -> Vec<(K, String)> {
+        self.rejections.take()
+    }
+
+    fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn column_count(&self) -> usize {
+        self.rows.first().map(|row| row.len()).unwrap_or(0)
+    }
+
+    fn column_sizes(&self) -> &[f32] {
+        &self.col_widths
+    }
+
+    fn row_sizes(&self) -> &[f32] {
+        &self.row_heights
+    }
+
+    fn column_sizes_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.col_widths
+    }
+
+    fn row_sizes_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.row_heights
+    }
+
+    fn internal(&self) -> &Internal {
+        &self.internal
+    }
+
+    /// Iterate over the cells a row at a time, unlike [`list::Content`],
+    /// which goes through them a column at a time.
+    fn iter(&self) -> Box<dyn Iterator<Item = (K, &T)> + '_> {
+        Box::new(iter(&self.rows, (0, 0)))
+    }
+
+    fn iter_mut(&mut self) -> Box<dyn Iterator<Item = (K, &mut T)> + '_> {
+        Box::new(iter_mut(&mut self.rows, (0, 0)))
+    }
+
+    fn iter_relative(&self) -> Box<dyn Iterator<Item = (K, &T)> + '_> {
+        let offset = self.range.start().as_tuple();
+        Box::new(iter(&self.rows, offset))
+    }
+
+    fn iter_relative_mut(&mut self) -> Box<dyn Iterator<Item = (K, &mut T)> + '_> {
+        let offset = self.range.start().as_tuple();
+        Box::new(iter_mut(&mut self.rows, offset))
+    }
+
+    fn iter_range<'a>(&'a self, range: &R) -> Box<dyn Iterator<Item = (K, &'a T)> + 'a>
+    where
+        K: 'a,
+    {
+        let (columns, rows) = clamp_range(range, self.column_count(), self.row_count());
+        Box::new(
+            self.rows[rows.clone()]
+                .iter()
+                .zip(rows)
+                .flat_map(move |(items, y)| {
+                    items[columns.clone()]
+                        .iter()
+                        .zip(columns.clone())
+                        .map(move |(item, x)| (K::new(x, y), item))
+                }),
+        )
+    }
+
+    fn iter_range_mut<'a>(&'a mut self, range: &R) -> Box<dyn Iterator<Item = (K, &'a mut T)> + 'a>
+    where
+        K: 'a,
+        R: 'a,
+    {
+        let (columns, rows) = clamp_range(range, self.column_count(), self.row_count());
+        Box::new(
+            self.rows[rows.clone()]
+                .iter_mut()
+                .zip(rows)
+                .flat_map(move |(items, y)| {
+                    items[columns.clone()]
+                        .iter_mut()
+                        .zip(columns.clone())
+                        .map(move |(item, x)| (K::new(x, y), item))
+                }),
+        )
+    }
+
+    fn selection(&self) -> &R {
+        &self.selection
+    }
+
+    fn selection_mut<'a>(&'a mut self) -> &'a mut R
+    where
+        K: 'a,
+    {
+        self.internal.set_selection_dirty();
+        &mut self.selection
+    }
+
+    fn select_cell(&mut self, cell: K) {
+        self.selection = cell.as_range();
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn select_range(&mut self, range: R) {
+        self.selection = range;
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn select_all(&mut self) {
+        let start = K::new(0, 0);
+        let end = K::new(
+            self.column_count().saturating_sub(1),
+            self.row_count().saturating_sub(1),
+        );
+        self.selection = R::new(start, Some(end));
+        self.active = None;
+        self.internal.set_selection_dirty();
+    }
+
+    fn active_cell(&self) -> K {
+        self.active
+            .filter(|cell| self.selection.normalize().contains(cell))
+            .unwrap_or(self.selection.start())
+    }
+
+    fn set_active_cell(&mut self, cell: K) {
+        if self.selection.normalize().contains(&cell) {
+            self.active = Some(cell);
+            self.internal.set_selection_dirty();
+        } else {
+            self.select_cell(cell);
+        }
+    }
+
+    fn with_reference(&mut self, cell: impl Into<K>, f: impl Fn(&K, &mut T)) {
+        let key = cell.into();
+        if let Some(value) = self.get_mut(key) {
+            f(&key, value);
+        }
+    }
+
+    fn with_reference_range(&mut self, range: &R, f: impl Fn(&K, &mut T)) {
+        for (cell, value) in self.iter_range_mut(range) {
+            f(&cell, value);
+        }
+    }
+}
+
+impl<T, K: Reference, R: ReferenceRange<K>> Content<T, K, R> {
+    /// Set the row heights for the grid.
+    pub fn with_row_heights(self, sizes: Vec<f32>) -> Self {
+        Self {
+            row_heights: sizes,
+            ..self
+        }
+    }
+
+    /// Set the column widths for the grid.
+    pub fn with_column_widths(self, sizes: Vec<f32>) -> Self {
+        Self {
+            col_widths: sizes,
+            ..self
+        }
+    }
+
+    /// Create a grid of default items covering the given range.
+    pub fn with_range(range: R) -> Self
+    where
+        T: Default,
+    {
+        let mut content = Self::from_range(&range);
+        content.ensure_uniform_grid(range.height(), range.width());
+        content
+    }
+
+    /// Set how items are converted to and from text, e.g. to copy them to
+    /// and paste them from the clipboard.
+    pub fn set_text_conversion(
+        &mut self,
+        to_text: impl Fn(&T) -> String + Send + Sync + 'static,
+        from_text: impl Fn(&str) -> T + Send + Sync + 'static,
+    ) {
+        self.text = Some((Arc::new(to_text), Arc::new(from_text)));
+    }
+
+    /// Keep an undo history of up to `capacity` steps, like
+    /// [`list::Content::with_history`].
+    pub fn with_history(self, capacity: usize) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            history: Some(History::new(capacity, T::clone)),
+            ..self
+        }
+    }
+
+    /// Whether there is anything to undo.
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.undo.is_empty())
+    }
+
+    /// Whether there is anything to redo.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .is_some_and(|history| !history.redo.is_empty())
+    }
+
+    /// Undo the last recorded step. Returns the range it affected, so that it
+    /// can be selected again.
+    pub fn undo(&mut self) -> Option<R>
+    where
+        T: Default,
+    {
+        history::undo(self)
+    }
+
+    /// Redo the last undone step. Returns the range it affected, so that it
+    /// can be selected again.
+    pub fn redo(&mut self) -> Option<R>
+    where
+        T: Default,
+    {
+        history::redo(self)
+    }
+
+    /// Forget every undo and redo step.
+    pub fn clear_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.undo.clear();
+            history.redo.clear();
+        }
+    }
+
+    /// Set the validator consulted before an item is inserted.
+    ///
+    /// Items that fail validation are not written; the rejection is kept
+    /// until it is drained by [`Tabular::take_rejections`].
+    pub fn set_validator(
+        &mut self,
+        validator: impl Fn(K, &T) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.validator = Some(Arc::new(validator));
+    }
+
+    /// Remove the validator, if any.
+    pub fn clear_validator(&mut self) {
+        self.validator = None;
+    }
+
+    /// Set the comparator used to sort the rows on [`Action::Sort`].
+    ///
+    /// Without a comparator, the text of the items is compared with
+    /// [`ColumnType::compare`]. Without either, sorting does nothing.
+    pub fn set_comparator(
+        &mut self,
+        comparator: impl Fn(&ColumnType, &T, &T) -> Ordering + Send + Sync + 'static,
+    ) {
+        self.comparator = Some(Arc::new(comparator));
+    }
+
+    /// Sort the rows by the given column with a comparator, keeping rows
+    /// with equal values in their current order.
+    ///
+    /// The rows, selection and merged cells move like in
+    /// [`list::Content::sort_by`].
+    pub fn sort_by(
+        &mut self,
+        column: usize,
+        ascending: bool,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) where
+        T: Default,
+    {
+        if column >= self.column_count() {
+            return;
+        }
+
+        // the stable sort keeps equal rows in order in both directions
+        let rows = &self.rows;
+        let mut order: Vec<usize> = (0..rows.len()).collect();
+        if ascending {
+            order.sort_by(|&a, &b| compare(&rows[a][column], &rows[b][column]));
+        } else {
+            order.sort_by(|&a, &b| compare(&rows[b][column], &rows[a][column]));
+        }
+
+        let spans = self.spans.clone();
+        let moved = self.permute_rows(&order);
+        self.record(Kind::Other, vec![Change::Reorder { order, spans }]);
+
+        let start = self.selection.start();
+        let end = self.selection.end().unwrap_or(start);
+        let follow = |cell: K| K::new(cell.x(), moved.get(cell.y()).copied().unwrap_or(cell.y()));
+        self.selection = if start.y() == end.y() {
+            R::new(follow(start), self.selection.end().map(follow))
+        } else {
+            follow(start).as_range()
+        };
+        self.active = None;
+
+        self.internal.set_dirty();
+    }
+
+    /// Set the [`ColumnType`] of a specific column.
+    pub fn set_column_type(&mut self, col: usize, column_type: ColumnType) {
+        if col >= self.column_types.len() {
+            self.column_types.resize(col + 1, ColumnType::Text);
+        }
+        self.column_types[col] = column_type;
+    }
+
+    /// Group the given rows under their first row at the given outline level.
+    ///
+    /// A group with the same header row and level is replaced.
+    pub fn set_row_group(&mut self, rows: std::ops::Range<usize>, level: usize) {
+        set_row_group(&mut self.row_groups, rows, level);
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Remove every row group.
+    pub fn clear_row_groups(&mut self) {
+        self.row_groups.clear();
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Collapse or expand the groups whose header is the given row.
+    pub fn set_row_group_collapsed(&mut self, header: usize, collapsed: bool) {
+        for group in &mut self.row_groups {
+            if group.header() == header {
+                group.collapsed = collapsed;
+            }
+        }
+        self.internal.set_sizes_dirty();
+    }
+
+    /// Toggle the groups whose header is the given row.
+    pub fn toggle_row_group(&mut self, header: usize) {
+        let collapsed = self
+            .row_groups
+            .iter()
+            .any(|group| group.header() == header && !group.collapsed);
+        self.set_row_group_collapsed(header, collapsed);
+    }
+
+    /// Merge the cells of a range into its top-left cell.
+    ///
+    /// Any merged spans overlapping the range are unmerged first. Merging a
+    /// single cell only unmerges it.
+    pub fn merge_cells(&mut self, range: R) {
+        merge_span(&mut self.spans, range);
+        self.internal.set_data_dirty();
+    }
+
+    /// Unmerge the span containing the given cell, if any.
+    pub fn unmerge_cells(&mut self, cell: K) {
+        self.spans.retain(|span| !span.contains(cell.x(), cell.y()));
+        self.internal.set_data_dirty();
+    }
+
+    /// Set the height for a specific row.
+    pub fn set_row_height(&mut self, row: usize, height: f32) {
+        if let Some(size) = self.row_heights.get_mut(row) {
+            *size = height;
+            self.internal.set_sizes_dirty();
+        }
+    }
+
+    /// Set the width for a specific column.
+    pub fn set_column_width(&mut self, col: usize, width: f32) {
+        if let Some(size) = self.col_widths.get_mut(col) {
+            *size = width;
+            self.internal.set_sizes_dirty();
+        }
+    }
+
+    fn ensure_uniform_grid(&mut self, rows: usize, cols: usize)
+    where
+        T: Default,
+    {
+        if self.rows.len() < rows {
+            self.rows.resize_with(rows, || Vec::with_capacity(cols));
+            self.row_heights.resize(rows, DEFAULT_ROW_HEIGHT);
+        }
+
+        for row in &mut self.rows {
+            row.resize_with(cols, T::default);
+        }
+
+        self.col_widths.resize(cols, DEFAULT_COLUMN_WIDTH);
+    }
+
+    // Move the selection and the active cell along with the cells they're on
+    fn shift_selection(&mut self, shift: impl Fn(K) -> K) {
+        self.selection = R::new(
+            shift(self.selection.start()),
+            self.selection.end().map(&shift),
+        );
+        self.active = self.active.map(&shift);
+        self.internal.set_dirty();
+    }
+
+    fn record(&mut self, kind: Kind, changes: Vec<Change<T, K>>) {
+        if let Some(history) = &mut self.history {
+            history.record(kind, changes);
+        }
+    }
+}
+
+impl<T: Default, K: Reference, R: ReferenceRange<K>> Layout<T, K, R> for Content<T, K, R> {
+    fn history(&mut self) -> &mut Option<History<T, K>> {
+        &mut self.history
+    }
+
+    fn insert_row_at(&mut self, row: usize) {
+        let (rows, columns) = (self.row_count(), self.column_count());
+
+        // a grid without columns has no rows to grow
+        let columns = columns.max(1);
+        if self.col_widths.len() < columns {
+            self.col_widths.resize(columns, DEFAULT_COLUMN_WIDTH);
+        }
+
+        let mut items = Vec::with_capacity(columns);
+        items.resize_with(columns, T::default);
+        self.rows.insert(row.min(rows), items);
+        self.row_heights
+            .insert(row.min(self.row_heights.len()), DEFAULT_ROW_HEIGHT);
+
+        insert_row_in_outline(&mut self.row_groups, &mut self.spans, row);
+
+        // appending a row moves no cells
+        self.shift_selection(|cell| {
+            if cell.y() >= row && row < rows {
+                K::new(cell.x(), cell.y() + 1)
+            } else {
+                cell
+            }
+        });
+    }
+
+    fn insert_column_at(&mut self, column: usize) {
+        let columns = self.column_count();
+
+        for items in &mut self.rows {
+            items.insert(column.min(items.len()), T::default());
+        }
+        self.col_widths
+            .insert(column.min(self.col_widths.len()), DEFAULT_COLUMN_WIDTH);
+        if column < self.column_types.len() {
+            self.column_types.insert(column, ColumnType::Text);
+        }
+
+        insert_column_in_spans(&mut self.spans, column);
+
+        // appending a column moves no cells
+        self.shift_selection(|cell| {
+            if cell.x() >= column && column < columns {
+                K::new(cell.x() + 1, cell.y())
+            } else {
+                cell
+            }
+        });
+    }
+
+    fn remove_row_at(&mut self, row: usize) {
+        if row < self.rows.len() {
+            self.rows.remove(row);
+        }
+        if row < self.row_heights.len() {
+            self.row_heights.remove(row);
+        }
+
+        remove_row_from_outline(&mut self.row_groups, &mut self.spans, row);
+
+        let last = self.row_count().saturating_sub(1);
+        self.shift_selection(|cell| {
+            let y = if cell.y() > row {
+                cell.y() - 1
+            } else {
+                cell.y()
+            };
+            K::new(cell.x(), y.min(last))
+        });
+    }
+
+    fn remove_column_at(&mut self, column: usize) {
+        for items in &mut self.rows {
+            if column < items.len() {
+                items.remove(column);
+            }
+        }
+        if column < self.col_widths.len() {
+            self.col_widths.remove(column);
+        }
+        if column < self.column_types.len() {
+            self.column_types.remove(column);
+        }
+
+        // without columns, there are no rows either
+        if self.rows.first().is_some_and(Vec::is_empty) {
+            self.rows.clear();
+            self.row_heights.clear();
+            self.row_groups.clear();
+        }
+
+        remove_column_from_spans(&mut self.spans, column);
+
+        let last = self.column_count().saturating_sub(1);
+        self.shift_selection(|cell| {
+            let x = if cell.x() > column {
+                cell.x() - 1
+            } else {
+                cell.x()
+            };
+            K::new(x.min(last), cell.y())
+        });
+    }
+
+    fn move_column_at(&mut self, from: usize, to: usize) {
+        let count = self.column_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        for items in &mut self.rows {
+            let item = items.remove(from);
+            items.insert(to, item);
+        }
+        if self.col_widths.len() < count {
+            self.col_widths.resize(count, DEFAULT_COLUMN_WIDTH);
+        }
+        let width = self.col_widths.remove(from);
+        self.col_widths.insert(to, width);
+        if self.column_types.len() > from.min(to) {
+            self.column_types
+                .resize(count.max(self.column_types.len()), ColumnType::Text);
+            let column_type = self.column_types.remove(from);
+            self.column_types.insert(to, column_type);
+        }
+
+        move_column_in_spans(&mut self.spans, from, to);
+
+        self.shift_selection(|cell| K::new(moved_index(cell.x(), from, to), cell.y()));
+    }
+
+    fn move_row_at(&mut self, from: usize, to: usize) {
+        let count = self.row_count();
+        if from >= count || to >= count || from == to {
+            return;
+        }
+
+        let items = self.rows.remove(from);
+        self.rows.insert(to, items);
+        if self.row_heights.len() < count {
+            self.row_heights.resize(count, DEFAULT_ROW_HEIGHT);
+        }
+        let height = self.row_heights.remove(from);
+        self.row_heights.insert(to, height);
+
+        move_row_in_outline(&mut self.row_groups, &mut self.spans, from, to);
+
+        self.shift_selection(|cell| K::new(cell.x(), moved_index(cell.y(), from, to)));
+    }
+
+    fn swap_rows_at(&mut self, a: usize, b: usize) {
+        let count = self.row_count();
+        if a == b || a >= count || b >= count {
+            return;
+        }
+
+        self.rows.swap(a, b);
+        if self.row_heights.len() < count {
+            self.row_heights.resize(count, DEFAULT_ROW_HEIGHT);
+        }
+        self.row_heights.swap(a, b);
+        self.internal.set_dirty();
+    }
+
+    fn swap_columns_at(&mut self, a: usize, b: usize) {
+        let count = self.column_count();
+        if a == b || a >= count || b >= count {
+            return;
+        }
+
+        for items in &mut self.rows {
+            items.swap(a, b);
+        }
+        if self.col_widths.len() < count {
+            self.col_widths.resize(count, DEFAULT_COLUMN_WIDTH);
+        }
+        self.col_widths.swap(a, b);
+        if self.column_types.len() > a.min(b) {
+            self.column_types
+                .resize(count.max(self.column_types.len()), ColumnType::Text);
+            self.column_types.swap(a, b);
+        }
+        self.internal.set_dirty();
+    }
+
+    fn permute_rows(&mut self, order: &[usize]) -> Vec<usize> {
+        // a list too short for the order is filled up first, and the items
+        // past the order stay where they are
+        fn permute<V>(items: &mut Vec<V>, order: &[usize], fill: impl Fn() -> V) {
+            if items.len() < order.len() {
+                items.resize_with(order.len(), &fill);
+            }
+            let rest = items.split_off(order.len());
+            let mut old: Vec<_> = std::mem::take(items).into_iter().map(Some).collect();
+            items.extend(order.iter().filter_map(|&i| old[i].take()));
+            items.extend(rest);
+        }
+
+        let columns = self.column_count();
+        permute(&mut self.rows, order, || {
+            let mut items = Vec::with_capacity(columns);
+            items.resize_with(columns, T::default);
+            items
+        });
+        permute(&mut self.row_heights, order, || DEFAULT_ROW_HEIGHT);
+
+        let mut moved = vec![0; order.len()];
+        for (new, &old) in order.iter().enumerate() {
+            moved[old] = new;
+        }
+
+        permute_spans(&mut self.spans, &moved);
+        moved
+    }
+
+    fn column_size(&self, column: usize) -> Option<(f32, Option<Sizing>)> {
+        self.col_widths.get(column).map(|width| (*width, None))
+    }
+
+    fn set_column_size(&mut self, column: usize, (width, _): (f32, Option<Sizing>)) {
+        if let Some(size) = self.col_widths.get_mut(column) {
+            *size = width;
+        }
+    }
+
+    fn row_size(&self, row: usize) -> Option<(f32, Option<u16>)> {
+        self.row_heights.get(row).map(|height| (*height, None))
+    }
+
+    fn set_row_size(&mut self, row: usize, (height, _): (f32, Option<u16>)) {
+        if let Some(size) = self.row_heights.get_mut(row) {
+            *size = height;
+        }
+    }
+
+    fn restore_row(&mut self, row: usize, items: Vec<T>) {
+        for (cell, item) in self.rows[row].iter_mut().zip(items) {
+            *cell = item;
+        }
+    }
+
+    fn restore_column(&mut self, column: usize, items: Vec<T>, column_type: Option<ColumnType>) {
+        // the table may have lost its rows along with its last column
+        let rows = items.len().max(self.row_count());
+        self.ensure_uniform_grid(rows, self.column_count().max(column + 1));
+        for (row, item) in self.rows.iter_mut().zip(items) {
+            row[column] = item;
+        }
+        if let Some(column_type) = column_type {
+            self.set_column_type(column, column_type);
+        }
+    }
+
+    fn restore_outline(&mut self, groups: Option<&[RowGroup]>, spans: &[CellSpan<K>]) {
+        if let Some(groups) = groups {
+            self.row_groups = groups.to_vec();
+        }
+        self.spans = spans.to_vec();
+    }
+}
+
+impl<T, K: Reference, R: ReferenceRange<K>> Default for Content<T, K, R>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::from_range(&R::default())
+    }
+}
+
+impl<T: PartialEq, K: Reference, R: ReferenceRange<K>> PartialEq for Content<T, K, R> {
+    // Ignore internal
+    fn eq(&self, other: &Self) -> bool {
+        self.rows == other.rows
+            && self.selection == other.selection
+            && self.col_widths == other.col_widths
+            && self.row_heights == other.row_heights
+    }
+}
+
+impl<T: Default, K: Reference, R: ReferenceRange<K>> From<list::Content<T, K, R>>
+    for Content<T, K, R>
+{
+    /// Lay the cells of a [`list::Content`] out a row at a time.
+    ///
+    /// Everything but the column sizings, filling rows, emptiness check and
+    /// display text is kept, including the undo history.
+    fn from(content: list::Content<T, K, R>) -> Self {
+        let parts = content.into_parts();
+        let rows = parts.columns.first().map_or(0, Vec::len);
+        let mut columns: Vec<_> = parts.columns.into_iter().map(Vec::into_iter).collect();
+
+        Self {
+            rows: (0..rows)
+                .map(|_| {
+                    columns
+                        .iter_mut()
+                        .map(|column| column.next().unwrap_or_default())
+                        .collect()
+                })
+                .collect(),
+            selection: parts.selection,
+            active: parts.active,
+            col_widths: parts.col_widths,
+            row_heights: parts.row_heights,
+            range: parts.range,
+            internal: Internal::default(),
+            validator: parts.validator,
+            comparator: parts.comparator,
+            text: parts.text,
+            column_types: parts.column_types,
+            row_groups: parts.row_groups,
+            spans: parts.spans,
+            history: parts.history,
+            rejections: parts.rejections,
+        }
+    }
+}
+
+impl<T: Default, K: Reference, R: ReferenceRange<K>> From<Content<T, K, R>>
+    for list::Content<T, K, R>
+{
+    /// Lay the cells out a column at a time in a [`list::Content`], keeping
+    /// everything along with them, including the undo history.
+    fn from(content: Content<T, K, R>) -> Self {
+        let columns = content.column_count();
+        let mut rows: Vec<_> = content.rows.into_iter().map(Vec::into_iter).collect();
+
+        Self::from_parts(list::Parts {
+            columns: (0..columns)
+                .map(|_| {
+                    rows.iter_mut()
+                        .map(|row| row.next().unwrap_or_default())
+                        .collect()
+                })
+                .collect(),
+            selection: content.selection,
+            active: content.active,
+            col_widths: content.col_widths,
+            row_heights: content.row_heights,
+            range: content.range,
+            validator: content.validator,
+            comparator: content.comparator,
+            text: content.text,
+            column_types: content.column_types,
+            row_groups: content.row_groups,
+            spans: content.spans,
+            history: content.history,
+            rejections: content.rejections,
+        })
+    }
+}
+
+// Walk the rows in order, giving each item its (column, row) reference
+fn iter<T, K: Reference>(rows: &[Vec<T>], (x, y): (usize, usize)) -> impl Iterator<Item = (K, &T)> {
+    rows.iter().enumerate().flat_map(move |(row, items)| {
+        items
+            .iter()
+            .enumerate()
+            .map(move |(column, item)| (K::new(column + x, row + y), item))
+    })
+}
+
+fn iter_mut<T, K: Reference>(
+    rows: &mut [Vec<T>],
+    (x, y): (usize, usize),
+) -> impl Iterator<Item = (K, &mut T)> {
+    rows.iter_mut().enumerate().flat_map(move |(row, items)| {
+        items
+            .iter_mut()
+            .enumerate()
+            .map(move |(column, item)| (K::new(column + x, row + y), item))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tabular::action::Edit;
+
+    // A grid with the given rows of text
+    fn content(rows: &[&[&str]]) -> Content {
+        let mut content: Content = Content::default();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, text) in row.iter().enumerate() {
+                content.insert(Address::new(x, y), *text);
+            }
+        }
+        content.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        content
+    }
+
+    fn texts(content: &Content) -> Vec<Vec<&str>> {
+        content
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.as_str()).collect())
+            .collect()
+    }
+
+    fn range(a: &str, b: &str) -> Range {
+        Range::new(Address::parse(a).unwrap(), Address::parse(b).ok())
+    }
+
+    #[test]
+    fn converting_to_a_list_and_back_keeps_everything() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"]]).with_history(10);
+        data.set_column_width(1, 40.0);
+        data.set_row_height(1, 30.0);
+        data.set_column_type(2, ColumnType::Integer);
+        data.set_row_group(0..2, 1);
+        data.merge_cells(range("A1", "B1"));
+        data.select_range(range("B1", "C2"));
+        data.set_active_cell(Address::new(2, 1));
+        data.insert(Address::new(0, 1), "x");
+
+        let list = list::Content::from(data.clone());
+        assert_eq!(list.column(0).map(<[_]>::len), Some(2));
+        assert_eq!(list.get(Address::new(0, 1)).unwrap().content, "x");
+        assert_eq!(list.get(Address::new(2, 0)).unwrap().content, "c");
+        assert_eq!(list.column_sizes(), [100.0, 40.0, 100.0]);
+        assert_eq!(list.row_sizes(), [20.0, 30.0]);
+        assert!(matches!(list.column_type(2), ColumnType::Integer));
+        assert_eq!(list.row_groups(), data.row_groups());
+        assert_eq!(list.spans(), data.spans());
+        assert_eq!(list.active_cell(), Address::new(2, 1));
+
+        let mut back = Content::from(list);
+        assert!(back == data);
+        assert_eq!(back.active_cell(), Address::new(2, 1));
+        assert_eq!(back.row_groups(), data.row_groups());
+        assert_eq!(back.spans(), data.spans());
+        assert!(matches!(back.column_type(2), ColumnType::Integer));
+
+        // the history came along too
+        back.undo();
+        assert_eq!(texts(&back), [["a", "b", "c"], ["d", "e", "f"]]);
+    }
+
+    #[test]
+    fn inserting_a_row_moves_the_selection_down() {
+        let mut data = content(&[&["a", "b"], &["c", "d"], &["e", "f"]]);
+        data.select_range(range("A2", "B3"));
+        data.set_active_cell(Address::new(1, 2));
+
+        // a row above the selection pushes it down
+        data.insert_row(1);
+        assert_eq!(texts(&data), [["a", "b"], ["", ""], ["c", "d"], ["e", "f"]]);
+        assert_eq!(*data.selection(), range("A3", "B4"));
+        assert_eq!(data.active_cell(), Address::new(1, 3));
+
+        // a row below it, or appended, doesn't
+        data.insert_row(4);
+        data.insert_row(99);
+        assert_eq!(data.row_count(), 6);
+        assert_eq!(*data.selection(), range("A3", "B4"));
+        assert_eq!(data.row_sizes().len(), 6);
+    }
+
+    #[test]
+    fn removing_a_column_moves_the_selection_left() {
+        let mut data = content(&[&["a", "b", "c"], &["d", "e", "f"]]);
+        data.select_range(range("B1", "C2"));
+
+        data.remove_column(0);
+        assert_eq!(texts(&data), [["b", "c"], ["e", "f"]]);
+        assert_eq!(*data.selection(), range("A1", "B2"));
+
+        // the selection stays within the table as its columns go
+        data.remove_column(1);
+        assert_eq!(*data.selection(), range("A1", "A2"));
+        data.remove_column(0);
+        assert_eq!((data.row_count(), data.column_count()), (0, 0));
+        assert_eq!(*data.selection(), range("A1", "A2"));
+    }
+
+    #[test]
+    fn undo_restores_removed_rows_and_columns() {
+        let mut data = content(&[&["a", "b"], &["c", "d"]]).with_history(10);
+        data.merge_cells(range("A2", "B2"));
+        data.set_row_height(1, 30.0);
+
+        data.remove_row(1);
+        assert!(data.spans().is_empty());
+        data.remove_column(0);
+        data.remove_column(0);
+        assert_eq!(data.row_count(), 0);
+
+        data.undo();
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b"]]);
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"]]);
+        assert_eq!(data.row_sizes(), [20.0, 30.0]);
+        assert_eq!(data.spans(), [CellSpan::new(Address::new(0, 1), 2, 1)]);
+        assert!(!data.can_undo());
+
+        data.redo();
+        assert_eq!(texts(&data), [["a", "b"]]);
+        assert!(data.can_redo());
+    }
+
+    #[test]
+    fn pastes_and_moves_are_undone_as_single_steps() {
+        let mut data = content(&[&["a", "b"], &["c", "d"]]).with_history(10);
+
+        data.perform(Action::Edit(Edit::Paste {
+            anchor: Address::new(0, 0),
+            rows: vec![
+                vec!["1".to_string(), "2".to_string()],
+                vec!["3".to_string(), "4".to_string()],
+            ],
+        }));
+        data.move_row(0, 1);
+        assert_eq!(texts(&data), [["3", "4"], ["1", "2"]]);
+
+        data.undo();
+        assert_eq!(texts(&data), [["1", "2"], ["3", "4"]]);
+        data.undo();
+        assert_eq!(texts(&data), [["a", "b"], ["c", "d"]]);
+    }
+
+    #[test]
+    fn rejected_items_and_text_are_not_written() {
+        let mut data = content(&[&["a", "1"]]);
+        data.set_column_type(1, ColumnType::Integer);
+        data.set_validator(|cell: Address, item: &Cell| {
+            if item.content == "forbidden" {
+                Err(format!("{cell} can't be forbidden"))
+            } else {
+                Ok(())
+            }
+        });
+
+        data.insert(Address::new(0, 0), "forbidden");
+        data.write_cell_text(Address::new(1, 0), "one");
+        data.write_cell_text(Address::new(1, 0), "2");
+
+        assert_eq!(texts(&data), [["a", "2"]]);
+        let rejections = data.take_rejections();
+        assert_eq!(rejections.len(), 2);
+        assert_eq!(
+            rejections[0],
+            (Address::new(0, 0), "A1 can't be forbidden".into())
+        );
+        assert_eq!(rejections[1].0, Address::new(1, 0));
+        assert!(data.take_rejections().is_empty());
+    }
+
+    #[test]
+    fn sorting_moves_the_rows_with_their_groups_and_spans() {
+        let mut data =
+            content(&[&["3", "c", ""], &["1", "a", ""], &["2", "b", ""]]).with_history(10);
+        data.set_column_type(0, ColumnType::Integer);
+        data.set_row_height(0, 30.0);
+        data.merge_cells(range("B1", "C1"));
+        data.select_cell(Address::new(1, 0));
+
+        data.sort_rows(0, true);
+        assert_eq!(
+            texts(&data),
+            [["1", "a", ""], ["2", "b", ""], ["3", "c", ""]]
+        );
+        assert_eq!(data.row_sizes(), [20.0, 20.0, 30.0]);
+        assert_eq!(data.spans(), [CellSpan::new(Address::new(1, 2), 2, 1)]);
+        assert_eq!(*data.selection(), Address::new(1, 2).as_range());
+
+        data.undo();
+        assert_eq!(
+            texts(&data),
+            [["3", "c", ""], ["1", "a", ""], ["2", "b", ""]]
+        );
+        assert_eq!(data.spans(), [CellSpan::new(Address::new(1, 0), 2, 1)]);
+
+        // groups follow the rows they hold
+        data.set_row_group(1..3, 1);
+        data.insert_row(0);
+        assert_eq!(data.row_groups()[0].rows, 2..4);
+        data.toggle_row_group(2);
+        assert!(data.is_row_hidden(3));
+    }
+}
@@ -0,0 +1,342 @@
+//! Import and export the contents of a [`Content`] as CSV.
+use std::fmt;
+use std::io;
+
+use super::list::{Cell, Content};
+use super::{Reference, ReferenceRange, Tabular};
+
+/// The height of an imported row, per line of its tallest cell.
+const LINE_HEIGHT: f32 = 20.0;
+/// The narrowest and widest an imported column is made.
+const COLUMN_WIDTH_LIMITS: (f32, f32) = (40.0, 400.0);
+
+/// The ways reading or writing CSV can fail.
+#[derive(Debug)]
+pub enum CsvError {
+    /// Reading from or writing to the underlying stream failed.
+    Io(io::Error),
+    /// A record has a different number of fields than the first one.
+    Ragged {
+        /// The 0-based index of the record.
+        record: usize,
+        /// The number of fields of the first record.
+        expected: usize,
+        /// The number of fields of this record.
+        found: usize,
+    },
+    /// A quoted field is followed by something other than a delimiter or
+    /// the end of its record.
+    TrailingQuote {
+        /// The 0-based index of the record.
+        record: usize,
+    },
+    /// A quoted field is never closed.
+    UnterminatedQuote {
+        /// The 0-based index of the record.
+        record: usize,
+    },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "{}", error),
+            Self::Ragged {
+                record,
+                expected,
+                found,
+            } => write!(
+                f,
+                "record {}: expected {} fields, found {}",
+                record + 1,
+                expected,
+                found
+            ),
+            Self::TrailingQuote { record } => {
+                write!(f, "record {} has text after a closing quote", record + 1)
+            }
+            Self::UnterminatedQuote { record } => {
+                write!(f, "record {} has an unclosed quote", record + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl<K: Reference, R: ReferenceRange<K>> Content<Cell, K, R> {
+    /// Read a table from CSV text, with fields separated by the given
+    /// delimiter.
+    ///
+    /// Fields may be quoted to hold delimiters, quotes (doubled) and line
+    /// breaks. Records end with `\n` or `\r\n`, and all of them must have as
+    /// many fields as the first. Columns are made wide enough for their
+    /// longest line, within reason, and rows tall enough for their lines.
+    pub fn from_csv(reader: impl io::Read, delimiter: char) -> Result<Self, CsvError> {
+        let mut reader = reader;
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+
+        let records = parse(&text, delimiter)?;
+        let columns = records.first().map_or(0, Vec::len);
+
+        let mut content = Self::with_range(R::new(
+            K::new(0, 0),
+            Some(K::new(
+                columns.saturating_sub(1),
+                records.len().saturating_sub(1),
+            )),
+        ));
+
+        let mut widths = vec![0usize; columns.max(1)];
+        let mut heights = vec![1usize; records.len().max(1)];
+
+        for (y, record) in records.into_iter().enumerate() {
+            for (x, field) in record.into_iter().enumerate() {
+                let longest = field.lines().map(|line| line.chars().count()).max();
+                widths[x] = widths[x].max(longest.unwrap_or(0));
+                heights[y] = heights[y].max(field.lines().count());
+                content.insert(K::new(x, y), Cell::from(field));
+            }
+        }
+
+        // roughly 8 pixels per character of the default font
+        let (min, max) = COLUMN_WIDTH_LIMITS;
+        Ok(content
+            .with_column_widths(
+                widths
+                    .into_iter()
+                    .map(|chars| (chars as f32 * 8.0 + 16.0).clamp(min, max))
+                    .collect(),
+            )
+            .with_row_heights(
+                heights
+                    .into_iter()
+                    .map(|lines| lines as f32 * LINE_HEIGHT)
+                    .collect(),
+            ))
+    }
+
+    /// Write the table, or only the given range of it, as CSV with fields
+    /// separated by the given delimiter.
+    ///
+    /// Fields holding the delimiter, quotes or line breaks are quoted, and
+    /// every record ends with `\n`. Parts of the range outside of the table
+    /// are ignored.
+    pub fn to_csv(
+        &self,
+        writer: impl io::Write,
+        delimiter: char,
+        range: Option<&R>,
+    ) -> Result<(), CsvError> {
+        let mut writer = writer;
+
+        let (start, end) = match range {
+            Some(range) => {
+                let range = range.normalize();
+                let start = range.start();
+                let end = range.end().unwrap_or(start);
+                ((start.x(), start.y()), (end.x(), end.y()))
+            }
+            None => ((0, 0), (usize::MAX, usize::MAX)),
+        };
+        let (Some(last_column), Some(last_row)) = (
+            self.column_count().checked_sub(1),
+            self.row_count().checked_sub(1),
+        ) else {
+            return Ok(());
+        };
+
+        for y in start.1..=end.1.min(last_row) {
+            let record = (start.0..=end.0.min(last_column))
+                .map(|x| {
+                    let text = self
+                        .get(K::new(x, y))
+                        .map_or("", |cell| cell.content.as_str());
+                    quote(text, delimiter)
+                })
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string());
+
+            writeln!(writer, "{}", record)?;
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// Split CSV text into records of fields
+fn parse(text: &str, delimiter: char) -> Result<Vec<Vec<String>>, CsvError> {
+    let mut records: Vec<Vec<String>> = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    // whether anything of the current record has been read yet
+    let mut started = false;
+    let mut chars = text.chars().peekable();
+
+    let finish = |record: Vec<String>, records: &mut Vec<Vec<String>>| {
+        if let Some(expected) = records.first().map(Vec::len) {
+            if record.len() != expected {
+                return Err(CsvError::Ragged {
+                    record: records.len(),
+                    expected,
+                    found: record.len(),
+                });
+            }
+        }
+        records.push(record);
+        Ok(())
+    };
+
+    while let Some(c) = chars.next() {
+        started = true;
+
+        if c == '"' && field.is_empty() {
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => {
+                        return Err(CsvError::UnterminatedQuote {
+                            record: records.len(),
+                        })
+                    }
+                }
+            }
+
+            match chars.peek() {
+                Some(&c) if c != delimiter && c != '\n' && c != '\r' => {
+                    return Err(CsvError::TrailingQuote {
+                        record: records.len(),
+                    })
+                }
+                _ => {}
+            }
+        } else if c == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if c == '\n' || (c == '\r' && chars.peek() == Some(&'\n')) {
+            if c == '\r' {
+                chars.next();
+            }
+            record.push(std::mem::take(&mut field));
+            finish(std::mem::take(&mut record), &mut records)?;
+            started = false;
+        } else {
+            field.push(c);
+        }
+    }
+
+    // the last record may not end with a line break
+    if started {
+        record.push(field);
+        finish(record, &mut records)?;
+    }
+
+    Ok(records)
+}
+
+// Quote a field if it holds the delimiter, quotes or line breaks
+fn quote(text: &str, delimiter: char) -> String {
+    if text.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Address, Range};
+
+    const FIXTURE: &str = "name,note,city\n\
+        \"Smith, J.\",\"said \"\"hi\"\"\",Zürich\n\
+        plain,\"two\nlines\",東京\n\
+        ,,naïve 🙂\n";
+
+    fn export(content: &Content, range: Option<&Range>) -> String {
+        let mut bytes = Vec::new();
+        content.to_csv(&mut bytes, ',', range).unwrap();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn fixture_round_trips_byte_for_byte() {
+        let content = Content::<Cell>::from_csv(FIXTURE.as_bytes(), ',').unwrap();
+
+        assert_eq!(content.column_count(), 3);
+        assert_eq!(content.row_count(), 4);
+        let cell = |x, y| content.get(Address::new(x, y)).unwrap().content.as_str();
+        assert_eq!(cell(0, 1), "Smith, J.");
+        assert_eq!(cell(1, 1), "said \"hi\"");
+        assert_eq!(cell(1, 2), "two\nlines");
+        assert_eq!(cell(2, 2), "東京");
+        assert_eq!(cell(2, 3), "naïve 🙂");
+
+        assert_eq!(cell(0, 3), "");
+        assert_eq!(export(&content, None), FIXTURE);
+    }
+
+    #[test]
+    fn crlf_records_and_a_missing_final_line_break_are_read() {
+        let content = Content::<Cell>::from_csv("a;b\r\n\"c\r\nd\";e".as_bytes(), ';').unwrap();
+
+        assert_eq!(export(&content, None), "a,b\n\"c\r\nd\",e\n");
+    }
+
+    #[test]
+    fn export_only_writes_the_range() {
+        let content = Content::<Cell>::from_csv(FIXTURE.as_bytes(), ',').unwrap();
+        let range = Range::parse("C2:B3").unwrap();
+
+        assert_eq!(
+            export(&content, Some(&range)),
+            "\"said \"\"hi\"\"\",Zürich\n\"two\nlines\",東京\n"
+        );
+        // the parts outside of the table are ignored
+        let range = Range::parse("C4:E9").unwrap();
+        assert_eq!(export(&content, Some(&range)), "naïve 🙂\n");
+    }
+
+    #[test]
+    fn ragged_records_are_an_error() {
+        let error = Content::<Cell>::from_csv("a,b\nc,d\ne\n".as_bytes(), ',').unwrap_err();
+
+        assert!(matches!(
+            error,
+            CsvError::Ragged {
+                record: 2,
+                expected: 2,
+                found: 1
+            }
+        ));
+        assert_eq!(error.to_string(), "record 3: expected 2 fields, found 1");
+    }
+
+    #[test]
+    fn malformed_quotes_are_an_error() {
+        let error = Content::<Cell>::from_csv("a,\"b\"c\n".as_bytes(), ',').unwrap_err();
+        assert!(matches!(error, CsvError::TrailingQuote { record: 0 }));
+
+        let error = Content::<Cell>::from_csv("a,b\n\"c,d\n".as_bytes(), ',').unwrap_err();
+        assert!(matches!(error, CsvError::UnterminatedQuote { record: 1 }));
+    }
+}
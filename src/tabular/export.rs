@@ -0,0 +1,247 @@
+//! Export the contents of a table to other formats.
+use std::fmt::Write;
+
+use iced::{Background, Border, Color};
+
+use super::list::Cell;
+use super::{Reference, ReferenceRange, Style, Tabular};
+
+/// Export the given range of a table as a standalone HTML `<table>`.
+///
+/// The column widths and row heights are taken from the table's sizes, the
+/// row and column lines and outer border from the given [`Style`], and the
+/// borders and background colors of individual cells from the [`Cell`]s
+/// themselves. The text of each cell is its [`Tabular::display`] value,
+/// falling back to its content.
+///
+/// Merged cells become a single `<td>` with a `colspan` and `rowspan`, cut
+/// down to the part of the span inside the range. Parts of the range outside
+/// of the table are ignored.
+pub fn to_html<K, R>(data: &impl Tabular<Cell, K, R>, range: &R, style: &Style) -> String
+where
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let range = range.normalize();
+    let start = range.start();
+    let end = range.end().unwrap_or(start);
+
+    let (left, top) = (start.x(), start.y());
+    let right = end.x().min(data.column_count().saturating_sub(1));
+    let bottom = end.y().min(data.row_count().saturating_sub(1));
+    let columns = left..=right;
+    let rows = top..=bottom;
+
+    let row_line = css_border(&Border {
+        radius: 0.0.into(),
+        ..style.row_lines
+    });
+    let column_line = css_border(&Border {
+        radius: 0.0.into(),
+        ..style.column_lines
+    });
+    let gridlines = format!(
+        "border-top: {row_line}; border-bottom: {row_line}; \
+         border-left: {column_line}; border-right: {column_line}"
+    );
+
+    let mut html = String::new();
+
+    let _ = write!(
+        html,
+        "<table style=\"border-collapse: collapse; table-layout: fixed; border: {}\">",
+        css_border(&style.border),
+    );
+
+    html.push_str("<colgroup>");
+    for x in columns.clone() {
+        let width = data.column_sizes().get(x).copied().unwrap_or_default();
+        let _ = write!(html, "<col style=\"width: {}px\">", width);
+    }
+    html.push_str("</colgroup>");
+
+    for y in rows {
+        let height = data.row_sizes().get(y).copied().unwrap_or_default();
+        let _ = write!(html, "<tr style=\"height: {}px\">", height);
+
+        for x in columns.clone() {
+            // A merged span is written once, at its first cell inside the range,
+            // with the item of its anchor
+            let mut cell_ref = K::new(x, y);
+            let mut spans = String::new();
+            if let Some(span) = data.span_at(x, y) {
+                let (x1, y1) = (span.anchor.x().max(left), span.anchor.y().max(top));
+                if (x, y) != (x1, y1) {
+                    continue;
+                }
+                let columns = span.end().x().min(right) + 1 - x1;
+                let rows = span.end().y().min(bottom) + 1 - y1;
+                if columns > 1 {
+                    let _ = write!(spans, " colspan=\"{}\"", columns);
+                }
+                if rows > 1 {
+                    let _ = write!(spans, " rowspan=\"{}\"", rows);
+                }
+                cell_ref = span.anchor;
+            }
+            let cell = data.get(cell_ref);
+
+            let mut css = cell
+                .and_then(|cell| cell.border.as_ref())
+                .map(|border| format!("border: {}", css_border(border)))
+                .unwrap_or_else(|| gridlines.clone());
+            if let Some(Background::Color(color)) = cell.and_then(|cell| cell.background) {
+                let _ = write!(css, "; background-color: {}", css_color(color));
+            }
+
+            let text = data
+                .display(cell_ref)
+                .map(|text| text.into_owned())
+                .or_else(|| cell.map(|cell| cell.content.clone()))
+                .unwrap_or_default();
+
+            let _ = write!(
+                html,
+                "<td{} style=\"{}\">{}</td>",
+                spans,
+                css,
+                escape(&text)
+            );
+        }
+
+        html.push_str("</tr>");
+    }
+
+    html.push_str("</table>");
+    html
+}
+
+// Formats a border as a CSS `border` shorthand value
+fn css_border(border: &Border) -> String {
+    let radius: [f32; 4] = border.radius.into();
+    let mut css = format!("{}px solid {}", border.width, css_color(border.color));
+
+    if radius.iter().any(|r| *r > 0.0) {
+        let _ = write!(
+            css,
+            "; border-radius: {}px {}px {}px {}px",
+            radius[0], radius[1], radius[2], radius[3]
+        );
+    }
+
+    css
+}
+
+// Formats a color as a CSS `rgba()` value
+fn css_color(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("rgba({}, {}, {}, {})", r, g, b, color.a)
+}
+
+// Escapes text for use in HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::list::Content;
+    use crate::tabular::theme;
+    use crate::tabular::Status;
+    use crate::{Address, Range};
+
+    // Compares the output with its fixture in `tests/fixtures/export`, or
+    // rewrites the fixture when `UPDATE_FIXTURES` is set
+    fn assert_fixture(name: &str, html: &str) {
+        let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests/fixtures/export", name]
+            .iter()
+            .collect();
+
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(&path, format!("{html}\n")).unwrap();
+        }
+        let expected = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(html, expected.trim_end(), "{name} differs from its fixture");
+    }
+
+    fn style() -> Style {
+        theme::default(&iced::Theme::Light, Status::Unfocused)
+    }
+
+    fn content(range: &str) -> Content {
+        let mut data: Content = Content::with_range(Range::parse(range).unwrap());
+        data.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        data
+    }
+
+    #[test]
+    fn escapes_the_text_of_the_cells() {
+        let mut data = content("A1:B2");
+        data.insert(Address::new(0, 0), "<b>Tom & Jerry</b>");
+        data.insert(Address::new(1, 0), "\"quoted\" 'text'");
+        data.insert(Address::new(0, 1), "a > b");
+        data.insert(Address::new(1, 1), "plain");
+
+        let html = to_html(&data, &Range::parse("A1:B2").unwrap(), &style());
+        assert_fixture("escaping.html", &html);
+    }
+
+    #[test]
+    fn merged_cells_span_columns_and_rows() {
+        let mut data = content("A1:C3");
+        for y in 0..3 {
+            for x in 0..3 {
+                let cell = Address::new(x, y);
+                data.insert(cell, cell.to_string().as_str());
+            }
+        }
+        data.merge_cells(Range::parse("A1:B2").unwrap());
+        data.merge_cells(Range::parse("C2:C3").unwrap());
+
+        let html = to_html(&data, &Range::parse("A1:C3").unwrap(), &style());
+        assert_fixture("merged.html", &html);
+
+        // Only the part of a span inside the range is kept
+        let html = to_html(&data, &Range::parse("B2:C2").unwrap(), &style());
+        assert_fixture("merged_cut.html", &html);
+    }
+
+    #[test]
+    fn empty_and_styled_cells() {
+        let mut data = content("A1:C2");
+        data.insert(Address::new(0, 0), "first");
+        data.insert(
+            Address::new(1, 0),
+            Cell {
+                content: "boxed".to_string(),
+                border: Some(Border {
+                    color: Color::from_rgb8(255, 0, 0),
+                    width: 2.0,
+                    radius: 4.0.into(),
+                }),
+                background: Some(Color::from_rgb8(0, 128, 255).into()),
+            },
+        );
+        data.set_column_width(2, 40.0);
+
+        // The range goes past the table, C1 is never set and row 2 is empty
+        let html = to_html(&data, &Range::parse("A1:D5").unwrap(), &style());
+        assert_fixture("empty.html", &html);
+    }
+}
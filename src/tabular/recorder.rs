@@ -0,0 +1,307 @@
+//! Record the [`Action`]s performed on a table and replay them later, like
+//! a macro.
+use std::time::{Duration, Instant};
+
+use super::{Action, Axis, Edit, Reference, ReferenceRange, Tabular};
+use crate::{Address, Range};
+
+/// Records the [`Action`]s fed to it while recording.
+///
+/// The app feeds every action to [`Recorder::record`] before performing it,
+/// and can later [`replay`] the recorded actions on some table.
+#[derive(Debug, Clone)]
+pub struct Recorder<K: Reference = Address, R: ReferenceRange<K> = Range> {
+    started: Option<Instant>,
+    selection: bool,
+    entries: Vec<Entry<K, R>>,
+}
+
+/// A recorded [`Action`].
+#[derive(Debug, Clone)]
+pub struct Entry<K: Reference = Address, R: ReferenceRange<K> = Range> {
+    /// The time the action was recorded at, since the recording started.
+    pub at: Duration,
+    /// The recorded action.
+    pub action: Action<K, R>,
+}
+
+impl<K: Reference, R: ReferenceRange<K>> Recorder<K, R> {
+    /// Creates a new, idle [`Recorder`].
+    pub fn new() -> Self {
+        Self {
+            started: None,
+            selection: true,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Sets whether actions that only change the selection are recorded.
+    pub fn record_selection(mut self, record: bool) -> Self {
+        self.selection = record;
+        self
+    }
+
+    /// Starts a new recording, discarding the previous one.
+    pub fn start(&mut self) {
+        self.entries.clear();
+        self.started = Some(Instant::now());
+    }
+
+    /// Stops recording.
+    pub fn stop(&mut self) {
+        self.started = None;
+    }
+
+    /// Returns whether the [`Recorder`] is recording.
+    pub fn is_recording(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Records the given action, if recording.
+    pub fn record(&mut self, action: &Action<K, R>) {
+        let Some(started) = self.started else {
+            return;
+        };
+
+        if !self.selection && action.is_selection() {
+            return;
+        }
+
+        self.entries.push(Entry {
+            at: started.elapsed(),
+            action: action.clone(),
+        });
+    }
+
+    /// The recorded entries.
+    pub fn entries(&self) -> &[Entry<K, R>] {
+        &self.entries
+    }
+
+    /// The recorded actions, in order.
+    pub fn actions(&self) -> Vec<Action<K, R>> {
+        self.entries
+            .iter()
+            .map(|entry| entry.action.clone())
+            .collect()
+    }
+}
+
+impl<K: Reference, R: ReferenceRange<K>> Default for Recorder<K, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The outcome of a [`replay`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replay {
+    /// The number of actions that were performed.
+    pub performed: usize,
+    /// The actions that were skipped, by index, with the reason why.
+    pub skipped: Vec<(usize, String)>,
+}
+
+/// Performs the given actions on the table, in order.
+///
+/// If `remap` is given, every reference in the actions is translated through
+/// it first, e.g. to anchor the replay at a different cell. The indices of
+/// resized dividers are translated as the references of their first cell.
+///
+/// Actions that no longer apply to the table, such as a resize of a column
+/// that doesn't exist, are skipped and reported. Rows and columns inserted
+/// past the end of the table are appended, as [`Action::InsertRow`] does.
+pub fn replay<T, K, R>(
+    data: &mut impl Tabular<T, K, R>,
+    actions: &[Action<K, R>],
+    remap: Option<&dyn Fn(K) -> K>,
+) -> Replay
+where
+    T: Default,
+    K: Reference,
+    R: ReferenceRange<K>,
+{
+    let mut report = Replay::default();
+    let remap = |cell: K| remap.map(|remap| remap(cell)).unwrap_or(cell);
+
+    for (i, action) in actions.iter().cloned().enumerate() {
+        let (columns, rows) = (data.column_count(), data.row_count());
+        let column = |index| in_bounds(Axis::Column, remap(K::new(index, 0)).x(), columns);
+        let row = |index| in_bounds(Axis::Row, remap(K::new(0, index)).y(), rows);
+
+        let action = match action {
+            Action::Select(range) => Ok(Action::Select(R::new(
+                remap(range.start()),
+                range.end().map(remap),
+            ))),
+            Action::Fill { source, target } => Ok(Action::Fill {
+                source: R::new(remap(source.start()), source.end().map(remap)),
+                target: R::new(remap(target.start()), target.end().map(remap)),
+            }),
+            Action::Edit(Edit::Paste { anchor, rows }) => Ok(Action::Edit(Edit::Paste {
+                anchor: remap(anchor),
+                rows,
+            })),
+            Action::ResizeDivider(axis, index, size) => match axis {
+                Axis::Column => column(index),
+                Axis::Row => row(index),
+            }
+            .map(|index| Action::ResizeDivider(axis, index, size)),
+            Action::Sort {
+                column: index,
+                ascending,
+            } => column(index).map(|column| Action::Sort { column, ascending }),
+            Action::InsertRow(index) => Ok(Action::InsertRow(remap(K::new(0, index)).y())),
+            Action::InsertColumn(index) => Ok(Action::InsertColumn(remap(K::new(index, 0)).x())),
+            Action::RemoveRow(index) => row(index).map(Action::RemoveRow),
+            Action::RemoveColumn(index) => column(index).map(Action::RemoveColumn),
+            Action::MoveColumn { from, to } => {
+                column(from).and_then(|from| column(to).map(|to| Action::MoveColumn { from, to }))
+            }
+            Action::MoveRow { from, to } => {
+                row(from).and_then(|from| row(to).map(|to| Action::MoveRow { from, to }))
+            }
+            Action::SetColumnWidth(index, width) => {
+                column(index).map(|column| Action::SetColumnWidth(column, width))
+            }
+            Action::SetRowHeight(index, height) => {
+                row(index).map(|row| Action::SetRowHeight(row, height))
+            }
+            action => Ok(action),
+        };
+
+        match action {
+            Ok(action) => {
+                data.perform(action);
+                report.performed += 1;
+            }
+            Err(reason) => report.skipped.push((i, reason)),
+        }
+    }
+
+    report
+}
+
+// The given index along an axis, if the table has that many rows or columns
+fn in_bounds(axis: Axis, index: usize, count: usize) -> Result<usize, String> {
+    if index < count {
+        Ok(index)
+    } else {
+        Err(format!(
+            "{:?} {} is out of bounds ({} in total)",
+            axis, index, count
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list::{Cell, Content};
+    use crate::Motion;
+
+    fn content(range: &str) -> Content {
+        let mut content: Content = Content::with_range(Range::parse(range).unwrap());
+        content.set_text_conversion(|cell| cell.content.clone(), |text| Cell::from(text));
+        content
+    }
+
+    fn paste(anchor: &str, text: &str) -> Action {
+        Action::Edit(Edit::Paste {
+            anchor: anchor.parse().unwrap(),
+            rows: vec![vec![text.to_string()]],
+        })
+    }
+
+    #[test]
+    fn records_only_while_recording_and_filters_selections() {
+        let mut recorder = Recorder::<Address, Range>::new().record_selection(false);
+        recorder.record(&Action::InsertRow(0));
+        assert!(recorder.entries().is_empty());
+
+        recorder.start();
+        assert!(recorder.is_recording());
+        recorder.record(&Action::SelectAll);
+        recorder.record(&Action::MoveSelection(Motion::Down));
+        recorder.record(&Action::InsertRow(1));
+        recorder.record(&Action::Select(Range::parse("A1").unwrap()));
+        recorder.record(&Action::RemoveColumn(2));
+        recorder.stop();
+        recorder.record(&Action::InsertRow(3));
+
+        assert!(matches!(
+            recorder.actions()[..],
+            [Action::InsertRow(1), Action::RemoveColumn(2)]
+        ));
+
+        // starting again discards the previous recording
+        recorder.start();
+        assert!(recorder.entries().is_empty());
+    }
+
+    #[test]
+    fn replay_remaps_the_actions() {
+        let mut data = content("A1:D4");
+        let actions = [
+            paste("A1", "a"),
+            Action::SetColumnWidth(0, 40.0),
+            Action::SetRowHeight(1, 30.0),
+        ];
+
+        // anchored one column right and two rows down
+        let remap = |cell: Address| Address::new(cell.x() + 1, cell.y() + 2);
+        let report = replay(&mut data, &actions, Some(&remap));
+
+        assert_eq!(report.performed, 3);
+        assert!(report.skipped.is_empty());
+        assert_eq!(data.get(Address::new(1, 2)).unwrap().content, "a");
+        assert_eq!(data.column_sizes()[1], 40.0);
+        assert_eq!(data.row_sizes()[3], 30.0);
+    }
+
+    #[test]
+    fn replay_skips_and_reports_what_no_longer_applies() {
+        let mut data = content("A1:B2");
+        let actions = [
+            Action::RemoveRow(5),
+            Action::SetColumnWidth(1, 40.0),
+            Action::MoveColumn { from: 0, to: 3 },
+            Action::ResizeDivider(Axis::Row, 2, 10.0),
+            Action::Sort {
+                column: 2,
+                ascending: true,
+            },
+        ];
+
+        let report = replay(&mut data, &actions, None);
+
+        assert_eq!(report.performed, 1);
+        assert_eq!(
+            report.skipped,
+            [
+                (0, "Row 5 is out of bounds (2 in total)".to_string()),
+                (2, "Column 3 is out of bounds (2 in total)".to_string()),
+                (3, "Row 2 is out of bounds (2 in total)".to_string()),
+                (4, "Column 2 is out of bounds (2 in total)".to_string()),
+            ]
+        );
+        assert_eq!(data.column_sizes()[1], 40.0);
+    }
+
+    #[test]
+    fn replay_appends_rows_and_columns_inserted_past_the_end() {
+        let mut data = content("A1:B2");
+        data.insert(Address::new(1, 1), Cell::from("b"));
+
+        let report = replay(
+            &mut data,
+            &[Action::InsertRow(9), Action::InsertColumn(9)],
+            None,
+        );
+
+        assert_eq!(report.performed, 2);
+        assert!(report.skipped.is_empty());
+        assert_eq!((data.column_count(), data.row_count()), (3, 3));
+        assert_eq!(data.get(Address::new(1, 1)).unwrap().content, "b");
+    }
+}
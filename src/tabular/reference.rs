@@ -1,6 +1,7 @@
 //! Reference cells and ranges in a table.
 use std::fmt;
 use std::hash::Hash;
+use std::str::FromStr;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -20,7 +21,7 @@ pub trait Reference:
     + Default
     + fmt::Debug
     + fmt::Display
-    + std::convert::From<(usize, usize)>
+    + std::convert::From<CellIndex>
 {
     fn new(x: usize, y: usize) -> Self;
     fn x(&self) -> usize;
@@ -28,15 +29,56 @@ pub trait Reference:
     fn as_tuple(&self) -> (usize, usize) {
         (self.x(), self.y())
     }
+    /// The column and row of the reference, named so they can't be swapped.
+    fn index(&self) -> CellIndex {
+        CellIndex::new(self.x(), self.y())
+    }
     fn as_range<R>(self) -> R
     where
         R: ReferenceRange<Self>,
     {
         R::new(self, None)
     }
+
+    /// The reference shifted by the given number of columns and rows, or
+    /// `None` if it would go past the first column or row, or overflow.
+    fn offset(&self, dx: isize, dy: isize) -> Option<Self> {
+        let x = self.x().checked_add_signed(dx)?;
+        let y = self.y().checked_add_signed(dy)?;
+        Some(Self::new(x, y))
+    }
+
+    /// The reference shifted by the given number of columns and rows,
+    /// stopping at the first column and row.
+    fn saturating_offset(&self, dx: isize, dy: isize) -> Self {
+        Self::new(
+            self.x().saturating_add_signed(dx),
+            self.y().saturating_add_signed(dy),
+        )
+    }
+}
+
+/// The column and row of a cell, named so they can't be mixed up as the two
+/// halves of a tuple can.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellIndex {
+    /// The column of the cell, from 0.
+    pub column: usize,
+    /// The row of the cell, from 0.
+    pub row: usize,
+}
+
+impl CellIndex {
+    /// Creates a [`CellIndex`] for the given column and row.
+    pub fn new(column: usize, row: usize) -> Self {
+        Self { column, row }
+    }
 }
 
 /// A trait for a range of references to cells in a table.
+// a range always holds at least one cell, so it has no `is_empty`
+#[allow(clippy::len_without_is_empty)]
 pub trait ReferenceRange<K: Reference>:
     Sized + Clone + Copy + Default + PartialEq + fmt::Debug + fmt::Display
 {
@@ -53,6 +95,66 @@ pub trait ReferenceRange<K: Reference>:
         (start.x() <= other.x() && other.x() <= end.x())
             && (start.y() <= other.y() && other.y() <= end.y())
     }
+
+    /// The number of columns the range spans.
+    fn width(&self) -> usize {
+        let (start, end) = corners(self);
+        end.x() - start.x() + 1
+    }
+
+    /// The number of rows the range spans.
+    fn height(&self) -> usize {
+        let (start, end) = corners(self);
+        end.y() - start.y() + 1
+    }
+
+    /// The number of cells in the range, which is never 0.
+    fn len(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    /// Whether the range is a single cell.
+    fn is_single_cell(&self) -> bool {
+        self.width() == 1 && self.height() == 1
+    }
+
+    /// Whether every cell of `other` is also in this range, edges included.
+    fn contains_range(&self, other: &Self) -> bool {
+        let (a, b) = (corners(self), corners(other));
+        (a.0.x() <= b.0.x() && b.1.x() <= a.1.x()) && (a.0.y() <= b.0.y() && b.1.y() <= a.1.y())
+    }
+
+    /// The cells covered by both ranges, or `None` if they don't overlap.
+    fn intersection(&self, other: &Self) -> Option<Self> {
+        let (a, b) = (corners(self), corners(other));
+        let start = K::new(a.0.x().max(b.0.x()), a.0.y().max(b.0.y()));
+        let end = K::new(a.1.x().min(b.1.x()), a.1.y().min(b.1.y()));
+
+        if start.x() <= end.x() && start.y() <= end.y() {
+            Some(Self::new(start, Some(end)))
+        } else {
+            None
+        }
+    }
+
+    /// The smallest range covering both ranges.
+    ///
+    /// This is the bounding box of the two, not their exact union: it also
+    /// covers the cells between them that lie in neither range.
+    fn bounding_union(&self, other: &Self) -> Self {
+        let (a, b) = (corners(self), corners(other));
+        let start = K::new(a.0.x().min(b.0.x()), a.0.y().min(b.0.y()));
+        let end = K::new(a.1.x().max(b.1.x()), a.1.y().max(b.1.y()));
+
+        Self::new(start, Some(end))
+    }
+}
+
+// The top-left and bottom-right cells of a range
+fn corners<K: Reference, R: ReferenceRange<K>>(range: &R) -> (K, K) {
+    let range = range.normalize();
+    let start = range.start();
+    (start, range.end().unwrap_or(start))
 }
 
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -106,9 +208,139 @@ impl Reference for Address {
     }
 }
 
-impl From<(usize, usize)> for Address {
-    fn from((x, y): (usize, usize)) -> Self {
-        Self { x, y }
+impl Address {
+    /// Parses an A1-style address such as `"B3"` or `"aa12"`.
+    ///
+    /// Column letters are case-insensitive and rows start at 1, so `"aa12"`
+    /// is the address with `x = 26` and `y = 11`. Surrounding whitespace is
+    /// ignored.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let split = s
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(s.len());
+        let (column, row) = s.split_at(split);
+
+        if column.is_empty() {
+            return Err(ParseError::MissingColumn);
+        }
+
+        Ok(Self {
+            x: parse_column(column)?,
+            y: parse_row(row)?,
+        })
+    }
+}
+
+/// The ways parsing a reference can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input is empty.
+    Empty,
+    /// The column letters are missing.
+    MissingColumn,
+    /// The row number is missing.
+    MissingRow,
+    /// The row is `0`; rows start at `1`.
+    ZeroRow,
+    /// The column number of an [`R1C1`] reference is `0`; columns start at
+    /// `1`.
+    ZeroColumn,
+    /// The column or row is too large to be represented.
+    Overflow,
+    /// The input contains an unexpected character.
+    InvalidCharacter(char),
+    /// The input is a whole-column (`"B:B"`) or whole-row (`"3:3"`) range,
+    /// which needs the dimensions of a table to be resolved.
+    ///
+    /// Use [`Range::parse_within`] to parse these.
+    Unbounded,
+    /// The range spans whole columns or rows of a table without any cells.
+    OutOfBounds,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "reference is empty"),
+            Self::MissingColumn => write!(f, "reference has no column letters"),
+            Self::MissingRow => write!(f, "reference has no row number"),
+            Self::ZeroRow => write!(f, "rows start at 1"),
+            Self::ZeroColumn => write!(f, "columns start at 1"),
+            Self::Overflow => write!(f, "reference is out of range"),
+            Self::InvalidCharacter(c) => write!(f, "unexpected character '{}' in reference", c),
+            Self::Unbounded => write!(f, "whole-column and whole-row ranges need table dimensions"),
+            Self::OutOfBounds => write!(f, "range is outside of the table"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses column letters (e.g. `"AA"`) into a 0-based column index.
+pub(crate) fn parse_column(s: &str) -> Result<usize, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::MissingColumn);
+    }
+
+    let mut column: Option<usize> = None;
+
+    for c in s.chars() {
+        if !c.is_ascii_alphabetic() {
+            return Err(ParseError::InvalidCharacter(c));
+        }
+        let digit = (c.to_ascii_uppercase() as u8 - b'A') as usize;
+
+        // Column names are bijective base 26: "A" is 0, "Z" is 25, "AA" is 26.
+        column = Some(match column {
+            None => digit,
+            Some(column) => column
+                .checked_add(1)
+                .and_then(|n| n.checked_mul(26))
+                .and_then(|n| n.checked_add(digit))
+                .ok_or(ParseError::Overflow)?,
+        });
+    }
+
+    column.ok_or(ParseError::MissingColumn)
+}
+
+/// Parses a 1-based row number (e.g. `"12"`) into a 0-based row index.
+pub(crate) fn parse_row(s: &str) -> Result<usize, ParseError> {
+    if s.is_empty() {
+        return Err(ParseError::MissingRow);
+    }
+
+    if let Some(c) = s.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(ParseError::InvalidCharacter(c));
+    }
+
+    match s.parse::<usize>() {
+        Ok(0) => Err(ParseError::ZeroRow),
+        Ok(row) => Ok(row - 1),
+        Err(_) => Err(ParseError::Overflow),
+    }
+}
+
+impl FromStr for Address {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<CellIndex> for Address {
+    fn from(index: CellIndex) -> Self {
+        Self {
+            x: index.column,
+            y: index.row,
+        }
     }
 }
 
@@ -118,6 +350,198 @@ impl std::fmt::Display for Address {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+/// An [`Address`] that records whether each axis is anchored with a `$`, as
+/// in `"$A1"`, `"A$1"` or `"$A$1"`.
+///
+/// Anchored (absolute) axes stay put when the reference is
+/// [shifted](AbsAddress::shift), while relative axes move by the offset.
+pub struct AbsAddress {
+    address: Address,
+    absolute_x: bool,
+    absolute_y: bool,
+}
+
+impl AbsAddress {
+    /// Creates a new [`AbsAddress`] with the given anchoring per axis.
+    pub fn new(address: Address, absolute_x: bool, absolute_y: bool) -> Self {
+        Self {
+            address,
+            absolute_x,
+            absolute_y,
+        }
+    }
+
+    /// Parses an A1-style address where the column and the row may each be
+    /// prefixed with a `$`, e.g. `"$A$1"`.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let (absolute_x, rest) = match s.strip_prefix('$') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let split = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let (column, row) = rest.split_at(split);
+        let (absolute_y, row) = match row.strip_prefix('$') {
+            Some(row) => (true, row),
+            None => (false, row),
+        };
+
+        if column.is_empty() {
+            return Err(ParseError::MissingColumn);
+        }
+
+        Ok(Self {
+            address: Address::new(parse_column(column)?, parse_row(row)?),
+            absolute_x,
+            absolute_y,
+        })
+    }
+
+    /// The plain [`Address`] of this reference.
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    /// Whether the column is anchored.
+    pub fn is_absolute_x(&self) -> bool {
+        self.absolute_x
+    }
+
+    /// Whether the row is anchored.
+    pub fn is_absolute_y(&self) -> bool {
+        self.absolute_y
+    }
+
+    /// Moves the relative axes of this reference by the given offset, keeping
+    /// the anchored ones in place.
+    ///
+    /// Returns `None` if a relative axis would move out of bounds.
+    pub fn shift(&self, dx: isize, dy: isize) -> Option<Self> {
+        let shift = |n: usize, d: isize, absolute: bool| {
+            if absolute {
+                Some(n)
+            } else {
+                n.checked_add_signed(d)
+            }
+        };
+
+        Some(Self {
+            address: Address::new(
+                shift(self.address.x, dx, self.absolute_x)?,
+                shift(self.address.y, dy, self.absolute_y)?,
+            ),
+            ..*self
+        })
+    }
+}
+
+impl From<Address> for AbsAddress {
+    fn from(address: Address) -> Self {
+        Self::new(address, false, false)
+    }
+}
+
+impl From<AbsAddress> for Address {
+    fn from(address: AbsAddress) -> Self {
+        address.address
+    }
+}
+
+impl FromStr for AbsAddress {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl std::fmt::Display for AbsAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let anchor = |absolute| if absolute { "$" } else { "" };
+
+        write!(
+            f,
+            "{}{}{}{}",
+            anchor(self.absolute_x),
+            to_column_name(self.address.x),
+            anchor(self.absolute_y),
+            self.address.y + 1
+        )
+    }
+}
+
+impl Range {
+    /// Parses an A1-style range such as `"A1:C10"`, or a single address such
+    /// as `"A1"`.
+    ///
+    /// Reversed ranges (`"C3:A1"`) are normalized and whitespace around
+    /// either address is ignored. Whole-column and whole-row ranges fail with
+    /// [`ParseError::Unbounded`]; use [`Range::parse_within`] for those.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        Self::parse_bounded(s, None)
+    }
+
+    /// Parses a range like [`Range::parse`], also accepting whole-column
+    /// (`"B:D"`) and whole-row (`"3:5"`) ranges, which are clamped to a table
+    /// of the given number of columns and rows.
+    pub fn parse_within(s: &str, columns: usize, rows: usize) -> Result<Self, ParseError> {
+        Self::parse_bounded(s, Some((columns, rows)))
+    }
+
+    fn parse_bounded(s: &str, bounds: Option<(usize, usize)>) -> Result<Self, ParseError> {
+        let Some((a, b)) = s.split_once(':') else {
+            return Address::parse(s).map(|start| Self { start, end: None });
+        };
+        let (a, b) = (a.trim(), b.trim());
+
+        let whole = |valid: fn(char) -> bool| {
+            !a.is_empty() && !b.is_empty() && a.chars().chain(b.chars()).all(valid)
+        };
+
+        // The last cell of the table, which whole columns and rows extend to.
+        let last = || {
+            let (columns, rows) = bounds.ok_or(ParseError::Unbounded)?;
+            columns
+                .checked_sub(1)
+                .zip(rows.checked_sub(1))
+                .ok_or(ParseError::OutOfBounds)
+        };
+
+        let (start, end) = if whole(|c| c.is_ascii_alphabetic()) {
+            let (x, y) = last()?;
+            let (a, b) = (parse_column(a)?.min(x), parse_column(b)?.min(x));
+
+            (Address::new(a, 0), Address::new(b, y))
+        } else if whole(|c| c.is_ascii_digit()) {
+            let (x, y) = last()?;
+            let (a, b) = (parse_row(a)?.min(y), parse_row(b)?.min(y));
+
+            (Address::new(0, a), Address::new(x, b))
+        } else {
+            (Address::parse(a)?, Address::parse(b)?)
+        };
+
+        Ok(Self::new(start, Some(end)).normalize())
+    }
+}
+
+impl FromStr for Range {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
 impl std::fmt::Display for Range {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}:{}", self.start, self.end.unwrap_or(self.start))
@@ -125,7 +549,7 @@ impl std::fmt::Display for Range {
 }
 
 impl ReferenceRange<Address> for Range {
-    type Iterator = std::vec::IntoIter<Address>;
+    type Iterator = Cells;
 
     fn new(start: Address, end: Option<Address>) -> Self {
         Self { start, end }
@@ -147,30 +571,508 @@ impl ReferenceRange<Address> for Range {
     }
 
     fn iter(&self) -> Self::Iterator {
-        let start = self.start;
-        let end = self.end.unwrap_or(start);
-        let x_range = if start.x <= end.x {
-            start.x..=end.x
-        } else {
-            end.x..=start.x
+        Cells::new(self)
+    }
+
+    fn start(&self) -> Address {
+        self.start
+    }
+
+    fn end(&self) -> Option<Address> {
+        self.end
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// An implementation of `Reference` in R1C1 notation, such as `"R3C2"` for
+/// the cell in the third row and second column.
+///
+/// It refers to the same cells as an [`Address`], and converts to and from
+/// one.
+pub struct R1C1 {
+    x: usize,
+    y: usize,
+}
+
+impl Reference for R1C1 {
+    fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    fn x(&self) -> usize {
+        self.x
+    }
+
+    fn y(&self) -> usize {
+        self.y
+    }
+}
+
+impl R1C1 {
+    /// Parses an R1C1-style reference such as `"R3C2"`.
+    ///
+    /// The letters are case-insensitive and rows and columns start at 1, so
+    /// `"r3c2"` is the reference with `x = 1` and `y = 2`. Surrounding
+    /// whitespace is ignored.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        let s = s.trim();
+
+        let Some(c) = s.chars().next() else {
+            return Err(ParseError::Empty);
         };
-        let y_range = if start.y <= end.y {
-            start.y..=end.y
-        } else {
-            end.y..=start.y
+        let Some(rest) = s.strip_prefix(['R', 'r']) else {
+            return Err(ParseError::InvalidCharacter(c));
         };
+        let Some((row, column)) = rest.split_once(['C', 'c']) else {
+            parse_row(rest)?;
+            return Err(ParseError::MissingColumn);
+        };
+
+        let column = parse_row(column).map_err(|error| match error {
+            ParseError::MissingRow => ParseError::MissingColumn,
+            ParseError::ZeroRow => ParseError::ZeroColumn,
+            error => error,
+        })?;
 
-        x_range
-            .flat_map(move |x| y_range.clone().map(move |y| Address { x, y }))
-            .collect::<Vec<_>>()
-            .into_iter()
+        Ok(Self {
+            x: column,
+            y: parse_row(row)?,
+        })
     }
+}
 
-    fn start(&self) -> Address {
+impl FromStr for R1C1 {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<CellIndex> for R1C1 {
+    fn from(index: CellIndex) -> Self {
+        Self {
+            x: index.column,
+            y: index.row,
+        }
+    }
+}
+
+impl From<Address> for R1C1 {
+    fn from(address: Address) -> Self {
+        Self {
+            x: address.x,
+            y: address.y,
+        }
+    }
+}
+
+impl From<R1C1> for Address {
+    fn from(reference: R1C1) -> Self {
+        Self {
+            x: reference.x,
+            y: reference.y,
+        }
+    }
+}
+
+impl std::fmt::Display for R1C1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "R{}C{}", self.y + 1, self.x + 1)
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+/// An implementation of `ReferenceRange` for a range of [`R1C1`] references,
+/// such as `"R1C1:R3C2"`.
+pub struct R1C1Range {
+    start: R1C1,
+    end: Option<R1C1>,
+}
+
+impl R1C1Range {
+    /// Parses an R1C1-style range such as `"R1C1:R10C3"`, or a single
+    /// reference such as `"R1C1"`.
+    ///
+    /// Reversed ranges are normalized and whitespace around either reference
+    /// is ignored.
+    pub fn parse(s: &str) -> Result<Self, ParseError> {
+        match s.split_once(':') {
+            Some((a, b)) => Ok(Self::new(R1C1::parse(a)?, Some(R1C1::parse(b)?)).normalize()),
+            None => R1C1::parse(s).map(|start| Self { start, end: None }),
+        }
+    }
+}
+
+impl FromStr for R1C1Range {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<Range> for R1C1Range {
+    fn from(range: Range) -> Self {
+        Self {
+            start: range.start.into(),
+            end: range.end.map(R1C1::from),
+        }
+    }
+}
+
+impl From<R1C1Range> for Range {
+    fn from(range: R1C1Range) -> Self {
+        Self {
+            start: range.start.into(),
+            end: range.end.map(Address::from),
+        }
+    }
+}
+
+impl std::fmt::Display for R1C1Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.start, self.end.unwrap_or(self.start))
+    }
+}
+
+impl ReferenceRange<R1C1> for R1C1Range {
+    type Iterator = std::iter::Map<Cells, fn(Address) -> R1C1>;
+
+    fn new(start: R1C1, end: Option<R1C1>) -> Self {
+        Self { start, end }
+    }
+
+    fn normalize(&self) -> Self {
+        Range::from(*self).normalize().into()
+    }
+
+    fn iter(&self) -> Self::Iterator {
+        Range::from(*self).iter().map(R1C1::from)
+    }
+
+    fn start(&self) -> R1C1 {
         self.start
     }
 
-    fn end(&self) -> Option<Address> {
+    fn end(&self) -> Option<R1C1> {
         self.end
     }
 }
+
+/// An iterator over the addresses of a [`Range`], from [`ReferenceRange::iter`].
+///
+/// The addresses come column by column, from the top-left cell to the
+/// bottom-right one of the normalized range, and are made as they're needed
+/// rather than collected up front.
+#[derive(Debug, Clone)]
+pub struct Cells {
+    start: Address,
+    height: usize,
+    // The index of the next address from the front, and of the one after the
+    // last address from the back
+    front: usize,
+    back: usize,
+}
+
+impl Cells {
+    fn new(range: &Range) -> Self {
+        Self {
+            start: range.normalize().start,
+            height: range.height(),
+            front: 0,
+            back: range.width().saturating_mul(range.height()),
+        }
+    }
+
+    fn address(&self, index: usize) -> Address {
+        Address {
+            x: self.start.x + index / self.height,
+            y: self.start.y + index % self.height,
+        }
+    }
+}
+
+impl Iterator for Cells {
+    type Item = Address;
+
+    fn next(&mut self) -> Option<Address> {
+        if self.front < self.back {
+            self.front += 1;
+            Some(self.address(self.front - 1))
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Address> {
+        self.front = self.front.saturating_add(n).min(self.back);
+        self.next()
+    }
+}
+
+impl DoubleEndedIterator for Cells {
+    fn next_back(&mut self) -> Option<Address> {
+        if self.front < self.back {
+            self.back -= 1;
+            Some(self.address(self.back))
+        } else {
+            None
+        }
+    }
+}
+
+impl ExactSizeIterator for Cells {}
+
+impl std::iter::FusedIterator for Cells {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn address_round_trips_through_display() {
+        let coordinates = (0..2_000)
+            .chain((1..usize::BITS).map(|shift| (1 << shift) - 1))
+            .chain([usize::MAX - 1]);
+
+        for n in coordinates {
+            for address in [Address::new(n, 0), Address::new(0, n), Address::new(n, n)] {
+                assert_eq!(address.to_string().parse(), Ok(address), "{address}");
+            }
+        }
+    }
+
+    #[test]
+    fn address_parses_case_insensitive_columns() {
+        assert_eq!("aa12".parse(), Ok(Address::new(26, 11)));
+        assert_eq!("AA12".parse(), Ok(Address::new(26, 11)));
+        assert_eq!("z1".parse(), Ok(Address::new(25, 0)));
+        assert_eq!(" B3 ".parse(), Ok(Address::new(1, 2)));
+    }
+
+    #[test]
+    fn address_rejects_invalid_input() {
+        assert_eq!("".parse::<Address>(), Err(ParseError::Empty));
+        assert_eq!("12".parse::<Address>(), Err(ParseError::MissingColumn));
+        assert_eq!("A".parse::<Address>(), Err(ParseError::MissingRow));
+        assert_eq!("A0".parse::<Address>(), Err(ParseError::ZeroRow));
+        assert_eq!(
+            "A1x".parse::<Address>(),
+            Err(ParseError::InvalidCharacter('x'))
+        );
+        assert_eq!(
+            "A-1".parse::<Address>(),
+            Err(ParseError::InvalidCharacter('-'))
+        );
+        assert_eq!(
+            "A99999999999999999999999".parse::<Address>(),
+            Err(ParseError::Overflow)
+        );
+        assert_eq!(
+            "ZZZZZZZZZZZZZZZZZZZZ1".parse::<Address>(),
+            Err(ParseError::Overflow)
+        );
+    }
+
+    #[test]
+    fn range_parses_addresses_and_normalizes() {
+        let range = |a: (usize, usize), b: (usize, usize)| {
+            Range::new(Address::new(a.0, a.1), Some(Address::new(b.0, b.1)))
+        };
+
+        assert_eq!("A1:C10".parse(), Ok(range((0, 0), (2, 9))));
+        assert_eq!("C3:A1".parse(), Ok(range((0, 0), (2, 2))));
+        assert_eq!("A3:C1".parse(), Ok(range((0, 0), (2, 2))));
+        assert_eq!(" A1 : B2 ".parse(), Ok(range((0, 0), (1, 1))));
+        assert_eq!("B2".parse(), Ok(Range::new(Address::new(1, 1), None)));
+    }
+
+    #[test]
+    fn range_resolves_whole_columns_and_rows_within_bounds() {
+        let range = |a: (usize, usize), b: (usize, usize)| {
+            Range::new(Address::new(a.0, a.1), Some(Address::new(b.0, b.1)))
+        };
+
+        assert_eq!("B:B".parse::<Range>(), Err(ParseError::Unbounded));
+        assert_eq!("3:3".parse::<Range>(), Err(ParseError::Unbounded));
+        assert_eq!(Range::parse_within("B:B", 4, 5), Ok(range((1, 0), (1, 4))));
+        assert_eq!(Range::parse_within("D:b", 4, 5), Ok(range((1, 0), (3, 4))));
+        assert_eq!(Range::parse_within("3:3", 4, 5), Ok(range((0, 2), (3, 2))));
+        assert_eq!(Range::parse_within("Z:Z", 4, 5), Ok(range((3, 0), (3, 4))));
+        assert_eq!(Range::parse_within("1:9", 4, 5), Ok(range((0, 0), (3, 4))));
+        assert_eq!(
+            Range::parse_within("B:B", 0, 0),
+            Err(ParseError::OutOfBounds)
+        );
+    }
+
+    #[test]
+    fn range_rejects_malformed_input() {
+        assert_eq!("".parse::<Range>(), Err(ParseError::Empty));
+        assert_eq!(":".parse::<Range>(), Err(ParseError::Empty));
+        assert_eq!("A1:".parse::<Range>(), Err(ParseError::Empty));
+        assert_eq!(":B2".parse::<Range>(), Err(ParseError::Empty));
+        assert_eq!(
+            "A1:B2:C3".parse::<Range>(),
+            Err(ParseError::InvalidCharacter(':'))
+        );
+        assert_eq!("A1:B0".parse::<Range>(), Err(ParseError::ZeroRow));
+        assert_eq!(
+            "A1-B2".parse::<Range>(),
+            Err(ParseError::InvalidCharacter('-'))
+        );
+        assert_eq!(
+            Range::parse_within("B:3", 4, 5),
+            Err(ParseError::MissingRow)
+        );
+    }
+
+    #[test]
+    fn abs_address_round_trips_anchors() {
+        for (s, absolute_x, absolute_y) in [
+            ("A1", false, false),
+            ("$A1", true, false),
+            ("A$1", false, true),
+            ("$A$1", true, true),
+        ] {
+            let address = AbsAddress::parse(s).unwrap();
+
+            assert_eq!(address.address(), Address::new(0, 0));
+            assert_eq!(address.is_absolute_x(), absolute_x, "{s}");
+            assert_eq!(address.is_absolute_y(), absolute_y, "{s}");
+            assert_eq!(address.to_string(), s);
+        }
+
+        assert_eq!(
+            "$aa$12".parse(),
+            Ok(AbsAddress::new(Address::new(26, 11), true, true))
+        );
+        assert_eq!("$".parse::<AbsAddress>(), Err(ParseError::MissingColumn));
+        assert_eq!("$$A1".parse::<AbsAddress>(), Err(ParseError::MissingColumn));
+        assert_eq!("A$".parse::<AbsAddress>(), Err(ParseError::MissingRow));
+    }
+
+    #[test]
+    fn abs_address_shift_keeps_anchored_axes() {
+        let shift = |s: &str, dx, dy| {
+            s.parse::<AbsAddress>()
+                .unwrap()
+                .shift(dx, dy)
+                .map(|address| address.to_string())
+        };
+
+        assert_eq!(shift("B2", 2, 3).as_deref(), Some("D5"));
+        assert_eq!(shift("$B2", 2, 3).as_deref(), Some("$B5"));
+        assert_eq!(shift("B$2", 2, 3).as_deref(), Some("D$2"));
+        assert_eq!(shift("$B$2", 2, 3).as_deref(), Some("$B$2"));
+        assert_eq!(shift("B2", -1, -1).as_deref(), Some("A1"));
+        assert_eq!(shift("B2", -2, 0), None);
+        assert_eq!(shift("$B2", -2, 0).as_deref(), Some("$B2"));
+        assert_eq!(shift("B$2", 0, -5).as_deref(), Some("B$2"));
+        assert_eq!(
+            Address::from(AbsAddress::parse("$C$4").unwrap()),
+            Address::new(2, 3)
+        );
+    }
+
+    fn range(a: &str, b: &str) -> Range {
+        Range::new(a.parse().unwrap(), Some(b.parse().unwrap()))
+    }
+
+    #[test]
+    fn range_intersection_and_bounding_union() {
+        let a = range("B2", "D4");
+
+        assert_eq!(a.intersection(&range("C3", "F9")), Some(range("C3", "D4")));
+        // reversed ranges are normalized first
+        assert_eq!(a.intersection(&range("E1", "A3")), Some(range("B2", "D3")));
+        // touching at a corner shares a cell
+        assert_eq!(a.intersection(&range("D4", "E5")), Some(range("D4", "D4")));
+        assert_eq!(a.intersection(&range("E5", "F6")), None);
+        assert_eq!(a.intersection(&range("B5", "D6")), None);
+        assert_eq!(a.intersection(&a), Some(a));
+
+        assert_eq!(a.bounding_union(&range("F1", "E2")), range("B1", "F4"));
+        // the bounding box, including the cells in neither range
+        assert_eq!(
+            range("A1", "A1").bounding_union(&range("C3", "C3")),
+            range("A1", "C3")
+        );
+        assert_eq!(a.bounding_union(&range("C3", "C3")), a);
+    }
+
+    #[test]
+    fn range_contains_ranges_with_touching_edges() {
+        let a = range("B2", "D4");
+
+        assert!(a.contains_range(&a));
+        assert!(a.contains_range(&range("D4", "B2")));
+        assert!(a.contains_range(&range("B2", "B4")));
+        assert!(a.contains_range(&range("C3", "C3")));
+        assert!(a.contains_range(&range("D4", "D4")));
+        assert!(!a.contains_range(&range("D4", "E4")));
+        assert!(!a.contains_range(&range("A1", "B2")));
+        assert!(!a.contains_range(&range("A1", "E5")));
+
+        let single = range("C3", "C3");
+        assert!(single.contains_range(&Range::new("C3".parse().unwrap(), None)));
+        assert!(!single.contains_range(&range("C3", "C4")));
+        assert!(!single.contains_range(&range("B3", "B3")));
+    }
+
+    #[test]
+    fn range_sizes_of_reversed_and_single_cell_ranges() {
+        let reversed = range("D2", "B5");
+        assert_eq!(
+            (reversed.width(), reversed.height(), reversed.len()),
+            (3, 4, 12)
+        );
+        assert!(!reversed.is_single_cell());
+
+        let row = range("C7", "A7");
+        assert_eq!((row.width(), row.height(), row.len()), (3, 1, 3));
+
+        for single in [range("C3", "C3"), Range::new("C3".parse().unwrap(), None)] {
+            assert_eq!((single.width(), single.height(), single.len()), (1, 1, 1));
+            assert!(single.is_single_cell());
+        }
+    }
+
+    #[test]
+    fn address_offsets_stop_at_the_origin_and_overflow() {
+        let b2 = Address::new(1, 1);
+
+        assert_eq!(b2.offset(-1, -1), Some(Address::new(0, 0)));
+        assert_eq!(b2.offset(-2, 0), None);
+        assert_eq!(b2.offset(0, isize::MIN), None);
+        assert_eq!(b2.saturating_offset(-5, 3), Address::new(0, 4));
+        assert_eq!(
+            b2.saturating_offset(isize::MIN, isize::MIN),
+            Address::new(0, 0)
+        );
+
+        // far past the 32k columns of an i16
+        let wide = Address::new(40_000, 70_000);
+        assert_eq!(wide.offset(1, -1), Some(Address::new(40_001, 69_999)));
+        assert_eq!(
+            wide.offset(isize::MAX, 0),
+            Some(Address::new(40_000 + isize::MAX as usize, 70_000))
+        );
+
+        let last = Address::new(usize::MAX, usize::MAX);
+        assert_eq!(last.offset(1, 0), None);
+        assert_eq!(
+            last.offset(0, -1),
+            Some(Address::new(usize::MAX, usize::MAX - 1))
+        );
+        assert_eq!(last.saturating_offset(1, 1), last);
+    }
+}
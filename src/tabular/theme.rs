@@ -5,34 +5,142 @@ use super::Status;
 /// The appearance of a [`Table`].
 #[derive(Debug, Clone, Copy)]
 pub struct Style {
-    /// The default [`Background`] of the grid.
+    /// The [`Background`] of the grid, drawn beneath its cells within the
+    /// radius of its [`Border`].
     pub background: Background,
     /// The [`Border`] of the grid.
     pub border: Border,
-    /// The gridlines border
-    pub gridlines: Border,
+    /// The border of the lines between rows.
+    pub row_lines: Border,
+    /// The border of the lines between columns.
+    pub column_lines: Border,
+    /// The [`LinePattern`] of the lines between rows and columns.
+    pub line_pattern: LinePattern,
     /// The color of the overlay when hovering a cell.
     pub hovered: Color,
-    /// The default [`Color`] of the value of the grid's cells.
+    /// The default [`Color`] of the value of the grid's cells, given to
+    /// cells that inherit it.
     pub value: Color,
     /// The style of some selection of the grid.
     pub selection: SelectionStyle,
+    /// The [`Color`] of the row group outline markers.
+    pub outline: Color,
+    /// The style of the rows pinned to the edges of the grid.
+    pub pinned: PinnedStyle,
+    /// The style of the column and row headers.
+    pub header: HeaderStyle,
+    /// The style of the scrollbars of a scrollable grid.
+    pub scrollbar: ScrollbarStyle,
+    /// The [`Color`] of the line previewing where a divider being dragged
+    /// will be dropped.
+    pub resize_preview: Color,
+}
+
+/// The pattern of the lines between the rows and columns of a [`Table`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LinePattern {
+    /// An unbroken line.
+    #[default]
+    Solid,
+    /// Dashes of the given length, with gaps of the given length between
+    /// them.
+    Dashed {
+        /// The length of each dash.
+        dash: f32,
+        /// The length of the gap after each dash.
+        gap: f32,
+    },
+    /// Square dots as wide as the line, one line width apart.
+    Dotted,
 }
 
 #[derive(Debug, Clone, Copy)]
 /// The appearance of a selection in a [`Table`].
 pub struct SelectionStyle {
-    /// The fill of the selection
+    /// The fill of the selected cells, given to them by the default
+    /// [`Catalog::cell_style`]
     pub fill: Color,
     /// The stroke of the selection
     pub stroke: Color,
     /// The width of the stroke
     pub stroke_width: f32,
+    /// The [`Color`] of the fill handle at the end corner of the selection
+    pub handle: Color,
+    /// The style of the active cell, which is left out of the fill of the
+    /// rest of the selection
+    pub active: ActiveCellStyle,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The appearance of the active cell of a selection in a [`Table`].
+pub struct ActiveCellStyle {
+    /// The fill of the active cell
+    pub fill: Color,
+    /// The stroke of the active cell
+    pub stroke: Color,
+    /// The width of the stroke
+    pub stroke_width: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The appearance of the rows pinned to an edge of a [`Table`].
+pub struct PinnedStyle {
+    /// The [`Background`] drawn behind the pinned rows
+    pub background: Background,
+    /// The separator between the pinned rows and the rest of the grid
+    pub separator: Border,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The appearance of the column and row headers of a [`Table`].
+pub struct HeaderStyle {
+    /// The [`Background`] of the headers
+    pub background: Background,
+    /// The [`Color`] of the header labels
+    pub text: Color,
+    /// The lines between the headers and around the header strip
+    pub separator: Border,
+}
+
+#[derive(Debug, Clone, Copy)]
+/// The appearance of the scrollbars of a scrollable [`Table`].
+pub struct ScrollbarStyle {
+    /// The [`Background`] of the scrollbar tracks
+    pub background: Background,
+    /// The [`Color`] of the scrollbar thumbs
+    pub thumb: Color,
+}
+
+/// The state of a cell of a [`Table`], for styling it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellStatus {
+    /// Whether the cell is within the selection.
+    pub selected: bool,
+    /// Whether the cell is the active cell of the selection.
+    pub active: bool,
+    /// Whether the cursor is over the cell.
+    pub hovered: bool,
+    /// The row of the cell.
+    pub row: usize,
+    /// The column of the cell.
+    pub column: usize,
+    /// The [`Status`] of the whole table.
+    pub status: Status,
+}
+
+/// The appearance of a cell of a [`Table`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CellStyle {
+    /// The [`Background`] of the cell, drawn beneath its content.
+    pub background: Option<Background>,
 }
 
 /// A styling function for a [`Table`].
 pub type StyleFn<'a, Theme> = Box<dyn Fn(&Theme, Status) -> Style + 'a>;
 
+/// A styling function for the cells of a [`Table`].
+pub type CellStyleFn<'a, Theme> = Box<dyn Fn(&Theme, CellStatus) -> CellStyle + 'a>;
+
 impl Catalog for Theme {
     type Class<'a> = StyleFn<'a, Self>;
 
@@ -56,38 +164,109 @@ pub fn default(theme: &iced::Theme, status: Status) -> Style {
             width: 1.0,
             color: palette.background.strong.color,
         },
-        gridlines: Border {
+        row_lines: Border {
             radius: 0.0.into(),
             width: 1.0,
             color: palette.background.strong.color,
         },
+        column_lines: Border {
+            radius: 0.0.into(),
+            width: 1.0,
+            color: palette.background.strong.color,
+        },
+        line_pattern: LinePattern::Solid,
         hovered: palette.primary.weak.color.scale_alpha(0.2),
-        value: palette.primary.base.text,
+        value: palette.background.base.text,
         selection: SelectionStyle {
             fill: palette.primary.weak.color.scale_alpha(0.20),
             stroke: palette.primary.weak.color.scale_alpha(0.5),
             stroke_width: 2.0,
+            handle: palette.primary.weak.color,
+            active: ActiveCellStyle {
+                fill: Color::TRANSPARENT,
+                stroke: palette.primary.weak.color,
+                stroke_width: 2.0,
+            },
+        },
+        outline: palette.background.strong.text,
+        pinned: PinnedStyle {
+            background: palette.background.base.color.into(),
+            separator: Border {
+                radius: 0.0.into(),
+                width: 2.0,
+                color: palette.background.strong.color,
+            },
+        },
+        header: HeaderStyle {
+            background: palette.background.weak.color.into(),
+            text: palette.background.weak.text,
+            separator: Border {
+                radius: 0.0.into(),
+                width: 1.0,
+                color: palette.background.strong.color,
+            },
+        },
+        scrollbar: ScrollbarStyle {
+            background: palette.background.weak.color.into(),
+            thumb: palette.background.strong.color,
         },
+        resize_preview: palette.primary.base.color,
     };
 
-    match status {
-        Status::Focused => Style {
-            selection: SelectionStyle {
-                fill: palette.primary.base.color.scale_alpha(0.20),
-                stroke: palette.primary.base.color.scale_alpha(1.0),
+    let focused = Style {
+        selection: SelectionStyle {
+            fill: palette.primary.base.color.scale_alpha(0.20),
+            stroke: palette.primary.base.color.scale_alpha(1.0),
+            stroke_width: 2.0,
+            handle: palette.primary.base.color,
+            active: ActiveCellStyle {
+                fill: Color::TRANSPARENT,
+                stroke: palette.primary.base.color,
                 stroke_width: 2.0,
             },
-            ..base
         },
-        Status::Unfocused => Style {
-            selection: SelectionStyle {
-                fill: base.selection.fill.scale_alpha(0.5),
-                stroke: base.selection.stroke.scale_alpha(0.5),
-                ..base.selection
+        ..base
+    };
+    let unfocused = Style {
+        selection: SelectionStyle {
+            fill: base.selection.fill.scale_alpha(0.5),
+            stroke: base.selection.stroke.scale_alpha(0.5),
+            handle: base.selection.handle.scale_alpha(0.5),
+            active: ActiveCellStyle {
+                stroke: base.selection.active.stroke.scale_alpha(0.5),
+                ..base.selection.active
             },
+            ..base.selection
+        },
+        ..base
+    };
+    // the border is strengthened under the cursor
+    let hovered = |style: Style| Style {
+        border: Border {
+            color: palette.background.base.text.scale_alpha(0.5),
+            ..style.border
+        },
+        ..style
+    };
+
+    match status {
+        Status::Focused => focused,
+        Status::Unfocused => unfocused,
+        Status::Hovered { focused: true } => hovered(focused),
+        Status::Hovered { focused: false } => hovered(unfocused),
+        Status::Disabled => Style {
+            value: base.value.scale_alpha(0.5),
             ..base
         },
-        Status::Disabled => Style { ..base },
+    }
+}
+
+/// The default style of a cell of a [`Table`] with the given [`Style`]: the
+/// selected cells other than the active cell are filled with the fill of the
+/// selection.
+pub fn default_cell(style: &Style, status: CellStatus) -> CellStyle {
+    CellStyle {
+        background: (status.selected && !status.active).then(|| style.selection.fill.into()),
     }
 }
 
@@ -101,4 +280,16 @@ pub trait Catalog: iced::widget::container::Catalog {
 
     /// The [`Style`] of a class with the given status.
     fn style(&self, class: &<Self as Catalog>::Class<'_>, status: Status) -> Style;
+
+    /// The [`CellStyle`] of a cell of a class with the given status.
+    ///
+    /// Each visible cell is filled with its background after the background
+    /// of its [`Cell`](crate::Cell), and before its content and
+    /// [`Cell::fill_border_quads`](crate::Cell::fill_border_quads). The
+    /// selection is drawn over all of them.
+    ///
+    /// Defaults to [`default_cell`] with the [`Style`] of the class.
+    fn cell_style(&self, class: &<Self as Catalog>::Class<'_>, status: CellStatus) -> CellStyle {
+        default_cell(&Catalog::style(self, class, status.status), status)
+    }
 }
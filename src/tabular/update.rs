@@ -1,10 +1,12 @@
 use iced::advanced::graphics::core::SmolStr;
 use iced::advanced::mouse;
 use iced::keyboard::{self, key};
-use iced::{Point, Rectangle};
+use iced::{Point, Rectangle, Vector};
 
-use super::reference::Reference;
-use super::{Interaction, State, Status};
+use super::{Axis, Interaction, State, Status};
+
+// How far a line of a mouse wheel scrolls, in pixels
+const SCROLL_LINE: f32 = 60.0;
 
 #[derive(Clone)]
 pub enum Update<Message: Clone> {
@@ -12,10 +14,19 @@ pub enum Update<Message: Clone> {
     RedrawRequested,
     /// Click
     Click(mouse::Click),
+    /// Right click at the given position relative to the table
+    SecondaryClick(Point),
     /// Drag
     Drag(Point),
+    /// The cursor moved without dragging, to the given position relative to
+    /// the table if it's over it
+    Hover(Option<Point>),
     /// Release the mouse
     Release,
+    /// Scroll by the given amount, in pixels
+    Scroll(Vector),
+    /// The keyboard modifiers changed
+    ModifiersChanged(keyboard::Modifiers),
     /// Call some binding
     Binding(Binding<Message>),
 }
@@ -32,8 +43,15 @@ pub enum Binding<Message: Clone> {
     MoveSelection(Motion),
     /// Expand the selection by the given [`Motion`].
     ExpandSelection(Motion),
-    /// Select the entire buffer.
+    /// Select the used range, then the entire buffer if pressed again.
     SelectAll,
+    /// Select the entire rows covered by the selection.
+    SelectRow,
+    /// Select the entire columns covered by the selection.
+    SelectColumn,
+    /// Resize the column or row of the active cell by the given number of
+    /// steps, which shrink it if negative.
+    Resize(Axis, f32),
     /// Break the current line.
     Enter,
     /// Delete the selection.
@@ -48,6 +66,8 @@ pub enum Binding<Message: Clone> {
     Custom(Message),
     /// Start editing the active cell
     StartEdit,
+    /// Start editing the active cell with the given text
+    Type(SmolStr),
 }
 
 /// A key press.
@@ -64,7 +84,7 @@ pub struct KeyPress {
 }
 
 impl<Message: Clone> Update<Message> {
-    pub(super) fn from_event<K: Reference>(
+    pub(super) fn from_event(
         event: &iced::Event,
         state: &State,
         bounds: Rectangle,
@@ -77,6 +97,9 @@ impl<Message: Clone> Update<Message> {
         let binding = |binding| Some(Update::Binding(binding));
 
         match &event {
+            iced::Event::Window(iced::window::Event::RedrawRequested(_)) => {
+                Some(Update::RedrawRequested)
+            }
             iced::Event::Window(_) => None,
             iced::Event::Mouse(event) => match event {
                 mouse::Event::ButtonPressed(mouse::Button::Left) => {
@@ -91,17 +114,41 @@ impl<Message: Clone> Update<Message> {
                         None
                     }
                 }
+                mouse::Event::ButtonPressed(mouse::Button::Right) => {
+                    cursor.position_in(bounds).map(Update::SecondaryClick)
+                }
                 mouse::Event::ButtonReleased(mouse::Button::Left) => Some(Update::Release),
+                mouse::Event::WheelScrolled { delta } => {
+                    cursor.position_in(bounds)?;
+                    let delta = match *delta {
+                        mouse::ScrollDelta::Lines { x, y } => Vector::new(x, y) * SCROLL_LINE,
+                        mouse::ScrollDelta::Pixels { x, y } => Vector::new(x, y),
+                    };
+
+                    // a vertical wheel scrolls horizontally with shift held
+                    if state.modifiers.shift() && delta.x == 0.0 {
+                        Some(Update::Scroll(Vector::new(delta.y, 0.0)))
+                    } else {
+                        Some(Update::Scroll(delta))
+                    }
+                }
                 mouse::Event::CursorMoved { .. } => match state.drag_click {
                     Some(mouse::click::Kind::Single) => {
                         match state.interaction {
                             // If we're resizing, return the update regardless of bounds
                             Interaction::ResizeDivider(_) => Some(Update::Drag(cursor.position()?)),
+                            Interaction::ReorderColumn { .. }
+                            | Interaction::ReorderRow { .. }
+                            | Interaction::Scroll { .. }
+                            | Interaction::FillDrag { .. } => {
+                                Some(Update::Drag(cursor.position()?))
+                            }
                             Interaction::None => Some(Update::Drag(cursor.position()?)),
                         }
                     }
-                    _ => None,
+                    _ => Some(Update::Hover(cursor.position_in(bounds))),
                 },
+                mouse::Event::CursorLeft => Some(Update::Hover(None)),
                 _ => None,
             },
             iced::Event::Keyboard(event) => match event {
@@ -131,39 +178,75 @@ impl<Message: Clone> Update<Message> {
                     }
                     .map(Self::Binding)
                 }
+                keyboard::Event::ModifiersChanged(modifiers) => {
+                    Some(Update::ModifiersChanged(*modifiers))
+                }
                 _ => None,
             },
-            iced::Event::Touch(event) => match event {
-                _ => None,
-            },
+            iced::Event::Touch(_) => None,
         }
     }
 }
 
 impl<Message: Clone> Binding<Message> {
+    /// Swaps the horizontal motions of the [`Binding`], for right-to-left
+    /// layouts.
+    pub fn mirror(self) -> Self {
+        match self {
+            Self::MoveSelection(motion) => Self::MoveSelection(motion.mirror()),
+            Self::ExpandSelection(motion) => Self::ExpandSelection(motion.mirror()),
+            Self::Resize(Axis::Column, steps) => Self::Resize(Axis::Column, -steps),
+            _ => self,
+        }
+    }
+
+    /// Gives the page motions of the [`Binding`] that don't say how far to
+    /// move the given number of rows.
+    pub fn paged(self, rows: usize) -> Self {
+        match self {
+            Self::MoveSelection(motion) => Self::MoveSelection(motion.paged(rows)),
+            Self::ExpandSelection(motion) => Self::ExpandSelection(motion.paged(rows)),
+            _ => self,
+        }
+    }
+
     /// Returns the default [`Binding`] for the given key press.
     pub fn from_key_press(event: KeyPress) -> Option<Self> {
         let KeyPress {
             key,
             modifiers,
+            text,
             status,
-            ..
         } = event;
 
         if status != Status::Focused {
-            return None;
+            // Enter still submits an edit in one of the cells
+            let submit = status == Status::Unfocused
+                && matches!(key.as_ref(), keyboard::Key::Named(key::Named::Enter));
+            return submit.then_some(Self::Enter);
         }
 
         match key.as_ref() {
             keyboard::Key::Named(key::Named::F2) => match status {
-                Status::Focused => Some(Self::StartEdit),
-                Status::Unfocused => Some(Self::Focus),
+                Status::Focused | Status::Hovered { focused: true } => Some(Self::StartEdit),
+                Status::Unfocused | Status::Hovered { focused: false } => Some(Self::Focus),
                 Status::Disabled => None,
             },
             keyboard::Key::Named(key::Named::Enter) => Some(Self::Enter),
             keyboard::Key::Named(key::Named::Delete)
             | keyboard::Key::Named(key::Named::Backspace) => Some(Self::Delete),
             keyboard::Key::Named(key::Named::Escape) => Some(Self::Focus),
+            keyboard::Key::Named(key::Named::Space) if modifiers.shift() => Some(Self::SelectRow),
+            keyboard::Key::Named(key::Named::Space) if modifiers.control() => {
+                Some(Self::SelectColumn)
+            }
+            keyboard::Key::Named(key::Named::Tab) => {
+                Some(Self::MoveSelection(if modifiers.shift() {
+                    Motion::Back
+                } else {
+                    Motion::Forward
+                }))
+            }
             keyboard::Key::Character("c") if modifiers.command() => Some(Self::Copy),
             keyboard::Key::Character("x") if modifiers.command() => Some(Self::Cut),
             keyboard::Key::Character("v") if modifiers.command() && !modifiers.alt() => {
@@ -171,8 +254,28 @@ impl<Message: Clone> Binding<Message> {
             }
             keyboard::Key::Character("a") if modifiers.command() => Some(Self::SelectAll),
             _ => {
-                if let keyboard::Key::Named(named_key) = key.as_ref() {
-                    let motion = motion(named_key)?;
+                let motion = match key.as_ref() {
+                    keyboard::Key::Named(named_key) => motion(named_key),
+                    _ => None,
+                };
+
+                if let Some(motion) = motion {
+                    // Alt resizes, or Control where Alt jumps to the edges of the data
+                    let resize = if cfg!(target_os = "macos") {
+                        modifiers.control()
+                    } else {
+                        modifiers.alt()
+                    };
+                    let resized = match motion {
+                        Motion::Left => Some(Self::Resize(Axis::Column, -1.0)),
+                        Motion::Right => Some(Self::Resize(Axis::Column, 1.0)),
+                        Motion::Up => Some(Self::Resize(Axis::Row, -1.0)),
+                        Motion::Down => Some(Self::Resize(Axis::Row, 1.0)),
+                        _ => None,
+                    };
+                    if let (true, Some(resized)) = (resize, resized) {
+                        return Some(resized);
+                    }
 
                     let motion = if modifiers.macos_command() {
                         match motion {
@@ -196,7 +299,13 @@ impl<Message: Clone> Binding<Message> {
                         Self::MoveSelection(motion)
                     })
                 } else {
-                    None
+                    // anything else that produces text starts an edit
+                    text.filter(|text| {
+                        !modifiers.command()
+                            && !text.is_empty()
+                            && !text.chars().any(char::is_control)
+                    })
+                    .map(Self::Type)
                 }
             }
         }
@@ -211,6 +320,8 @@ fn motion(key: key::Named) -> Option<Motion> {
         key::Named::ArrowDown => Some(Motion::Down),
         key::Named::Home => Some(Motion::Home),
         key::Named::End => Some(Motion::End),
+        key::Named::PageUp => Some(Motion::PageUp(0)),
+        key::Named::PageDown => Some(Motion::PageDown(0)),
         _ => None,
     }
 }
@@ -226,18 +337,47 @@ pub enum Motion {
     Up,
     /// Move down.
     Down,
-    /// Move to the start of the row.
+    /// Move to the start of the row, or of the selection if it holds several
+    /// cells.
     Home,
-    /// Move to the end of the row.
+    /// Move to the end of the row, or of the selection if it holds several
+    /// cells.
     End,
     /// Move to the next cell on the table.
+    ///
+    /// Within a selection of several cells, this cycles the active cell
+    /// through the selection, row by row. When expanding a selection, it
+    /// moves the end of the selection right, like [`Motion::Right`].
     Forward,
     /// Move to the previous cell on the table.
+    ///
+    /// Within a selection of several cells, this cycles the active cell
+    /// back through the selection. When expanding a selection, it moves the
+    /// end of the selection left, like [`Motion::Left`].
     Back,
     /// Move to the start of the document.
     DocumentStart,
     /// Move to the end of the document.
     DocumentEnd,
+    /// Move up by the given number of rows.
+    ///
+    /// A count of zero moves by a page: the [`Table`](crate::Table) replaces
+    /// it with the number of rows in view.
+    PageUp(usize),
+    /// Move down by the given number of rows.
+    ///
+    /// A count of zero moves by a page: the [`Table`](crate::Table) replaces
+    /// it with the number of rows in view.
+    PageDown(usize),
+    /// Move left to the edge of the data: the last filled cell before an
+    /// empty one, or the next filled cell past the empty ones.
+    DataLeft,
+    /// Move right to the edge of the data.
+    DataRight,
+    /// Move up to the edge of the data.
+    DataUp,
+    /// Move down to the edge of the data.
+    DataDown,
 }
 
 impl Motion {
@@ -246,6 +386,58 @@ impl Motion {
         match self {
             Self::Home => Self::DocumentStart,
             Self::End => Self::DocumentEnd,
+            Self::Left => Self::DataLeft,
+            Self::Right => Self::DataRight,
+            Self::Up => Self::DataUp,
+            Self::Down => Self::DataDown,
+            _ => self,
+        }
+    }
+
+    /// Swaps the horizontal motions, for right-to-left layouts.
+    ///
+    /// The arrows swap along with [`Motion::Home`] and [`Motion::End`], so
+    /// that they move towards the visual left and right.
+    pub fn mirror(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::DataLeft => Self::DataRight,
+            Self::DataRight => Self::DataLeft,
+            Self::Home => Self::End,
+            Self::End => Self::Home,
+            _ => self,
+        }
+    }
+
+    /// Returns the [`Motion`] in the opposite direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+            Self::Up => Self::Down,
+            Self::Down => Self::Up,
+            Self::Home => Self::End,
+            Self::End => Self::Home,
+            Self::Forward => Self::Back,
+            Self::Back => Self::Forward,
+            Self::DocumentStart => Self::DocumentEnd,
+            Self::DocumentEnd => Self::DocumentStart,
+            Self::PageUp(rows) => Self::PageDown(rows),
+            Self::PageDown(rows) => Self::PageUp(rows),
+            Self::DataLeft => Self::DataRight,
+            Self::DataRight => Self::DataLeft,
+            Self::DataUp => Self::DataDown,
+            Self::DataDown => Self::DataUp,
+        }
+    }
+
+    /// Gives page motions that don't say how far to move the given number of
+    /// rows.
+    pub fn paged(self, rows: usize) -> Self {
+        match self {
+            Self::PageUp(0) => Self::PageUp(rows),
+            Self::PageDown(0) => Self::PageDown(rows),
             _ => self,
         }
     }
@@ -253,13 +445,44 @@ impl Motion {
     /// Returns the [`Direction`] of the [`Motion`].
     pub fn direction(&self) -> Direction {
         match self {
-            Self::Left | Self::Up | Self::Home | Self::DocumentStart => Direction::Left,
-            Self::Right | Self::Down | Self::End | Self::DocumentEnd => Direction::Right,
+            Self::Left
+            | Self::Up
+            | Self::Home
+            | Self::DocumentStart
+            | Self::PageUp(_)
+            | Self::DataLeft
+            | Self::DataUp => Direction::Left,
+            Self::Right
+            | Self::Down
+            | Self::End
+            | Self::DocumentEnd
+            | Self::PageDown(_)
+            | Self::DataRight
+            | Self::DataDown => Direction::Right,
             Self::Forward | Self::Back => Direction::Arbitrary,
         }
     }
 }
 
+/// What an arrow key does at the edge of the table.
+///
+/// A single selected cell moves forward and back like it moves right and
+/// left, so <kbd>Tab</kbd> follows the policy too. A selection of several
+/// cells is always cycled through by [`Motion::Forward`] and
+/// [`Motion::Back`], whatever the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Wrap {
+    /// Stay on the edge of the table.
+    #[default]
+    Clamp,
+    /// Move on to the start of the next row, or the top of the next column,
+    /// and the other way around, stopping at the corners of the table.
+    WrapRow,
+    /// Like [`Wrap::WrapRow`], but also from the last cell of the table
+    /// back to the first one, and the other way around.
+    WrapTable,
+}
+
 /// A direction in some text.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
@@ -276,8 +499,12 @@ impl<Message: Clone> std::fmt::Debug for Update<Message> {
         match self {
             Self::RedrawRequested => write!(f, "RedrawRequested"),
             Self::Click(click) => write!(f, "Click({:?})", click),
+            Self::SecondaryClick(position) => write!(f, "SecondaryClick({:?})", position),
             Self::Drag(position) => write!(f, "Drag({:?})", position),
+            Self::Hover(position) => write!(f, "Hover({:?})", position),
             Self::Release => write!(f, "Release"),
+            Self::Scroll(delta) => write!(f, "Scroll({:?})", delta),
+            Self::ModifiersChanged(modifiers) => write!(f, "ModifiersChanged({:?})", modifiers),
             Self::Binding(binding) => write!(f, "Binding({:?})", binding),
         }
     }
@@ -292,6 +519,9 @@ impl<Message: Clone> std::fmt::Debug for Binding<Message> {
             Self::MoveSelection(motion) => write!(f, "MoveSelection({:?})", motion),
             Self::ExpandSelection(motion) => write!(f, "ExpandSelection({:?})", motion),
             Self::SelectAll => write!(f, "SelectAll"),
+            Self::SelectRow => write!(f, "SelectRow"),
+            Self::SelectColumn => write!(f, "SelectColumn"),
+            Self::Resize(axis, steps) => write!(f, "Resize({:?}, {})", axis, steps),
             Self::Enter => write!(f, "Enter"),
             Self::Delete => write!(f, "Delete"),
             Self::Focus => write!(f, "Focus"),
@@ -299,6 +529,7 @@ impl<Message: Clone> std::fmt::Debug for Binding<Message> {
             Self::ClickedOutside => write!(f, "ClickedOutside"),
             Self::Custom(_) => write!(f, "Custom"),
             Self::StartEdit => write!(f, "StartEdit"),
+            Self::Type(text) => write!(f, "Type({:?})", text),
         }
     }
 }
@@ -1,3 +1,5 @@
+use iced::Rectangle;
+
 /// An axis of a [`Table`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Axis {
@@ -5,6 +7,23 @@ pub enum Axis {
     Row,
 }
 
+/// How the columns and rows of a [`Table`] are scaled to its bounds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Stretch the columns and rows to fill the bounds.
+    #[default]
+    Stretch,
+    /// Keep the columns and rows at their own sizes, leaving empty space
+    /// past them or overflowing the bounds.
+    Natural,
+    /// Stretch the columns to fill the width, keeping the rows at their own
+    /// heights.
+    FitWidth,
+    /// Stretch the rows to fill the height, keeping the columns at their own
+    /// widths.
+    FitHeight,
+}
+
 /// The possible statuses of a [`Table`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Status {
@@ -12,6 +31,14 @@ pub enum Status {
     Focused,
     /// The table is unfocused.
     Unfocused,
+    /// The cursor is over the table, which may be focused.
+    ///
+    /// Only used for styling; a [`KeyPress`](crate::KeyPress) is always
+    /// either focused or unfocused.
+    Hovered {
+        /// Whether the table is focused.
+        focused: bool,
+    },
     /// The table cannot be interacted with.
     Disabled,
 }
@@ -30,13 +57,40 @@ impl PartialEq for DividerHit {
     }
 }
 
+/// Rounds a length to whole device pixels at the given scale factor, keeping
+/// a length that isn't zero at least one device pixel long.
+pub fn snap_length(length: f32, scale_factor: f32) -> f32 {
+    if length <= 0.0 {
+        return length;
+    }
+
+    (length * scale_factor).round().max(1.0) / scale_factor
+}
+
+/// Snaps some bounds to the device pixel grid at the given scale factor, so a
+/// line one pixel thick covers exactly one row or column of device pixels
+/// instead of blurring over two.
+///
+/// The position and the size are rounded separately, so lines of the same
+/// thickness stay as thick wherever they are.
+pub fn snap_to_pixels(bounds: Rectangle, scale_factor: f32) -> Rectangle {
+    let snap = |value: f32| (value * scale_factor).round() / scale_factor;
+
+    Rectangle {
+        x: snap(bounds.x),
+        y: snap(bounds.y),
+        width: snap_length(bounds.width, scale_factor),
+        height: snap_length(bounds.height, scale_factor),
+    }
+}
+
 pub fn to_column_name(mut n: usize) -> String {
     let mut name = String::new();
 
     loop {
         let rem = n % 26;
         name.push((b'A' + rem as u8) as char);
-        n = n / 26;
+        n /= 26;
         if n == 0 {
             break;
         }
@@ -47,25 +101,48 @@ pub fn to_column_name(mut n: usize) -> String {
 }
 
 pub fn from_column_name(s: &str) -> Result<usize, String> {
-    let mut column = 0u32;
-
-    for c in s.chars() {
-        let c = if c.is_ascii_lowercase() {
-            ((c as u8) - (b'a' - b'A')) as char
-        } else {
-            c
-        };
-        column = column
-            .checked_mul(26)
-            .ok_or_else(|| format!("Column index overflow while parsing: {}", s))?;
-        column = column
-            .checked_add((c as u32) - (b'A' as u32) + 1)
-            .ok_or_else(|| format!("Column index overflow while parsing: {}", s))?;
+    super::reference::parse_column(s)
+        .map_err(|error| format!("Invalid column name {}: {}", s, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::{Point, Size};
+
+    // The bounds in device pixels, which must be whole
+    fn device_pixels(bounds: Rectangle, scale_factor: f32) -> [f32; 4] {
+        [bounds.x, bounds.y, bounds.width, bounds.height].map(|value| {
+            let pixels = value * scale_factor;
+            assert!((pixels - pixels.round()).abs() < 1e-4, "{pixels}");
+            pixels.round()
+        })
     }
 
-    if column == 0 {
-        return Err(format!("Invalid column name: {}", s));
+    #[test]
+    fn snapping_covers_whole_device_pixels() {
+        let line = Rectangle::new(Point::new(10.3, 20.6), Size::new(1.0, 0.2));
+
+        let cases = [
+            (1.0, [10.0, 21.0, 1.0, 1.0]),
+            (1.25, [13.0, 26.0, 1.0, 1.0]),
+            (2.0, [21.0, 41.0, 2.0, 1.0]),
+        ];
+        for (scale_factor, expected) in cases {
+            let snapped = snap_to_pixels(line, scale_factor);
+            assert_eq!(device_pixels(snapped, scale_factor), expected);
+        }
     }
 
-    Ok(usize::try_from(column - 1).map_err(|_| format!("Column index too large: {}", column))?)
+    #[test]
+    fn snapped_lengths_keep_at_least_one_device_pixel() {
+        assert_eq!(snap_length(0.0, 2.0), 0.0);
+        assert_eq!(snap_length(-3.0, 2.0), -3.0);
+        assert_eq!(snap_length(0.1, 1.0), 1.0);
+        assert_eq!(snap_length(0.1, 2.0), 0.5);
+        assert_eq!(snap_length(0.1, 1.25), 0.8);
+        assert_eq!(snap_length(2.4, 1.0), 2.0);
+        assert_eq!(snap_length(2.4, 1.25), 2.4);
+        assert_eq!(snap_length(2.4, 2.0), 2.5);
+    }
 }